@@ -0,0 +1,24 @@
+use std::env;
+use std::process::Command;
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    // Only needed for `UnitTestOptions::include_build_info`.
+    if env::var_os("CARGO_FEATURE_BUILD_INFO").is_none() {
+        return;
+    }
+
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| String::from("rustc"));
+    let version = Command::new(rustc)
+        .arg("--version")
+        .output()
+        .ok()
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|version| version.trim().to_owned())
+        .unwrap_or_else(|| String::from("unknown"));
+    let target = env::var("TARGET").unwrap_or_else(|_| String::from("unknown"));
+
+    println!("cargo:rustc-env=EXCEPTIONAL_RUSTC_VERSION={}", version);
+    println!("cargo:rustc-env=EXCEPTIONAL_TARGET={}", target);
+}
@@ -0,0 +1,75 @@
+use std::future::Future;
+use std::panic::Location;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use {Executable, MaybeOwned, UnitTest};
+
+/// The future returned by [AsyncExecutable::execute_async](trait.AsyncExecutable.html#tymethod.execute_async).
+pub type ExecuteFuture<'a, E> =
+    Pin<Box<dyn Future<Output = Result<<E as Executable>::Result, <E as Executable>::Error>> + 'a>>;
+
+/// An [Executable](trait.Executable.html) whose logic is asynchronous.
+///
+/// The associated types, the paths and the description come from `Executable`. Its synchronous `execute` is not used by [execute_async](fn.execute_async.html) or by the generated async tests, so it can block on `execute_async` or be left `unreachable!()` for types that are only executed asynchronously.
+///
+/// ```rust,ignore
+/// impl exceptional::AsyncExecutable for FetchUser {
+///     fn execute_async<'a>(&'a mut self, id: &'a u64) -> exceptional::ExecuteFuture<'a, Self> {
+///         Box::pin(async move { self.client.fetch(*id).await })
+///     }
+/// }
+/// ```
+///
+/// Render the generated test as an async test with [async_test](struct.UnitTestOptions.html#method.async_test).
+pub trait AsyncExecutable: Executable {
+    /// Execute the logic. Call this with [execute_async](fn.execute_async.html) to generate a unit test out of the error.
+    fn execute_async<'a>(&'a mut self, args: &'a Self::Arguments) -> ExecuteFuture<'a, Self>;
+}
+
+/// Like [execute](fn.execute.html), for [AsyncExecutable](trait.AsyncExecutable.html)s. The returned future resolves to the result of the executable, or to a unit test if it failed.
+///
+/// The executable is cloned when this is called, and the location of this call is stored in the test.
+#[track_caller]
+pub fn execute_async<'a, E: AsyncExecutable + 'a>(
+    executable: &'a mut E,
+    arguments: &'a E::Arguments,
+) -> ExecuteAsync<'a, E> {
+    ExecuteAsync {
+        old: Some(executable.clone()),
+        arguments,
+        location: Location::caller(),
+        future: executable.execute_async(arguments),
+    }
+}
+
+/// The future returned by [execute_async](fn.execute_async.html).
+pub struct ExecuteAsync<'a, E: AsyncExecutable + 'a> {
+    old: Option<E>,
+    arguments: &'a E::Arguments,
+    location: &'static Location<'static>,
+    future: ExecuteFuture<'a, E>,
+}
+
+// The snapshot is never pinned, only the boxed future is polled.
+impl<'a, E: AsyncExecutable + 'a> Unpin for ExecuteAsync<'a, E> {}
+
+impl<'a, E: AsyncExecutable + 'a> Future for ExecuteAsync<'a, E> {
+    type Output = Result<E::Result, UnitTest<'a, E>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        match this.future.as_mut().poll(cx) {
+            Poll::Pending => Poll::Pending,
+            Poll::Ready(Ok(value)) => Poll::Ready(Ok(value)),
+            Poll::Ready(Err(error)) => {
+                let old = this
+                    .old
+                    .take()
+                    .expect("ExecuteAsync polled after completion");
+                let mut test = UnitTest::new(old, MaybeOwned::Borrowed(this.arguments), error);
+                test.location = Some(this.location);
+                Poll::Ready(Err(test))
+            }
+        }
+    }
+}
@@ -0,0 +1,163 @@
+//! Embedding byte arrays as base64 strings. See [UnitTestOptions::base64_bytes](struct.UnitTestOptions.html#method.base64_bytes).
+//!
+//! serde serializes a `Vec<u8>` as an array of numbers, which the pretty printer writes with one number per line. Both directions work on the pretty-printed text instead of a `Value`, so the decoded JSON is exactly the JSON that was encoded, and hashes of it stay the same.
+
+/// The start of an encoded array, which is followed by the base64 string and `"}`.
+const ENCODED_START: &str = "{\"$base64\": \"";
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encode `bytes` as standard base64, with padding.
+fn encode(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (i, &byte)| {
+            group | u32::from(byte) << (16 - 8 * i)
+        });
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(group >> (18 - 6 * i)) as usize & 0x3f] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// Decode standard base64 with padding, or return `None` if `encoded` is not valid base64.
+fn decode(encoded: &str) -> Option<Vec<u8>> {
+    if !encoded.len().is_multiple_of(4) {
+        return None;
+    }
+    let mut bytes = Vec::with_capacity(encoded.len() / 4 * 3);
+    for chunk in encoded.as_bytes().chunks(4) {
+        let padding = chunk.iter().rev().take_while(|&&c| c == b'=').count();
+        if padding > 2 {
+            return None;
+        }
+        let mut group = 0u32;
+        for (i, &c) in chunk[..4 - padding].iter().enumerate() {
+            let value = ALPHABET.iter().position(|&a| a == c)? as u32;
+            group |= value << (18 - 6 * i);
+        }
+        for i in 0..3 - padding {
+            bytes.push((group >> (16 - 8 * i)) as u8);
+        }
+    }
+    Some(bytes)
+}
+
+/// The array of `bytes` as the pretty printer of serde_json writes it, on a line that is indented by `indent`.
+fn pretty_array(bytes: &[u8], indent: &str) -> String {
+    let mut array = String::from("[\n");
+    for (i, byte) in bytes.iter().enumerate() {
+        array.push_str(indent);
+        array.push_str("  ");
+        array.push_str(&byte.to_string());
+        array.push_str(if i + 1 < bytes.len() { ",\n" } else { "\n" });
+    }
+    array.push_str(indent);
+    array.push(']');
+    array
+}
+
+/// The leading whitespace of the line that contains the byte at `at`.
+fn line_indent(json: &str, at: usize) -> &str {
+    let start = json[..at].rfind('\n').map_or(0, |newline| newline + 1);
+    let line = &json[start..];
+    &line[..line.len() - line.trim_start_matches(' ').len()]
+}
+
+/// The numbers of the array of integers from 0 to 255 at the start of `src`, and the length of the array in `src`, or `None` if `src` does not start with such an array.
+fn byte_array(src: &str) -> Option<(Vec<u8>, usize)> {
+    let mut rest = src.strip_prefix('[')?;
+    let mut bytes = Vec::new();
+    loop {
+        let trimmed = rest.trim_start();
+        let digits = trimmed.len()
+            - trimmed
+                .trim_start_matches(|c: char| c.is_ascii_digit())
+                .len();
+        if digits == 0 || digits > 3 {
+            return None;
+        }
+        bytes.push(trimmed[..digits].parse().ok()?);
+        let after = trimmed[digits..].trim_start();
+        if let Some(next) = after.strip_prefix(',') {
+            rest = next;
+        } else {
+            let end = after.strip_prefix(']')?;
+            return Some((bytes, src.len() - end.len()));
+        }
+    }
+}
+
+/// Replace every array of at least `min_len` integers from 0 to 255 in the pretty-printed `json` by a `{"$base64": "..."}` object, or return `None` if there is no such array.
+pub(crate) fn encode_byte_arrays(json: &str, min_len: usize) -> Option<String> {
+    let mut encoded = String::with_capacity(json.len());
+    let mut copied = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in json.char_indices() {
+        if i < copied {
+            continue;
+        }
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '[' => {
+                if let Some((bytes, len)) = byte_array(&json[i..]) {
+                    // Only arrays in the layout of the pretty printer can be restored exactly.
+                    let array = &json[i..i + len];
+                    if bytes.len() >= min_len && array == pretty_array(&bytes, line_indent(json, i))
+                    {
+                        encoded.push_str(&json[copied..i]);
+                        encoded.push_str(ENCODED_START);
+                        encoded.push_str(&encode(&bytes));
+                        encoded.push_str("\"}");
+                        copied = i + len;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    if copied == 0 {
+        return None;
+    }
+    encoded.push_str(&json[copied..]);
+    Some(encoded)
+}
+
+/// Restore the byte arrays in JSON that was embedded with [base64_bytes](struct.UnitTestOptions.html#method.base64_bytes), which replaces every array by a `{"$base64": "..."}` object. Generated tests call this before they deserialize the JSON.
+///
+/// The result is the JSON exactly as it was serialized. JSON without encoded arrays is returned as is, and so is an encoded array that is not valid base64, so deserializing it reports the error.
+pub fn decode_base64_json(json: &str) -> String {
+    let mut decoded = String::with_capacity(json.len());
+    let mut rest = json;
+    while let Some(start) = rest.find(ENCODED_START) {
+        let encoded = &rest[start + ENCODED_START.len()..];
+        let bytes = encoded
+            .find("\"}")
+            .and_then(|end| decode(&encoded[..end]).map(|bytes| (bytes, end)));
+        let (bytes, end) = match bytes {
+            Some((bytes, end)) if !bytes.is_empty() => (bytes, end),
+            _ => break,
+        };
+        decoded.push_str(&rest[..start]);
+        let offset = json.len() - rest.len() + start;
+        decoded.push_str(&pretty_array(&bytes, line_indent(json, offset)));
+        rest = &encoded[end + 2..];
+    }
+    decoded.push_str(rest);
+    decoded
+}
@@ -0,0 +1,65 @@
+use std::collections::HashSet;
+use {Executable, MaybeOwned, OwnedUnitTest, UnitTest};
+
+/// How many failures [execute_all](fn.execute_all.html) collects.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+    /// Stop at the first input that fails.
+    FirstFailure,
+    /// Run every input and collect all failures.
+    AllFailures,
+    /// Run every input and collect all failures, but skip inputs that serialize to the same JSON as an input that already failed during this run.
+    UniqueFailures,
+}
+
+/// Execute a clone of `executable` with every item of `arguments`, and collect the failures as unit tests according to `mode`.
+///
+/// Every input runs on a fresh clone of `executable`, so inputs don't influence each other and every generated test starts from the same state. The tests own their arguments, so they can outlive the iterator. Successful results are discarded.
+///
+/// This scans a range of inputs like the nested loops in `main.rs`, but collects every failure instead of returning at the first one:
+///
+/// ```rust,ignore
+/// for i1 in 0..10 {
+///     for i2 in 0..10 {
+///         let action = SomeImportantAction { var_1: i1, var_2: i2 };
+///         let inputs = (0..10).flat_map(|i3| (0..10).map(move |i4| (i3, i4)));
+///         for test in exceptional::execute_all(&action, inputs, exceptional::Mode::AllFailures) {
+///             test.append_to_file("src/test.rs").expect("Could not write unit test");
+///         }
+///     }
+/// }
+/// ```
+#[track_caller]
+pub fn execute_all<E, I>(executable: &E, arguments: I, mode: Mode) -> Vec<OwnedUnitTest<E>>
+where
+    E: Executable + 'static,
+    I: IntoIterator<Item = E::Arguments>,
+{
+    let mut failures = Vec::new();
+    let mut seen = HashSet::new();
+    for arguments in arguments {
+        let key = match mode {
+            Mode::UniqueFailures => match ::serde_json::to_string(&arguments) {
+                Ok(json) if seen.contains(&json) => continue,
+                Ok(json) => Some(json),
+                Err(_) => None,
+            },
+            _ => None,
+        };
+        let mut trial = executable.clone();
+        if let Err(error) = trial.execute(&arguments) {
+            failures.push(UnitTest::new(
+                executable.clone(),
+                MaybeOwned::Owned(arguments),
+                error,
+            ));
+            if let Some(key) = key {
+                seen.insert(key);
+            }
+            if mode == Mode::FirstFailure {
+                break;
+            }
+        }
+    }
+    failures
+}
@@ -0,0 +1,167 @@
+use async_executable::{AsyncExecutable, ExecuteFuture};
+use std::future::Future;
+use std::panic::Location;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use {MaybeOwned, OwnedUnitTest, UnitTest};
+
+/// Execute a clone of `executable` with every item of `arguments`, running up to `concurrency` executions at the same time, and collect the failures as unit tests.
+///
+/// This is the asynchronous counterpart of [execute_all](fn.execute_all.html) with [Mode::AllFailures](enum.Mode.html#variant.AllFailures), for executables that spend most of their time waiting, like network requests. Every input runs on a fresh clone of `executable`. New inputs are taken from the iterator as soon as a running execution finishes, so the failures are collected in the order in which they finished, not in the order of the inputs. The tests own their arguments, and can be rendered as [async tests](struct.UnitTestOptions.html#method.async_test). Successful results are discarded.
+///
+/// The future does not spawn tasks. All executions are polled by the task that awaits it, so this works with any executor.
+///
+/// ```rust,ignore
+/// let tests = exceptional::execute_all_async(&client, 0..1000, 16).await;
+/// for test in tests {
+///     test.with_options(UnitTestOptions::default().async_test("tokio::test"))
+///         .append_to_file("tests/regressions.rs")?;
+/// }
+/// ```
+///
+/// # Panics
+///
+/// Panics if `concurrency` is 0.
+#[track_caller]
+pub fn execute_all_async<E, I>(
+    executable: &E,
+    arguments: I,
+    concurrency: usize,
+) -> ExecuteAllAsync<E, I::IntoIter>
+where
+    E: AsyncExecutable + 'static,
+    I: IntoIterator<Item = E::Arguments>,
+{
+    assert!(concurrency > 0, "concurrency must be at least 1");
+    ExecuteAllAsync {
+        executable: executable.clone(),
+        arguments: arguments.into_iter(),
+        concurrency,
+        in_flight: Vec::with_capacity(concurrency),
+        failures: Some(Vec::new()),
+        location: Location::caller(),
+    }
+}
+
+/// The future returned by [execute_all_async](fn.execute_all_async.html).
+pub struct ExecuteAllAsync<E: AsyncExecutable + 'static, I> {
+    executable: E,
+    arguments: I,
+    concurrency: usize,
+    in_flight: Vec<InFlight<E>>,
+    failures: Option<Vec<OwnedUnitTest<E>>>,
+    location: &'static Location<'static>,
+}
+
+// Only the boxed futures of the executions are polled, the batch itself is never pinned.
+impl<E: AsyncExecutable + 'static, I> Unpin for ExecuteAllAsync<E, I> {}
+
+impl<E, I> Future for ExecuteAllAsync<E, I>
+where
+    E: AsyncExecutable + 'static,
+    I: Iterator<Item = E::Arguments>,
+{
+    type Output = Vec<OwnedUnitTest<E>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        loop {
+            while this.in_flight.len() < this.concurrency {
+                match this.arguments.next() {
+                    Some(arguments) => this
+                        .in_flight
+                        .push(InFlight::start(this.executable.clone(), arguments)),
+                    None => break,
+                }
+            }
+            if this.in_flight.is_empty() {
+                let failures = this
+                    .failures
+                    .take()
+                    .expect("ExecuteAllAsync polled after completion");
+                return Poll::Ready(failures);
+            }
+
+            let mut finished = false;
+            let mut i = 0;
+            while i < this.in_flight.len() {
+                match this.in_flight[i].poll(cx) {
+                    Poll::Pending => i += 1,
+                    Poll::Ready(result) => {
+                        finished = true;
+                        let (arguments, result) = this.in_flight.swap_remove(i).finish(result);
+                        if let Err(error) = result {
+                            let mut test = UnitTest::new(
+                                this.executable.clone(),
+                                MaybeOwned::Owned(arguments),
+                                error,
+                            );
+                            test.location = Some(this.location);
+                            if let Some(ref mut failures) = this.failures {
+                                failures.push(test);
+                            }
+                        }
+                    }
+                }
+            }
+            if !finished {
+                return Poll::Pending;
+            }
+        }
+    }
+}
+
+/// A running execution, which owns the clone and the arguments that its future borrows.
+struct InFlight<E: AsyncExecutable + 'static> {
+    // Declared first, so it is dropped before the allocations it borrows.
+    future: Option<ExecuteFuture<'static, E>>,
+    trial: *mut E,
+    arguments: *mut E::Arguments,
+}
+
+impl<E: AsyncExecutable + 'static> InFlight<E> {
+    fn start(trial: E, arguments: E::Arguments) -> Self {
+        let trial = Box::into_raw(Box::new(trial));
+        let arguments = Box::into_raw(Box::new(arguments));
+        // SAFETY: both allocations are only freed in `finish` or `drop`, after the future that borrows them was dropped. They are not accessed in any other way while the future exists, so the mutable borrow of the trial is unique.
+        let future = unsafe { (*trial).execute_async(&*arguments) };
+        InFlight {
+            future: Some(future),
+            trial,
+            arguments,
+        }
+    }
+
+    fn poll(&mut self, cx: &mut Context) -> Poll<Result<E::Result, E::Error>> {
+        self.future
+            .as_mut()
+            .expect("execution polled after completion")
+            .as_mut()
+            .poll(cx)
+    }
+
+    /// Drop the finished future, and return the arguments with its result.
+    fn finish(
+        mut self,
+        result: Result<E::Result, E::Error>,
+    ) -> (E::Arguments, Result<E::Result, E::Error>) {
+        self.future = None;
+        // SAFETY: the future that borrowed the arguments was dropped above, and `drop` won't free them again because the pointer is replaced.
+        let arguments = unsafe { Box::from_raw(self.arguments) };
+        self.arguments = ::std::ptr::null_mut();
+        (*arguments, result)
+    }
+}
+
+impl<E: AsyncExecutable + 'static> Drop for InFlight<E> {
+    fn drop(&mut self) {
+        self.future = None;
+        // SAFETY: the future that borrowed the allocations was dropped above, and nothing else refers to them.
+        unsafe {
+            drop(Box::from_raw(self.trial));
+            if !self.arguments.is_null() {
+                drop(Box::from_raw(self.arguments));
+            }
+        }
+    }
+}
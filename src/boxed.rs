@@ -0,0 +1,50 @@
+use std::error::Error;
+use std::ops::Deref;
+use {Executable, MaybeOwned, UnitTest};
+
+/// Like [execute](fn.execute.html), for executables whose error is a boxed `std::error::Error`, like `Box<dyn Error>`.
+///
+/// Trait objects can't be serialized, and their `Debug` output is often not helpful in a test. Instead, the [error chain](fn.error_chain.html) of the failure is stored in [error_message](struct.UnitTest.html#structfield.error_message). The header of the generated test shows this message, and unless the executable has a custom [assertion](trait.Executable.html#method.assertion), the generated test asserts that the executable still fails with an error whose chain contains the message:
+///
+/// ```rust,ignore
+/// match obj.execute(&args) {
+///     Ok(_) => panic!("{} no longer fails", obj.description()),
+///     Err(e) => {
+///         let message = ::exceptional::error_chain(&*e);
+///         assert!(message.contains("could not parse config: invalid digit found in string"), "unexpected error: {}", message);
+///     }
+/// }
+/// ```
+#[track_caller]
+pub fn execute_boxed_err<'a, E>(
+    executable: &'a mut E,
+    arguments: &'a E::Arguments,
+) -> Result<E::Result, UnitTest<'a, E>>
+where
+    E: Executable + 'a,
+    E::Error: Deref,
+    <E::Error as Deref>::Target: Error,
+{
+    let old = executable.clone();
+    match executable.execute(arguments) {
+        Ok(value) => Ok(value),
+        Err(error) => {
+            let message = error_chain(&*error);
+            let mut test = UnitTest::new(old, MaybeOwned::Borrowed(arguments), error);
+            test.error_message = Some(message);
+            Err(test)
+        }
+    }
+}
+
+/// The `Display` output of `error` followed by those of its sources, separated by `": "`, e.g. `could not parse config: invalid digit found in string`.
+pub fn error_chain<T: Error + ?Sized>(error: &T) -> String {
+    let mut chain = error.to_string();
+    let mut source = error.source();
+    while let Some(error) = source {
+        chain.push_str(": ");
+        chain.push_str(&error.to_string());
+        source = error.source();
+    }
+    chain
+}
@@ -0,0 +1,73 @@
+use std::path::Path;
+use {Executable, UnitTest};
+
+/// The file that [capture!](macro.capture.html) appends to if no file is given.
+pub const CAPTURE_FILE: &str = "src/test.rs";
+
+/// Run a call that returns a `Result`, and append a unit test for it to a file if it fails, without the [from_result](struct.UnitTest.html#method.from_result) plumbing at every call site:
+///
+/// ```rust,ignore
+/// let user = exceptional::capture!(service, "looking up the user of a session", service.lookup(session_id))?;
+/// let user = exceptional::capture!("src/regressions.rs", service, "looking up the user", lookup(session_id))?;
+/// ```
+///
+/// The first argument is the executable whose state is snapshotted. It is cloned before the call runs, so the call can borrow or mutate the same value, and the generated test executes the state from before the call. The call must be a function call `operation(arguments)` or a method call `receiver.method(arguments)` with a single argument, which is evaluated once, stored as the [arguments](trait.Executable.html#associatedtype.Arguments) of the test and passed to the call by reference, and which fails with the [error](trait.Executable.html#associatedtype.Error) of the executable. The description is written to the header of the test as its `context` [metadata](struct.UnitTest.html#method.with_metadata). Without a file, the test is appended to [CAPTURE_FILE](constant.CAPTURE_FILE.html).
+///
+/// The `Result` of the call is returned as it is, so `?` and `match` keep working. The location of the macro is stored in the test.
+///
+/// # Panics
+///
+/// Panics if the call fails and the test can't be rendered or written, like [append_to_file](struct.UnitTest.html#method.append_to_file).
+#[macro_export]
+macro_rules! capture {
+    ($snapshot:expr, $description:expr, $receiver:ident . $method:ident ( $arguments:expr )) => {
+        $crate::capture!(
+            $crate::CAPTURE_FILE,
+            $snapshot,
+            $description,
+            $receiver.$method($arguments)
+        )
+    };
+    ($snapshot:expr, $description:expr, $($operation:ident)::+ ( $arguments:expr )) => {
+        $crate::capture!(
+            $crate::CAPTURE_FILE,
+            $snapshot,
+            $description,
+            $($operation)::+($arguments)
+        )
+    };
+    ($file:expr, $snapshot:expr, $description:expr, $receiver:ident . $method:ident ( $arguments:expr )) => {{
+        let arguments = $arguments;
+        let snapshot = ::std::clone::Clone::clone(&$snapshot);
+        let result = $receiver.$method(&arguments);
+        $crate::capture_result($file, snapshot, arguments, $description, result)
+    }};
+    ($file:expr, $snapshot:expr, $description:expr, $($operation:ident)::+ ( $arguments:expr )) => {{
+        let arguments = $arguments;
+        let snapshot = ::std::clone::Clone::clone(&$snapshot);
+        let result = $($operation)::+(&arguments);
+        $crate::capture_result($file, snapshot, arguments, $description, result)
+    }};
+}
+
+/// The part of [capture!](macro.capture.html) that does not depend on the shape of the call.
+#[doc(hidden)]
+#[track_caller]
+pub fn capture_result<E: Executable + 'static, T>(
+    file: impl AsRef<Path>,
+    executable: E,
+    arguments: E::Arguments,
+    description: &str,
+    result: Result<T, E::Error>,
+) -> Result<T, E::Error> {
+    let test = match UnitTest::from_result(executable, arguments, result) {
+        Ok(value) => return Ok(value),
+        Err(test) => test.with_metadata("context", description),
+    };
+    let snapshot = test
+        .snapshot()
+        .unwrap_or_else(|e| panic!("Could not render unit test: {}", e));
+    test.append_snapshot(file.as_ref(), &snapshot)
+        .unwrap_or_else(|e| panic!("Could not write unit test: {}", e));
+    Err(test.error)
+}
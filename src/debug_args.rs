@@ -0,0 +1,119 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::any;
+use std::fmt::Debug;
+use {Executable, MaybeOwned, OwnedUnitTest, UnitTest};
+
+/// An executable whose arguments can't be serialized, like a handle to a connection pool or an HTTP client of another crate, which only have to implement `Debug`.
+///
+/// The generated test can't deserialize such arguments, so it documents them instead: their `Debug` output is written as a comment, followed by a stub for the arguments binding that the reproduction has to be completed with. **The generated test doesn't compile until the stub is replaced** by code that constructs the arguments again, which makes sure that an incomplete reproduction isn't mistaken for a passing test:
+///
+/// ```text
+///     // The arguments can not be serialized. Their `Debug` output was:
+///     // Client { base_url: "https://example.com", retries: 3 }
+///     // TODO: reconstruct arguments
+///     let args: my_crate::Client = compile_error!("TODO: reconstruct the arguments from their Debug output in the comment above");
+/// ```
+///
+/// The executable itself is serialized as usual. Capture a failure with [execute_debug](fn.execute_debug.html):
+///
+/// ```rust,ignore
+/// impl exceptional::DebugExecutable for Download {
+///     type Result = Vec<u8>;
+///     type Error = String;
+///     type Arguments = reqwest::blocking::Client;
+///
+///     fn full_path(&self) -> &'static str { "::my_crate::Download" }
+///     fn description(&self) -> String { String::from("Downloads the file") }
+///     fn execute(&mut self, client: &reqwest::blocking::Client) -> Result<Vec<u8>, String> {
+///         self.fetch(client)
+///     }
+/// }
+///
+/// if let Err(test) = exceptional::execute_debug(&mut download, &client) {
+///     test.append_to_file("src/regressions.rs").unwrap();
+/// }
+/// ```
+///
+/// The `Debug` output takes the place of the serialized arguments in the marker hash, so failures with different arguments are different tests. The hooks of `Executable` keep their default implementations.
+pub trait DebugExecutable: Serialize + for<'a> Deserialize<'a> + Clone {
+    /// The result of the execute action.
+    type Result;
+
+    /// The error of the execute action.
+    type Error: Debug;
+
+    /// The arguments that will be passed to the execute action, which are only written to the generated test with `Debug`.
+    type Arguments: Debug;
+
+    /// Get the full path for this type. This will be used to generate the unit test.
+    fn full_path(&self) -> &'static str;
+
+    /// Get the description for this type, which will show up in the description of the unit test.
+    fn description(&self) -> String;
+
+    /// Execute the logic.
+    fn execute(&mut self, args: &Self::Arguments) -> Result<Self::Result, Self::Error>;
+}
+
+/// The [Executable](trait.Executable.html) that a failure of a [DebugExecutable](trait.DebugExecutable.html) is captured as: the executable with the `Debug` output of its arguments as its arguments.
+///
+/// It serializes as the executable alone, so the generated test deserializes the `DebugExecutable` itself. The arguments are gone, so it can't be executed again, and `execute` panics.
+#[derive(Clone, Debug)]
+pub struct DebugArguments<E: DebugExecutable> {
+    /// The executable, in its state from before it failed
+    pub executable: E,
+}
+
+impl<E: DebugExecutable> Serialize for DebugArguments<E> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.executable.serialize(serializer)
+    }
+}
+
+impl<'de, E: DebugExecutable> Deserialize<'de> for DebugArguments<E> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        E::deserialize(deserializer).map(|executable| DebugArguments { executable })
+    }
+}
+
+impl<E: DebugExecutable> Executable for DebugArguments<E> {
+    type Result = E::Result;
+    type Error = E::Error;
+    type Arguments = String;
+
+    fn full_path(&self) -> &'static str {
+        self.executable.full_path()
+    }
+
+    fn debug_arguments_type(&self) -> Option<&'static str> {
+        Some(any::type_name::<E::Arguments>())
+    }
+
+    fn description(&self) -> String {
+        self.executable.description()
+    }
+
+    fn execute(&mut self, _args: &String) -> Result<E::Result, E::Error> {
+        panic!(
+            "{} can not be executed again, because its arguments were only captured with Debug",
+            self.executable.full_path()
+        )
+    }
+}
+
+/// Like [execute](fn.execute.html), for a [DebugExecutable](trait.DebugExecutable.html), whose arguments are captured with their `Debug` output.
+#[track_caller]
+pub fn execute_debug<E: DebugExecutable + 'static>(
+    executable: &mut E,
+    arguments: &E::Arguments,
+) -> Result<E::Result, OwnedUnitTest<DebugArguments<E>>> {
+    let old = executable.clone();
+    match executable.execute(arguments) {
+        Ok(value) => Ok(value),
+        Err(error) => Err(UnitTest::new(
+            DebugArguments { executable: old },
+            MaybeOwned::Owned(format!("{:?}", arguments)),
+            error,
+        )),
+    }
+}
@@ -0,0 +1,49 @@
+use serde_json::{Map, Value};
+use {Executable, UnitTest};
+
+impl<'a, E: Executable + Default + 'a> UnitTest<'a, E> {
+    /// Construct the executable in the generated test from `Default::default()`, and only set the fields that differ from the default:
+    ///
+    /// ```rust,ignore
+    /// let mut obj: ::my_crate::Config = Default::default();
+    /// obj.retries = ::serde_json::from_str(r#"3"#).expect("Could not deserialize json");
+    /// ```
+    ///
+    /// This keeps reproductions of executables with a lot of default state readable. It relies on the serialized field names being the names of public fields of the executable, so it can't be used with `#[serde(rename)]`, `#[serde(flatten)]` or private fields. If the executable does not serialize to a JSON object with the same fields as its default, the full JSON is embedded as usual. The full JSON is also used for [sidecar files](struct.UnitTestOptions.html#method.sidecar_dir) and [groups](fn.render_group.html).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `E::default()` can't be serialized to JSON.
+    pub fn with_default_overrides(mut self) -> Self {
+        let json = ::serde_json::to_string(&E::default())
+            .unwrap_or_else(|e| panic!("could not serialize the default executable: {}", e));
+        self.default_json = Some(json);
+        self
+    }
+}
+
+/// The fields of `executable_json` that differ from `default_json`, as pairs of field name and compact JSON value, sorted by name.
+///
+/// Returns `None` if either is not a JSON object, if they don't have the same fields, or if a field name is not a valid identifier.
+pub(crate) fn overrides(
+    default_json: &str,
+    executable_json: &str,
+) -> Option<Vec<(String, String)>> {
+    let default: Map<String, Value> = ::serde_json::from_str(default_json).ok()?;
+    let executable: Map<String, Value> = ::serde_json::from_str(executable_json).ok()?;
+    if default.len() != executable.len() {
+        return None;
+    }
+    let mut overrides = Vec::new();
+    for (field, value) in &executable {
+        if !::options::is_identifier(field) {
+            return None;
+        }
+        match default.get(field) {
+            Some(default) if default == value => {}
+            Some(_) => overrides.push((field.clone(), value.to_string())),
+            None => return None,
+        }
+    }
+    Some(overrides)
+}
@@ -0,0 +1,149 @@
+use parse::{self, Marker};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// The differences between the generated tests of two files, as returned by [diff_files](fn.diff_files.html).
+///
+/// Tests are matched by their id. The `Display` output is a human-readable summary with one line per test, like:
+///
+/// ```text
+/// 1 added, 1 removed, 1 changed
+/// + test_1539761233543 ::my_crate::SomeImportantAction
+/// - test_1539761230000 ::my_crate::OtherAction
+/// ~ test_1539761231234 ::my_crate::SomeImportantAction (error changed)
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TestDiff {
+    /// The markers of the tests that are only in the head file, in the order of that file
+    pub added: Vec<Marker>,
+
+    /// The markers of the tests that are only in the base file, in the order of that file
+    pub removed: Vec<Marker>,
+
+    /// The tests that are in both files, but whose snapshot or error changed, in the order of the head file
+    pub changed: Vec<TestChange>,
+}
+
+/// A test that is in both files of a [TestDiff](struct.TestDiff.html), with a different snapshot or error.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TestChange {
+    /// The marker of the test in the base file
+    pub base: Marker,
+
+    /// The marker of the test in the head file
+    pub head: Marker,
+}
+
+impl TestChange {
+    /// Whether the hash of the serialized executable and arguments changed.
+    pub fn snapshot_changed(&self) -> bool {
+        self.base.hash != self.head.hash
+    }
+
+    /// Whether the hash of the error changed. Groups don't record the hash of their errors, so this is always `false` for them.
+    pub fn error_changed(&self) -> bool {
+        self.base.error != self.head.error
+    }
+}
+
+impl TestDiff {
+    /// Whether both files contain the same tests, with the same snapshots and errors.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// Compare the generated tests in the sources `base` and `head`. See [diff_files](fn.diff_files.html).
+    pub fn between(base: &str, head: &str) -> TestDiff {
+        let base = markers(base);
+        let head = markers(head);
+        let mut base_by_id: HashMap<&str, &Marker> = HashMap::new();
+        for marker in &base {
+            base_by_id.entry(&marker.id).or_insert(marker);
+        }
+        let mut head_by_id: HashMap<&str, &Marker> = HashMap::new();
+        for marker in &head {
+            head_by_id.entry(&marker.id).or_insert(marker);
+        }
+
+        let mut diff = TestDiff::default();
+        for marker in &head {
+            match base_by_id.remove(marker.id.as_str()) {
+                Some(old) if old.hash != marker.hash || old.error != marker.error => {
+                    diff.changed.push(TestChange {
+                        base: old.clone(),
+                        head: marker.clone(),
+                    })
+                }
+                Some(_) => {}
+                None if head_by_id.remove(marker.id.as_str()).is_some() => {
+                    diff.added.push(marker.clone())
+                }
+                None => {}
+            }
+        }
+        diff.removed = base
+            .iter()
+            .filter(|marker| base_by_id.remove(marker.id.as_str()).is_some())
+            .cloned()
+            .collect();
+        diff
+    }
+}
+
+impl fmt::Display for TestDiff {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            fmt,
+            "{} added, {} removed, {} changed",
+            self.added.len(),
+            self.removed.len(),
+            self.changed.len()
+        )?;
+        for marker in &self.added {
+            writeln!(fmt, "+ test_{} {}", marker.id, marker.path)?;
+        }
+        for marker in &self.removed {
+            writeln!(fmt, "- test_{} {}", marker.id, marker.path)?;
+        }
+        for change in &self.changed {
+            let what = match (change.snapshot_changed(), change.error_changed()) {
+                (true, true) => "snapshot and error changed",
+                (true, false) => "snapshot changed",
+                _ => "error changed",
+            };
+            writeln!(
+                fmt,
+                "~ test_{} {} ({})",
+                change.head.id, change.head.path, what
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// The markers of the complete generated items in `src`.
+fn markers(src: &str) -> Vec<Marker> {
+    parse::find_items(src)
+        .into_iter()
+        .map(|item| item.marker)
+        .collect()
+}
+
+/// Compare the generated tests in the files `base` and `head`, e.g. the test file before and after a pull request, and report which tests were added, removed or changed.
+///
+/// Tests are found and matched by their [marker](struct.Marker.html) comments. A test changed if the hash of its snapshot or of its error is different. Hand-written code, and changes to the body of a test that don't change its marker, are not reported. If a file contains several tests with the same id, only the first one is compared.
+///
+/// ```rust,ignore
+/// let diff = exceptional::diff_files("base/src/regressions.rs", "src/regressions.rs")?;
+/// if !diff.is_empty() {
+///     print!("{}", diff);
+/// }
+/// ```
+pub fn diff_files(base: impl AsRef<Path>, head: impl AsRef<Path>) -> io::Result<TestDiff> {
+    let base = fs::read_to_string(base)?;
+    let head = fs::read_to_string(head)?;
+    Ok(TestDiff::between(&base, &head))
+}
@@ -0,0 +1,108 @@
+use error::AppendError;
+use file;
+use insert;
+use options::LineEnding;
+use render;
+use sidecar;
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use {Executable, UnitTest};
+
+/// The name of the file in a test directory that declares the modules of the tests.
+const MOD_FILE: &str = "mod.rs";
+
+impl<'a, E: Executable + 'a> UnitTest<'a, E> {
+    /// Write this unit test to a file of its own in `dir`, named after the id of the test, and declare it in the `mod.rs` of that directory. Returns the path of the new file.
+    ///
+    /// One file per test keeps the diffs of generated tests small and makes it easy to delete a test that is no longer needed, together with its file. The directory is included once, like any other module, e.g. with `mod regressions;` for `src/regressions/`:
+    ///
+    /// ```text
+    /// // src/regressions/mod.rs
+    /// mod test_1539761233543;
+    ///
+    /// // src/regressions/test_1539761233543.rs
+    /// #[allow(unused_imports)]
+    /// use super::*;
+    /// #[allow(unused_imports)]
+    /// use exceptional::Executable;
+    ///
+    /// // @exceptional id=1539761233543 ...
+    /// #[test]
+    /// pub fn test_1539761233543() {
+    /// ...
+    /// ```
+    ///
+    /// The directory and the `mod.rs` are created if they don't exist, and a `mod` line is only added once. Like in [append_to_module](#method.append_to_module), the imports of the test are at the top of its file instead of in the test. If a file for the id already exists, the test gets a [unique id](#method.unique_id) instead, so an earlier test is never overwritten. A [sidecar directory](struct.UnitTestOptions.html#method.sidecar_dir) is relative to `dir`; the other options that place a test in a file, like the banner, the target module and upserts, are not used.
+    ///
+    /// A test that can not be rendered is an error of kind `InvalidData`, like for [append_to_file](#method.append_to_file), and nothing is written.
+    pub fn write_to_dir(self, dir: impl AsRef<Path>) -> io::Result<PathBuf> {
+        let dir = dir.as_ref();
+        let mut snapshot = self
+            .snapshot()
+            .map_err(|e| AppendError::Render(e).into_io())?;
+        fs::create_dir_all(dir)?;
+        snapshot.id = render::unique_id(&snapshot, &test_ids(dir)?);
+        let module = format!("test_{}", snapshot.id);
+        let path = dir.join(format!("{}.rs", module));
+        if let Some(ref sidecar_dir) = self.options.sidecar_dir {
+            sidecar::write(&path, sidecar_dir, &snapshot)?;
+        }
+
+        let mut text = String::new();
+        insert::push_imports(
+            &mut text,
+            &insert::module_imports(self.options.async_test.is_some()),
+            "",
+        );
+        text.push_str(&self.render_without_imports(&snapshot));
+        if let Some(ending) = self.options.line_ending {
+            text = ending.apply(&text).into_owned();
+        }
+        fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .and_then(|mut file| file.write_all(text.as_bytes()))?;
+
+        let declaration = format!("mod {};", module);
+        let line_ending = self.options.line_ending;
+        file::edit(
+            &dir.join(MOD_FILE),
+            self.options.sync_on_write,
+            |existing| {
+                if existing.lines().any(|line| line.trim() == declaration) {
+                    return existing.to_owned();
+                }
+                let mut edited = existing.to_owned();
+                if !edited.is_empty() && !edited.ends_with('\n') {
+                    edited.push('\n');
+                }
+                edited.push_str(&declaration);
+                edited.push('\n');
+                match line_ending.or_else(|| LineEnding::detect(existing)) {
+                    Some(ending) => ending.apply(&edited).into_owned(),
+                    None => edited,
+                }
+            },
+        )?;
+        Ok(path)
+    }
+}
+
+/// The ids of the tests that already have a file in `dir`.
+fn test_ids(dir: &Path) -> io::Result<HashSet<String>> {
+    let mut ids = HashSet::new();
+    for entry in fs::read_dir(dir)? {
+        let name = entry?.file_name();
+        let name = name.to_string_lossy();
+        if let Some(id) = name
+            .strip_prefix("test_")
+            .and_then(|name| name.strip_suffix(".rs"))
+        {
+            ids.insert(id.to_owned());
+        }
+    }
+    Ok(ids)
+}
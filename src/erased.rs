@@ -0,0 +1,71 @@
+use error::RenderError;
+use options::UnitTestOptions;
+use Executable;
+
+/// An object-safe view of an [Executable](trait.Executable.html), for storing executables of different types together, like `Vec<Box<dyn ErasedExecutable>>`.
+///
+/// `Executable` has associated types, so it can't be used as a trait object. This trait exchanges the arguments as JSON and returns generated tests as rendered source instead. It is implemented for every executable:
+///
+/// ```rust,ignore
+/// let mut registry: Vec<(Box<dyn ErasedExecutable>, String)> = vec![
+///     (Box::new(parser), String::from(r#""1 + ""#)),
+///     (Box::new(account), String::from("[10, 20]")),
+/// ];
+/// for (executable, arguments_json) in &mut registry {
+///     if let Some(test) = executable.execute_json(arguments_json, &UnitTestOptions::default())? {
+///         source.push_str(&test);
+///     }
+/// }
+/// ```
+///
+/// The methods have different names than the ones of `Executable`, so calls on concrete types are not ambiguous when both traits are in scope.
+pub trait ErasedExecutable {
+    /// The [full path](trait.Executable.html#tymethod.full_path) of the executable.
+    fn erased_full_path(&self) -> &'static str;
+
+    /// The [description](trait.Executable.html#tymethod.description) of the executable.
+    fn erased_description(&self) -> String;
+
+    /// The current state of the executable, serialized like in the generated test.
+    fn executable_json(&self) -> Result<String, RenderError>;
+
+    /// Deserialize the arguments from `arguments_json` and [execute](fn.execute.html) the executable with them.
+    ///
+    /// Returns `None` if the executable succeeded, and the rendered test, with the given options, if it failed. If the arguments can't be deserialized, [InvalidArguments](enum.RenderError.html#variant.InvalidArguments) is returned without executing anything.
+    fn execute_json(
+        &mut self,
+        arguments_json: &str,
+        options: &UnitTestOptions,
+    ) -> Result<Option<String>, RenderError>;
+}
+
+impl<E: Executable> ErasedExecutable for E {
+    fn erased_full_path(&self) -> &'static str {
+        self.full_path()
+    }
+
+    fn erased_description(&self) -> String {
+        self.description()
+    }
+
+    fn executable_json(&self) -> Result<String, RenderError> {
+        ::render::to_json(self).map_err(RenderError::Executable)
+    }
+
+    #[track_caller]
+    fn execute_json(
+        &mut self,
+        arguments_json: &str,
+        options: &UnitTestOptions,
+    ) -> Result<Option<String>, RenderError> {
+        let arguments: E::Arguments = ::serde_json::from_str(arguments_json)
+            .map_err(|e| RenderError::InvalidArguments(e.to_string()))?;
+        match ::execute(self, &arguments) {
+            Ok(_) => Ok(None),
+            Err(test) => test
+                .with_options(options.clone())
+                .try_to_test_source()
+                .map(Some),
+        }
+    }
+}
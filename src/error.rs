@@ -0,0 +1,222 @@
+use options::SerializationFormat;
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+/// The reason why a [UnitTest](struct.UnitTest.html) could not be rendered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RenderError {
+    /// The executable could not be serialized.
+    Executable(SerializeError),
+
+    /// The arguments could not be serialized.
+    Arguments(SerializeError),
+
+    /// The configured [id strategy](struct.UnitTestOptions.html#method.id_strategy) returned an id that can not be used in a function name.
+    InvalidId(String),
+
+    /// The configured [custom hasher](enum.Hasher.html#variant.Custom) returned a hash that can not be written to a marker comment.
+    InvalidHash(String),
+
+    /// The serialized executable could not be deserialized into an equal value, so the generated test could not reproduce the failure. See [check_round_trip](struct.UnitTest.html#method.check_round_trip).
+    ExecutableRoundTrip(String),
+
+    /// The serialized arguments could not be deserialized into an equal value, so the generated test could not reproduce the failure. See [check_round_trip](struct.UnitTest.html#method.check_round_trip).
+    ArgumentsRoundTrip(String),
+
+    /// The JSON passed to [execute_json](trait.ErasedExecutable.html#tymethod.execute_json) could not be deserialized into the arguments of the executable.
+    InvalidArguments(String),
+
+    /// The [construct_expr](trait.Executable.html#method.construct_expr) of the executable returned an empty expression.
+    EmptyConstructExpr,
+
+    /// [JSON post-processing](struct.UnitTestOptions.html#method.json_postprocess) or [key sorting](struct.UnitTestOptions.html#method.sort_keys) is configured, but the inputs are embedded in the given [format](struct.UnitTestOptions.html#method.serialization_format), which they don't apply to.
+    PostprocessedFormat(SerializationFormat),
+
+    /// The serialized executable and arguments are larger than the configured [maximum](struct.UnitTestOptions.html#method.max_snapshot_bytes).
+    SnapshotTooLarge {
+        /// The combined size of the serialized executable and arguments, in bytes
+        bytes: usize,
+
+        /// The configured maximum, in bytes
+        limit: usize,
+    },
+
+    /// An option of [UnitTestOptions](struct.UnitTestOptions.html) has a value that its setter rejects, e.g. because the field was written directly through [options_mut](struct.UnitTest.html#method.options_mut).
+    InvalidOption {
+        /// The name of the option, like `obj_binding`
+        option: &'static str,
+
+        /// The value of the option, as it is written in the options
+        value: String,
+    },
+}
+
+impl fmt::Display for RenderError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RenderError::Executable(e) => write!(fmt, "could not serialize the executable{}", e),
+            RenderError::Arguments(e) => write!(fmt, "could not serialize the arguments{}", e),
+            RenderError::InvalidId(id) => write!(
+                fmt,
+                "id_strategy returned {:?}, which is not valid in an identifier",
+                id
+            ),
+            RenderError::InvalidHash(hash) => write!(
+                fmt,
+                "the hasher returned {:?}, which is not a non-empty string of ASCII letters and digits",
+                hash
+            ),
+            RenderError::ExecutableRoundTrip(e) => {
+                write!(fmt, "the serialized executable does not round-trip: {}", e)
+            }
+            RenderError::ArgumentsRoundTrip(e) => {
+                write!(fmt, "the serialized arguments do not round-trip: {}", e)
+            }
+            RenderError::InvalidArguments(e) => {
+                write!(fmt, "could not deserialize the arguments: {}", e)
+            }
+            RenderError::EmptyConstructExpr => {
+                write!(fmt, "construct_expr returned an empty expression")
+            }
+            RenderError::PostprocessedFormat(format) => write!(
+                fmt,
+                "json_postprocess and sort_keys only apply to JSON, but the inputs are embedded as {:?}",
+                format
+            ),
+            RenderError::SnapshotTooLarge { bytes, limit } => write!(
+                fmt,
+                "the snapshot is {} bytes, which is more than the maximum of {} bytes; reduce the state with execute_minimized, or store it in sidecar files and raise max_snapshot_bytes",
+                bytes, limit
+            ),
+            RenderError::InvalidOption { option, value } => write!(
+                fmt,
+                "{:?} is not a valid value for {}, see UnitTestOptions::{}",
+                value, option, option
+            ),
+        }
+    }
+}
+
+impl Error for RenderError {}
+
+/// A serialization failure, with the location of the value that could not be serialized.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SerializeError {
+    /// The path to the value that failed to serialize, like `items[3].name`. This is `None` if the failure could not be attributed to a single value, and empty if the top-level value itself failed.
+    pub path: Option<String>,
+
+    /// The error reported by the serializer
+    pub message: String,
+}
+
+impl fmt::Display for SerializeError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self.path {
+            Some(ref path) if !path.is_empty() => write!(fmt, " at `{}`: {}", path, self.message),
+            _ => write!(fmt, ": {}", self.message),
+        }
+    }
+}
+
+/// The reason why [try_append_to_file](struct.UnitTest.html#method.try_append_to_file) failed.
+#[derive(Debug)]
+pub enum AppendError {
+    /// The test could not be rendered, so nothing was written.
+    Render(RenderError),
+
+    /// The file or its directory can not be written to, because of its permissions or because the file system is read-only.
+    ReadOnly {
+        /// The file that could not be written
+        path: PathBuf,
+
+        /// The error reported by the OS
+        source: io::Error,
+    },
+
+    /// The file is locked by another process.
+    Locked {
+        /// The file that could not be written
+        path: PathBuf,
+
+        /// The error reported by the OS
+        source: io::Error,
+    },
+
+    /// Any other IO error.
+    Io {
+        /// The file that could not be written
+        path: PathBuf,
+
+        /// The error reported by the OS
+        source: io::Error,
+    },
+}
+
+impl AppendError {
+    /// Sort an IO error that occurred while writing to `path` into the matching variant.
+    pub(crate) fn from_io(path: PathBuf, source: io::Error) -> Self {
+        match source.kind() {
+            io::ErrorKind::PermissionDenied | io::ErrorKind::ReadOnlyFilesystem => {
+                AppendError::ReadOnly { path, source }
+            }
+            io::ErrorKind::WouldBlock | io::ErrorKind::ResourceBusy => {
+                AppendError::Locked { path, source }
+            }
+            // ERROR_SHARING_VIOLATION and ERROR_LOCK_VIOLATION
+            _ if cfg!(windows)
+                && (source.raw_os_error() == Some(32) || source.raw_os_error() == Some(33)) =>
+            {
+                AppendError::Locked { path, source }
+            }
+            _ => AppendError::Io { path, source },
+        }
+    }
+
+    /// Whether writing to a [fallback directory](struct.UnitTestOptions.html#method.fallback_dir) may succeed where this write failed.
+    pub(crate) fn can_fall_back(&self) -> bool {
+        match self {
+            AppendError::ReadOnly { .. } | AppendError::Locked { .. } => true,
+            AppendError::Render(_) | AppendError::Io { .. } => false,
+        }
+    }
+
+    /// Convert this into the IO error of [append_to_file](struct.UnitTest.html#method.append_to_file). Render errors become an error of kind `InvalidData`.
+    pub(crate) fn into_io(self) -> io::Error {
+        match self {
+            AppendError::Render(e) => io::Error::new(io::ErrorKind::InvalidData, e),
+            AppendError::ReadOnly { source, .. }
+            | AppendError::Locked { source, .. }
+            | AppendError::Io { source, .. } => source,
+        }
+    }
+}
+
+impl fmt::Display for AppendError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AppendError::Render(e) => write!(fmt, "could not render the test: {}", e),
+            AppendError::ReadOnly { path, source } => {
+                write!(fmt, "{} is read-only: {}", path.display(), source)
+            }
+            AppendError::Locked { path, source } => {
+                write!(fmt, "{} is locked: {}", path.display(), source)
+            }
+            AppendError::Io { path, source } => {
+                write!(fmt, "could not write to {}: {}", path.display(), source)
+            }
+        }
+    }
+}
+
+impl Error for AppendError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            AppendError::Render(e) => Some(e),
+            AppendError::ReadOnly { source, .. }
+            | AppendError::Locked { source, .. }
+            | AppendError::Io { source, .. } => Some(source),
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use std::fmt::Display;
+use {Executable, UnitTest};
+
+/// How the error is described in the comment of the generated test. See [with_error_render](struct.UnitTest.html#method.with_error_render).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ErrorRender {
+    /// The `Debug` output of the error. This is the default, because every error implements `Debug`.
+    #[default]
+    Debug,
+    /// The `Display` output of the error.
+    Display,
+    /// The `Display` output, followed by a line with the `Debug` output.
+    Both,
+}
+
+impl<'a, E: Executable + 'a> UnitTest<'a, E>
+where
+    E::Error: Display,
+{
+    /// Describe the error in the comment of the generated test by its `Display` output, or by both its `Display` and its `Debug` output, which is easier to read for rich error types:
+    ///
+    /// ```text
+    /// /// exception was connection to db-1 timed out after 30s
+    /// /// exception debug: Timeout { host: "db-1", seconds: 30 }
+    /// ```
+    ///
+    /// The `Display` output is captured when this is called. Every line of it is written as a comment line of its own, like a multi-line `Debug` output. An [error message](#structfield.error_message) takes precedence. Only the comment changes, the generated check is the same.
+    pub fn with_error_render(mut self, render: ErrorRender) -> Self {
+        self.error_render = render;
+        self.error_display = match render {
+            ErrorRender::Debug => None,
+            ErrorRender::Display | ErrorRender::Both => Some(self.error.to_string()),
+        };
+        self
+    }
+
+    /// Print the error with its `Display` output instead of its `Debug` output when the generated test fails, which is easier to read for error types with a hand-written `Display`.
+    ///
+    /// The verbose check prints the error with `println!("{}", e)`, and the short check of [verbose_body(false)](struct.UnitTestOptions.html#method.verbose_body) becomes `obj.execute(&args).unwrap_or_else(|e| panic!("regression still fails: {}", e));`. The same applies to [examples](#method.render_as_example). The comment of the test describes the error by its `Display` output as well, like with [ErrorRender::Display](enum.ErrorRender.html#variant.Display), unless another [error render](#method.with_error_render) was chosen already. A custom [assertion](trait.Executable.html#method.assertion) is not affected.
+    ///
+    /// The generated test only compiles if the error implements `Display`, so this is only available for such errors, and calling it for an error that only implements `Debug` is a compile error instead of a broken test:
+    ///
+    /// ```compile_fail
+    /// # extern crate exceptional;
+    /// fn render<E: exceptional::Executable>(test: exceptional::UnitTest<E>) -> String {
+    ///     test.prefer_display().to_string()
+    /// }
+    /// # fn main() {}
+    /// ```
+    pub fn prefer_display(mut self) -> Self {
+        if self.error_render == ErrorRender::Debug {
+            self = self.with_error_render(ErrorRender::Display);
+        }
+        self.prefer_display = true;
+        self
+    }
+}
@@ -0,0 +1,110 @@
+use error::RenderError;
+use render::Snapshot;
+use std::fmt::{self, Write};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use {sidecar, Executable, UnitTest};
+
+/// Crates that are not declared with `extern crate` in generated examples, because they are always declared or are declared separately.
+const KNOWN_CRATES: &[&str] = &["alloc", "core", "exceptional", "serde_json", "std"];
+
+impl<'a, E: Executable + 'a> UnitTest<'a, E> {
+    /// Render this failure as a standalone program with a `fn main()`, instead of a `#[test]`, to step through it in a debugger.
+    ///
+    /// The program deserializes the executable and the arguments like the generated test, executes them, and prints the error instead of panicking. It exits with status 1 while the executable still fails. The crates of the [full path](trait.Executable.html#tymethod.full_path) are declared with `extern crate`, so the output can be used as an example of the crate that defines the executable. The executable is always executed synchronously, even if [async_test](struct.UnitTestOptions.html#method.async_test) is set. See [write_example](#method.write_example).
+    pub fn render_as_example(&self) -> Result<String, RenderError> {
+        let snapshot = self.snapshot()?;
+        let mut fmt = String::new();
+        self.write_example_source(&mut fmt, &snapshot)
+            .expect("Writing to a String cannot fail");
+        Ok(fmt)
+    }
+
+    /// Write this failure as an example program to `<crate_dir>/examples/repro_<id>.rs`, and return its path. The example can then be run with `cargo run --example repro_<id>`.
+    ///
+    /// An existing file with the same name is overwritten. Sidecar files are written relative to the example, like in [append_to_file](#method.append_to_file). The rendered example is described in [render_as_example](#method.render_as_example).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the executable or the arguments can't be serialized.
+    pub fn write_example(self, crate_dir: impl AsRef<Path>) -> io::Result<PathBuf> {
+        let snapshot = self.snapshot().unwrap_or_else(|e| panic!("{}", e));
+        let dir = crate_dir.as_ref().join("examples");
+        fs::create_dir_all(&dir)?;
+        let file = dir.join(format!("repro_{}.rs", snapshot.id));
+        if let Some(ref sidecar_dir) = self.options.sidecar_dir {
+            sidecar::write(&file, sidecar_dir, &snapshot)?;
+        }
+        let mut text = String::new();
+        self.write_example_source(&mut text, &snapshot)
+            .expect("Writing to a String cannot fail");
+        fs::write(&file, text)?;
+        Ok(file)
+    }
+
+    fn write_example_source<W: Write>(&self, fmt: &mut W, snapshot: &Snapshot) -> fmt::Result {
+        let obj = &self.options.obj_binding;
+        writeln!(fmt, "extern crate exceptional;")?;
+        writeln!(fmt, "extern crate serde_json;")?;
+        for name in crate_names(&self.type_path(self.executable.full_path())) {
+            writeln!(fmt, "extern crate {};", name)?;
+        }
+        writeln!(fmt)?;
+        self.write_marker(fmt, snapshot)?;
+        self.write_inputs(fmt, snapshot)?;
+        self.write_header(fmt, "reproduction")?;
+        writeln!(fmt, "fn main() {{")?;
+        writeln!(fmt, "\tuse exceptional::Executable;")?;
+        self.write_snapshot(fmt, snapshot)?;
+        writeln!(fmt)?;
+        let seed = self.executable.rng_seed().map(|seed| seed.to_string());
+        self.write_setup(fmt, seed.as_deref())?;
+        writeln!(fmt, "\tmatch {} {{", self.call(false))?;
+        writeln!(
+            fmt,
+            "\t\tOk(_) => println!(\"{{}} no longer fails\", {}.description()),",
+            obj
+        )?;
+        writeln!(fmt, "\t\tErr(e) => {{")?;
+        writeln!(
+            fmt,
+            "\t\t\tprintln!(\"Could not execute {{}}\", {}.description());",
+            obj
+        )?;
+        writeln!(fmt, "\t\t\tprintln!(\"{}\", e);", self.error_format())?;
+        writeln!(
+            fmt,
+            "\t\t\t{}(1);",
+            self.options.extern_path("::std::process::exit")
+        )?;
+        writeln!(fmt, "\t\t}}")?;
+        writeln!(fmt, "\t}}")?;
+        writeln!(fmt, "}}")
+    }
+}
+
+/// The crates that the absolute paths in `full_path` start with, like `my_crate` for `::my_crate::Action<::other::Config>`, in order of appearance.
+fn crate_names(full_path: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+    let bytes = full_path.as_bytes();
+    let mut i = 0;
+    while let Some(n) = full_path[i..].find("::") {
+        let start = i + n;
+        i = start + 2;
+        let absolute =
+            start == 0 || matches!(bytes[start - 1], b'<' | b',' | b' ' | b'(' | b'[' | b'&');
+        if !absolute {
+            continue;
+        }
+        let name_len = full_path[i..]
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(full_path.len() - i);
+        let name = &full_path[i..i + name_len];
+        let is_module = full_path[i + name_len..].starts_with("::");
+        if is_module && !KNOWN_CRATES.contains(&name) && !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
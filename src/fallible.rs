@@ -0,0 +1,97 @@
+use std::error::Error;
+use std::fmt;
+use {Executable, MaybeOwned, UnitTest};
+
+/// An [Executable](trait.Executable.html) that can tell when it can not be snapshotted faithfully.
+///
+/// [execute](fn.execute.html) snapshots the executable with `Clone`. For types that share mutable state (e.g. an `Rc<RefCell<T>>`), the clone shares that state with the original, so the snapshot changes while the executable runs and the generated test is misleading. Implement `try_snapshot` to detect this, and call [try_execute](fn.try_execute.html) instead of `execute`.
+///
+/// The default implementation is a plain `clone`, so `impl TryExecutable for MyType {}` behaves like `execute`.
+pub trait TryExecutable: Executable {
+    /// Take a snapshot of the state from before the execution, or explain why that is not possible.
+    fn try_snapshot(&self) -> Result<Self, SnapshotError> {
+        Ok(self.clone())
+    }
+}
+
+/// The reason why an executable could not be snapshotted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotError {
+    message: String,
+}
+
+impl SnapshotError {
+    /// Create a new error with the given explanation.
+    pub fn new(message: impl Into<String>) -> Self {
+        SnapshotError {
+            message: message.into(),
+        }
+    }
+
+    /// The explanation of why the snapshot was not possible.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "could not snapshot executable: {}", self.message)
+    }
+}
+
+impl Error for SnapshotError {}
+
+/// The error returned by [try_execute](fn.try_execute.html).
+// The captured test is returned by value, like the error of `execute`.
+#[allow(clippy::large_enum_variant)]
+pub enum TryExecuteError<'a, E: Executable + 'a> {
+    /// The executable failed and a unit test was generated.
+    Captured(UnitTest<'a, E>),
+
+    /// The executable failed, but no unit test was generated because the executable could not be snapshotted.
+    NotCaptured {
+        /// The error that was thrown when the Executable failed
+        error: E::Error,
+        /// Why the executable could not be snapshotted
+        reason: SnapshotError,
+    },
+}
+
+impl<'a, E: Executable + 'a> TryExecuteError<'a, E> {
+    /// The error that was thrown when the Executable failed.
+    pub fn error(&self) -> &E::Error {
+        match self {
+            TryExecuteError::Captured(test) => &test.error,
+            TryExecuteError::NotCaptured { error, .. } => error,
+        }
+    }
+
+    /// The generated unit test, if the executable could be snapshotted.
+    pub fn unit_test(self) -> Option<UnitTest<'a, E>> {
+        match self {
+            TryExecuteError::Captured(test) => Some(test),
+            TryExecuteError::NotCaptured { .. } => None,
+        }
+    }
+}
+
+/// Like [execute](fn.execute.html), but uses [try_snapshot](trait.TryExecutable.html#method.try_snapshot) instead of `Clone` to store the state from before the failure.
+///
+/// The executable is always executed. If it fails and the snapshot was not possible, the error is returned as [NotCaptured](enum.TryExecuteError.html#variant.NotCaptured) instead of generating a misleading test.
+#[track_caller]
+pub fn try_execute<'a, E: TryExecutable + 'a>(
+    executable: &'a mut E,
+    arguments: &'a E::Arguments,
+) -> Result<E::Result, TryExecuteError<'a, E>> {
+    let old = executable.try_snapshot();
+    match (executable.execute(arguments), old) {
+        (Ok(value), _) => Ok(value),
+        (Err(error), Ok(old)) => Err(TryExecuteError::Captured(UnitTest::new(
+            old,
+            MaybeOwned::Borrowed(arguments),
+            error,
+        ))),
+        (Err(error), Err(reason)) => Err(TryExecuteError::NotCaptured { error, reason }),
+    }
+}
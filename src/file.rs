@@ -0,0 +1,112 @@
+use options::LineEnding;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::ops::{Deref, DerefMut};
+use std::path::Path;
+
+/// Append `bytes` to the end of `path`, creating the file if it does not exist.
+///
+/// With the `locking` feature enabled, an exclusive advisory lock is held on the file while writing. See [append_to_file](struct.UnitTest.html#method.append_to_file) for the details. If `sync` is set, the file is synced to disk before the lock is released.
+pub(crate) fn append(path: &Path, bytes: &[u8], sync: bool) -> io::Result<()> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    let mut file = LockedFile::acquire(file)?;
+    file.write_all(bytes)?;
+    if sync {
+        file.sync_all()?;
+    }
+    Ok(())
+}
+
+/// Replace the contents of `path` with the result of `f`, creating the file if it does not exist.
+///
+/// `f` receives the current contents, which are empty for a new file. If the new contents start with the old contents, only the difference is written. The file is locked and synced like in [append](fn.append.html).
+pub(crate) fn edit<F>(path: &Path, sync: bool, f: F) -> io::Result<()>
+where
+    F: FnOnce(&str) -> String,
+{
+    try_edit(path, sync, |existing| Ok(f(existing)))
+}
+
+/// Like [edit](fn.edit.html), but `f` can fail, in which case the file is left unchanged and its error is returned.
+///
+/// The lock is held while `f` runs, so decisions that `f` makes from the current contents, like which ids are taken, can't be invalidated by another process before the new contents are written.
+pub(crate) fn try_edit<F>(path: &Path, sync: bool, f: F) -> io::Result<()>
+where
+    F: FnOnce(&str) -> io::Result<String>,
+{
+    let file = OpenOptions::new()
+        .create(true)
+        .read(true)
+        .write(true)
+        .truncate(false)
+        .open(path)?;
+    let mut file = LockedFile::acquire(file)?;
+    let mut existing = String::new();
+    file.read_to_string(&mut existing)?;
+
+    let new = f(&existing)?;
+    if new.starts_with(&existing) {
+        file.seek(SeekFrom::End(0))?;
+        file.write_all(&new.as_bytes()[existing.len()..])?;
+    } else {
+        file.seek(SeekFrom::Start(0))?;
+        file.set_len(0)?;
+        file.write_all(new.as_bytes())?;
+    }
+    if sync {
+        file.sync_all()?;
+    }
+    Ok(())
+}
+
+/// The line ending of the start of `path`, or `None` if the file doesn't exist or has no line breaks there. Only the first few KiB are read, so this stays cheap for large files.
+pub(crate) fn line_ending(path: &Path) -> io::Result<Option<LineEnding>> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    let mut start = Vec::new();
+    file.take(8 * 1024).read_to_end(&mut start)?;
+    Ok(LineEnding::detect(&String::from_utf8_lossy(&start)))
+}
+
+/// A file that is exclusively locked until it is dropped.
+///
+/// Without the `locking` feature, this is a plain wrapper around `File`.
+pub(crate) struct LockedFile(File);
+
+impl LockedFile {
+    #[cfg(feature = "locking")]
+    pub(crate) fn acquire(file: File) -> io::Result<Self> {
+        ::fs2::FileExt::lock_exclusive(&file)?;
+        Ok(LockedFile(file))
+    }
+
+    #[cfg(not(feature = "locking"))]
+    pub(crate) fn acquire(file: File) -> io::Result<Self> {
+        Ok(LockedFile(file))
+    }
+}
+
+impl Deref for LockedFile {
+    type Target = File;
+
+    fn deref(&self) -> &File {
+        &self.0
+    }
+}
+
+impl DerefMut for LockedFile {
+    fn deref_mut(&mut self) -> &mut File {
+        &mut self.0
+    }
+}
+
+#[cfg(feature = "locking")]
+impl Drop for LockedFile {
+    fn drop(&mut self) {
+        // Closing the file releases the lock as well, so an error here is not fatal.
+        let _ = ::fs2::FileExt::unlock(&self.0);
+    }
+}
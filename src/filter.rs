@@ -0,0 +1,201 @@
+use options::UnitTestOptions;
+use {Executable, MaybeOwned, UnitTest};
+
+/// Whether a failure generates a unit test, as decided by [Executable::capture_decision](trait.Executable.html#method.capture_decision), with an optional reason.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CaptureDecision {
+    /// Generate a unit test. The reason is written to its header as a `/// captured because: <reason>` line.
+    Capture {
+        /// Why the failure is captured
+        reason: Option<String>,
+    },
+    /// Don't generate a unit test. The reason is passed to the [on_skip](struct.ExecuteOptions.html#method.on_skip) hook.
+    Skip {
+        /// Why the failure is skipped
+        reason: Option<String>,
+    },
+}
+
+impl CaptureDecision {
+    /// Returns `true` if the failure generates a unit test.
+    pub fn captures(&self) -> bool {
+        match self {
+            CaptureDecision::Capture { .. } => true,
+            CaptureDecision::Skip { .. } => false,
+        }
+    }
+
+    /// The reason of the decision, if there is one.
+    pub fn reason(&self) -> Option<&str> {
+        match self {
+            CaptureDecision::Capture { reason } | CaptureDecision::Skip { reason } => {
+                reason.as_deref()
+            }
+        }
+    }
+}
+
+/// A hook that is called with every error that [execute_with_options](fn.execute_with_options.html) ignores, and the reason, see [ExecuteOptions::on_skip](struct.ExecuteOptions.html#method.on_skip).
+pub type SkipHook<E> = Box<dyn Fn(&<E as Executable>::Error, Option<&str>)>;
+
+/// A predicate that decides whether an error is ignored, see [ExecuteOptions::ignore](struct.ExecuteOptions.html#method.ignore).
+pub type ErrorPredicate<E> = Box<dyn Fn(&<E as Executable>::Error) -> bool>;
+
+/// Per-call options for [execute_with_options](fn.execute_with_options.html).
+pub struct ExecuteOptions<E: Executable> {
+    /// Errors for which any of these predicates returns `true` don't generate a unit test.
+    pub ignore_errors: Vec<ErrorPredicate<E>>,
+
+    /// See [on_skip](#method.on_skip).
+    pub on_skip: Option<SkipHook<E>>,
+
+    /// See [with_state_diff](#method.with_state_diff).
+    pub state_diff: bool,
+
+    /// See [with_test_options](#method.with_test_options).
+    pub test_options: UnitTestOptions,
+}
+
+impl<E: Executable> Default for ExecuteOptions<E> {
+    fn default() -> Self {
+        ExecuteOptions {
+            ignore_errors: Vec::new(),
+            on_skip: None,
+            state_diff: false,
+            test_options: UnitTestOptions::default(),
+        }
+    }
+}
+
+impl<E: Executable> ExecuteOptions<E> {
+    /// Don't generate a unit test for errors that match `predicate`, e.g. expected validation errors.
+    ///
+    /// ```rust,ignore
+    /// let options = ExecuteOptions::default()
+    ///     .ignore(|e: &MyError| *e == MyError::NotFound);
+    /// ```
+    pub fn ignore<F>(mut self, predicate: F) -> Self
+    where
+        F: Fn(&E::Error) -> bool + 'static,
+    {
+        self.ignore_errors.push(Box::new(predicate));
+        self
+    }
+
+    /// Call `hook` with every error that is ignored, and the reason of the [capture decision](trait.Executable.html#method.capture_decision) of the executable, e.g. to log skipped failures. Errors that are ignored by one of the [ignore_errors](#structfield.ignore_errors) predicates have no reason.
+    ///
+    /// ```rust,ignore
+    /// let options = ExecuteOptions::default().on_skip(|e: &MyError, reason| {
+    ///     log::debug!("not capturing {:?}: {}", e, reason.unwrap_or("ignored"));
+    /// });
+    /// ```
+    pub fn on_skip<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&E::Error, Option<&str>) + 'static,
+    {
+        self.on_skip = Some(Box::new(hook));
+        self
+    }
+
+    /// Serialize the executable a second time after it failed, and list the values that changed during the failing run in the header of the generated test:
+    ///
+    /// ```text
+    /// /// changed during the run: balance: 10 -> -5
+    /// /// changed during the run: history[2]: (missing) -> "withdraw"
+    /// ```
+    ///
+    /// The test is still generated from the state from before the run, but the changes show what the failing run mutated, including state that is shared with the snapshot through interior mutability, like an `Rc<RefCell<T>>`, which then doesn't show up as a change. The header says so if nothing changed. The state after the run is stored in [post_state](struct.UnitTest.html#structfield.post_state). This is disabled by default, because it serializes every failing executable twice.
+    pub fn with_state_diff(mut self, enabled: bool) -> Self {
+        self.state_diff = enabled;
+        self
+    }
+
+    /// Render the unit tests that are captured with these options, instead of the default options, so the call sites that share these options don't have to configure every test with [with_options](struct.UnitTest.html#method.with_options):
+    ///
+    /// ```rust,ignore
+    /// let options = ExecuteOptions::default().with_test_options(
+    ///     UnitTestOptions::default().verbose_body(false).sort_keys(true),
+    /// );
+    /// if let Err(ExecuteError::Captured(test)) = exceptional::execute_with_options(&mut action, &args, &options) {
+    ///     test.append_to_file("src/regressions.rs")?;
+    /// }
+    /// ```
+    pub fn with_test_options(mut self, options: UnitTestOptions) -> Self {
+        self.test_options = options;
+        self
+    }
+
+    /// The decision whether `error` generates a unit test.
+    fn decision(&self, executable: &E, error: &E::Error) -> CaptureDecision {
+        match executable.capture_decision(error) {
+            CaptureDecision::Capture { .. }
+                if self.ignore_errors.iter().any(|ignore| ignore(error)) =>
+            {
+                CaptureDecision::Skip { reason: None }
+            }
+            decision => decision,
+        }
+    }
+}
+
+/// The error returned by [execute_with_options](fn.execute_with_options.html).
+// The captured test is returned by value, like the error of `execute`.
+#[allow(clippy::large_enum_variant)]
+pub enum ExecuteError<'a, E: Executable + 'a> {
+    /// The executable failed and a unit test was generated.
+    Captured(UnitTest<'a, E>),
+
+    /// The executable failed with an error that is ignored, so no unit test was generated.
+    Ignored(E::Error),
+}
+
+impl<'a, E: Executable + 'a> ExecuteError<'a, E> {
+    /// The error that was thrown when the Executable failed.
+    pub fn error(&self) -> &E::Error {
+        match self {
+            ExecuteError::Captured(test) => &test.error,
+            ExecuteError::Ignored(error) => error,
+        }
+    }
+
+    /// The generated unit test, if the error was not ignored.
+    pub fn unit_test(self) -> Option<UnitTest<'a, E>> {
+        match self {
+            ExecuteError::Captured(test) => Some(test),
+            ExecuteError::Ignored(_) => None,
+        }
+    }
+}
+
+/// Like [execute](fn.execute.html), but only generates a unit test for errors that are not ignored.
+///
+/// An error is ignored if the executable's [capture_decision](trait.Executable.html#method.capture_decision) skips it, or if any of the [ignore_errors](struct.ExecuteOptions.html#structfield.ignore_errors) predicates of `options` returns `true`. The executable is asked first, so it applies to every call site, and the predicates only narrow it down further for this call: neither can force a test that the other rejects. Ignored errors are passed to the [on_skip](struct.ExecuteOptions.html#method.on_skip) hook and returned as [Ignored](enum.ExecuteError.html#variant.Ignored). The reason of a capture is stored in the [test](struct.UnitTest.html#structfield.capture_reason).
+#[track_caller]
+pub fn execute_with_options<'a, E: Executable + 'a>(
+    executable: &'a mut E,
+    arguments: &'a E::Arguments,
+    options: &ExecuteOptions<E>,
+) -> Result<E::Result, ExecuteError<'a, E>> {
+    let old = executable.clone();
+    let error = match executable.execute(arguments) {
+        Ok(value) => return Ok(value),
+        Err(error) => error,
+    };
+    match options.decision(&old, &error) {
+        CaptureDecision::Capture { reason } => {
+            let mut test = UnitTest::new(old, MaybeOwned::Borrowed(arguments), error);
+            test.capture_reason = reason;
+            test.options = options.test_options.clone();
+            if options.state_diff {
+                test.post_state = serde_json::to_string(&*executable).ok();
+            }
+            Err(ExecuteError::Captured(test))
+        }
+        CaptureDecision::Skip { reason } => {
+            if let Some(ref hook) = options.on_skip {
+                hook(&error, reason.as_deref());
+            }
+            Err(ExecuteError::Ignored(error))
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use error::RenderError;
+use renderer::Renderer;
+use std::fmt;
+use {Executable, UnitTest};
+
+/// The structured form of a generated `#[test]`, which can be changed before it is rendered, e.g. to reorder or inject statements without writing a [Renderer](trait.Renderer.html) from scratch.
+///
+/// This is the intermediate representation that the default renderer assembles the test from: [generated_test](struct.UnitTest.html#method.generated_test) builds it from the same stages as [try_to_test_source](struct.UnitTest.html#method.try_to_test_source), so rendering an unchanged `GeneratedTest` gives the same source. The sections are written in the order of the fields, so the rendered test looks like:
+///
+/// ```text
+/// <marker>
+/// <header_lines>
+/// <attributes>
+/// <signature> {
+///     <imports>
+///     <setup>
+///     <body>
+///     <teardown>
+/// }
+/// ```
+///
+/// Every line is stored without its line break, but with its indentation, like `"\tuse exceptional::Executable;"`. The fields are stable: new sections are only added in a new major version. Only the regular `#[test]` has this form, so groups, benchmarks and [insta](struct.InstaRenderer.html) tests can't be transformed this way. Use a [TransformRenderer](struct.TransformRenderer.html) to write a transformed test to a file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GeneratedTest {
+    /// The id of the test, which is the suffix of its function name. Changing it does not rename the function, which is part of the [signature](#structfield.signature).
+    pub id: String,
+
+    /// The [marker](struct.Marker.html) comment. It has to stay the line that starts the test, because upserts, deduplication and the maintenance functions find generated tests by it.
+    pub marker: String,
+
+    /// The comment lines that describe the failure, including empty lines. They are preceded by the consts of the [hoisted inputs](struct.UnitTestOptions.html#method.hoist_inputs), if there are any.
+    pub header_lines: Vec<String>,
+
+    /// The attributes of the function, ending with the attribute of the test harness, like `#[test]`
+    pub attributes: Vec<String>,
+
+    /// The signature of the function, like `pub fn test_1539761233543()`, without the opening brace
+    pub signature: String,
+
+    /// The `use` statements at the start of the function
+    pub imports: Vec<String>,
+
+    /// The statements that deserialize the executable and the arguments, after the [prelude](trait.Executable.html#method.test_prelude) of the executable
+    pub setup: Vec<String>,
+
+    /// The statements that run the executable and check the outcome
+    pub body: Vec<String>,
+
+    /// The statements after the body, which are the [epilogue](trait.Executable.html#method.test_epilogue) of the executable
+    pub teardown: Vec<String>,
+}
+
+impl GeneratedTest {
+    /// Assemble the source of the test.
+    pub fn render(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl fmt::Display for GeneratedTest {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(fmt, "{}", self.marker)?;
+        for line in self.header_lines.iter().chain(&self.attributes) {
+            writeln!(fmt, "{}", line)?;
+        }
+        writeln!(fmt, "{} {{", self.signature)?;
+        for line in self
+            .imports
+            .iter()
+            .chain(&self.setup)
+            .chain(&self.body)
+            .chain(&self.teardown)
+        {
+            writeln!(fmt, "{}", line)?;
+        }
+        writeln!(fmt, "}}")
+    }
+}
+
+impl<'a, E: Executable + 'a> UnitTest<'a, E> {
+    /// The structured form of the `#[test]` that [try_to_test_source](#method.try_to_test_source) renders, to change it before it is rendered. Errors are returned like in `try_to_test_source`.
+    ///
+    /// ```rust,ignore
+    /// let mut generated = test.generated_test()?;
+    /// generated.setup.insert(0, String::from("\t::my_crate::init_logging();"));
+    /// std::fs::write("src/regressions.rs", generated.render())?;
+    /// ```
+    pub fn generated_test(&self) -> Result<GeneratedTest, RenderError> {
+        let snapshot = self.snapshot()?;
+        let section = |write: &dyn Fn(&mut String) -> fmt::Result| {
+            let mut text = String::new();
+            write(&mut text).expect("Writing to a String cannot fail");
+            text
+        };
+        let lines = |text: String| text.lines().map(str::to_owned).collect::<Vec<_>>();
+        Ok(GeneratedTest {
+            id: snapshot.id.clone(),
+            marker: section(&|fmt| self.write_marker(fmt, &snapshot))
+                .trim_end_matches('\n')
+                .to_owned(),
+            header_lines: lines(section(&|fmt| {
+                self.write_inputs(fmt, &snapshot)?;
+                self.write_test_header(fmt, &snapshot)
+            })),
+            attributes: lines(section(&|fmt| self.write_test_attributes(fmt))),
+            signature: section(&|fmt| self.write_signature(fmt, &snapshot)),
+            imports: lines(section(&|fmt| self.write_imports(fmt))),
+            setup: lines(section(&|fmt| self.write_test_setup(fmt, &snapshot))),
+            body: lines(section(&|fmt| self.write_test_body(fmt))),
+            teardown: lines(section(&|fmt| self.write_test_teardown(fmt))),
+        })
+    }
+}
+
+/// A renderer that generates the usual `#[test]`, but lets a function change its [structured form](struct.GeneratedTest.html) first.
+///
+/// ```rust,ignore
+/// let renderer = exceptional::TransformRenderer::new(|generated: &mut GeneratedTest| {
+///     generated.attributes.insert(0, String::from("#[ignore]"));
+/// });
+/// test.append_to_file_with("src/regressions.rs", &renderer)?;
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct TransformRenderer<F> {
+    transform: F,
+}
+
+impl<F: Fn(&mut GeneratedTest)> TransformRenderer<F> {
+    /// A renderer that calls `transform` on every test before it is rendered.
+    pub fn new(transform: F) -> Self {
+        TransformRenderer { transform }
+    }
+}
+
+impl<F: Fn(&mut GeneratedTest)> Renderer for TransformRenderer<F> {
+    fn render<'a, E: Executable + 'a>(
+        &self,
+        test: &UnitTest<'a, E>,
+    ) -> Result<String, RenderError> {
+        let mut generated = test.generated_test()?;
+        (self.transform)(&mut generated);
+        Ok(generated.render())
+    }
+}
@@ -0,0 +1,111 @@
+use options::UnitTestOptions;
+use std::io;
+use std::path::Path;
+use writer::{DedupKey, StreamingWriter};
+use {execute, Executable, UnitTest};
+
+/// A central place to execute and capture executables of any type, with one configuration and one deduplication cache for all of them.
+///
+/// Large projects have many executable types, and threading the same options, output file and deduplication state through every call site is tedious. A generator is configured once, and [run](#method.run) accepts any [Executable](trait.Executable.html):
+///
+/// ```rust,ignore
+/// let mut generator = exceptional::Generator::open(
+///     "src/regressions.rs",
+///     UnitTestOptions::default().namespace_id(true),
+/// )?;
+/// generator.run(&mut parser, &input)?;
+/// generator.run(&mut account, &(10, 20))?;
+/// println!("{} of {} runs failed", generator.failures(), generator.runs());
+/// ```
+///
+/// Tests are written by a [StreamingWriter](struct.StreamingWriter.html), so the file is locked while the generator exists, every test is written as soon as it is captured, and failures that are already in the file are skipped, across all executable types. Like for the writer, options that place a test in the file are not applied: the banner, the insertion marker, the target module, upserts and the index. A [sidecar directory](struct.UnitTestOptions.html#method.sidecar_dir) is relative to the file, like for [append_to_file](struct.UnitTest.html#method.append_to_file), and the sidecar files are written before the test.
+pub struct Generator {
+    writer: StreamingWriter,
+    options: UnitTestOptions,
+    dedup: DedupKey,
+    runs: usize,
+    failures: usize,
+    written: usize,
+}
+
+impl Generator {
+    /// Open (or create) the file that the tests are written to, and use `options` for every test.
+    pub fn open(file: impl AsRef<Path>, options: UnitTestOptions) -> io::Result<Self> {
+        Ok(Generator {
+            writer: StreamingWriter::open(file)?,
+            options,
+            dedup: DedupKey::default(),
+            runs: 0,
+            failures: 0,
+            written: 0,
+        })
+    }
+
+    /// Decide which tests are the same by `key` instead of by their executable and arguments. See [DedupKey](enum.DedupKey.html).
+    pub fn dedup_by(mut self, key: DedupKey) -> Self {
+        self.dedup = key;
+        self
+    }
+
+    /// [Execute](fn.execute.html) `executable` with `arguments`, and write a test if it fails.
+    ///
+    /// The result of the executable is returned as is, so the caller can handle the error as usual. The outer error is a failure to write the test, or a [RenderError](enum.RenderError.html) as an error of kind `InvalidData`.
+    #[track_caller]
+    pub fn run<E: Executable>(
+        &mut self,
+        executable: &mut E,
+        arguments: &E::Arguments,
+    ) -> io::Result<Result<E::Result, E::Error>> {
+        self.runs += 1;
+        match execute(executable, arguments) {
+            Ok(value) => Ok(Ok(value)),
+            Err(test) => {
+                let test = test.with_options(self.options.clone());
+                self.record(&test)?;
+                Ok(Err(test.into_error()))
+            }
+        }
+    }
+
+    /// Write a test that was captured elsewhere, e.g. with [execute_all](fn.execute_all.html), with the options of this generator instead of its own. Returns `false` if the failure is already in the file.
+    pub fn capture<E: Executable>(&mut self, test: UnitTest<E>) -> io::Result<bool> {
+        self.record(&test.with_options(self.options.clone()))
+    }
+
+    /// Returns `true` if [capturing](#method.capture) `test` would skip it because the failure is already in the file, according to the options and the [DedupKey](enum.DedupKey.html) of this generator. Like [UnitTest::is_captured](struct.UnitTest.html#method.is_captured), this only hashes the test, without rendering it.
+    pub fn is_captured<E: Executable>(&self, test: &UnitTest<E>) -> bool {
+        match test.marker_hashes(&self.options) {
+            Ok((hash, error)) => self.writer.index().contains(self.dedup, &hash, &error),
+            Err(_) => false,
+        }
+    }
+
+    fn record<E: Executable>(&mut self, test: &UnitTest<E>) -> io::Result<bool> {
+        self.failures += 1;
+        let written = self.writer.record_ref(test, self.dedup)?;
+        if written {
+            self.written += 1;
+        }
+        Ok(written)
+    }
+
+    /// The number of executables that were [run](#method.run).
+    pub fn runs(&self) -> usize {
+        self.runs
+    }
+
+    /// The number of failures that were run or captured, including the ones that were already in the file.
+    pub fn failures(&self) -> usize {
+        self.failures
+    }
+
+    /// The number of tests that were written to the file.
+    pub fn written(&self) -> usize {
+        self.written
+    }
+
+    /// The options that are used for every test.
+    pub fn options(&self) -> &UnitTestOptions {
+        &self.options
+    }
+}
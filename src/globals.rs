@@ -0,0 +1,84 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+
+/// Process-global state that an executable depends on, captured when it failed. See [capture_globals](struct.UnitTest.html#method.capture_globals).
+///
+/// Only the current directory and the listed environment variables are handled. Other process-global state, like the locale, the umask or signal handlers, is not captured.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Globals {
+    /// The current directory, if it could be determined
+    pub current_dir: Option<PathBuf>,
+
+    /// The captured environment variables, with `None` for variables that were not set or not valid unicode
+    pub env: BTreeMap<String, Option<String>>,
+}
+
+impl Globals {
+    /// Capture the current directory and the given environment variables of this process.
+    pub fn capture(env_names: &[&str]) -> Self {
+        Globals {
+            current_dir: env::current_dir().ok(),
+            env: env_names
+                .iter()
+                .map(|name| (name.to_string(), env::var(name).ok()))
+                .collect(),
+        }
+    }
+}
+
+/// Serializes the generated tests that change process-global state, because the test harness runs tests in parallel.
+static GLOBALS_LOCK: Mutex<()> = Mutex::new(());
+
+/// Sets process-global state for the duration of a generated test, and restores the previous state when it is dropped.
+///
+/// Generated tests create this guard before running the executable if the failure was [captured with its globals](struct.UnitTest.html#method.capture_globals). Every guard holds a process-wide lock, so generated tests that use it don't run at the same time and don't see each other's state. Tests that don't use the guard are not synchronized with it.
+pub struct GlobalsGuard {
+    previous: Globals,
+    _lock: MutexGuard<'static, ()>,
+}
+
+impl GlobalsGuard {
+    /// Change to `current_dir`, if it is `Some`, and set or remove the given environment variables. `None` removes a variable.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the current directory can't be changed to `current_dir`, e.g. because it does not exist on this machine.
+    pub fn set(current_dir: Option<&str>, env: &[(&str, Option<&str>)]) -> Self {
+        // A test that panicked while holding the lock has restored the state when its guard was dropped.
+        let lock = GLOBALS_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let names: Vec<&str> = env.iter().map(|&(name, _)| name).collect();
+        let guard = GlobalsGuard {
+            previous: Globals::capture(&names),
+            _lock: lock,
+        };
+        if let Some(dir) = current_dir {
+            if let Err(e) = env::set_current_dir(dir) {
+                panic!("Could not change the current directory to {:?}: {}", dir, e);
+            }
+        }
+        for &(name, value) in env {
+            set_var(name, value);
+        }
+        guard
+    }
+}
+
+impl Drop for GlobalsGuard {
+    fn drop(&mut self) {
+        if let Some(ref dir) = self.previous.current_dir {
+            let _ = env::set_current_dir(dir);
+        }
+        for (name, value) in &self.previous.env {
+            set_var(name, value.as_ref().map(String::as_str));
+        }
+    }
+}
+
+fn set_var(name: &str, value: Option<&str>) {
+    match value {
+        Some(value) => env::set_var(name, value),
+        None => env::remove_var(name),
+    }
+}
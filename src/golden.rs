@@ -0,0 +1,81 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fmt::Debug;
+use {Executable, UnitTest};
+
+impl<'a, E: Executable + 'a> UnitTest<'a, E>
+where
+    E::Result: Serialize + DeserializeOwned + PartialEq + Debug,
+{
+    /// Turn this test into a golden-value test, which asserts that executing the same state with the same arguments returns `expected`:
+    ///
+    /// ```rust,ignore
+    /// let expected = ::exceptional::expected_result(&obj, r#"42"#);
+    /// assert_eq!(obj.execute(&args).unwrap(), expected);
+    /// ```
+    ///
+    /// This replaces the default check of the generated test, but not a custom [assertion](trait.Executable.html#method.assertion).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `expected` can't be serialized to JSON.
+    pub fn with_expected(mut self, expected: &E::Result) -> Self {
+        let json = ::serde_json::to_string_pretty(expected)
+            .unwrap_or_else(|e| panic!("could not serialize the expected result: {}", e));
+        self.expected = Some(json);
+        self
+    }
+
+    /// Execute a copy of the stored state with the stored arguments again, and use its result as the [expected](#method.with_expected) value.
+    ///
+    /// This is meant to be used after the bug was fixed, to pin the corrected output. If the executable still fails, its error is returned instead.
+    pub fn capture_expected(self) -> Result<Self, E::Error> {
+        let result = self.executable.clone().execute(&self.arguments)?;
+        Ok(self.with_expected(&result))
+    }
+}
+
+impl<'a, E: Executable + 'a> UnitTest<'a, E>
+where
+    E::Error: Serialize + DeserializeOwned + PartialEq,
+{
+    /// Pin the error of this failure: the generated test asserts that executing the same state with the same arguments fails with an equal error, instead of only checking that it fails:
+    ///
+    /// ```rust,ignore
+    /// let expected_error = ::exceptional::expected_error(&obj, r#""Whoopsie""#);
+    /// match obj.execute(&args) {
+    ///     Ok(_) => panic!("{} no longer fails", obj.description()),
+    ///     Err(e) => assert_eq!(e, expected_error),
+    /// }
+    /// ```
+    ///
+    /// This needs an error type that can be compared and serialized. Without this, tests keep the default check. An [expected](#method.with_expected) value and a custom [assertion](trait.Executable.html#method.assertion) take precedence.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the error can't be serialized to JSON.
+    pub fn with_expected_error(mut self) -> Self {
+        let json = ::serde_json::to_string_pretty(&self.error)
+            .unwrap_or_else(|e| panic!("could not serialize the error: {}", e));
+        self.expected_error = Some(json);
+        self
+    }
+}
+
+/// Deserialize the expected error of `executable` from `json`. Tests that [pin their error](struct.UnitTest.html#method.with_expected_error) use this to get a value of the right type without naming it.
+pub fn expected_error<E>(_executable: &E, json: &str) -> E::Error
+where
+    E: Executable,
+    E::Error: DeserializeOwned,
+{
+    ::serde_json::from_str(json).expect("Could not deserialize json")
+}
+
+/// Deserialize the expected result of `executable` from `json`. Generated golden-value tests use this to get a value of the right type without naming it.
+pub fn expected_result<E>(_executable: &E, json: &str) -> E::Result
+where
+    E: Executable,
+    E::Result: DeserializeOwned,
+{
+    ::serde_json::from_str(json).expect("Could not deserialize json")
+}
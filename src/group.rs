@@ -0,0 +1,146 @@
+use error::RenderError;
+use options::Harness;
+use render::{write_comment_lines, Snapshot};
+use std::fmt::{self, Write};
+use {Executable, UnitTest};
+
+/// Render a group of failures of the same executable type, for the [harness](struct.UnitTestOptions.html#method.harness) configured in the options of the first test.
+///
+/// With [Harness::Std](enum.Harness.html#variant.Std), this is every test rendered on its own, one after another. With [Harness::TestCase](enum.Harness.html#variant.TestCase), a single function is rendered with one `#[test_case(...)]` attribute per failure. The function is named after the first failure, and its header mentions the errors of all failures. If all failures have the same executable state, the state is deserialized in the function body and every case only holds the arguments:
+///
+/// ```rust,ignore
+/// #[test_case(r#"[2, 3]"#)]
+/// #[test_case(r#"[4, 3]"#)]
+/// pub fn test_1539761233543(arg_json: &str) {
+///     // ...
+/// }
+/// ```
+///
+/// Otherwise every case holds the state as well, as `#[test_case(obj_json, arg_json)]`.
+///
+/// The crate that the tests are written to needs `test-case` as a dev-dependency, and the macro has to be in scope in the generated file:
+///
+/// ```toml
+/// [dev-dependencies]
+/// test-case = "1.0"
+/// ```
+///
+/// ```rust,ignore
+/// extern crate test_case;
+/// use test_case::test_case;
+/// ```
+///
+/// Returns an empty string if `tests` is empty.
+pub fn render_group<'a, E: Executable + 'a>(
+    tests: &[UnitTest<'a, E>],
+) -> Result<String, RenderError> {
+    let first = match tests.first() {
+        Some(first) => first,
+        None => return Ok(String::new()),
+    };
+    let mut source = String::new();
+    match first.options.harness {
+        Harness::Std => {
+            for test in tests {
+                source.push_str(&test.try_to_test_source()?);
+            }
+        }
+        Harness::TestCase => {
+            let snapshots = tests
+                .iter()
+                .map(UnitTest::snapshot)
+                .collect::<Result<Vec<_>, _>>()?;
+            write_test_cases(&mut source, tests, &snapshots)
+                .expect("Writing to a String cannot fail");
+        }
+    }
+    Ok(source)
+}
+
+/// Write a single function with a `#[test_case]` attribute for every snapshot.
+fn write_test_cases<'a, E: Executable + 'a, W: Write>(
+    fmt: &mut W,
+    tests: &[UnitTest<'a, E>],
+    snapshots: &[Snapshot],
+) -> fmt::Result {
+    let first = &tests[0];
+    let shared_state = snapshots
+        .iter()
+        .all(|s| s.executable_json == snapshots[0].executable_json);
+
+    first.write_marker_for(fmt, &snapshots[0].id, &group_hash(first, snapshots), None)?;
+    first.write_header(fmt, "parameterized unit test")?;
+    let prefix = first.options.comment_style.prefix();
+    for test in &tests[1..] {
+        write_comment_lines(fmt, prefix, "and ", &format!("{:?}", test.error))?;
+    }
+    first.write_attributes(fmt)?;
+    for snapshot in snapshots {
+        if shared_state {
+            writeln!(
+                fmt,
+                "#[test_case({})]",
+                ::literal::raw_string(&snapshot.arguments_json)
+            )?;
+        } else {
+            writeln!(
+                fmt,
+                "#[test_case({}, {})]",
+                ::literal::raw_string(&snapshot.executable_json),
+                ::literal::raw_string(&snapshot.arguments_json)
+            )?;
+        }
+    }
+    let asyncness = match first.options.async_test {
+        Some(ref attribute) => {
+            writeln!(fmt, "#[{}]", attribute)?;
+            "async "
+        }
+        None => "",
+    };
+    if shared_state {
+        writeln!(
+            fmt,
+            "pub {}fn test_{}(arg_json: &str) {{",
+            asyncness, snapshots[0].id
+        )?;
+    } else {
+        writeln!(
+            fmt,
+            "pub {}fn test_{}(obj_json: &str, arg_json: &str) {{",
+            asyncness, snapshots[0].id
+        )?;
+    }
+    first.write_imports(fmt)?;
+    first.write_prelude(fmt)?;
+    if shared_state {
+        writeln!(
+            fmt,
+            "\tlet obj_json = {};",
+            ::literal::raw_string(&snapshots[0].executable_json)
+        )?;
+    }
+    first.write_obj_from_json(fmt)?;
+    writeln!(fmt, "\t")?;
+    first.write_args_from_json(fmt)?;
+    writeln!(fmt)?;
+    // Every case has its own state, and with it its own seed.
+    let seed = first.executable.rng_seed().map(|_| {
+        format!(
+            "{}.rng_seed().expect(\"the executable has no rng seed\")",
+            first.options.obj_binding
+        )
+    });
+    first.write_body(fmt, seed.as_deref())?;
+    first.write_test_teardown(fmt)?;
+    writeln!(fmt, "}}")
+}
+
+/// The hash of a group is the hash of the hashes of its cases, in order, with the hasher of the first test.
+fn group_hash<'a, E: Executable + 'a>(first: &UnitTest<'a, E>, snapshots: &[Snapshot]) -> String {
+    let mut hashes = Vec::with_capacity(snapshots.len() * 8);
+    for snapshot in snapshots {
+        hashes.extend_from_slice(&snapshot.payload_hash().to_le_bytes());
+    }
+    first.options.hasher.hash(&hashes)
+}
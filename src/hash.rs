@@ -0,0 +1,202 @@
+//! Stable hashing of generated payloads.
+//!
+//! `std::collections::hash_map::DefaultHasher` is not guaranteed to be stable across Rust releases, so ids that end up in generated files are hashed with FNV-1a or another [Hasher](enum.Hasher.html) instead.
+
+/// The hash function for the `hash` and `error` fields of marker comments, which deduplication and [upserts](struct.UnitTestOptions.html#method.upsert) compare. See [UnitTestOptions::hasher](struct.UnitTestOptions.html#method.hasher).
+///
+/// All of them give the same hash for the same snapshot on every platform and with every Rust release, so generating the same failure again writes the same marker.
+#[derive(Clone, Copy, Debug, Default)]
+pub enum Hasher {
+    /// 64-bit FNV-1a, written as 16 hex digits. This is the default. It is fast, but collisions become likely in files with billions of tests.
+    #[default]
+    Fnv1a,
+    /// SHA-256, written as 64 hex digits. It is implemented in this crate, and checked against the NIST test vectors, so it doesn't add a dependency.
+    Sha256,
+    /// A custom hash function, e.g. from a crate like `blake3`, which is not built in. It must return a non-empty string of ASCII letters and digits, or rendering fails with [InvalidHash](enum.RenderError.html#variant.InvalidHash).
+    Custom(fn(&[u8]) -> String),
+}
+
+impl Hasher {
+    /// Hash the given bytes.
+    pub fn hash(self, bytes: &[u8]) -> String {
+        match self {
+            Hasher::Fnv1a => format!("{:016x}", fnv1a(bytes)),
+            Hasher::Sha256 => sha256(bytes)
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect(),
+            Hasher::Custom(hash) => hash(bytes),
+        }
+    }
+}
+
+/// Returns `true` if `hash` can be written to a marker comment.
+pub(crate) fn is_valid_hash(hash: &str) -> bool {
+    !hash.is_empty() && hash.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+/// Hash the given bytes with 64-bit FNV-1a.
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(FNV_OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(FNV_PRIME)
+    })
+}
+
+/// Hash the serialized executable and arguments of a unit test.
+pub(crate) fn payload_hash(executable_json: &str, arguments_json: &str) -> u64 {
+    fnv1a(&payload(executable_json, arguments_json))
+}
+
+/// The bytes of the serialized executable and arguments that are hashed.
+pub(crate) fn payload(executable_json: &str, arguments_json: &str) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(executable_json.len() + arguments_json.len() + 1);
+    bytes.extend_from_slice(executable_json.as_bytes());
+    bytes.push(0);
+    bytes.extend_from_slice(arguments_json.as_bytes());
+    bytes
+}
+
+/// Hash the description and the full path of an executable, to tell apart the ids of different executable types.
+pub(crate) fn type_hash(description: &str, full_path: &str) -> u32 {
+    let mut bytes = Vec::with_capacity(description.len() + full_path.len() + 1);
+    bytes.extend_from_slice(description.as_bytes());
+    bytes.push(0);
+    bytes.extend_from_slice(full_path.as_bytes());
+    let hash = fnv1a(&bytes);
+    (hash ^ (hash >> 32)) as u32
+}
+
+const SHA256_K: [u32; 64] = [
+    0x428a_2f98,
+    0x7137_4491,
+    0xb5c0_fbcf,
+    0xe9b5_dba5,
+    0x3956_c25b,
+    0x59f1_11f1,
+    0x923f_82a4,
+    0xab1c_5ed5,
+    0xd807_aa98,
+    0x1283_5b01,
+    0x2431_85be,
+    0x550c_7dc3,
+    0x72be_5d74,
+    0x80de_b1fe,
+    0x9bdc_06a7,
+    0xc19b_f174,
+    0xe49b_69c1,
+    0xefbe_4786,
+    0x0fc1_9dc6,
+    0x240c_a1cc,
+    0x2de9_2c6f,
+    0x4a74_84aa,
+    0x5cb0_a9dc,
+    0x76f9_88da,
+    0x983e_5152,
+    0xa831_c66d,
+    0xb003_27c8,
+    0xbf59_7fc7,
+    0xc6e0_0bf3,
+    0xd5a7_9147,
+    0x06ca_6351,
+    0x1429_2967,
+    0x27b7_0a85,
+    0x2e1b_2138,
+    0x4d2c_6dfc,
+    0x5338_0d13,
+    0x650a_7354,
+    0x766a_0abb,
+    0x81c2_c92e,
+    0x9272_2c85,
+    0xa2bf_e8a1,
+    0xa81a_664b,
+    0xc24b_8b70,
+    0xc76c_51a3,
+    0xd192_e819,
+    0xd699_0624,
+    0xf40e_3585,
+    0x106a_a070,
+    0x19a4_c116,
+    0x1e37_6c08,
+    0x2748_774c,
+    0x34b0_bcb5,
+    0x391c_0cb3,
+    0x4ed8_aa4a,
+    0x5b9c_ca4f,
+    0x682e_6ff3,
+    0x748f_82ee,
+    0x78a5_636f,
+    0x84c8_7814,
+    0x8cc7_0208,
+    0x90be_fffa,
+    0xa450_6ceb,
+    0xbef9_a3f7,
+    0xc671_78f2,
+];
+
+/// Hash the given bytes with SHA-256.
+fn sha256(bytes: &[u8]) -> [u8; 32] {
+    let mut state: [u32; 8] = [
+        0x6a09_e667,
+        0xbb67_ae85,
+        0x3c6e_f372,
+        0xa54f_f53a,
+        0x510e_527f,
+        0x9b05_688c,
+        0x1f83_d9ab,
+        0x5be0_cd19,
+    ];
+    let mut message = bytes.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&((bytes.len() as u64).wrapping_mul(8)).to_be_bytes());
+
+    for block in message.chunks(64) {
+        let mut w = [0u32; 64];
+        for (i, word) in block.chunks(4).enumerate() {
+            w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[i - 7])
+                .wrapping_add(s1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state;
+        for i in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = h
+                .wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(SHA256_K[i])
+                .wrapping_add(w[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.wrapping_add(t2);
+        }
+        for (word, value) in state.iter_mut().zip(&[a, b, c, d, e, f, g, h]) {
+            *word = word.wrapping_add(*value);
+        }
+    }
+
+    let mut digest = [0u8; 32];
+    for (chunk, word) in digest.chunks_mut(4).zip(&state) {
+        chunk.copy_from_slice(&word.to_be_bytes());
+    }
+    digest
+}
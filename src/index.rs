@@ -0,0 +1,131 @@
+use chrono::SecondsFormat;
+use error::RenderError;
+use parse;
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use {Executable, UnitTest};
+
+/// An entry of the `<file>.meta.json` index of a file with generated tests. See [meta_index](struct.UnitTestOptions.html#method.meta_index).
+///
+/// The index is a JSON array with one object per test, in the order of the file, like:
+///
+/// ```json
+/// [
+///   {
+///     "id": "1539761233543",
+///     "full_path": "::my_crate::SomeImportantAction",
+///     "time": "2018-10-17T07:27:13.543Z",
+///     "severity": "medium",
+///     "hash": "4f7b2a1c9d3e5f60",
+///     "error_hash": "a1b2c3d4e5f60718",
+///     "description": "Executes some very important action!",
+///     "error_debug": "\"Whoopsie\""
+///   }
+/// ]
+/// ```
+///
+/// The first six fields are the fields of the [marker](struct.Marker.html) of the test, with the severity as its [name](enum.Severity.html#method.name). `description` and `error_debug` are `null` for tests that were not written with the index enabled, because the file does not contain them in a form that can be read back. This schema is stable: fields are never removed or renamed, and new fields are only added with a `null` default, so readers should ignore fields they don't know.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexEntry {
+    /// The id of the test, which is the suffix of its function name
+    pub id: String,
+
+    /// The full path of the executable
+    pub full_path: String,
+
+    /// The time of the failure, in RFC 3339 format
+    pub time: String,
+
+    /// The name of the severity of the failure, like `medium`
+    pub severity: String,
+
+    /// The hash of the serialized executable and arguments
+    pub hash: String,
+
+    /// The hash of the `Debug` output of the error, which is empty for groups
+    pub error_hash: String,
+
+    /// The [description](trait.Executable.html#tymethod.description) of the executable
+    #[serde(default)]
+    pub description: Option<String>,
+
+    /// The `Debug` output of the error
+    #[serde(default)]
+    pub error_debug: Option<String>,
+}
+
+/// The path of the index of `file`, which is `file` with `.meta.json` appended, like `src/regressions.rs.meta.json`.
+pub fn index_path(file: impl AsRef<Path>) -> PathBuf {
+    let mut path = OsString::from(file.as_ref());
+    path.push(".meta.json");
+    PathBuf::from(path)
+}
+
+/// Write the [index](struct.IndexEntry.html) of the generated tests in `file` again, from the markers in the file, and return its entries.
+///
+/// The index lists exactly the tests that are in the file, so this brings it up to date after the file was changed without the index, e.g. by a [StreamingWriter](struct.StreamingWriter.html), by [resplit_by_type](fn.resplit_by_type.html) or by hand. The description and the error of tests that were already in the index are kept. Like every update of the index, the new index is written to a temporary file that then replaces the old one, so readers never see a partially written index.
+pub fn rebuild_index(file: impl AsRef<Path>) -> io::Result<Vec<IndexEntry>> {
+    update(file.as_ref(), None)
+}
+
+/// Rebuild the index of `file`, with the details of a test that was just written to it.
+pub(crate) fn update(file: &Path, written: Option<IndexEntry>) -> io::Result<Vec<IndexEntry>> {
+    let src = fs::read_to_string(file)?;
+    let path = index_path(file);
+    let mut known: Vec<IndexEntry> = match fs::read_to_string(&path) {
+        // An index that can't be read is replaced by a new one.
+        Ok(json) => ::serde_json::from_str(&json).unwrap_or_default(),
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+        Err(e) => return Err(e),
+    };
+    known.extend(written);
+
+    let entries: Vec<IndexEntry> = parse::find_items(&src)
+        .into_iter()
+        .map(|item| {
+            let marker = item.marker;
+            // Entries are matched by their content, because migrating the ids of a file renames its tests.
+            let details = known
+                .iter()
+                .rev()
+                .find(|entry| entry.hash == marker.hash && entry.error_hash == marker.error);
+            IndexEntry {
+                description: details.and_then(|entry| entry.description.clone()),
+                error_debug: details.and_then(|entry| entry.error_debug.clone()),
+                id: marker.id,
+                full_path: marker.path,
+                time: marker.time,
+                severity: marker.severity.name().to_owned(),
+                hash: marker.hash,
+                error_hash: marker.error,
+            }
+        })
+        .collect();
+
+    let json =
+        ::serde_json::to_string_pretty(&entries).expect("The index can always be serialized");
+    let mut temporary = OsString::from(&path);
+    temporary.push(".tmp");
+    fs::write(&temporary, json)?;
+    fs::rename(&temporary, &path)?;
+    Ok(entries)
+}
+
+impl<'a, E: Executable + 'a> UnitTest<'a, E> {
+    /// The index entry of this test, with the details that only the failure itself has.
+    pub(crate) fn index_entry(&self) -> Result<IndexEntry, RenderError> {
+        let snapshot = self.snapshot()?;
+        Ok(IndexEntry {
+            id: snapshot.id,
+            full_path: self.executable.full_path().to_owned(),
+            time: self.time.to_rfc3339_opts(SecondsFormat::Millis, true),
+            severity: self.executable.severity().name().to_owned(),
+            hash: snapshot.hash,
+            error_hash: snapshot.error_hash,
+            description: Some(self.executable.description()),
+            error_debug: Some(format!("{:?}", self.error)),
+        })
+    }
+}
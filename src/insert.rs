@@ -0,0 +1,345 @@
+use options::UnitTestOptions;
+use parse::{self, Marker};
+use std::borrow::Cow;
+
+/// The comment that marks where [append_to_file](struct.UnitTest.html#method.append_to_file) inserts new tests, if [use_insertion_marker](struct.UnitTestOptions.html#method.use_insertion_marker) is enabled.
+pub const INSERTION_MARKER: &str = "// EXCEPTIONAL:INSERT";
+
+/// Returns `true` if placing a test according to `options` needs the current contents of the file, instead of a blind append.
+pub(crate) fn needs_contents(options: &UnitTestOptions) -> bool {
+    options.banner.is_some()
+        || options.table_of_contents
+        || options.use_insertion_marker
+        || options.upsert
+        || options.target_module.is_some()
+}
+
+/// Place a rendered test in the existing contents of a file, according to `options`, together with the [preamble](trait.Executable.html#method.file_preamble) of the executable at the given full path. With `imports`, the test goes into the target module, which gets these `use` statements at its top if it doesn't have them yet.
+pub(crate) fn insert_test(
+    existing: &str,
+    text: &str,
+    options: &UnitTestOptions,
+    preamble: Option<(&str, &str)>,
+    imports: Option<&[&str]>,
+) -> String {
+    let mut new = String::with_capacity(existing.len() + text.len() + 256);
+    let mut body = existing;
+    let banner = options.rendered_banner();
+    if let Some(ref banner) = banner {
+        if body.starts_with(banner.as_str()) {
+            body = &body[banner.len()..];
+        }
+        new.push_str(banner);
+    }
+    if options.table_of_contents {
+        body = strip_table_of_contents(body);
+    }
+    let body = match preamble {
+        Some((path, preamble)) => with_preamble(body, path, preamble),
+        None => Cow::Borrowed(body),
+    };
+    let body = place_test(&body, text, options, imports);
+    if !options.table_of_contents {
+        new.push_str(&body);
+        return new;
+    }
+
+    // The table of contents itself shifts the lines of the tests.
+    let items = parse::find_items(&body);
+    let offset = new.lines().count() + items.len() + 2;
+    new.push_str(TABLE_OF_CONTENTS);
+    new.push('\n');
+    for item in &items {
+        let item_src = &body[item.range.clone()];
+        new.push_str(&format!(
+            "{}test_{}: {} (line {})\n",
+            TABLE_OF_CONTENTS_ENTRY,
+            item.marker.id,
+            description(item_src).unwrap_or(&item.marker.path),
+            offset + body[..item.range.start].lines().count() + 1
+        ));
+    }
+    new.push('\n');
+    new.push_str(&body);
+    new
+}
+
+/// The start of the line before the preamble of an executable, which is followed by its full path.
+const PREAMBLE_START: &str = "// @exceptional-preamble path=";
+
+/// The line after a preamble.
+const PREAMBLE_END: &str = "// @exceptional-preamble-end\n";
+
+/// Return `body` with the preamble of the executable at `path`. An existing preamble of the same path is replaced, otherwise the preamble is added at the start.
+fn with_preamble<'a>(body: &'a str, path: &str, preamble: &str) -> Cow<'a, str> {
+    let start_line = format!("{}{}\n", PREAMBLE_START, path);
+    let mut block = start_line.clone();
+    block.push_str(preamble);
+    if !preamble.ends_with('\n') {
+        block.push('\n');
+    }
+    block.push_str(PREAMBLE_END);
+
+    let mut line_start = 0;
+    for line in body.split_inclusive('\n') {
+        if line == start_line {
+            let end = match body[line_start..].find(PREAMBLE_END) {
+                Some(n) => line_start + n + PREAMBLE_END.len(),
+                // An unterminated preamble was cut off, so it is completed.
+                None => body.len(),
+            };
+            if body[line_start..end] == block {
+                return Cow::Borrowed(body);
+            }
+            return Cow::Owned(format!("{}{}{}", &body[..line_start], block, &body[end..]));
+        }
+        line_start += line.len();
+    }
+    block.push('\n');
+    block.push_str(body);
+    Cow::Owned(block)
+}
+
+/// The first line of the [table of contents](struct.UnitTestOptions.html#method.table_of_contents).
+const TABLE_OF_CONTENTS: &str = "//! Generated tests:";
+
+/// The start of every entry of the table of contents.
+const TABLE_OF_CONTENTS_ENTRY: &str = "//! - ";
+
+/// Remove the table of contents, and the empty line after it, from the start of `src`.
+fn strip_table_of_contents(src: &str) -> &str {
+    let mut rest = match src.strip_prefix(TABLE_OF_CONTENTS) {
+        Some(rest) if rest.starts_with('\n') => &rest[1..],
+        _ => return src,
+    };
+    while rest.starts_with(TABLE_OF_CONTENTS_ENTRY) {
+        rest = rest.find('\n').map(|n| &rest[n + 1..]).unwrap_or("");
+    }
+    rest.strip_prefix('\n').unwrap_or(rest)
+}
+
+/// The description of the executable, from the header of a generated item.
+fn description(item: &str) -> Option<&str> {
+    let mut lines = item
+        .lines()
+        .map(|line| line.trim_start().trim_start_matches('/').trim())
+        .skip_while(|line| !line.starts_with("Automatically generated "));
+    lines.next()?;
+    lines.find(|line| !line.is_empty())
+}
+
+/// Place a rendered test in `body`, the contents of a file without its banner and table of contents.
+///
+/// A test without its own imports has to end up in the module that has them, so the insertion marker is skipped, and only a test in that module is upserted.
+fn place_test(
+    body: &str,
+    text: &str,
+    options: &UnitTestOptions,
+    imports: Option<&[&str]>,
+) -> String {
+    if options.upsert {
+        let within = match (imports, options.target_module.as_ref()) {
+            (Some(_), Some(module)) => parse::find_module(body, module).map(Some),
+            _ => Some(None),
+        };
+        if let Some(upserted) = within.and_then(|within| upsert(body, text, within)) {
+            return upserted;
+        }
+    }
+
+    let mut new = String::with_capacity(body.len() + text.len() + 256);
+    let marker = match imports {
+        Some(_) => None,
+        None => find_insertion_marker(body).filter(|_| options.use_insertion_marker),
+    };
+    match marker {
+        Some(line_start) => {
+            let indent: String = body[line_start..]
+                .chars()
+                .take_while(|c| *c == ' ' || *c == '\t')
+                .collect();
+            new.push_str(&body[..line_start]);
+            push_indented(&mut new, text, &indent);
+            new.push_str(&body[line_start..]);
+        }
+        None => match options.target_module {
+            Some(ref module) => {
+                insert_into_module(&mut new, body, text, module, imports.unwrap_or(&[]))
+            }
+            None => {
+                new.push_str(body);
+                new.push_str(text);
+            }
+        },
+    }
+    new
+}
+
+/// The doc comment of a module that is created for generated tests. The module also allows missing docs, so it keeps compiling in crates that deny them if it is made public, even with [plain comments](enum.CommentStyle.html#variant.Plain).
+const MODULE_DOC: &str = "/// Regression tests that were generated from failures.\n";
+
+/// The `use` statements that [append_to_module](struct.UnitTest.html#method.append_to_module) writes once at the top of the module, instead of in every test.
+pub(crate) fn module_imports(asynchronous: bool) -> [&'static str; 2] {
+    [
+        "use super::*;",
+        if asynchronous {
+            "use exceptional::{AsyncExecutable, Executable};"
+        } else {
+            "use exceptional::Executable;"
+        },
+    ]
+}
+
+/// Push `imports` to `out`, each allowed to be unused, followed by an empty line.
+pub(crate) fn push_imports(out: &mut String, imports: &[&str], indent: &str) {
+    for import in imports {
+        out.push_str(&format!(
+            "{0}#[allow(unused_imports)]\n{0}{1}\n",
+            indent, import
+        ));
+    }
+    out.push('\n');
+}
+
+/// Push `src` to `out` with `text` inserted at the end of the module with the given name. If there is no such module, it is created at the end of `src`. The `imports` that the module doesn't have yet are added at its top.
+fn insert_into_module(out: &mut String, src: &str, text: &str, module: &str, imports: &[&str]) {
+    let (open, close) = match parse::find_module(src, module) {
+        Some(braces) => braces,
+        None => {
+            out.push_str(src);
+            if !src.is_empty() && !src.ends_with("\n\n") {
+                out.push('\n');
+            }
+            out.push_str(MODULE_DOC);
+            out.push_str(&format!("#[allow(missing_docs)]\nmod {} {{\n", module));
+            if !imports.is_empty() {
+                push_imports(out, imports, "    ");
+            }
+            push_indented(out, text, "    ");
+            out.push_str("}\n");
+            return;
+        }
+    };
+    // Only the items of the module count, not the imports inside the functions of older tests.
+    let indent = module_indent(src, open, close);
+    let missing: Vec<&str> = imports
+        .iter()
+        .cloned()
+        .filter(|import| {
+            !src[open..close]
+                .lines()
+                .any(|line| line.strip_prefix(indent.as_str()).map(str::trim_end) == Some(*import))
+        })
+        .collect();
+    if !missing.is_empty() {
+        let first_line = match src[open..close].find('\n') {
+            Some(n) => open + n + 1,
+            None => open + 1,
+        };
+        let mut with_imports = String::with_capacity(src.len() + 256);
+        with_imports.push_str(&src[..first_line]);
+        if first_line == open + 1 {
+            with_imports.push('\n');
+        }
+        push_imports(&mut with_imports, &missing, &indent);
+        with_imports.push_str(&src[first_line..]);
+        return insert_into_module(out, &with_imports, text, module, &[]);
+    }
+
+    let close_line = src[..close].rfind('\n').map(|n| n + 1).unwrap_or(0);
+    if src[close_line..close].trim().is_empty() && close_line > open {
+        // The closing brace is on its own line.
+        out.push_str(&src[..close_line]);
+        push_indented(out, text, &module_indent(src, open, close));
+        out.push_str(&src[close_line..]);
+    } else {
+        // An empty module on one line, like `mod tests {}`.
+        out.push_str(&src[..close]);
+        out.push('\n');
+        push_indented(out, text, "    ");
+        out.push_str(&src[close..]);
+    }
+}
+
+/// The indentation of the items of the module between the given braces: that of its first line, or one level deeper than the closing brace.
+fn module_indent(src: &str, open: usize, close: usize) -> String {
+    let close_line = src[..close].rfind('\n').map(|n| n + 1).unwrap_or(0);
+    let close_indent = &src[close_line..close];
+    let close_indent = if close_indent.trim().is_empty() {
+        close_indent
+    } else {
+        ""
+    };
+    src[open + 1..close]
+        .lines()
+        .find(|line| !line.trim().is_empty())
+        .map(|line| line[..line.len() - line.trim_start().len()].to_owned())
+        .unwrap_or_else(|| format!("{}    ", close_indent))
+}
+
+/// If `src` already contains a test with the same hash as `text`, return `src` with that test replaced by `text` if their errors differ, or unchanged if they don't. Returns `None` if there is no such test, or if it is not `within` the given braces.
+fn upsert(src: &str, text: &str, within: Option<(usize, usize)>) -> Option<String> {
+    let marker = text.lines().next().and_then(Marker::parse)?;
+    let item = parse::find_items(src)
+        .into_iter()
+        .find(|item| item.marker.hash == marker.hash)?;
+    if let Some((open, close)) = within {
+        if item.range.start < open || item.range.start > close {
+            return None;
+        }
+    }
+    let old = &src[item.range.clone()];
+    if exception(old) == exception(text) {
+        return Some(src.to_owned());
+    }
+
+    let indent: String = old
+        .chars()
+        .take_while(|c| *c == ' ' || *c == '\t')
+        .collect();
+    let mut new = String::with_capacity(src.len() + text.len());
+    new.push_str(&src[..item.range.start]);
+    push_indented(&mut new, text, &indent);
+    new.push_str(&src[item.range.end..]);
+    Some(new)
+}
+
+/// The error that a generated test was generated for, as written in its header: the line with the error and the comment lines after it, which continue an error with line breaks.
+fn exception(item: &str) -> Option<Vec<&str>> {
+    let mut lines = item
+        .lines()
+        .map(str::trim_start)
+        .skip_while(|line| !line.trim_start_matches('/').starts_with(" exception was "));
+    let first = lines.next()?;
+    Some(
+        Some(first)
+            .into_iter()
+            .chain(lines.take_while(|line| line.starts_with("//")))
+            .map(|line| line.trim_start_matches('/'))
+            .collect(),
+    )
+}
+
+/// Find the start of the first line that consists of the insertion marker.
+fn find_insertion_marker(src: &str) -> Option<usize> {
+    let mut line_start = 0;
+    for line in src.split_inclusive('\n') {
+        if line.trim() == INSERTION_MARKER {
+            return Some(line_start);
+        }
+        line_start += line.len();
+    }
+    None
+}
+
+/// Push every line of `text` to `out`, prefixed with `indent`. Empty lines are not indented.
+///
+/// Indenting the embedded JSON is safe, because whitespace outside of JSON strings is insignificant and serde_json escapes newlines inside strings.
+pub(crate) fn push_indented(out: &mut String, text: &str, indent: &str) {
+    for line in text.split_inclusive('\n') {
+        if !indent.is_empty() && !line.trim().is_empty() {
+            out.push_str(indent);
+        }
+        out.push_str(line);
+    }
+}
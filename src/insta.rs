@@ -0,0 +1,138 @@
+use error::RenderError;
+use render::Snapshot;
+use renderer::Renderer;
+use serde_json::{Map, Value};
+use std::fmt::{self, Write};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use {Executable, UnitTest};
+
+/// A renderer that outputs an [insta](https://insta.rs) snapshot test, which asserts that the outcome of `obj.execute(&args)` has not changed, instead of asserting that it no longer fails:
+///
+/// ```rust,ignore
+/// ::insta::assert_json_snapshot!("test_1539761233543", obj.execute(&args).map_err(|e| format!("{:?}", e)));
+/// ```
+///
+/// The error is snapshotted as its `Debug` output, so only the `Result` of the executable has to implement `Serialize`. To make the test pass as long as the executable fails in the same way, seed the `.snap` file with the captured outcome with [write_snapshot_file](#method.write_snapshot_file) before appending the test, and write both next to each other:
+///
+/// ```rust,ignore
+/// let renderer = exceptional::InstaRenderer::new("my_crate::regressions");
+/// renderer.write_snapshot_file(&test, "src/regressions.rs")?;
+/// test.append_to_file_with("src/regressions.rs", &renderer)?;
+/// ```
+///
+/// When the behavior changes, e.g. because the bug was fixed, the test fails with a diff, and `cargo insta review` accepts the new outcome as the snapshot. The crate that the tests are written to needs `insta` with its `json` feature as a dev-dependency:
+///
+/// ```toml
+/// [dev-dependencies]
+/// insta = { version = "1", features = ["json"] }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InstaRenderer {
+    /// The path of the module that the tests are written to, including the crate name, like `my_crate::regressions`. This is what `module_path!()` returns in that module, and determines the name of the `.snap` file.
+    pub module_path: String,
+}
+
+impl InstaRenderer {
+    /// A renderer for tests in the module at `module_path`, like `my_crate::regressions`.
+    pub fn new(module_path: &str) -> Self {
+        InstaRenderer {
+            module_path: module_path.to_owned(),
+        }
+    }
+
+    /// Write the captured outcome of `test` as the initial snapshot of the test that this renderer generates for it, and return the path of the file.
+    ///
+    /// The file is written to the `snapshots` directory next to `test_file`, where insta looks for it, and named after the module path and the name of the test function, like `snapshots/my_crate__regressions__test_1539761233543.snap`. An existing file is overwritten.
+    pub fn write_snapshot_file<'a, E: Executable + 'a>(
+        &self,
+        test: &UnitTest<'a, E>,
+        test_file: impl AsRef<Path>,
+    ) -> io::Result<PathBuf> {
+        let snapshot = test
+            .snapshot()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let test_file = test_file.as_ref();
+        let dir = test_file
+            .parent()
+            .unwrap_or_else(|| Path::new(""))
+            .join("snapshots");
+        fs::create_dir_all(&dir)?;
+        let file = dir.join(format!(
+            "{}__test_{}.snap",
+            self.module_path.replace("::", "__"),
+            snapshot.id
+        ));
+        let mut outcome = Map::new();
+        outcome.insert(
+            String::from("Err"),
+            Value::String(format!("{:?}", test.error)),
+        );
+        let contents = format!(
+            "---\nsource: {}\nexpression: {:?}\n---\n{}\n",
+            test_file.display(),
+            expression(test),
+            ::serde_json::to_string_pretty(&outcome)
+                .expect("A JSON value can always be serialized")
+        );
+        fs::write(&file, contents)?;
+        Ok(file)
+    }
+}
+
+impl Renderer for InstaRenderer {
+    fn render<'a, E: Executable + 'a>(
+        &self,
+        test: &UnitTest<'a, E>,
+    ) -> Result<String, RenderError> {
+        let snapshot = test.snapshot()?;
+        let mut source = String::new();
+        write_insta_test(&mut source, test, &snapshot).expect("Writing to a String cannot fail");
+        Ok(source)
+    }
+}
+
+/// The expression whose outcome is snapshotted.
+fn expression<'a, E: Executable + 'a>(test: &UnitTest<'a, E>) -> String {
+    format!(
+        "{}.map_err(|e| format!(\"{{:?}}\", e))",
+        test.execute_call()
+    )
+}
+
+fn write_insta_test<'a, E: Executable + 'a, W: Write>(
+    fmt: &mut W,
+    test: &UnitTest<'a, E>,
+    snapshot: &Snapshot,
+) -> fmt::Result {
+    test.write_marker(fmt, snapshot)?;
+    test.write_inputs(fmt, snapshot)?;
+    test.write_header(fmt, "insta snapshot test")?;
+    test.write_attributes(fmt)?;
+    match test.options.async_test {
+        Some(ref attribute) => {
+            writeln!(fmt, "#[{}]", attribute)?;
+            writeln!(fmt, "pub async fn test_{}() {{", snapshot.id)?;
+        }
+        None => {
+            writeln!(fmt, "#[{}]", test.options.test_attribute)?;
+            writeln!(fmt, "pub fn test_{}() {{", snapshot.id)?;
+        }
+    }
+    test.write_imports(fmt)?;
+    test.write_prelude(fmt)?;
+    test.write_snapshot(fmt, snapshot)?;
+    writeln!(fmt)?;
+    let seed = test.executable.rng_seed().map(|seed| seed.to_string());
+    test.write_setup(fmt, seed.as_deref())?;
+    writeln!(
+        fmt,
+        "\t{}!(\"test_{}\", {});",
+        test.options.extern_path("::insta::assert_json_snapshot"),
+        snapshot.id,
+        expression(test)
+    )?;
+    test.write_test_teardown(fmt)?;
+    writeln!(fmt, "}}")
+}
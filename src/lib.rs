@@ -60,23 +60,140 @@
 //! }
 //! ```
 #![deny(missing_docs)]
+// The execute functions return the failed test by value, so it can be inspected and configured before it is written.
+#![allow(clippy::result_large_err)]
 
 extern crate chrono;
+#[cfg(feature = "locking")]
+extern crate fs2;
 extern crate serde;
+#[macro_use]
+extern crate serde_derive;
 extern crate serde_json;
+#[cfg(feature = "validate")]
+extern crate syn;
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, SubsecRound, TimeZone, Utc};
+use render::Snapshot;
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashSet};
+use std::ffi::OsStr;
 use std::fmt::Debug;
-use std::fs::OpenOptions;
-use std::io::{self, Write};
-use std::path::Path;
+use std::fs;
+use std::io;
+use std::panic::Location;
+use std::path::{Path, PathBuf};
+
+mod async_executable;
+mod base64;
+mod batch;
+mod batch_async;
+mod boxed;
+mod capture;
+mod debug_args;
+mod defaults;
+mod diff;
+mod dir;
+mod erased;
+mod error;
+mod error_render;
+mod example;
+mod fallible;
+mod file;
+mod filter;
+mod generated;
+mod generator;
+mod globals;
+mod golden;
+mod group;
+mod hash;
+mod index;
+mod insert;
+#[cfg(feature = "insta")]
+mod insta;
+mod literal;
+mod maintenance;
+mod marker_index;
+mod maybe_owned;
+mod minimize;
+mod options;
+mod out_dir;
+mod panic;
+mod parse;
+mod render;
+mod renderer;
+mod report;
+mod rerun;
+mod ron;
+mod round_trip;
+mod route;
+mod self_args;
+mod sequence;
+mod serialize;
+mod severity;
+mod sidecar;
+mod timeout;
+#[cfg(feature = "validate")]
+mod validate;
+mod writer;
+
+pub use async_executable::{execute_async, AsyncExecutable, ExecuteAsync, ExecuteFuture};
+pub use base64::decode_base64_json;
+pub use batch::{execute_all, Mode};
+pub use batch_async::{execute_all_async, ExecuteAllAsync};
+pub use boxed::{error_chain, execute_boxed_err};
+#[doc(hidden)]
+pub use capture::capture_result;
+pub use capture::CAPTURE_FILE;
+pub use debug_args::{execute_debug, DebugArguments, DebugExecutable};
+pub use diff::{diff_files, TestChange, TestDiff};
+pub use erased::ErasedExecutable;
+pub use error::{AppendError, RenderError, SerializeError};
+pub use error_render::ErrorRender;
+pub use fallible::{try_execute, SnapshotError, TryExecutable, TryExecuteError};
+pub use filter::{
+    execute_with_options, CaptureDecision, ErrorPredicate, ExecuteError, ExecuteOptions, SkipHook,
+};
+pub use generated::{GeneratedTest, TransformRenderer};
+pub use generator::Generator;
+pub use globals::{Globals, GlobalsGuard};
+pub use golden::{expected_error, expected_result};
+pub use group::render_group;
+pub use hash::Hasher;
+pub use index::{index_path, rebuild_index, IndexEntry};
+pub use insert::INSERTION_MARKER;
+#[cfg(feature = "insta")]
+pub use insta::InstaRenderer;
+pub use maintenance::{migrate_ids, regenerate_suite, resplit_by_type, IdMigration};
+pub use marker_index::MarkerIndex;
+pub use maybe_owned::MaybeOwned;
+pub use minimize::{execute_minimized, execute_shrinking, MAX_MINIMIZE_ATTEMPTS};
+pub use options::{
+    CommentStyle, Edition, Harness, IdStrategy, JsonPostprocess, LineEnding, Route,
+    SerializationFormat, TestKind, UnitTestMeta, UnitTestOptions, DEFAULT_BANNER,
+};
+pub use out_dir::out_dir_file;
+pub use panic::execute_catch_unwind;
+pub use parse::{parse_markers, Marker};
+pub use render::total_rendered_len;
+pub use renderer::{DefaultRenderer, JunitRenderer, Renderer};
+pub use report::UnitTestReport;
+pub use rerun::{replay, rerun_file, Outcome, ReplayError};
+pub use self_args::{execute_self, ExecutableSelfArgs};
+pub use sequence::{Sequence, SequenceError};
+pub use severity::{tests_with_severity, Severity};
+pub use sidecar::prune_sidecars;
+pub use timeout::run_with_timeout;
+#[cfg(feature = "validate")]
+pub use validate::ValidationError;
+pub use writer::{DedupKey, StreamingWriter, TestFileWriter, TestSuite};
 
 /// The trait that structs should implement to make them executable.
 ///
 /// Note: this is cloned every time this is executed. Consider putting non-mutable values in `Arguments` rather than this struct.
 ///
-/// Note: Any internally mutable values (like Rc<RefCell<T>>) will write the post-exception state to the test. It is assumed that these structs are not Serialize-safe.
+/// Note: Any internally mutable values (like Rc<RefCell<T>>) will write the post-exception state to the test. It is assumed that these structs are not Serialize-safe. [with_state_diff](struct.ExecuteOptions.html#method.with_state_diff) shows what a failing run changed.
 pub trait Executable: Serialize + for<'a> Deserialize<'a> + Clone {
     /// The result of the execute action.
     type Result;
@@ -84,22 +201,241 @@ pub trait Executable: Serialize + for<'a> Deserialize<'a> + Clone {
     /// The error of the execute action.
     type Error: Debug;
 
-    /// The arguments that will be passed to the execute action
-    type Arguments: Serialize;
+    /// The arguments that will be passed to the execute action. The generated test deserializes them, like the executable itself, so they are [checked](struct.UnitTest.html#method.check_round_trip) when the test is rendered.
+    ///
+    /// Since 0.2.0 the arguments have to implement `Deserialize`. Before, only `Serialize` was required, but the generated test did not compile without `Deserialize` either, so derive it for arguments that don't have it yet.
+    type Arguments: Serialize + for<'a> Deserialize<'a>;
 
     /// Get the full path for this type. This will be used to generate the unit test.
     fn full_path(&self) -> &'static str;
 
+    /// The full path of the `Arguments` type, like `::my_crate::Query`, if it is a struct with named fields.
+    ///
+    /// If this returns `Some` and the arguments serialize to a JSON object, the generated test constructs them with a struct literal, so the names of the fields are visible in the test:
+    ///
+    /// ```rust,ignore
+    /// let args = ::my_crate::Query {
+    ///     limit: ::serde_json::from_str(r#"10"#).expect("Could not deserialize json"),
+    ///     name: ::serde_json::from_str(r#""bob""#).expect("Could not deserialize json"),
+    /// };
+    /// ```
+    ///
+    /// Every value is deserialized on its own, so fields of any type work. Like [with_default_overrides](struct.UnitTest.html#method.with_default_overrides), this relies on the serialized field names being the names of public fields, so it can't be used with `#[serde(rename)]`, `#[serde(flatten)]`, skipped or private fields. Other arguments, [sidecar files](struct.UnitTestOptions.html#method.sidecar_dir) and [groups](fn.render_group.html) use the JSON as usual. The default implementation returns `None`.
+    fn arguments_path(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Rust source of an expression that constructs this state directly, like `::my_crate::Counter { count: 3 }`, for executables whose JSON does not round-trip but whose state is simple to write down.
+    ///
+    /// If this returns `Some`, the generated test assigns the expression to the executable binding instead of deserializing the embedded JSON, and the executable doesn't have to [round-trip](enum.RenderError.html#variant.ExecutableRoundTrip):
+    ///
+    /// ```rust,ignore
+    /// fn construct_expr(&self) -> Option<String> {
+    ///     Some(format!("::my_crate::Counter::starting_at({})", self.count))
+    /// }
+    /// ```
+    ///
+    /// The expression is pasted into the generated source as is, so it is trusted like the [assertion](#method.assertion): it is compiled and run with the tests of the crate. Build it from the state of the executable with `Debug` formatting or another escaping format, and never paste strings from untrusted input, like the text of an error or a request, into it unescaped. An empty expression fails to render with [EmptyConstructExpr](enum.RenderError.html#variant.EmptyConstructExpr).
+    ///
+    /// The executable is still serialized for the marker hash and the deduplication, and the arguments are embedded as usual. [Groups](fn.render_group.html) and [rerun_file](fn.rerun_file.html) still read the JSON of the executable, which therefore has to deserialize for them. The default implementation returns `None`.
+    fn construct_expr(&self) -> Option<String> {
+        None
+    }
+
+    /// Whether the arguments of this executable are `()`, because it takes everything it needs from its own state, like an [ExecutableSelfArgs](trait.ExecutableSelfArgs.html). If this returns `true`, the generated test only deserializes the executable, and binds the arguments to `()`. The default implementation returns `false`.
+    fn arguments_from_self(&self) -> bool {
+        false
+    }
+
+    /// The type of the arguments if they were only captured with `Debug`, by a [DebugExecutable](trait.DebugExecutable.html).
+    #[doc(hidden)]
+    fn debug_arguments_type(&self) -> Option<&'static str> {
+        None
+    }
+
     /// Get the description for this type to describe what it's function is, etc. This will show up in the description of the unit test
     fn description(&self) -> String;
 
     /// Execute the logic. Call this with [execute](method.execute.html) to generate a Unit Test out of this error.
     fn execute(&mut self, args: &Self::Arguments) -> Result<Self::Result, Self::Error>;
+
+    /// Rust statements that replace the default check of the generated test, which only verifies that `obj.execute(&args)` no longer fails.
+    ///
+    /// The deserialized executable is in scope as `obj` (declared `mut`) and the deserialized arguments as `args`, unless other names are configured with [obj_binding](struct.UnitTestOptions.html#method.obj_binding) and [args_binding](struct.UnitTestOptions.html#method.args_binding). The code is inserted as-is, one level inside the test function.
+    ///
+    /// ```rust,ignore
+    /// fn assertion(&self) -> Option<String> {
+    ///     Some(String::from("let result = obj.execute(&args).unwrap();\nassert!((result - 1.0).abs() < 1e-9);"))
+    /// }
+    /// ```
+    fn assertion(&self) -> Option<String> {
+        None
+    }
+
+    /// Rust statements that check the result of the call, instead of the default check of the generated test. The test calls `obj.execute(&args)` itself and binds the `Result` to a variable named `result_var`, so the statements only have to inspect it:
+    ///
+    /// ```rust,ignore
+    /// fn assertion_body(&self, result_var: &str) -> Option<String> {
+    ///     Some(format!("assert_eq!({}, Err(MyError::Overflow));", result_var))
+    /// }
+    /// ```
+    ///
+    /// `obj` and `args` are still in scope, like for an [assertion](#method.assertion), which takes precedence. The call is awaited for [async tests](struct.UnitTestOptions.html#method.async_test). The default implementation returns `None`, which keeps the default check.
+    fn assertion_body(&self, _result_var: &str) -> Option<String> {
+        None
+    }
+
+    /// A pattern for the variant of `error`, like `MyError::Timeout { .. }`, to make the generated test check that the executable still fails with the same kind of error:
+    ///
+    /// ```rust,ignore
+    /// assert!(matches!(obj.execute(&args), Err(MyError::Timeout { .. })), "{} no longer fails with {}", obj.description(), "MyError::Timeout { .. }");
+    /// ```
+    ///
+    /// This is useful for enum errors whose inner data differs between runs. The pattern is inserted as-is, so the type of the error has to be nameable from the generated file. A custom [assertion](#method.assertion), an [expected](struct.UnitTest.html#method.with_expected) value and an [error message](struct.UnitTest.html#structfield.error_message) take precedence. The default implementation returns `None`, which keeps the default check.
+    ///
+    /// ```rust,ignore
+    /// fn error_pattern(&self, error: &MyError) -> Option<String> {
+    ///     match error {
+    ///         MyError::Timeout { .. } => Some(String::from("::my_crate::MyError::Timeout { .. }")),
+    ///         _ => None,
+    ///     }
+    /// }
+    /// ```
+    fn error_pattern(&self, _error: &Self::Error) -> Option<String> {
+        None
+    }
+
+    /// The seed of the random number generator that this run used, if the logic is randomized with an externally seeded RNG. A reproduction that does not reseed the RNG with the same seed exercises a different code path.
+    ///
+    /// If this returns `Some`, the seed is written to the comment of the generated test, and the test runs the [rng_setup](#method.rng_setup) statements before the executable. The seed is read from the state that is embedded in the test, i.e. from before the failing call. The default implementation returns `None`.
+    fn rng_seed(&self) -> Option<u64> {
+        None
+    }
+
+    /// Rust statements that seed the RNG in the generated test, before the executable runs. The [seed](#method.rng_seed) is in scope as `seed: u64`:
+    ///
+    /// ```rust,ignore
+    /// fn rng_setup(&self) -> Option<String> {
+    ///     Some(String::from("::my_crate::rng::reseed(seed);"))
+    /// }
+    /// ```
+    ///
+    /// The code is inserted as-is, like an [assertion](#method.assertion). It is only used if `rng_seed` returns `Some`. The default implementation returns `None`, which only records the seed in the comment.
+    fn rng_setup(&self) -> Option<String> {
+        None
+    }
+
+    /// Rust statements that the generated test runs first, before the executable and the arguments are deserialized, like setting up global state that `execute` depends on:
+    ///
+    /// ```rust,ignore
+    /// fn test_prelude(&self) -> Option<String> {
+    ///     Some(String::from("let _ = env_logger::try_init();"))
+    /// }
+    /// ```
+    ///
+    /// The code is inserted as-is, like an [assertion](#method.assertion). It runs on the thread of the test, also with a [timeout](struct.UnitTestOptions.html#method.timeout), so thread-local state that the executable reads is better seeded in [rng_setup](#method.rng_setup). The default implementation returns `None`.
+    fn test_prelude(&self) -> Option<String> {
+        None
+    }
+
+    /// Rust statements that the generated test runs last, after the outcome is checked, like cleaning up what the [prelude](#method.test_prelude) set up. The bindings of the test, like `obj` and `args`, are still in scope.
+    ///
+    /// The code is inserted as-is, like an [assertion](#method.assertion). It doesn't run if the check panics, so cleanup that has to happen anyway belongs in a guard that the prelude creates. The default implementation returns `None`.
+    fn test_epilogue(&self) -> Option<String> {
+        None
+    }
+
+    /// Rust items that the generated tests of this executable need once per file, like a helper function or a type alias, instead of once per test:
+    ///
+    /// ```rust,ignore
+    /// fn file_preamble(&self) -> Option<String> {
+    ///     Some(String::from("type Db = ::my_crate::Database<::my_crate::InMemory>;"))
+    /// }
+    /// ```
+    ///
+    /// [append_to_file](struct.UnitTest.html#method.append_to_file) writes the preamble near the top of the file, after the banner and the [table of contents](struct.UnitTestOptions.html#method.table_of_contents), between `// @exceptional-preamble path=<full_path>` and `// @exceptional-preamble-end` comments. If the file already has the preamble of this [full path](#tymethod.full_path), it is not written again, but replaced if it changed. Tests in a [target module](struct.UnitTestOptions.html#method.target_module) have to refer to the items with `super::`. The [writers](struct.StreamingWriter.html) don't write preambles. The default implementation returns `None`.
+    fn file_preamble(&self) -> Option<String> {
+        None
+    }
+
+    /// Serialize the thread-local context that the logic reads, like the current tenant or request id, which is neither in `self` nor in the arguments.
+    ///
+    /// This is called when the executable fails, on the thread that ran it, and the result is stored in the [context](struct.UnitTest.html#structfield.context) of the test. If it is `Some`, the generated test passes it to [restore_context](#method.restore_context) right before it runs the executable, on the same thread. Context that is set this way is dropped with the thread of the test, so no teardown is generated. [Grouped](fn.render_group.html) tests restore the context of their first test. The default implementation returns `None`, which generates no setup.
+    ///
+    /// ```rust,ignore
+    /// fn context_snapshot(&self) -> Option<String> {
+    ///     ::my_crate::TENANT.with(|tenant| tenant.borrow().clone())
+    /// }
+    ///
+    /// fn restore_context(context: &str) {
+    ///     ::my_crate::TENANT.with(|tenant| *tenant.borrow_mut() = Some(context.to_owned()));
+    /// }
+    /// ```
+    fn context_snapshot(&self) -> Option<String> {
+        None
+    }
+
+    /// Re-establish the thread-local context that [context_snapshot](#method.context_snapshot) serialized. The generated test calls this as `<Path as Executable>::restore_context("...")`. The default implementation does nothing.
+    fn restore_context(_context: &str) {}
+
+    /// How urgent a failure of this executable is. Tests with a severity other than [Medium](enum.Severity.html#variant.Medium) have it in their comment and marker, and can be put in a module per severity with [severity_modules](struct.UnitTestOptions.html#method.severity_modules). Use [tests_with_severity](fn.tests_with_severity.html) to list the tests of a file by severity.
+    ///
+    /// The default implementation returns `Medium`.
+    fn severity(&self) -> Severity {
+        Severity::Medium
+    }
+
+    /// Returns `false` for errors that should never generate a unit test, e.g. expected failures like a missing file. This is consulted by [execute_with_options](fn.execute_with_options.html) through the default [capture_decision](#method.capture_decision), together with the per-call [ExecuteOptions](struct.ExecuteOptions.html). [execute](fn.execute.html) always generates a test.
+    ///
+    /// The default implementation captures every error.
+    fn should_capture(&self, _error: &Self::Error) -> bool {
+        true
+    }
+
+    /// Decide whether an error generates a unit test, like [should_capture](#method.should_capture), with a reason. This is consulted by [execute_with_options](fn.execute_with_options.html) instead of `should_capture`.
+    ///
+    /// The reason of a capture is written to the header of the generated test, so the reproduction documents why it exists:
+    ///
+    /// ```rust,ignore
+    /// fn capture_decision(&self, error: &MyError) -> CaptureDecision {
+    ///     match error {
+    ///         MyError::NotFound => CaptureDecision::Skip { reason: Some(String::from("expected")) },
+    ///         MyError::Corrupt(_) => CaptureDecision::Capture { reason: Some(String::from("data corruption")) },
+    ///         _ => CaptureDecision::Capture { reason: None },
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// The reason of a skip is passed to the [on_skip](struct.ExecuteOptions.html#method.on_skip) hook. The default implementation captures the errors that `should_capture` accepts, without a reason.
+    fn capture_decision(&self, error: &Self::Error) -> CaptureDecision {
+        if self.should_capture(error) {
+            CaptureDecision::Capture { reason: None }
+        } else {
+            CaptureDecision::Skip { reason: None }
+        }
+    }
+
+    /// Bring the state and the arguments of a failure into a canonical form before they are captured, e.g. clear a timestamp or sort a list, so failures that only differ in such details get the same hash, and are deduplicated into one test.
+    ///
+    /// This is called on the copy of the executable from before it failed, and on a copy of the arguments, when the failure is wrapped in a [UnitTest](struct.UnitTest.html). `execute` itself always runs on the original input, so this only changes what is embedded in the generated test, hashed for its marker and compared for deduplication. The normalized input has to fail in the same way, because that is what the generated test executes.
+    ///
+    /// ```rust,ignore
+    /// fn normalize(&mut self, request: &mut Request) {
+    ///     self.last_seen = None;
+    ///     request.received_at = 0;
+    /// }
+    /// ```
+    ///
+    /// Borrowed arguments are copied by serializing and deserializing them. Arguments that don't deserialize into an equal value are captured as they are, without calling this, and fail to render with a serialization or [round-trip](enum.RenderError.html#variant.ArgumentsRoundTrip) error. The default implementation does nothing.
+    fn normalize(&mut self, _args: &mut Self::Arguments) {}
 }
 
 /// Execute the given [Executable](trait.Executable.html). If the Executable fails, this struct will wrap the Error in a [UnitTest](struct.UnitTest.html) struct. This UnitTest struct can then be appended to a file.
 ///
-/// Note: this always clones the given executable, because we need to store the state from before it failed. Make sure the `clone` impl is not too heavy.
+/// Note: this always clones the given executable, because we need to store the state from before it failed. Make sure the `clone` impl is not too heavy, or use [execute_with](fn.execute_with.html) to only build the state when the executable fails.
+///
+/// The location from which `execute` is called is stored in the [UnitTest](struct.UnitTest.html#structfield.location) and mentioned in the generated test. The current time is recorded as the [time](struct.UnitTest.html#structfield.time) of the failure; use [execute_at](fn.execute_at.html) or [execute_with_clock](fn.execute_with_clock.html) for a fixed time.
+#[track_caller]
 pub fn execute<'a, E: Executable + 'a>(
     executable: &'a mut E,
     arguments: &'a E::Arguments,
@@ -109,81 +445,616 @@ pub fn execute<'a, E: Executable + 'a>(
     let old = executable.clone();
     match executable.execute(arguments) {
         Ok(value) => Ok(value),
-        Err(error) => Err(UnitTest {
+        Err(error) => Err(UnitTest::new(old, MaybeOwned::Borrowed(arguments), error)),
+    }
+}
+
+/// Like [execute](fn.execute.html), but records `time` as the time of the failure instead of the current time.
+///
+/// The time determines the default id and the name of the generated function, so this makes the output deterministic, e.g. in golden tests of generated files. It is the same as calling [with_time](struct.UnitTest.html#method.with_time) on the test.
+#[track_caller]
+pub fn execute_at<'a, E: Executable + 'a, Tz: TimeZone>(
+    executable: &'a mut E,
+    arguments: &'a E::Arguments,
+    time: DateTime<Tz>,
+) -> Result<E::Result, UnitTest<'a, E>> {
+    match execute(executable, arguments) {
+        Ok(value) => Ok(value),
+        Err(test) => Err(test.with_time(time)),
+    }
+}
+
+/// Like [execute_at](fn.execute_at.html), but only asks `clock` for the time if the executable fails. A clock that counts up, like in this test of a generator, gives every failure a different time, and the output is the same on every run:
+///
+/// ```rust,ignore
+/// let mut millis = 1_539_761_233_543;
+/// for args in &inputs {
+///     let clock = || {
+///         millis += 1;
+///         Utc.timestamp_millis_opt(millis).unwrap()
+///     };
+///     if let Err(test) = exceptional::execute_with_clock(&mut action, args, clock) {
+///         suite.push(test);
+///     }
+/// }
+/// assert_eq!(suite.iter().collect::<String>(), include_str!("expected.rs"));
+/// ```
+#[track_caller]
+pub fn execute_with_clock<'a, E, C>(
+    executable: &'a mut E,
+    arguments: &'a E::Arguments,
+    clock: C,
+) -> Result<E::Result, UnitTest<'a, E>>
+where
+    E: Executable + 'a,
+    C: FnOnce() -> DateTime<Utc>,
+{
+    match execute(executable, arguments) {
+        Ok(value) => Ok(value),
+        Err(test) => Err(test.with_time(clock())),
+    }
+}
+
+/// Like [execute](fn.execute.html), but takes the arguments by value, so the test owns them, e.g. when they are built just for this call:
+///
+/// ```rust,ignore
+/// let request = Request::builder().size(10).retries(2).build();
+/// if let Err(test) = exceptional::execute_consuming(&mut upload, request) {
+///     test.append_to_file("src/regressions.rs").unwrap();
+/// }
+/// ```
+///
+/// The arguments are not cloned: `execute` only borrows them, and they are moved into the [OwnedUnitTest](type.OwnedUnitTest.html) if it fails, or dropped if it succeeds.
+#[track_caller]
+pub fn execute_consuming<E: Executable + 'static>(
+    executable: &mut E,
+    arguments: E::Arguments,
+) -> Result<E::Result, OwnedUnitTest<E>> {
+    let old = executable.clone();
+    match executable.execute(&arguments) {
+        Ok(value) => Ok(value),
+        Err(error) => Err(UnitTest::new(old, MaybeOwned::Owned(arguments), error)),
+    }
+}
+
+/// Like [execute](fn.execute.html), but doesn't clone the executable before it runs. If it fails, `snapshot` is called with the executable to produce the state that the test is generated from, so the success path does no cloning at all, e.g. in a hot loop or for an executable with an expensive `Clone`:
+///
+/// ```rust,ignore
+/// let result = exceptional::execute_with(&mut parser, &input, |parser| Parser {
+///     config: parser.config.clone(),
+///     ..Parser::default()
+/// });
+/// ```
+///
+/// `snapshot` sees the executable after it failed, not before it ran. It must rebuild the state from before the call, or the generated test starts from a different state than the one that failed. This is only as simple as a clone for executables that don't change their own state, and `.clone()` is a fine snapshot for those.
+#[track_caller]
+pub fn execute_with<'a, E, F>(
+    executable: &'a mut E,
+    arguments: &'a E::Arguments,
+    snapshot: F,
+) -> Result<E::Result, UnitTest<'a, E>>
+where
+    E: Executable + 'a,
+    F: FnOnce(&E) -> E,
+{
+    match executable.execute(arguments) {
+        Ok(value) => Ok(value),
+        Err(error) => Err(UnitTest::new(
+            snapshot(executable),
+            MaybeOwned::Borrowed(arguments),
             error,
-            arguments,
-            executable: old,
-            time: Utc::now(),
-        }),
+        )),
     }
 }
 
+/// [Normalize](trait.Executable.html#method.normalize) the executable and a copy of the arguments of a failure.
+fn normalized<E: Executable>(
+    mut executable: E,
+    arguments: MaybeOwned<E::Arguments>,
+) -> (E, MaybeOwned<E::Arguments>) {
+    let mut arguments = match arguments {
+        MaybeOwned::Owned(arguments) => arguments,
+        MaybeOwned::Borrowed(borrowed) => match round_trip::copy(borrowed) {
+            Some(copy) => copy,
+            None => return (executable, MaybeOwned::Borrowed(borrowed)),
+        },
+    };
+    executable.normalize(&mut arguments);
+    (executable, MaybeOwned::Owned(arguments))
+}
+
+/// A [UnitTest](struct.UnitTest.html) that owns its arguments, so it does not borrow from the code that executed it.
+pub type OwnedUnitTest<E> = UnitTest<'static, E>;
+
 /// A unit-test-in-making. This wraps the Executable that failed, the arguments used, the actual error that was thrown, and when it happened.
 pub struct UnitTest<'a, E: Executable + 'a> {
     /// The error that was thrown when the Executable failed
     pub error: E::Error,
 
-    /// The arguments that were provided that caused the Executable to fail
-    pub arguments: &'a E::Arguments,
+    /// The arguments that were provided that caused the Executable to fail. These are borrowed from the caller of [execute](fn.execute.html), and owned by tests from [execute_all](fn.execute_all.html).
+    pub arguments: MaybeOwned<'a, E::Arguments>,
 
     /// The executable that failed, with the state from before it failed.
     pub executable: E,
 
-    /// The time at which this executable failed
+    /// The time at which this executable failed.
+    ///
+    /// This is always stored in UTC with millisecond precision, so the generated test name and the `generated at` comment do not depend on the timezone of the machine that generated it, and the id, the marker comment and the header all describe the same instant.
     pub time: DateTime<Utc>,
+
+    /// The source location from which the executable was executed, e.g. the call to [execute](fn.execute.html)
+    pub location: Option<&'static Location<'static>>,
+
+    /// The message of the error, for errors that are better described by their message than by their `Debug` output. This is set by [execute_boxed_err](fn.execute_boxed_err.html).
+    ///
+    /// If this is set, the generated test checks that the executable still fails with this message, instead of checking that it succeeds.
+    pub error_message: Option<String>,
+
+    /// The message of the panic, if the executable panicked instead of returning an error. This is set by [execute_catch_unwind](fn.execute_catch_unwind.html).
+    ///
+    /// If this is set, the generated test checks that the executable panics again with this message, instead of checking that it succeeds. An empty message only checks that it panics.
+    pub panic_message: Option<String>,
+
+    /// How the error is described in the comment of the generated test. See [with_error_render](#method.with_error_render).
+    pub error_render: ErrorRender,
+
+    /// The `Display` output of the error, if it was captured with [with_error_render](#method.with_error_render).
+    pub error_display: Option<String>,
+
+    /// Whether the generated test prints the error with `Display`. See [prefer_display](#method.prefer_display).
+    prefer_display: bool,
+
+    /// The expected `Ok` value as JSON, for golden-value tests. See [with_expected](#method.with_expected).
+    pub expected: Option<String>,
+
+    /// The error as JSON, if the generated test asserts that the executable fails with exactly this error. See [with_expected_error](#method.with_expected_error).
+    pub expected_error: Option<String>,
+
+    /// The serialized `Default::default()` of the executable, if the generated test constructs the executable from its default. See [with_default_overrides](#method.with_default_overrides).
+    pub default_json: Option<String>,
+
+    /// The thread-local context at the time of the failure, if the executable has a [context snapshot](trait.Executable.html#method.context_snapshot).
+    pub context: Option<String>,
+
+    /// The process-global state at the time of the failure, if it was captured with [capture_globals](#method.capture_globals).
+    pub globals: Option<Globals>,
+
+    /// Why the failure was captured, from the [capture decision](trait.Executable.html#method.capture_decision) of the executable. It is written to the header of the generated test as a `/// captured because: <reason>` line.
+    pub capture_reason: Option<String>,
+
+    /// The state of the executable after it failed, as JSON, if it was captured with [with_state_diff](struct.ExecuteOptions.html#method.with_state_diff). The header of the generated test lists the values that the failing run changed, like `/// changed during the run: balance: 10 -> -5`.
+    pub post_state: Option<String>,
+
+    /// Custom key/value data, like the owner or severity of a failure, that is written to the header of the generated test as `/// meta: key = value` lines, sorted by key. See [with_metadata](#method.with_metadata).
+    pub metadata: BTreeMap<String, String>,
+
+    /// The options that control how this test is rendered
+    pub options: UnitTestOptions,
+}
+
+impl<E: Executable + 'static> UnitTest<'static, E> {
+    /// Wrap the error of a `result` that was produced without [execute](fn.execute.html), e.g. by code that already ran or that only has a `&self`.
+    ///
+    /// `executable` must be a snapshot of the state from before the code ran, and `arguments` the arguments it was called with, because the generated test executes that state again. The location of this call is stored in the test.
+    ///
+    /// ```rust,ignore
+    /// let snapshot = action.clone();
+    /// let result = action.execute(&args);
+    /// if let Err(test) = exceptional::UnitTest::from_result(snapshot, args, result) {
+    ///     test.append_to_file("src/test.rs").expect("Could not write unit test");
+    /// }
+    /// ```
+    #[track_caller]
+    pub fn from_result<T>(
+        executable: E,
+        arguments: E::Arguments,
+        result: Result<T, E::Error>,
+    ) -> Result<T, Self> {
+        match result {
+            Ok(value) => Ok(value),
+            Err(error) => Err(UnitTest::new(
+                executable,
+                MaybeOwned::Owned(arguments),
+                error,
+            )),
+        }
+    }
 }
 
 impl<'a, E: Executable + 'a> UnitTest<'a, E> {
+    /// Wrap a failure that happened just now, at the location of the caller, with the default options.
+    #[track_caller]
+    pub(crate) fn new(
+        executable: E,
+        arguments: MaybeOwned<'a, E::Arguments>,
+        error: E::Error,
+    ) -> Self {
+        let (executable, arguments) = normalized(executable, arguments);
+        let context = executable.context_snapshot();
+        UnitTest {
+            error,
+            arguments,
+            executable,
+            time: Utc::now().trunc_subsecs(3),
+            location: Some(Location::caller()),
+            error_message: None,
+            panic_message: None,
+            error_render: ErrorRender::Debug,
+            error_display: None,
+            prefer_display: false,
+            expected: None,
+            expected_error: None,
+            default_json: None,
+            context,
+            globals: None,
+            capture_reason: None,
+            post_state: None,
+            metadata: BTreeMap::new(),
+            options: UnitTestOptions::default(),
+        }
+    }
+
+    /// Replace the options that control how this test is rendered.
+    pub fn with_options(mut self, options: UnitTestOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// The options that control how this test is rendered, to change some of them in place:
+    ///
+    /// ```rust,ignore
+    /// test.options_mut().verbose_body = false;
+    /// ```
+    ///
+    /// Fields that are written directly skip the checks of their setters, so they are checked again when the test is rendered: a value that the setter would reject, like an `obj_binding` that is a keyword, fails to render with [InvalidOption](enum.RenderError.html#variant.InvalidOption) instead of producing a test that doesn't compile.
+    pub fn options_mut(&mut self) -> &mut UnitTestOptions {
+        &mut self.options
+    }
+
+    /// Replace the time at which this executable failed. The time is converted to UTC and truncated to milliseconds first, see [time](#structfield.time).
+    pub fn with_time<Tz: TimeZone>(mut self, time: DateTime<Tz>) -> Self {
+        self.time = time.with_timezone(&Utc).trunc_subsecs(3);
+        self
+    }
+
+    /// Capture the current directory and the given environment variables, so the generated test runs with the same values. Call this right after the executable failed, before the state changes.
+    ///
+    /// The values are only used if [restore_globals](struct.UnitTestOptions.html#method.restore_globals) is enabled.
+    pub fn capture_globals(mut self, env_names: &[&str]) -> Self {
+        self.globals = Some(Globals::capture(env_names));
+        self
+    }
+
+    /// Attach a key/value pair to this test, replacing any earlier value for the same key.
+    ///
+    /// ```rust,ignore
+    /// test.with_metadata("owner", "payments-team")
+    ///     .with_metadata("severity", "high")
+    ///     .append_to_file("src/regressions.rs")?;
+    /// ```
+    ///
+    /// Line breaks and other control characters in the key and value are escaped, so they can't end the comment.
+    pub fn with_metadata(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.metadata.insert(key.into(), value.into());
+        self
+    }
+
+    /// The error that was thrown when the Executable failed.
+    ///
+    /// Rendering only borrows the error, so it is still available after the test has been generated, e.g. with `to_string()`.
+    pub fn error_ref(&self) -> &E::Error {
+        &self.error
+    }
+
+    /// Consume this unit test and return the error, e.g. to propagate it after the test has been rendered.
+    pub fn into_error(self) -> E::Error {
+        self.error
+    }
+
     /// Append this unit test to a file.
+    ///
+    /// # Names
+    ///
+    /// If the file already has a test with the same id, e.g. of another failure in the same millisecond, the id is made unique with [unique_id](#method.unique_id), so the file keeps compiling.
+    ///
+    /// # Locking
+    ///
+    /// With the `locking` feature enabled, an exclusive advisory lock is taken on the file before writing and released afterwards, so several processes (e.g. parallel CI shards) appending to the same file don't interleave their tests. Waiting for the lock blocks the current thread.
+    ///
+    /// The lock is advisory: it only serializes writers that also take it, like other processes using this crate. It uses `flock` on Unix and `LockFileEx` on Windows, through the [fs2](https://docs.rs/fs2) crate. Some network filesystems (e.g. older NFS setups) do not support these locks, in which case the append fails with the error reported by the OS.
+    ///
+    /// # Banner
+    ///
+    /// If a [banner](struct.UnitTestOptions.html#method.banner) is configured, it is written at the top of the file before the test, unless the file already starts with it.
+    ///
+    /// # Insertion marker
+    ///
+    /// If [use_insertion_marker](struct.UnitTestOptions.html#method.use_insertion_marker) is enabled and the file contains a line with only the [insertion marker](constant.INSERTION_MARKER.html), the test is inserted right above that line instead of at the end of the file, indented like the marker. The marker stays in place for the next test.
+    ///
+    /// # Target module
+    ///
+    /// If a [target module](struct.UnitTestOptions.html#method.target_module) is configured, the test is added at the end of that module, which is created if needed. With [severity_modules](struct.UnitTestOptions.html#method.severity_modules), the module is named after the severity of the executable instead.
+    ///
+    /// # Upsert
+    ///
+    /// If [upsert](struct.UnitTestOptions.html#method.upsert) is enabled, a test that was already generated for the same executable state and arguments is replaced if its error changed, and kept as is otherwise.
+    ///
+    /// # Sidecar files
+    ///
+    /// If a [sidecar directory](struct.UnitTestOptions.html#method.sidecar_dir) is configured, the serialized executable and arguments are written to that directory, relative to the directory of `file`, before the test is added.
+    ///
+    /// # Fallback directory
+    ///
+    /// If a [fallback directory](struct.UnitTestOptions.html#method.fallback_dir) is configured and `file` is read-only or locked, the test is written to a file with the same name in that directory instead.
+    ///
+    /// # Errors
+    ///
+    /// If the test can not be rendered, e.g. because the executable or the arguments can not be serialized, the [RenderError](enum.RenderError.html) is returned as an error of kind `InvalidData`, and the file is not touched. Use [try_append_to_file](#method.try_append_to_file) to get the `RenderError` itself.
     pub fn append_to_file(self, file: impl AsRef<Path>) -> io::Result<()> {
-        let mut file = OpenOptions::new().create(true).append(true).open(file)?;
-        let text = self.to_string();
-        file.write_all(text.as_bytes())?;
-        Ok(())
+        self.append_to_file_ref(file)
+    }
+
+    /// Like [append_to_file](#method.append_to_file), but only borrows this unit test, so it can be written to several files, or sent somewhere else afterwards:
+    ///
+    /// ```rust,ignore
+    /// if let Err(test) = exceptional::execute(&mut action, &args) {
+    ///     test.append_to_file_ref("src/regressions.rs")?;
+    ///     let report: UnitTestReport = Result::from(&test)?;
+    ///     service.send(&report)?;
+    /// }
+    /// ```
+    ///
+    /// A test that can not be rendered is an error of kind `InvalidData`, like for `append_to_file`.
+    pub fn append_to_file_ref(&self, file: impl AsRef<Path>) -> io::Result<()> {
+        let snapshot = self
+            .snapshot()
+            .map_err(|e| AppendError::Render(e).into_io())?;
+        self.append_snapshot(file.as_ref(), &snapshot)
+            .map(|_| ())
+            .map_err(AppendError::into_io)
+    }
+
+    /// Like [append_to_file](#method.append_to_file), but only if `file` doesn't have a test with the same [fingerprint](#method.fingerprint) yet, i.e. for the same executable, arguments and error. Returns `false` if the test was skipped.
+    ///
+    /// The markers are read while the file is locked, so two processes that capture the same failure at the same time don't both add it. This reads the whole file on every call. A loop that hits the same failures many times can keep a [MarkerIndex](struct.MarkerIndex.html) or use a [StreamingWriter](struct.StreamingWriter.html) instead, which only read the file once.
+    pub fn append_to_file_dedup(self, file: impl AsRef<Path>) -> io::Result<bool> {
+        let fingerprint = self
+            .fingerprint()
+            .map_err(|e| AppendError::Render(e).into_io())?;
+        let snapshot = self
+            .snapshot()
+            .map_err(|e| AppendError::Render(e).into_io())?;
+        self.append_snapshot_with(file.as_ref(), &snapshot, false, Some(&fingerprint))
+            .map(|(_, written)| written)
+            .map_err(AppendError::into_io)
+    }
+
+    /// Like [append_to_file](#method.append_to_file), but returns an [AppendError](enum.AppendError.html) that tells a read-only or locked file and a render error apart from other failures, instead of a plain IO error.
+    ///
+    /// Returns the file that the test was written to, which is in the [fallback directory](struct.UnitTestOptions.html#method.fallback_dir) if `file` could not be written. If the fallback fails as well, its error is returned.
+    pub fn try_append_to_file(self, file: impl AsRef<Path>) -> Result<PathBuf, AppendError> {
+        let snapshot = self.snapshot().map_err(AppendError::Render)?;
+        self.append_snapshot(file.as_ref(), &snapshot)
+    }
+
+    /// Append this unit test to the module with the given name in a file, which is created with a `use super::*;` and the imports of the tests at its top if the file doesn't have it yet. The tests in the module don't import anything themselves:
+    ///
+    /// ```text
+    /// /// Regression tests that were generated from failures.
+    /// #[allow(missing_docs)]
+    /// mod regressions {
+    ///     #[allow(unused_imports)]
+    ///     use super::*;
+    ///     #[allow(unused_imports)]
+    ///     use exceptional::Executable;
+    ///
+    ///     // @exceptional id=1539761233543 ...
+    ///     #[test]
+    ///     pub fn test_1539761233543() {
+    ///     ...
+    /// ```
+    ///
+    /// An existing module gets the imports that it is missing, once, like a module that was created as a [target module](struct.UnitTestOptions.html#method.target_module) by an earlier version. The file is created if it doesn't exist. The test is placed like in [append_to_file](#method.append_to_file) with `mod_name` as the target module, except that it always goes into that module: [severity modules](struct.UnitTestOptions.html#method.severity_modules) and the [insertion marker](struct.UnitTestOptions.html#method.use_insertion_marker) are not used, and an [upserted](struct.UnitTestOptions.html#method.upsert) test is only replaced if it is in the module.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mod_name` is not a valid module name. A test that can not be rendered is an error of kind `InvalidData`, like for `append_to_file`.
+    pub fn append_to_module(self, file: impl AsRef<Path>, mod_name: &str) -> io::Result<()> {
+        let mut options = self.options.clone().target_module(mod_name);
+        options.severity_modules = false;
+        let test = self.with_options(options);
+        let snapshot = test
+            .snapshot()
+            .map_err(|e| AppendError::Render(e).into_io())?;
+        test.append_snapshot_with(file.as_ref(), &snapshot, true, None)
+            .map(|_| ())
+            .map_err(AppendError::into_io)
+    }
+
+    /// Write the sidecar files and the test for `snapshot` to `file`, or to the fallback directory if `file` can't be written.
+    fn append_snapshot(&self, file: &Path, snapshot: &Snapshot) -> Result<PathBuf, AppendError> {
+        self.append_snapshot_with(file, snapshot, false, None)
+            .map(|(file, _)| file)
     }
-}
 
-impl<'a, E: Executable + 'a> std::fmt::Display for UnitTest<'a, E> {
-    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
-        writeln!(
-            fmt,
-            "/// Automatically generated unit test for Executable\n"
-        )?;
-        writeln!(fmt, "/// {}", self.executable.description())?;
-        writeln!(fmt, "/// generated at {}", self.time.to_rfc2822())?;
-        writeln!(fmt)?;
-        writeln!(fmt, "/// exception was {:?}", self.error)?;
-        writeln!(fmt, "#[test]")?;
-        writeln!(fmt, "pub fn test_{}() {{", self.time.timestamp_millis())?;
-        writeln!(fmt, "\tuse exceptional::Executable;")?;
-        writeln!(
-            fmt,
-            "\tlet obj_json = r#\"{}\"#;",
-            serde_json::to_string_pretty(&self.executable)
-                .expect("Could not serialize the executable")
-        )?;
-        writeln!(fmt, "\tlet mut obj: {} = ::serde_json::from_str(obj_json).expect(\"Could not deserialize json\");", self.executable.full_path())?;
-        writeln!(fmt, "\t")?;
-        writeln!(
-            fmt,
-            "\tlet arg_json = r#\"{}\"#;",
-            serde_json::to_string_pretty(&self.arguments).expect("Could not serialize arguments")
-        )?;
-        writeln!(
-            fmt,
-            "\tlet args = ::serde_json::from_str(arg_json).expect(\"Could not deserialize json\");"
-        )?;
-        writeln!(fmt)?;
-        writeln!(fmt, "\tif let Err(e) = obj.execute(&args) {{")?;
-        writeln!(
-            fmt,
-            "\t\tprintln!(\"Could not execute {{}}\", obj.description());"
-        )?;
-        writeln!(fmt, "\t\tprintln!(\"{{:?}}\", e);")?;
-        writeln!(fmt, "\t\tpanic!();")?;
-        writeln!(fmt, "\t}}")?;
-        writeln!(fmt, "}}")?;
+    /// Like `append_snapshot`, but with the imports of the test at the top of the target module instead of in the test if `shared_imports` is set, and nothing is written if the file already has a test with the given fingerprint. Returns whether the test was written, next to the file.
+    fn append_snapshot_with(
+        &self,
+        file: &Path,
+        snapshot: &Snapshot,
+        shared_imports: bool,
+        fingerprint: Option<&str>,
+    ) -> Result<(PathBuf, bool), AppendError> {
+        let error = match self.append_snapshot_at(file, snapshot, shared_imports, fingerprint) {
+            Ok(written) => return Ok((file.to_owned(), written)),
+            Err(error) => error,
+        };
+        match self.options.fallback_dir {
+            Some(ref dir) if error.can_fall_back() => {
+                let dir = Path::new(dir);
+                fs::create_dir_all(dir).map_err(|e| AppendError::from_io(dir.to_owned(), e))?;
+                let fallback = dir.join(file.file_name().unwrap_or_else(|| OsStr::new("tests.rs")));
+                let written =
+                    self.append_snapshot_at(&fallback, snapshot, shared_imports, fingerprint)?;
+                Ok((fallback, written))
+            }
+            _ => Err(error),
+        }
+    }
+
+    /// The ids in `file` are read and the test is written while the file is locked, so a test that another process adds at the same time can't end up with the same id.
+    fn append_snapshot_at(
+        &self,
+        file: &Path,
+        snapshot: &Snapshot,
+        shared_imports: bool,
+        fingerprint: Option<&str>,
+    ) -> Result<bool, AppendError> {
+        let mut written = false;
+        file::try_edit(file, self.options.sync_on_write, |existing| {
+            if let Some(fingerprint) = fingerprint {
+                if parse::parse_markers(existing)
+                    .iter()
+                    .any(|marker| marker.fingerprint() == fingerprint)
+                {
+                    return Ok(existing.to_owned());
+                }
+            }
+            let mut snapshot = Cow::Borrowed(snapshot);
+            let id = render::unique_id(&snapshot, &self.taken_ids(existing, &snapshot));
+            if id != snapshot.id {
+                snapshot.to_mut().id = id;
+            }
+            if let Some(ref dir) = self.options.sidecar_dir {
+                sidecar::write(file, dir, &snapshot)?;
+            }
+            let text = if shared_imports {
+                self.render_without_imports(&snapshot)
+            } else {
+                let mut text = String::new();
+                self.render_snapshot_into(&mut text, &snapshot);
+                text
+            };
+            written = true;
+            Ok(self.place_text(existing, &text, shared_imports))
+        })
+        .and_then(|()| {
+            if written {
+                self.update_meta_index(file)?;
+            }
+            Ok(written)
+        })
+        .map_err(|e| AppendError::from_io(file.to_owned(), e))
+    }
+
+    /// The test for `snapshot`, without the imports in its body, for a module that has them at its top.
+    fn render_without_imports(&self, snapshot: &Snapshot) -> String {
+        let mut text = String::new();
+        self.render_snapshot_into(&mut text, snapshot);
+        let mut imports = String::new();
+        self.write_imports(&mut imports)
+            .expect("Writing to a String cannot fail");
+        if let Some(ending) = self.options.line_ending {
+            imports = ending.apply(&imports).into_owned();
+        }
+        text.replacen(&imports, "", 1)
+    }
+
+    /// The ids of the tests in `src` that the test of `snapshot` must not reuse. With [upsert](struct.UnitTestOptions.html#method.upsert), the test that it replaces is not counted.
+    fn taken_ids(&self, src: &str, snapshot: &Snapshot) -> HashSet<String> {
+        parse::parse_markers(src)
+            .into_iter()
+            .filter(|marker| !(self.options.upsert && marker.hash == snapshot.hash))
+            .map(|marker| marker.id)
+            .collect()
+    }
+
+    /// Append the output of `renderer` for this unit test to a file, instead of the generated `#[test]`.
+    ///
+    /// The output is placed like in [append_to_file](#method.append_to_file): with the configured banner, at the insertion marker, and replacing an earlier version if [upsert](struct.UnitTestOptions.html#method.upsert) is enabled and the output starts with a marker comment. Sidecar files are not written. If the renderer fails, an error of kind `InvalidData` is returned and the file is not touched.
+    pub fn append_to_file_with<R: Renderer>(
+        self,
+        file: impl AsRef<Path>,
+        renderer: &R,
+    ) -> io::Result<()> {
+        let text = renderer
+            .render(&self)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        self.write_text(file.as_ref(), &text, false)
+    }
+
+    /// Place already rendered text in `file`, according to the options. With `shared_imports`, the text goes into the target module, which gets the imports of the test.
+    fn write_text(&self, file: &Path, text: &str, shared_imports: bool) -> io::Result<()> {
+        if self.needs_contents() {
+            file::edit(file, self.options.sync_on_write, |existing| {
+                self.place_text(existing, text, shared_imports)
+            })?;
+        } else {
+            let ending = match self.options.line_ending {
+                Some(ending) => Some(ending),
+                None => file::line_ending(file)?,
+            };
+            let text = match ending {
+                Some(ending) => ending.apply(text),
+                None => Cow::Borrowed(text),
+            };
+            file::append(file, text.as_bytes(), self.options.sync_on_write)?;
+        }
+        self.update_meta_index(file)
+    }
+
+    /// The options that place a test in a file, with the target module of the severity of the executable if [severity_modules](struct.UnitTestOptions.html#method.severity_modules) is enabled.
+    fn placement_options(&self) -> Cow<'_, UnitTestOptions> {
+        if self.options.severity_modules {
+            let mut options = self.options.clone();
+            options.target_module = self.target_module().map(str::to_owned);
+            Cow::Owned(options)
+        } else {
+            Cow::Borrowed(&self.options)
+        }
+    }
+
+    /// Whether placing a test needs the current contents of the file, instead of only appending to it.
+    fn needs_contents(&self) -> bool {
+        insert::needs_contents(&self.placement_options())
+            || self.executable.file_preamble().is_some()
+    }
+
+    /// The contents of a file after placing already rendered text in its `existing` contents, like [write_text](#method.write_text).
+    fn place_text(&self, existing: &str, text: &str, shared_imports: bool) -> String {
+        let options = self.placement_options();
+        let edited = if self.needs_contents() {
+            let preamble = self
+                .executable
+                .file_preamble()
+                .map(|preamble| (self.executable.full_path(), preamble));
+            let preamble = preamble
+                .as_ref()
+                .map(|&(path, ref preamble)| (path, preamble.as_str()));
+            let imports = insert::module_imports(options.async_test.is_some());
+            let imports = if shared_imports {
+                Some(&imports[..])
+            } else {
+                None
+            };
+            insert::insert_test(existing, text, &options, preamble, imports)
+        } else {
+            format!("{}{}", existing, text)
+        };
+        match options.line_ending.or_else(|| LineEnding::detect(existing)) {
+            Some(ending) => ending.apply(&edited).into_owned(),
+            None => edited,
+        }
+    }
 
+    /// Update the [meta index](struct.UnitTestOptions.html#method.meta_index) next to `file` for this test, if it is enabled.
+    fn update_meta_index(&self, file: &Path) -> io::Result<()> {
+        if self.options.meta_index {
+            let entry = self
+                .index_entry()
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            index::update(file, Some(entry))?;
+        }
         Ok(())
     }
 }
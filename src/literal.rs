@@ -0,0 +1,28 @@
+use serde_json::{Map, Value};
+
+/// The fields of the JSON object `json`, as pairs of field name and compact JSON value, sorted by name.
+///
+/// Returns `None` if `json` is not an object, or if a field name is not a valid identifier, so the value can't be written as a struct literal.
+pub(crate) fn struct_fields(json: &str) -> Option<Vec<(String, String)>> {
+    let object: Map<String, Value> = ::serde_json::from_str(json).ok()?;
+    object
+        .into_iter()
+        .map(|(field, value)| {
+            if ::options::is_identifier(&field) {
+                Some((field, value.to_string()))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// `text` as a raw string literal, with as many `#` as it takes for the literal not to end inside of `text`, and at least one, like `r#"..."#`.
+pub(crate) fn raw_string(text: &str) -> String {
+    let mut hashes = 1;
+    while text.contains(&format!("\"{}", "#".repeat(hashes))) {
+        hashes += 1;
+    }
+    let delimiter = "#".repeat(hashes);
+    format!("r{}\"{}\"{}", delimiter, text, delimiter)
+}
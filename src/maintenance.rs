@@ -0,0 +1,296 @@
+use parse::{self, SnapshotSource};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Split a file of generated tests into one file per executable type.
+///
+/// The tests in `src_file` are grouped by the [full path](trait.Executable.html#tymethod.full_path) recorded in their marker comment. Every group is written to `<out_dir>/<type>.rs`, where `<type>` is the full path converted to a snake case module name, followed by a short hash of the full path, e.g. `::my_crate::SomeImportantAction` becomes `my_crate_some_important_action_<hash>`. The hash keeps paths apart that convert to the same name, like `::my_crate::HttpClient` and `::my_crate::Http_Client`, and doesn't change between runs, so splitting a file again writes to the same modules. A `mod.rs` that declares all these modules is written next to them, so `out_dir` can replace the original file as a module.
+///
+/// The tests are copied byte-for-byte, in the order in which they appear in `src_file`. Anything in `src_file` that is not a generated test (hand-written code, imports, tests generated before markers were emitted) is not copied. Existing files in `out_dir` with the same names are overwritten, and `src_file` itself is left untouched.
+///
+/// Returns the paths of the per-type files that were written.
+pub fn resplit_by_type(
+    src_file: impl AsRef<Path>,
+    out_dir: impl AsRef<Path>,
+) -> io::Result<Vec<PathBuf>> {
+    let src = fs::read_to_string(src_file)?;
+    let out_dir = out_dir.as_ref();
+
+    let mut groups: BTreeMap<String, String> = BTreeMap::new();
+    for item in parse::find_items(&src) {
+        groups
+            .entry(module_name(&item.marker.path))
+            .or_default()
+            .push_str(&src[item.range]);
+    }
+
+    fs::create_dir_all(out_dir)?;
+    let mut written = Vec::with_capacity(groups.len());
+    let mut mod_rs = String::new();
+    for (module, tests) in &groups {
+        let path = out_dir.join(format!("{}.rs", module));
+        fs::write(&path, tests)?;
+        mod_rs.push_str(&format!("mod {};\n", module));
+        written.push(path);
+    }
+    fs::write(out_dir.join("mod.rs"), mod_rs)?;
+    Ok(written)
+}
+
+/// The line before the module declarations that [regenerate_suite](fn.regenerate_suite.html) writes.
+const SUITE_START: &str = "// @exceptional-suite\n";
+
+/// The line after the module declarations of a suite.
+const SUITE_END: &str = "// @exceptional-suite-end\n";
+
+/// Declare every file of generated tests in `dir` as a module in `<dir>/mod.rs`, so the whole directory compiles as one module, e.g. after tests were written to one file per type or [split](fn.resplit_by_type.html) into them.
+///
+/// A file is part of the suite if its name is a valid module name and it contains at least one generated test, so hand-written helpers in the same directory are left for `mod.rs` to declare by hand. The declarations are sorted by name and written between `// @exceptional-suite` and `// @exceptional-suite-end` comments. Everything else in `mod.rs` is kept, except plain `mod <name>;` lines of modules that the suite declares, like the ones written by `resplit_by_type`. `mod.rs` is only written if it changes, so regenerating an unchanged suite does nothing.
+///
+/// ```rust,ignore
+/// exceptional::regenerate_suite("src/regressions")?;
+/// ```
+///
+/// Returns the names of the modules of the suite.
+pub fn regenerate_suite(dir: impl AsRef<Path>) -> io::Result<Vec<String>> {
+    let dir = dir.as_ref();
+    let mut modules = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let name = match (path.file_stem(), path.extension()) {
+            (Some(stem), Some(extension)) if extension == "rs" => stem.to_str(),
+            _ => None,
+        };
+        let name = match name {
+            Some(name) if name != "mod" && ::options::is_identifier(name) => name,
+            _ => continue,
+        };
+        if !path.is_file() {
+            continue;
+        }
+        let src = match fs::read_to_string(&path) {
+            Ok(src) => src,
+            Err(ref e) if e.kind() == io::ErrorKind::InvalidData => continue,
+            Err(e) => return Err(e),
+        };
+        if !parse::find_items(&src).is_empty() {
+            modules.push(name.to_owned());
+        }
+    }
+    modules.sort();
+
+    let mut block = String::from(SUITE_START);
+    for module in &modules {
+        block.push_str(&format!("mod {};\n", module));
+    }
+    block.push_str(SUITE_END);
+
+    let mod_rs = dir.join("mod.rs");
+    let existing = match fs::read_to_string(&mod_rs) {
+        Ok(src) => src,
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e),
+    };
+    let suite_start = existing
+        .match_indices(SUITE_START)
+        .map(|(i, _)| i)
+        .find(|&i| i == 0 || existing[..i].ends_with('\n'));
+    let new = match suite_start {
+        Some(start) => {
+            let end = match existing[start..].find(SUITE_END) {
+                Some(n) => start + n + SUITE_END.len(),
+                None => existing.len(),
+            };
+            format!("{}{}{}", &existing[..start], block, &existing[end..])
+        }
+        None => {
+            let mut kept: String = existing
+                .split_inclusive('\n')
+                .filter(|line| {
+                    let line = line.trim();
+                    !modules
+                        .iter()
+                        .any(|module| line == format!("mod {};", module))
+                })
+                .collect();
+            if !kept.trim().is_empty() {
+                if !kept.ends_with('\n') {
+                    kept.push('\n');
+                }
+                kept.push('\n');
+            } else {
+                kept.clear();
+            }
+            kept.push_str(&block);
+            kept
+        }
+    };
+    if new != existing {
+        fs::write(&mod_rs, new)?;
+    }
+    Ok(modules)
+}
+
+/// The changes that [migrate_ids](fn.migrate_ids.html) made to a file.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IdMigration {
+    /// The old and the new id of every test that was renamed, in the order of the file
+    pub renamed: Vec<(String, String)>,
+
+    /// The old ids of the tests that were removed, because an earlier test in the file has the same content
+    pub removed: Vec<String>,
+}
+
+/// Rename the generated tests in `file` to content-hash ids, like the ones the [id strategy](struct.UnitTestOptions.html#method.id_strategy) `|meta| format!("{:016x}", meta.payload_hash)` produces, so timestamp-named tests can be migrated without generating them again.
+///
+/// The hash is computed from the embedded or [sidecar](struct.UnitTestOptions.html#method.sidecar_dir) JSON of every test. Tests that don't embed both snapshots, like groups or tests that [construct the executable from its default](struct.UnitTest.html#method.with_default_overrides), use the hash in their marker comment. The id in the marker, every `test_<id>` name in the file and the names of the sidecar files are changed, but the bodies of the tests are left as they are. If several tests end up with the same id, the first one is kept and the others are removed. Tests that already have their content-hash id are left alone, so migrating a file again changes nothing.
+///
+/// Sidecar files are renamed before the file is written, and [insta](struct.InstaRenderer.html) `.snap` files are not renamed. An [index](struct.UnitTestOptions.html#method.meta_index) of the file is updated after it was written, if there is one.
+///
+/// ```rust,ignore
+/// let migration = exceptional::migrate_ids("src/regressions.rs")?;
+/// println!("renamed {} tests", migration.renamed.len());
+/// ```
+pub fn migrate_ids(file: impl AsRef<Path>) -> io::Result<IdMigration> {
+    let file = file.as_ref();
+    let src = fs::read_to_string(file)?;
+    let mut migration = IdMigration::default();
+    let mut ids = HashSet::new();
+    let mut migrated = String::with_capacity(src.len());
+    let mut sidecars = Vec::new();
+    let mut end = 0;
+    for item in parse::find_items(&src) {
+        migrated.push_str(&src[end..item.range.start]);
+        end = item.range.end;
+        let item_src = &src[item.range];
+        let old = item.marker.id;
+        let new = match content_hash(file, item_src, &item.marker.hash) {
+            Some(hash) => format!("{:016x}", hash),
+            None => old.clone(),
+        };
+        if !ids.insert(new.clone()) {
+            migration.removed.push(old);
+            continue;
+        }
+        if new == old {
+            migrated.push_str(item_src);
+            continue;
+        }
+        let mut text = item_src.replacen(&format!("id={}", old), &format!("id={}", new), 1);
+        for &local in &["obj_json", "arg_json"] {
+            if let Some(SnapshotSource::Include(path)) = parse::snapshot_source(item_src, local) {
+                if let Some(renamed) = sidecar_path(&path, &old, &new) {
+                    text = text.replacen(&format!("{:?}", path), &format!("{:?}", renamed), 1);
+                    sidecars.push((path, renamed));
+                }
+            }
+        }
+        migrated.push_str(&text);
+        migration.renamed.push((old, new));
+    }
+    migrated.push_str(&src[end..]);
+
+    if migration.renamed.is_empty() && migration.removed.is_empty() {
+        return Ok(migration);
+    }
+    let names: HashMap<&str, &str> = migration
+        .renamed
+        .iter()
+        .map(|(old, new)| (old.as_str(), new.as_str()))
+        .collect();
+    let migrated = rename_tests(&migrated, &names);
+    let dir = file.parent().unwrap_or_else(|| Path::new(""));
+    for (from, to) in sidecars {
+        fs::rename(dir.join(from), dir.join(to))?;
+    }
+    fs::write(file, migrated)?;
+    if ::index::index_path(file).exists() {
+        ::index::update(file, None)?;
+    }
+    Ok(migration)
+}
+
+/// The payload hash of the generated item, from its snapshots if it embeds both of them, and from its marker hash otherwise.
+fn content_hash(file: &Path, item: &str, marker_hash: &str) -> Option<u64> {
+    match (
+        ::rerun::read_json(file, item, "obj_json"),
+        ::rerun::read_json(file, item, "arg_json"),
+    ) {
+        (Ok(executable), Ok(arguments)) => Some(::hash::payload_hash(&executable, &arguments)),
+        _ => u64::from_str_radix(marker_hash, 16).ok(),
+    }
+}
+
+/// The path of a sidecar file of the test `old` after it was renamed to `new`, or `None` if the file is not named after the test.
+fn sidecar_path(path: &str, old: &str, new: &str) -> Option<String> {
+    let (old_executable, old_arguments) = ::sidecar::file_names(old);
+    let (new_executable, new_arguments) = ::sidecar::file_names(new);
+    let pairs = [
+        (old_executable, new_executable),
+        (old_arguments, new_arguments),
+    ];
+    pairs.iter().find_map(|(from, to)| {
+        let dir = path.strip_suffix(from.as_str())?;
+        if dir.is_empty() || dir.ends_with('/') {
+            Some(format!("{}{}", dir, to))
+        } else {
+            None
+        }
+    })
+}
+
+/// Replace every `test_<id>` name in `src` whose id is a key of `names` with the name of the new id. Names that only start with `test_<id>`, like `test_1539761233543_x`, are left alone.
+fn rename_tests(src: &str, names: &HashMap<&str, &str>) -> String {
+    let mut renamed = String::with_capacity(src.len());
+    let mut rest = src;
+    while let Some(start) = rest.find("test_") {
+        let at_word_start = !rest[..start].ends_with(|c: char| c.is_alphanumeric() || c == '_');
+        let suffix = &rest[start + 5..];
+        let len = suffix
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(suffix.len());
+        renamed.push_str(&rest[..start + 5]);
+        match names.get(&suffix[..len]) {
+            Some(new) if at_word_start => renamed.push_str(new),
+            _ => renamed.push_str(&suffix[..len]),
+        }
+        rest = &suffix[len..];
+    }
+    renamed.push_str(rest);
+    renamed
+}
+
+/// Convert a full path like `::my_crate::SomeImportantAction` into a snake case module name like `my_crate_some_important_action_0123abcd`, which ends with the first 8 hex digits of the FNV-1a hash of the path.
+pub(crate) fn module_name(full_path: &str) -> String {
+    let mut name = String::with_capacity(full_path.len() + 8);
+    for segment in full_path.split("::").filter(|s| !s.is_empty()) {
+        if !name.is_empty() {
+            name.push('_');
+        }
+        let mut previous_lowercase = false;
+        for c in segment.chars() {
+            if c.is_ascii_uppercase() {
+                if previous_lowercase {
+                    name.push('_');
+                }
+                name.push(c.to_ascii_lowercase());
+                previous_lowercase = false;
+            } else if c.is_ascii_alphanumeric() {
+                name.push(c);
+                previous_lowercase = true;
+            } else {
+                name.push('_');
+                previous_lowercase = false;
+            }
+        }
+    }
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        name.insert(0, '_');
+    }
+    let hash = format!("{:016x}", ::hash::fnv1a(full_path.as_bytes()));
+    name.push('_');
+    name.push_str(&hash[..8]);
+    name
+}
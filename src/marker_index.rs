@@ -0,0 +1,82 @@
+use parse;
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::Path;
+use writer::DedupKey;
+
+/// The hashes of the generated tests in a file, kept in memory to check whether a failure [is already captured](struct.UnitTest.html#method.is_captured) without reading the file again.
+///
+/// Finding the markers of a file means reading and scanning all of it, which adds up when a hot loop checks every failure. An index is loaded once, and checking a failure only serializes and hashes its executable and arguments, without rendering the test:
+///
+/// ```rust,ignore
+/// let index = exceptional::MarkerIndex::load("src/regressions.rs")?;
+/// let mut writer = exceptional::TestFileWriter::new();
+/// for input in &inputs {
+///     if let Err(test) = exceptional::execute(&mut parser, input) {
+///         if !test.is_captured(&index) {
+///             writer.push(test);
+///         }
+///     }
+/// }
+/// writer.flush_to_file("src/regressions.rs")?;
+/// ```
+///
+/// The index is a snapshot: tests that are written to the file afterwards are only in it if they are [inserted](#method.insert). A [StreamingWriter](struct.StreamingWriter.html) keeps its own index up to date.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MarkerIndex {
+    /// The payload hashes of the tests
+    payloads: HashSet<String>,
+    /// The error hashes of the tests
+    errors: HashSet<String>,
+    /// The payload and error hashes of the tests
+    seen: HashSet<(String, String)>,
+}
+
+impl MarkerIndex {
+    /// Read the markers of the tests in `file`. A file that doesn't exist yet gives an empty index.
+    pub fn load(file: impl AsRef<Path>) -> io::Result<Self> {
+        match fs::read_to_string(file) {
+            Ok(src) => Ok(Self::from_source(&src)),
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// The markers of the tests in the source of a file.
+    pub fn from_source(src: &str) -> Self {
+        let mut index = Self::default();
+        for item in parse::find_items(src) {
+            index.insert(item.marker.hash, item.marker.error);
+        }
+        index
+    }
+
+    /// Remember a test by the `hash` and `error` fields of its marker. Empty error hashes, of groups and of tests from older versions, never match a test that is compared [by its error](enum.DedupKey.html#variant.ErrorOnly).
+    pub fn insert(&mut self, hash: String, error: String) {
+        self.payloads.insert(hash.clone());
+        if !error.is_empty() {
+            self.errors.insert(error.clone());
+        }
+        self.seen.insert((hash, error));
+    }
+
+    /// Whether a test with the given marker hashes is in the index, according to `key`.
+    pub fn contains(&self, key: DedupKey, hash: &str, error: &str) -> bool {
+        match key {
+            DedupKey::FullSnapshot => self.seen.contains(&(hash.to_owned(), error.to_owned())),
+            DedupKey::ErrorOnly => self.errors.contains(error),
+            DedupKey::ExecutableAndArgs => self.payloads.contains(hash),
+        }
+    }
+
+    /// The number of distinct tests in the index.
+    pub fn len(&self) -> usize {
+        self.seen.len()
+    }
+
+    /// Returns `true` if the index contains no tests.
+    pub fn is_empty(&self) -> bool {
+        self.seen.is_empty()
+    }
+}
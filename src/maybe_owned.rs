@@ -0,0 +1,36 @@
+use serde::{Serialize, Serializer};
+use std::fmt;
+use std::ops::Deref;
+
+/// Either a reference to a value or the value itself. This lets a [UnitTest](struct.UnitTest.html) borrow the arguments it failed with, like [execute](fn.execute.html) does, or own them, like [execute_all](fn.execute_all.html) does.
+///
+/// It dereferences to the value in both cases.
+pub enum MaybeOwned<'a, T: 'a> {
+    /// A value that is borrowed for `'a`
+    Borrowed(&'a T),
+    /// A value that is owned
+    Owned(T),
+}
+
+impl<'a, T: 'a> Deref for MaybeOwned<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        match self {
+            MaybeOwned::Borrowed(value) => value,
+            MaybeOwned::Owned(value) => value,
+        }
+    }
+}
+
+impl<'a, T: Serialize + 'a> Serialize for MaybeOwned<'a, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (**self).serialize(serializer)
+    }
+}
+
+impl<'a, T: fmt::Debug + 'a> fmt::Debug for MaybeOwned<'a, T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        (**self).fmt(fmt)
+    }
+}
@@ -0,0 +1,188 @@
+use serde_json::{Number, Value};
+use {Executable, MaybeOwned, UnitTest};
+
+/// The maximum number of times [execute_minimized](fn.execute_minimized.html) runs the executable while minimizing.
+pub const MAX_MINIMIZE_ATTEMPTS: usize = 1000;
+
+/// Like [execute](fn.execute.html), but reduces the state of the executable to a minimal still-failing form before generating the test.
+///
+/// When the executable fails, its pre-failure state is serialized to JSON and reduced step by step: fields are removed or set to `null`, arrays are emptied or lose elements, and strings, numbers and booleans are set to `""`, `0` and `false`. Every candidate that still deserializes is executed again with the same arguments, on a fresh copy, and kept if it fails with an error that has the same `Debug` output as the original failure. This repeats until no reduction keeps the failure, or until the executable ran [MAX_MINIMIZE_ATTEMPTS](constant.MAX_MINIMIZE_ATTEMPTS.html) times.
+///
+/// The arguments are left as they are. Because the executable runs many extra times, this should only be used for executables without side effects outside of their own state. If the state can't be serialized to JSON, the test is generated from the original state.
+#[track_caller]
+pub fn execute_minimized<'a, E: Executable + 'a>(
+    executable: &'a mut E,
+    arguments: &'a E::Arguments,
+) -> Result<E::Result, UnitTest<'a, E>> {
+    let old = executable.clone();
+    let error = match executable.execute(arguments) {
+        Ok(value) => return Ok(value),
+        Err(error) => error,
+    };
+    let (old, error) = minimize(old, arguments, error);
+    Err(UnitTest::new(old, MaybeOwned::Borrowed(arguments), error))
+}
+
+/// Like [execute](fn.execute.html), but shrinks the arguments to a minimal still-failing form before generating the test, like a property testing library.
+///
+/// When the executable fails, `shrinker` proposes smaller candidates for the failing arguments, and every candidate is executed again on a fresh copy of the state from before the failure. The first candidate that fails with an error that has the same `Debug` output as the original failure replaces the arguments, and is shrunk in turn. This repeats until no candidate keeps the failure, or until the executable ran [MAX_MINIMIZE_ATTEMPTS](constant.MAX_MINIMIZE_ATTEMPTS.html) times, and the test is generated from the arguments that were found:
+///
+/// ```rust,ignore
+/// // Try to bring every element closer to 0.
+/// let shrink = |&(a, b): &(u32, u32)| {
+///     let mut candidates = Vec::new();
+///     if a > 0 {
+///         candidates.extend(vec![(0, b), (a / 2, b), (a - 1, b)]);
+///     }
+///     if b > 0 {
+///         candidates.extend(vec![(a, 0), (a, b / 2), (a, b - 1)]);
+///     }
+///     candidates
+/// };
+/// if let Err(test) = exceptional::execute_shrinking(&mut action, &(7, 3), shrink) {
+///     // The test is generated for (0, 3) if that still fails in the same way.
+///     test.append_to_file("src/regressions.rs").unwrap();
+/// }
+/// ```
+///
+/// Candidates that are not smaller than the arguments they were proposed for make the shrinking go in circles until the attempts run out, so the shrinker should only ever propose smaller arguments. The state of the executable is left as it is; use [execute_minimized](fn.execute_minimized.html) to reduce that. Like there, the executable runs many extra times, so this should only be used for executables without side effects outside of their own state.
+#[track_caller]
+pub fn execute_shrinking<'a, E, S>(
+    executable: &'a mut E,
+    arguments: &'a E::Arguments,
+    shrinker: S,
+) -> Result<E::Result, UnitTest<'a, E>>
+where
+    E: Executable + 'a,
+    S: Fn(&E::Arguments) -> Vec<E::Arguments>,
+{
+    let old = executable.clone();
+    let error = match executable.execute(arguments) {
+        Ok(value) => return Ok(value),
+        Err(error) => error,
+    };
+    let target = format!("{:?}", error);
+    let mut best = (MaybeOwned::Borrowed(arguments), error);
+    let mut attempts = 0;
+
+    'shrink: while attempts < MAX_MINIMIZE_ATTEMPTS {
+        for candidate in shrinker(&best.0) {
+            if attempts == MAX_MINIMIZE_ATTEMPTS {
+                break 'shrink;
+            }
+            attempts += 1;
+            if let Err(e) = old.clone().execute(&candidate) {
+                if format!("{:?}", e) == target {
+                    best = (MaybeOwned::Owned(candidate), e);
+                    continue 'shrink;
+                }
+            }
+        }
+        break;
+    }
+    let (arguments, error) = best;
+    Err(UnitTest::new(old, arguments, error))
+}
+
+/// Reduce `executable` while it keeps failing with the same error. Returns the smallest state that was found and the error it failed with.
+fn minimize<E: Executable>(
+    executable: E,
+    arguments: &E::Arguments,
+    error: E::Error,
+) -> (E, E::Error) {
+    let target = format!("{:?}", error);
+    let mut current = match serde_json::to_value(&executable) {
+        Ok(value) => value,
+        Err(_) => return (executable, error),
+    };
+    let mut best = (executable, error);
+    let mut attempts = 0;
+
+    'reduce: while attempts < MAX_MINIMIZE_ATTEMPTS {
+        for candidate in reductions(&current) {
+            let snapshot: E = match serde_json::from_value(candidate.clone()) {
+                Ok(snapshot) => snapshot,
+                Err(_) => continue,
+            };
+            if attempts == MAX_MINIMIZE_ATTEMPTS {
+                break 'reduce;
+            }
+            attempts += 1;
+            let mut trial = snapshot.clone();
+            if let Err(e) = trial.execute(arguments) {
+                if format!("{:?}", e) == target {
+                    best = (snapshot, e);
+                    current = candidate;
+                    continue 'reduce;
+                }
+            }
+        }
+        break;
+    }
+    best
+}
+
+/// All values that are one reduction step smaller than `value`, the coarsest reductions first.
+fn reductions(value: &Value) -> Vec<Value> {
+    match value {
+        Value::Null | Value::Bool(false) => Vec::new(),
+        Value::Bool(true) => vec![Value::Bool(false)],
+        Value::Number(n) => match zero_like(n) {
+            Some(zero) => vec![Value::Number(zero)],
+            None => Vec::new(),
+        },
+        Value::String(s) if s.is_empty() => Vec::new(),
+        Value::String(_) => vec![Value::String(String::new())],
+        Value::Array(items) => {
+            let mut result = Vec::new();
+            if !items.is_empty() {
+                result.push(Value::Array(Vec::new()));
+            }
+            if items.len() > 1 {
+                for i in 0..items.len() {
+                    let mut smaller = items.clone();
+                    smaller.remove(i);
+                    result.push(Value::Array(smaller));
+                }
+            }
+            for (i, item) in items.iter().enumerate() {
+                for reduced in reductions(item) {
+                    let mut smaller = items.clone();
+                    smaller[i] = reduced;
+                    result.push(Value::Array(smaller));
+                }
+            }
+            result
+        }
+        Value::Object(map) => {
+            let mut result = Vec::new();
+            for (key, field) in map {
+                let mut removed = map.clone();
+                removed.remove(key);
+                result.push(Value::Object(removed));
+                if !field.is_null() {
+                    let mut nulled = map.clone();
+                    nulled.insert(key.clone(), Value::Null);
+                    result.push(Value::Object(nulled));
+                }
+                for reduced in reductions(field) {
+                    let mut smaller = map.clone();
+                    smaller.insert(key.clone(), reduced);
+                    result.push(Value::Object(smaller));
+                }
+            }
+            result
+        }
+    }
+}
+
+/// A zero of the same kind as `n`, or `None` if `n` is already zero.
+fn zero_like(n: &Number) -> Option<Number> {
+    if n.as_u64() == Some(0) || n.as_i64() == Some(0) || n.as_f64() == Some(0.0) {
+        None
+    } else if n.is_f64() {
+        Number::from_f64(0.0)
+    } else {
+        Some(Number::from(0u64))
+    }
+}
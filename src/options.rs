@@ -0,0 +1,1035 @@
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Utc};
+use error::RenderError;
+use hash::Hasher;
+use serde_json::Value;
+use severity::Severity;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The information about a failure that is available when choosing the name of its generated test, or the file it is [routed](struct.UnitTestOptions.html#method.route) to.
+pub struct UnitTestMeta {
+    /// The time at which the executable failed
+    pub time: DateTime<Utc>,
+
+    /// The [description](trait.Executable.html#tymethod.description) of the executable
+    pub description: String,
+
+    /// The [full path](trait.Executable.html#tymethod.full_path) of the executable
+    pub full_path: &'static str,
+
+    /// A stable hash of the serialized executable and arguments. Two failures with the same state and arguments have the same hash.
+    pub payload_hash: u64,
+
+    /// The hash of the serialized executable and arguments with the configured [hasher](struct.UnitTestOptions.html#method.hasher), as it is written to the marker comment
+    pub content_hash: String,
+
+    /// The [severity](trait.Executable.html#method.severity) of the failure
+    pub severity: Severity,
+
+    /// The [metadata](struct.UnitTest.html#method.with_metadata) of the failure, like a category
+    pub metadata: BTreeMap<String, String>,
+}
+
+/// The banner that [UnitTestOptions::default_banner](struct.UnitTestOptions.html#method.default_banner) writes at the top of a generated file.
+pub const DEFAULT_BANNER: &str = "This file is automatically generated by exceptional.\nDO NOT EDIT: manual changes may be lost when tests are generated again.";
+
+/// The kind of comment that is used for the header of every generated test.
+///
+/// Inner doc comments (`//!`) are not an option here, because they are only allowed at the top of a module. Use a [banner](struct.UnitTestOptions.html#method.banner) to document the whole file instead.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CommentStyle {
+    /// Outer doc comments (`///`), which document the generated function. This is the default.
+    #[default]
+    Doc,
+    /// Regular line comments (`//`), which are ignored by rustdoc and its lints. The generated function is marked `#[allow(missing_docs)]`, so it still compiles in crates that deny missing docs.
+    Plain,
+}
+
+impl CommentStyle {
+    /// The characters that start every comment line.
+    pub fn prefix(self) -> &'static str {
+        match self {
+            CommentStyle::Doc => "///",
+            CommentStyle::Plain => "//",
+        }
+    }
+}
+
+/// The line breaks of generated tests. See [UnitTestOptions::line_ending](struct.UnitTestOptions.html#method.line_ending).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LineEnding {
+    /// `\n`, which rendered tests use unless another line ending is configured
+    Lf,
+    /// `\r\n`, the usual line break on Windows
+    CrLf,
+}
+
+impl LineEnding {
+    /// The characters that end every line.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+        }
+    }
+
+    /// The line ending that most lines of `src` end with, or `None` if it has no line breaks. Ties are broken in favor of `\n`.
+    pub(crate) fn detect(src: &str) -> Option<Self> {
+        let breaks = src.matches('\n').count();
+        let crlf = src.matches("\r\n").count();
+        match breaks {
+            0 => None,
+            _ if crlf * 2 > breaks => Some(LineEnding::CrLf),
+            _ => Some(LineEnding::Lf),
+        }
+    }
+
+    /// Change every line break of `text` to this line ending.
+    pub(crate) fn apply<'t>(self, text: &'t str) -> Cow<'t, str> {
+        match self {
+            LineEnding::Lf if text.contains("\r\n") => Cow::Owned(text.replace("\r\n", "\n")),
+            LineEnding::Lf => Cow::Borrowed(text),
+            LineEnding::CrLf => {
+                let lone = text
+                    .match_indices('\n')
+                    .any(|(i, _)| !text[..i].ends_with('\r'));
+                if lone {
+                    Cow::Owned(text.replace("\r\n", "\n").replace('\n', "\r\n"))
+                } else {
+                    Cow::Borrowed(text)
+                }
+            }
+        }
+    }
+}
+
+/// The Rust edition of the crate that generated tests are written to. See [UnitTestOptions::edition](struct.UnitTestOptions.html#method.edition).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Edition {
+    /// Rust 2015, where a leading `::` starts a path at the root of the crate. This is the default.
+    #[default]
+    Edition2015,
+    /// Rust 2018 and later, where a leading `::` names an external crate, so paths in the crate start with `crate::`
+    Edition2018,
+}
+
+/// The format that the executable and the arguments are embedded in the generated test in. See [UnitTestOptions::serialization_format](struct.UnitTestOptions.html#method.serialization_format).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SerializationFormat {
+    /// Pretty-printed JSON, read with `serde_json::from_str`. This is the default.
+    #[default]
+    Json,
+    /// [RON](https://github.com/ron-rs/ron), read with `ron::from_str`, which is shorter than JSON for structs and enums
+    Ron,
+}
+
+impl SerializationFormat {
+    /// The name of the format, which the locals of the generated test that hold the inputs end with, like `obj_ron`.
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            SerializationFormat::Json => "json",
+            SerializationFormat::Ron => "ron",
+        }
+    }
+
+    /// The path of the function that the generated test deserializes the inputs with.
+    pub(crate) fn deserialize_path(self) -> &'static str {
+        match self {
+            SerializationFormat::Json => "::serde_json::from_str",
+            SerializationFormat::Ron => "::ron::from_str",
+        }
+    }
+}
+
+/// The test harness that a [group](fn.render_group.html) of failures is rendered for.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Harness {
+    /// One plain `#[test]` function per failure. This is the default.
+    #[default]
+    Std,
+    /// A single function with one `#[test_case(...)]` attribute per failure, for the [test-case](https://docs.rs/test-case) crate.
+    TestCase,
+}
+
+/// Where the generated tests are compiled, which decides how the leading segment of the [full path](trait.Executable.html#tymethod.full_path) of the executable is written. See [test_kind](struct.UnitTestOptions.html#method.test_kind).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TestKind {
+    /// A unit test inside the crate that defines the executable, like `src/regressions.rs`. Paths that start with the crate name, like `::my_crate::Action`, are written as `crate::Action`.
+    Unit,
+    /// An integration test in `tests/`, or another crate, which uses the crate of the executable as a dependency. Paths that start with `crate::`, like `crate::Action`, are written as `::my_crate::Action`.
+    Integration,
+}
+
+/// A function that returns the suffix of a generated function name.
+pub type IdStrategy = Arc<dyn Fn(&UnitTestMeta) -> String + Send + Sync>;
+
+/// A function that changes the JSON of the executable or the arguments before it is embedded. See [json_postprocess](struct.UnitTestOptions.html#method.json_postprocess).
+pub type JsonPostprocess = Arc<dyn Fn(&mut Value) + Send + Sync>;
+
+/// A function that chooses the file a generated test is written to, or vetoes it. See [route](struct.UnitTestOptions.html#method.route).
+pub type Route = Arc<dyn Fn(&UnitTestMeta) -> Option<PathBuf> + Send + Sync>;
+
+/// Options that control how a [UnitTest](struct.UnitTest.html) is rendered.
+///
+/// Every test has its own options, which are the defaults when it is captured. They are replaced with [with_options](struct.UnitTest.html#method.with_options) or changed with [options_mut](struct.UnitTest.html#method.options_mut) before the test is rendered or written, or attached when the test is captured with [execute_with_options](fn.execute_with_options.html) and [ExecuteOptions::with_test_options](struct.ExecuteOptions.html#method.with_test_options). The rendering and writing methods of the test all read them, so new settings are added here instead of as parameters of those methods.
+#[derive(Clone)]
+pub struct UnitTestOptions {
+    /// See [id_strategy](#method.id_strategy).
+    pub id_strategy: Option<IdStrategy>,
+
+    /// See [namespace_id](#method.namespace_id).
+    pub namespace_id: bool,
+
+    /// See [route](#method.route).
+    pub route: Option<Route>,
+
+    /// See [json_postprocess](#method.json_postprocess).
+    pub json_postprocess: Option<JsonPostprocess>,
+
+    /// See [sort_keys](#method.sort_keys).
+    pub sort_keys: bool,
+
+    /// See [hoist_inputs](#method.hoist_inputs).
+    pub hoist_inputs: bool,
+
+    /// See [base64_bytes](#method.base64_bytes).
+    pub base64_bytes: Option<usize>,
+
+    /// See [cfg_gate](#method.cfg_gate).
+    pub cfg_gate: Option<String>,
+
+    /// See [comment_style](#method.comment_style).
+    pub comment_style: CommentStyle,
+
+    /// See [link_executable](#method.link_executable).
+    pub link_executable: bool,
+
+    /// See [time_format](#method.time_format).
+    pub time_format: Option<String>,
+
+    /// See [banner](#method.banner).
+    pub banner: Option<String>,
+
+    /// See [table_of_contents](#method.table_of_contents).
+    pub table_of_contents: bool,
+
+    /// See [use_insertion_marker](#method.use_insertion_marker).
+    pub use_insertion_marker: bool,
+
+    /// See [upsert](#method.upsert).
+    pub upsert: bool,
+
+    /// See [meta_index](#method.meta_index).
+    pub meta_index: bool,
+
+    /// See [hasher](#method.hasher).
+    pub hasher: Hasher,
+
+    /// See [target_module](#method.target_module).
+    pub target_module: Option<String>,
+
+    /// See [severity_modules](#method.severity_modules).
+    pub severity_modules: bool,
+
+    /// See [run_hint](#method.run_hint).
+    pub run_hint: bool,
+
+    /// See [module_path](#method.module_path).
+    pub module_path: Option<String>,
+
+    /// See [test_kind](#method.test_kind). The kind and the name of the crate that defines the executable.
+    pub test_kind: Option<(TestKind, String)>,
+
+    /// See [sync_on_write](#method.sync_on_write).
+    pub sync_on_write: bool,
+
+    /// See [line_ending](#method.line_ending).
+    pub line_ending: Option<LineEnding>,
+
+    /// See [fallback_dir](#method.fallback_dir).
+    pub fallback_dir: Option<String>,
+
+    /// See [harness](#method.harness).
+    pub harness: Harness,
+
+    /// See [sidecar_dir](#method.sidecar_dir).
+    pub sidecar_dir: Option<String>,
+
+    /// See [max_snapshot_bytes](#method.max_snapshot_bytes).
+    pub max_snapshot_bytes: Option<usize>,
+
+    /// See [verbose_body](#method.verbose_body).
+    pub verbose_body: bool,
+
+    /// See [black_box](#method.black_box).
+    pub black_box: bool,
+
+    /// See [serialization_format](#method.serialization_format).
+    pub serialization_format: SerializationFormat,
+
+    /// See [edition](#method.edition).
+    pub edition: Edition,
+
+    /// See [test_attribute](#method.test_attribute).
+    pub test_attribute: String,
+
+    /// See [async_test](#method.async_test).
+    pub async_test: Option<String>,
+
+    /// See [timeout](#method.timeout).
+    pub timeout: Option<Duration>,
+
+    /// See [restore_globals](#method.restore_globals).
+    pub restore_globals: bool,
+
+    /// See [obj_binding](#method.obj_binding).
+    pub obj_binding: String,
+
+    /// See [args_binding](#method.args_binding).
+    pub args_binding: String,
+
+    /// See [include_build_info](#method.include_build_info).
+    #[cfg(feature = "build-info")]
+    pub include_build_info: bool,
+}
+
+impl Default for UnitTestOptions {
+    fn default() -> Self {
+        UnitTestOptions {
+            id_strategy: None,
+            namespace_id: false,
+            route: None,
+            json_postprocess: None,
+            sort_keys: false,
+            hoist_inputs: false,
+            base64_bytes: None,
+            cfg_gate: None,
+            comment_style: CommentStyle::default(),
+            link_executable: false,
+            time_format: None,
+            banner: None,
+            table_of_contents: false,
+            use_insertion_marker: false,
+            upsert: false,
+            meta_index: false,
+            hasher: Hasher::default(),
+            target_module: None,
+            severity_modules: false,
+            run_hint: false,
+            module_path: None,
+            test_kind: None,
+            sync_on_write: false,
+            line_ending: None,
+            fallback_dir: None,
+            harness: Harness::default(),
+            sidecar_dir: None,
+            max_snapshot_bytes: None,
+            verbose_body: true,
+            black_box: false,
+            serialization_format: SerializationFormat::default(),
+            edition: Edition::default(),
+            test_attribute: String::from("test"),
+            async_test: None,
+            timeout: None,
+            restore_globals: false,
+            obj_binding: String::from("obj"),
+            args_binding: String::from("args"),
+            #[cfg(feature = "build-info")]
+            include_build_info: false,
+        }
+    }
+}
+
+impl UnitTestOptions {
+    /// Use the given function to generate the suffix of the function name, e.g. `test_<id>`. By default the millisecond timestamp of the failure is used.
+    ///
+    /// The returned id must only contain ASCII letters, digits and underscores. Rendering fails with [InvalidId](enum.RenderError.html#variant.InvalidId) if it does not.
+    ///
+    /// ```rust
+    /// # use exceptional::UnitTestOptions;
+    /// let options = UnitTestOptions::default()
+    ///     .id_strategy(|meta| format!("{:016x}", meta.payload_hash));
+    /// ```
+    pub fn id_strategy<F>(mut self, strategy: F) -> Self
+    where
+        F: Fn(&UnitTestMeta) -> String + Send + Sync + 'static,
+    {
+        self.id_strategy = Some(Arc::new(strategy));
+        self
+    }
+
+    /// Append a short hash of the [description](trait.Executable.html#tymethod.description) and the [full path](trait.Executable.html#tymethod.full_path) of the executable to the default id, e.g. `test_1514764800000_3f2a9c1e`. Different executables that fail in the same millisecond then still get different function names.
+    ///
+    /// This only changes the default timestamp id. A configured [id strategy](#method.id_strategy) is used as is.
+    pub fn namespace_id(mut self, namespace: bool) -> Self {
+        self.namespace_id = namespace;
+        self
+    }
+
+    /// Choose the file that [append_routed](struct.UnitTest.html#method.append_routed) writes a test to, based on its metadata, e.g. to keep database failures apart from the others. The function returns `None` to veto the test, which is then not written at all.
+    ///
+    /// ```rust
+    /// # use exceptional::{Severity, UnitTestOptions};
+    /// # use std::path::PathBuf;
+    /// let options = UnitTestOptions::default().route(|meta| {
+    ///     if meta.severity == Severity::Low {
+    ///         None
+    ///     } else if meta.metadata.get("category").map(String::as_str) == Some("db") {
+    ///         Some(PathBuf::from("tests/db_repros.rs"))
+    ///     } else {
+    ///         Some(PathBuf::from("tests/repros.rs"))
+    ///     }
+    /// });
+    /// ```
+    pub fn route<F>(mut self, route: F) -> Self
+    where
+        F: Fn(&UnitTestMeta) -> Option<PathBuf> + Send + Sync + 'static,
+    {
+        self.route = Some(Arc::new(route));
+        self
+    }
+
+    /// Change the JSON `Value` of the executable and of the arguments before it is embedded in the test, e.g. to remove volatile fields like timestamps, or to redact secrets, so regenerated tests are stable.
+    ///
+    /// The function is called once for the executable and once for the arguments. The result is serialized again with sorted object keys, and hashed for the marker comment like unprocessed JSON. The post-processed JSON must still deserialize into `E` and `E::Arguments`, e.g. by giving removed fields a `#[serde(default)]`. Rendering fails with [ExecutableRoundTrip](enum.RenderError.html#variant.ExecutableRoundTrip) or [ArgumentsRoundTrip](enum.RenderError.html#variant.ArgumentsRoundTrip) if they don't.
+    ///
+    /// ```rust
+    /// # use exceptional::UnitTestOptions;
+    /// let options = UnitTestOptions::default().json_postprocess(|value| {
+    ///     if let Some(object) = value.as_object_mut() {
+    ///         object.remove("last_seen");
+    ///     }
+    /// });
+    /// ```
+    pub fn json_postprocess<F>(mut self, postprocess: F) -> Self
+    where
+        F: Fn(&mut Value) + Send + Sync + 'static,
+    {
+        self.json_postprocess = Some(Arc::new(postprocess));
+        self
+    }
+
+    /// Sort the keys of every JSON object in the embedded executable and arguments, at every level. Types like `HashMap` are serialized in their iteration order, which changes between runs, so without this the same state can produce a different snapshot every time it is generated.
+    ///
+    /// The keys are compared as strings. Like [json_postprocess](#method.json_postprocess), this goes through a JSON `Value`, so the marker hash is computed over the sorted JSON.
+    pub fn sort_keys(mut self, sort: bool) -> Self {
+        self.sort_keys = sort;
+        self
+    }
+
+    /// Write the JSON of the executable and the arguments to named consts above the generated test, and refer to them from the test, so the inputs are visible at a glance:
+    ///
+    /// ```rust,ignore
+    /// // @exceptional id=1539761233543 ...
+    /// const INPUT_1539761233543_OBJ: &str = r#"{"var_1": 0, "var_2": 0}"#;
+    /// const INPUT_1539761233543_ARGS: &str = r#"[2, 3]"#;
+    /// /// Automatically generated unit test for Executable
+    /// // ...
+    /// pub fn test_1539761233543() {
+    ///     let obj_json = INPUT_1539761233543_OBJ;
+    ///     // ...
+    /// }
+    /// ```
+    ///
+    /// The consts are named after the id of the test and belong to it, so tests with identical inputs each have their own consts, and every test can be upserted, removed or [resplit](fn.resplit_by_type.html) on its own. [rerun_file](fn.rerun_file.html) and the maintenance functions read the inputs from the consts. Inputs that are written to [sidecar files](#method.sidecar_dir), or as field assignments or struct literals, are not hoisted, and neither are the inputs of [groups](fn.render_group.html) with [Harness::TestCase](enum.Harness.html#variant.TestCase), which are arguments of their `#[test_case]` attributes.
+    pub fn hoist_inputs(mut self, enabled: bool) -> Self {
+        self.hoist_inputs = enabled;
+        self
+    }
+
+    /// Embed every array of at least `min_len` integers from 0 to 255, like a serialized `Vec<u8>`, as a base64 string instead of one number per line. `min_len` is at least 1.
+    ///
+    /// serde serializes byte buffers as arrays of numbers, so an executable that holds binary data produces huge, unreadable snapshots. With this option, the embedded JSON contains `{"$base64": "..."}` objects instead, and the generated test restores the arrays with [decode_base64_json](fn.decode_base64_json.html) before it deserializes the JSON:
+    ///
+    /// ```rust,ignore
+    /// let obj_json = &::exceptional::decode_base64_json(r#"{
+    ///   "image": {"$base64": "iVBORw0KGgo="}
+    /// }"#);
+    /// ```
+    ///
+    /// Arrays of other small integers, like a `Vec<u32>`, are encoded too, but that is harmless, because they are restored to the same JSON. The restored JSON is exactly the serialized JSON, so the marker hash does not depend on this option. [rerun_file](fn.rerun_file.html) and the maintenance functions decode the arrays as well. [Sidecar files](#method.sidecar_dir), inputs that are written as field assignments or struct literals, and the `#[test_case]` attributes of [groups](fn.render_group.html) are written as plain JSON.
+    pub fn base64_bytes(mut self, min_len: usize) -> Self {
+        self.base64_bytes = Some(min_len.max(1));
+        self
+    }
+
+    /// Only compile the generated test when the given `cfg` predicate holds, e.g. `feature = "slow-tests"` or `target_os = "linux"`. The predicate is emitted as `#[cfg(...)]` directly above `#[test]`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the predicate is empty, spans multiple lines, or has unbalanced parentheses or quotes.
+    pub fn cfg_gate(mut self, predicate: &str) -> Self {
+        assert!(
+            is_valid_cfg_predicate(predicate),
+            "{:?} is not a valid cfg predicate",
+            predicate
+        );
+        self.cfg_gate = Some(predicate.trim().to_owned());
+        self
+    }
+
+    /// Only compile the generated test when the crate that contains it is built with the given cargo feature, e.g. `generated_regressions`. This emits `#[cfg(feature = "...")]` like [cfg_gate](#method.cfg_gate), and combines with an earlier `cfg_gate` as `#[cfg(all(..., feature = "..."))]`.
+    ///
+    /// The feature has to be declared in the `[features]` of that crate. The generated tests then only run with `cargo test --features generated_regressions`, which keeps the default test run fast:
+    ///
+    /// ```toml
+    /// [features]
+    /// generated_regressions = []
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `feature` is not a valid feature name.
+    pub fn feature_gate(self, feature: &str) -> Self {
+        assert!(
+            is_valid_feature(feature),
+            "{:?} is not a valid feature name",
+            feature
+        );
+        let gate = format!("feature = {:?}", feature);
+        let predicate = match self.cfg_gate {
+            Some(ref existing) => format!("all({}, {})", existing, gate),
+            None => gate,
+        };
+        self.cfg_gate(&predicate)
+    }
+
+    /// Set the kind of comment used for the header of every generated test. Defaults to [CommentStyle::Doc](enum.CommentStyle.html#variant.Doc).
+    pub fn comment_style(mut self, style: CommentStyle) -> Self {
+        self.comment_style = style;
+        self
+    }
+
+    /// Add an intra-doc link to the executable type to the header of every generated test, so rustdoc connects the reproduction to the type it reproduces:
+    ///
+    /// ```text
+    /// /// executable: [`SomeImportantAction`](my_crate::SomeImportantAction)
+    /// ```
+    ///
+    /// The link target is the [full path](trait.Executable.html#tymethod.full_path) without the leading `::` and without generic arguments. It only resolves if the full path names a type that is visible from the generated file, so this is disabled by default, to not fail builds that deny `rustdoc::broken_intra_doc_links`. It has no effect with [CommentStyle::Plain](enum.CommentStyle.html#variant.Plain).
+    pub fn link_executable(mut self, enabled: bool) -> Self {
+        self.link_executable = enabled;
+        self
+    }
+
+    /// Write the time in the `generated at` comment with the given [strftime format](https://docs.rs/chrono/0.4/chrono/format/strftime/index.html), e.g. `%Y-%m-%dT%H:%M:%S%.3fZ`, instead of the default RFC 2822 format. The time is always in UTC.
+    ///
+    /// The id and the marker comment are not affected, so this only changes what readers of the generated file see.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `format` is not a valid strftime format, or contains a line break.
+    pub fn time_format(mut self, format: &str) -> Self {
+        assert!(
+            is_valid_time_format(format),
+            "{:?} is not a valid time format",
+            format
+        );
+        self.time_format = Some(format.to_owned());
+        self
+    }
+
+    /// Write the given text as an inner doc comment (`//!`) at the top of the file that tests are appended to.
+    ///
+    /// The banner is only written once: if the file does not start with it yet, it is prepended, otherwise the file is left as is. Every line of `text` becomes a separate `//!` line.
+    pub fn banner(mut self, text: &str) -> Self {
+        self.banner = Some(text.to_owned());
+        self
+    }
+
+    /// Write the [default banner](constant.DEFAULT_BANNER.html), which marks the file as generated and asks readers not to edit it.
+    pub fn default_banner(self) -> Self {
+        self.banner(DEFAULT_BANNER)
+    }
+
+    /// Keep a table of contents of all generated tests at the top of the file, after the banner, to find a way around large files:
+    ///
+    /// ```text
+    /// //! Generated tests:
+    /// //! - test_1539761233543: Executes some very important action! (line 4)
+    /// ```
+    ///
+    /// Every entry has the name of the test, the [description](trait.Executable.html#tymethod.description) of the executable and the line of its marker comment. The table is written again on every append, so it lists the tests in the order of the file and is never duplicated. Like the banner, it is an inner doc comment, so it can't be used for files that are included into a module, and it reads the whole file.
+    pub fn table_of_contents(mut self, enabled: bool) -> Self {
+        self.table_of_contents = enabled;
+        self
+    }
+
+    /// Insert new tests above the [insertion marker](constant.INSERTION_MARKER.html) if the target file contains one, instead of at the end of the file.
+    ///
+    /// This lets a file end with hand-written code, or with the closing brace of a module that the tests should end up in:
+    ///
+    /// ```rust,ignore
+    /// mod regressions {
+    ///     use super::*;
+    ///
+    ///     // EXCEPTIONAL:INSERT
+    /// }
+    /// ```
+    ///
+    /// Placing a test this way reads the whole file, while a plain append does not. Files without a marker are appended to as usual.
+    pub fn use_insertion_marker(mut self, enabled: bool) -> Self {
+        self.use_insertion_marker = enabled;
+        self
+    }
+
+    /// Replace a previously generated test for the same executable state and arguments, instead of adding another one.
+    ///
+    /// Tests are matched by the `hash` in their marker comment. If the file already contains a test with the same hash and it was generated for the same error, the file is left as is. If the error changed, the old test is replaced in place by the new one, with the new error and time. Otherwise the test is added as usual.
+    ///
+    /// Like the insertion marker, this reads the whole file.
+    pub fn upsert(mut self, enabled: bool) -> Self {
+        self.upsert = enabled;
+        self
+    }
+
+    /// Keep a machine-readable index of the generated tests next to the file they are written to, as `<file>.meta.json`, for dashboards and other tools that don't parse Rust. See [IndexEntry](struct.IndexEntry.html) for its schema. This is disabled by default.
+    ///
+    /// The index is rebuilt from the markers in the file every time [append_to_file](struct.UnitTest.html#method.append_to_file) or its variants write a test, so it lists exactly the tests that are in the file, including tests that were replaced by an [upsert](#method.upsert) or removed by hand. [migrate_ids](fn.migrate_ids.html) updates an existing index as well. Use [rebuild_index](fn.rebuild_index.html) after other changes, like writing with a [StreamingWriter](struct.StreamingWriter.html).
+    ///
+    /// The index is written after the test, to a temporary file that replaces the old index, so it is never partially written. Two processes that append to the same file at the same time can still write their indexes in a different order than their tests, which leaves out the details of one of the tests until the next write.
+    pub fn meta_index(mut self, enabled: bool) -> Self {
+        self.meta_index = enabled;
+        self
+    }
+
+    /// Hash the serialized executable and arguments, and the error, with the given [Hasher](enum.Hasher.html) instead of FNV-1a. The hashes are written to the marker comment, so they decide which tests [upserts](#method.upsert) and the [StreamingWriter](struct.StreamingWriter.html) consider the same, and they are passed to the [id strategy](#method.id_strategy) as the [content hash](struct.UnitTestMeta.html#structfield.content_hash):
+    ///
+    /// ```rust
+    /// # use exceptional::{Hasher, UnitTestOptions};
+    /// let options = UnitTestOptions::default()
+    ///     .hasher(Hasher::Sha256)
+    ///     .id_strategy(|meta| meta.content_hash.clone());
+    /// ```
+    ///
+    /// Tests that were written with a different hasher don't match, so a file should only be written with one hasher. The hash of the [namespaced id](#method.namespace_id) and the [payload hash](struct.UnitTestMeta.html#structfield.payload_hash) always use FNV-1a.
+    pub fn hasher(mut self, hasher: Hasher) -> Self {
+        self.hasher = hasher;
+        self
+    }
+
+    /// Add new tests at the end of the module with the given name, like `mod regression_tests { ... }`, instead of at the end of the file. The module is created at the end of the file if the file does not contain it.
+    ///
+    /// Tests are indented like the first line of the module. An [insertion marker](#method.use_insertion_marker) takes precedence, and an [upserted](#method.upsert) test is replaced where it is. Like the insertion marker, this reads the whole file.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` is not a valid module name.
+    pub fn target_module(mut self, name: &str) -> Self {
+        assert!(is_identifier(name), "{:?} is not a valid module name", name);
+        self.target_module = Some(name.to_owned());
+        self
+    }
+
+    /// Add a `run with: cargo test <filter>` line to the comment of the generated test, which runs only that test when it is copied to a terminal.
+    ///
+    /// The filter is the name of the function, prefixed with the [module path](#method.module_path) and the [target module](#method.target_module) if they are configured, e.g. `cargo test regressions::test_1539761233543`. `cargo test` matches tests that contain the filter, so the filter works without the module path too, but the function name alone may also match a test of another module that has the same id.
+    pub fn run_hint(mut self, enabled: bool) -> Self {
+        self.run_hint = enabled;
+        self
+    }
+
+    /// The path of the module that the tests are written into, relative to the crate root, e.g. `tests::regressions` for `src/tests/regressions.rs`. This is only used for the [run hint](#method.run_hint).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `path` is not a `::`-separated list of module names.
+    pub fn module_path(mut self, path: &str) -> Self {
+        assert!(
+            is_valid_module_path(path),
+            "{:?} is not a valid module path",
+            path
+        );
+        self.module_path = Some(path.to_owned());
+        self
+    }
+
+    /// Rewrite the leading segment of the [full path](trait.Executable.html#tymethod.full_path) of the executable, and of its [arguments path](trait.Executable.html#method.arguments_path), for the kind of test the file is compiled as. `crate_name` is the name of the crate that defines the executable, e.g. `my_crate`; dashes are replaced by underscores, like Cargo does.
+    ///
+    /// A full path like `crate::module::SomeAction` only resolves inside its own crate, and `::my_crate::module::SomeAction` only outside of it, so a test that moves between `src/` and `tests/` needs the other form. With [Unit](enum.TestKind.html#variant.Unit), `::my_crate::` and `my_crate::` become `crate::`, and with [Integration](enum.TestKind.html#variant.Integration), `crate::` and `::crate::` become `::my_crate::`. Other paths, and paths in generic arguments, are written as they are. By default, the full path is never rewritten.
+    ///
+    /// Only the generated code changes: the marker keeps the full path as the executable returns it, so [rerun_file](fn.rerun_file.html) and the other tools still match it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `crate_name` is not a valid crate name.
+    pub fn test_kind(mut self, kind: TestKind, crate_name: &str) -> Self {
+        let crate_name = crate_name.replace('-', "_");
+        assert!(
+            is_identifier(&crate_name),
+            "{:?} is not a valid crate name",
+            crate_name
+        );
+        self.test_kind = Some((kind, crate_name));
+        self
+    }
+
+    /// Add new tests at the end of a module named after the [severity](trait.Executable.html#method.severity) of the executable, like `mod critical { ... }`, so CI can run only the urgent reproductions with `cargo test critical::`. The modules are created like a [target module](#method.target_module), and this takes precedence over a configured target module.
+    pub fn severity_modules(mut self, enabled: bool) -> Self {
+        self.severity_modules = enabled;
+        self
+    }
+
+    /// Sync the file to disk with `File::sync_all` after every test that [append_to_file](struct.UnitTest.html#method.append_to_file) or a [StreamingWriter](struct.StreamingWriter.html) writes. This is disabled by default.
+    ///
+    /// Without syncing, the last tests of a long run, like a fuzzing session, can be lost if the machine crashes before the operating system flushed them. Syncing waits for the disk on every write, which is typically several milliseconds and can be much slower on network file systems, so it slows down runs that generate many tests.
+    pub fn sync_on_write(mut self, sync: bool) -> Self {
+        self.sync_on_write = sync;
+        self
+    }
+
+    /// End every line of the generated tests with `ending`.
+    ///
+    /// By default, tests are rendered with `\n`, and [append_to_file](struct.UnitTest.html#method.append_to_file) and a [StreamingWriter](struct.StreamingWriter.html) match the line ending that most lines of the existing file end with, so tests appended to a file with Windows line endings don't mix `\n` and `\r\n`. With a configured line ending, rendered tests like [to_string](struct.UnitTest.html#method.to_string) use it as well, and a file that is edited to place a test, e.g. at the [insertion marker](#method.use_insertion_marker), is converted to it completely. The embedded JSON is not affected, because the compiler reads `\r\n` in a string literal as `\n`.
+    pub fn line_ending(mut self, ending: LineEnding) -> Self {
+        self.line_ending = Some(ending);
+        self
+    }
+
+    /// Write tests to a file with the same name in `dir` if [append_to_file](struct.UnitTest.html#method.append_to_file) finds the target file read-only or locked, e.g. when the source tree is mounted read-only in CI. Without a fallback directory, the error is returned and the test is lost.
+    ///
+    /// `dir` is relative to the current directory, not to the target file, and is created if needed. Sidecar files are written next to the fallback file. Other IO errors, like a missing parent directory, are returned as is, because they are usually a mistake in the path.
+    pub fn fallback_dir(mut self, dir: &str) -> Self {
+        self.fallback_dir = Some(dir.to_owned());
+        self
+    }
+
+    /// Set the test harness that [render_group](fn.render_group.html) renders for. Defaults to [Harness::Std](enum.Harness.html#variant.Std).
+    pub fn harness(mut self, harness: Harness) -> Self {
+        self.harness = harness;
+        self
+    }
+
+    /// Store the serialized executable and arguments in separate files in `dir`, instead of as string literals in the generated test. `dir` is relative to the file the tests are written to, e.g. `snapshots`.
+    ///
+    /// The test then reads them with `include_str!("snapshots/<id>.exec.json")` and `include_str!("snapshots/<id>.args.json")`, where `<id>` is the same id as in the name of the test function. This keeps the generated file small when the state is large. The files are written by [append_to_file](struct.UnitTest.html#method.append_to_file), [write_to_dir](struct.UnitTest.html#method.write_to_dir), the [writers](struct.StreamingWriter.html), a [TestSuite](struct.TestSuite.html) and the [Generator](struct.Generator.html); other ways of rendering a test, like [to_test_source](struct.UnitTest.html#method.to_test_source), only refer to them. Use [prune_sidecars](fn.prune_sidecars.html) to remove the files of tests that were deleted.
+    pub fn sidecar_dir(mut self, dir: &str) -> Self {
+        self.sidecar_dir = Some(dir.trim_end_matches('/').to_owned());
+        self
+    }
+
+    /// Refuse to render a test whose serialized executable and arguments are together larger than `bytes`.
+    ///
+    /// This protects against accidentally generating multi-megabyte tests, e.g. from an executable that holds a whole cache. Rendering then fails with [SnapshotTooLarge](enum.RenderError.html#variant.SnapshotTooLarge), which names the size. The JSON is never truncated, because a truncated snapshot would not deserialize. To still generate the test, reduce the state with [execute_minimized](fn.execute_minimized.html), or store it in [sidecar files](#method.sidecar_dir) and raise the limit.
+    pub fn max_snapshot_bytes(mut self, bytes: usize) -> Self {
+        self.max_snapshot_bytes = Some(bytes);
+        self
+    }
+
+    /// Print the description and the error of the executable before the generated test panics. This is enabled by default.
+    ///
+    /// When disabled, the default check of the generated test is only `obj.execute(&args).expect("regression still fails");`, which keeps the output of test runs with many generated tests short. A custom [assertion](trait.Executable.html#method.assertion) is not affected by this option.
+    pub fn verbose_body(mut self, verbose: bool) -> Self {
+        self.verbose_body = verbose;
+        self
+    }
+
+    /// Pass the arguments and the result of the call through `std::hint::black_box`, so the compiler can't optimize away the work of a reproduction whose result is not used, e.g. of a performance problem. This is disabled by default.
+    ///
+    /// The call in the generated test, [benchmark](struct.UnitTest.html#method.render_as_benchmark) and [example](struct.UnitTest.html#method.render_as_example) becomes `::std::hint::black_box(obj.execute(::std::hint::black_box(&args)))`, and the outcome is checked as usual. A custom [assertion](trait.Executable.html#method.assertion) is not affected by this option.
+    pub fn black_box(mut self, enabled: bool) -> Self {
+        self.black_box = enabled;
+        self
+    }
+
+    /// Embed the executable and the arguments in the generated test in `format` instead of JSON. The test then deserializes them with the matching function, so the crate that contains it needs that crate as a dev-dependency, like `ron` for [RON](enum.SerializationFormat.html#variant.Ron):
+    ///
+    /// ```rust,ignore
+    /// let obj_ron = r#"(limit: 10, ratio: 0.30000000000000004, mode: Strict)"#;
+    /// let mut obj: ::my_crate::Parser = ::ron::from_str(obj_ron).expect("Could not deserialize ron");
+    /// ```
+    ///
+    /// The format only applies to the inputs that are embedded in the test, inline or [hoisted](#method.hoist_inputs). The marker hash, the [round-trip check](struct.UnitTest.html#method.check_round_trip) and [snapshots](struct.UnitTest.html#method.snapshots) stay JSON, as do [sidecar files](#method.sidecar_dir), [groups](fn.render_group.html), and fields that are written as assignments or struct literals, and [byte arrays are only encoded as base64](#method.base64_bytes) in JSON. [JSON post-processing](#method.json_postprocess) and [key sorting](#method.sort_keys) work on JSON values, so they can't be combined with another format: the test fails to render with [PostprocessedFormat](enum.RenderError.html#variant.PostprocessedFormat) instead of embedding inputs that were not post-processed. [rerun_file](fn.rerun_file.html) and the maintenance functions only read JSON, and report tests in another format as unreadable.
+    pub fn serialization_format(mut self, format: SerializationFormat) -> Self {
+        self.serialization_format = format;
+        self
+    }
+
+    /// Write the paths in the generated test for a crate of the given `edition`.
+    ///
+    /// A path like `::SomeImportantAction` starts at the root of the crate in Rust 2015, but names an external crate in Rust 2018 and later, so tests for those editions don't compile with the [full path](trait.Executable.html#tymethod.full_path) as it is. With [Edition2018](enum.Edition.html#variant.Edition2018), absolute paths in the types of the executable and the arguments start with `crate::` instead, like `crate::SomeImportantAction`, and the paths to external crates drop their leading `::`, like `serde_json::from_str` and `exceptional::Executable`:
+    ///
+    /// ```rust,ignore
+    /// let mut obj: crate::SomeImportantAction = serde_json::from_str(obj_json).expect("Could not deserialize json");
+    /// ```
+    ///
+    /// Paths to `std`, `core`, `alloc` and `exceptional` are kept as external crates, like the crate of an [integration test](#method.test_kind), and other `::name::` paths are taken to be modules of the crate, as in Rust 2015. The marker comment keeps the full path as it is. The default is `Edition2015`, which writes every path as before.
+    pub fn edition(mut self, edition: Edition) -> Self {
+        self.edition = edition;
+        self
+    }
+
+    /// `path`, an absolute path to an item of an external crate like `::serde_json::from_str`, as it is written in the generated code for the configured [edition](#method.edition).
+    pub(crate) fn extern_path<'p>(&self, path: &'p str) -> &'p str {
+        match self.edition {
+            Edition::Edition2015 => path,
+            Edition::Edition2018 => path.trim_start_matches("::"),
+        }
+    }
+
+    /// Generate an `async fn` test with the given test attribute, e.g. `tokio::test` or `async_std::test`, for an [AsyncExecutable](trait.AsyncExecutable.html). The generated test awaits `obj.execute_async(&args)` instead of calling `obj.execute(&args)`:
+    ///
+    /// ```text
+    /// #[tokio::test]
+    /// pub async fn test_1539761233543() {
+    ///     use exceptional::{AsyncExecutable, Executable};
+    ///     // ...
+    ///     if let Err(e) = obj.execute_async(&args).await {
+    /// ```
+    ///
+    /// The crate that contains the test needs the executor that provides the attribute as a dev-dependency. `attribute` can be given with or without the surrounding `#[...]`.
+    pub fn async_test(mut self, attribute: &str) -> Self {
+        self.async_test = Some(bare_attribute(attribute).to_owned());
+        self
+    }
+
+    /// Mark the generated test with the given attribute instead of `test`, e.g. `wasm_bindgen_test::wasm_bindgen_test` or a test macro of the project that sets up logging. Like for [async_test](#method.async_test), `attribute` can be given with or without the surrounding `#[...]`.
+    ///
+    /// The function stays synchronous and calls `obj.execute(&args)`. For an attribute that needs an `async fn`, like `tokio::test` or `actix_rt::test`, use `async_test` instead, which takes precedence over this attribute. The [test-case](enum.Harness.html#variant.TestCase) harness brings its own attribute, so groups rendered with it don't use this one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `attribute` is empty.
+    pub fn test_attribute(mut self, attribute: &str) -> Self {
+        let attribute = bare_attribute(attribute);
+        assert!(!attribute.is_empty(), "the test attribute can not be empty");
+        self.test_attribute = attribute.to_owned();
+        self
+    }
+
+    /// Fail the generated test if the executable does not finish within `timeout`, instead of hanging, for reproductions of failures that hang rather than return an error.
+    ///
+    /// The executable and its check run on a separate thread through [run_with_timeout](fn.run_with_timeout.html), so the executable and its arguments have to be `Send`. The thread can't be cancelled: after a timeout, a call that blocks forever, like a deadlock or a read from a socket that never answers, keeps running in the background until the test binary exits. The RNG is [seeded](trait.Executable.html#method.rng_setup) on that thread. [Async tests](#method.async_test) are rendered without a timeout; use the timer of their runtime instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `timeout` is less than a millisecond.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        assert!(
+            timeout.as_millis() > 0,
+            "the timeout must be at least 1 millisecond"
+        );
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Run the executable in the generated test inside a [GlobalsGuard](struct.GlobalsGuard.html), which restores the current directory and the [captured](struct.UnitTest.html#method.capture_globals) environment variables when the test ends, even if it panics.
+    ///
+    /// If globals were captured, the guard also sets them to the captured values before the executable runs. This keeps reproductions that change the current directory or the environment from affecting other tests. Generated tests with a guard don't run at the same time as each other. Other process-global state, like the locale, is not handled.
+    pub fn restore_globals(mut self, enabled: bool) -> Self {
+        self.restore_globals = enabled;
+        self
+    }
+
+    /// Set the name of the variable that holds the deserialized executable in the generated test. Defaults to `obj`.
+    ///
+    /// The name is used everywhere in the generated code, and an [assertion](trait.Executable.html#method.assertion) has to use it as well.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` is not a valid identifier, is a keyword, is the [args binding](#method.args_binding), or is one of the other names used in generated tests and benchmarks: `obj_json`, `arg_json`, `obj_ron`, `arg_ron`, `e`, `message`, `b`, `c`, `_globals`, `expected`, `expected_error`, `seed` and `result`.
+    pub fn obj_binding(mut self, name: &str) -> Self {
+        assert!(
+            is_valid_binding(name) && name != self.args_binding,
+            "{:?} can not be used as a binding",
+            name
+        );
+        self.obj_binding = name.to_owned();
+        self
+    }
+
+    /// Set the name of the variable that holds the deserialized arguments in the generated test. Defaults to `args`.
+    ///
+    /// # Panics
+    ///
+    /// Panics for the same names as [obj_binding](#method.obj_binding), and if `name` is the obj binding.
+    pub fn args_binding(mut self, name: &str) -> Self {
+        assert!(
+            is_valid_binding(name) && name != self.obj_binding,
+            "{:?} can not be used as a binding",
+            name
+        );
+        self.args_binding = name.to_owned();
+        self
+    }
+
+    /// Add the version of rustc and the target triple to the header of the generated test, as `/// rustc: {version}` and `/// target: {triple}`.
+    ///
+    /// Both are determined by this crate's build script, so they describe the compiler and target that this crate, and normally the program that generated the test, were built with.
+    #[cfg(feature = "build-info")]
+    pub fn include_build_info(mut self, include: bool) -> Self {
+        self.include_build_info = include;
+        self
+    }
+
+    /// Check the options that have a validating setter again, because they can also be changed directly, e.g. through [options_mut](struct.UnitTest.html#method.options_mut). Returns the first option with a value that its setter would have rejected.
+    pub(crate) fn validate(&self) -> Result<(), RenderError> {
+        let invalid = |option: &'static str, value: &str| {
+            Err(RenderError::InvalidOption {
+                option,
+                value: value.to_owned(),
+            })
+        };
+        if let Some(ref predicate) = self.cfg_gate {
+            if !is_valid_cfg_predicate(predicate) {
+                return invalid("cfg_gate", predicate);
+            }
+        }
+        if let Some(ref format) = self.time_format {
+            if !is_valid_time_format(format) {
+                return invalid("time_format", format);
+            }
+        }
+        if let Some(ref name) = self.target_module {
+            if !is_identifier(name) {
+                return invalid("target_module", name);
+            }
+        }
+        if let Some(ref path) = self.module_path {
+            if !is_valid_module_path(path) {
+                return invalid("module_path", path);
+            }
+        }
+        if let Some((_, ref crate_name)) = self.test_kind {
+            if !is_identifier(crate_name) {
+                return invalid("test_kind", crate_name);
+            }
+        }
+        if self.test_attribute.is_empty()
+            || bare_attribute(&self.test_attribute) != self.test_attribute
+        {
+            return invalid("test_attribute", &self.test_attribute);
+        }
+        if let Some(timeout) = self.timeout {
+            if timeout.as_millis() == 0 {
+                return invalid("timeout", &format!("{:?}", timeout));
+            }
+        }
+        if !is_valid_binding(&self.obj_binding) || self.obj_binding == self.args_binding {
+            return invalid("obj_binding", &self.obj_binding);
+        }
+        if !is_valid_binding(&self.args_binding) {
+            return invalid("args_binding", &self.args_binding);
+        }
+        Ok(())
+    }
+
+    /// The banner as it is written to the file, or `None` if no banner is configured.
+    pub(crate) fn rendered_banner(&self) -> Option<String> {
+        self.banner.as_ref().map(|text| {
+            let mut banner = String::new();
+            for line in text.lines() {
+                banner.push_str("//!");
+                if !line.is_empty() {
+                    banner.push(' ');
+                    banner.push_str(line);
+                }
+                banner.push('\n');
+            }
+            banner.push('\n');
+            banner
+        })
+    }
+}
+
+/// The names of the other local variables in a generated test.
+const GENERATED_LOCALS: &[&str] = &[
+    "obj_json",
+    "arg_json",
+    "obj_ron",
+    "arg_ron",
+    "e",
+    "message",
+    "b",
+    "c",
+    "_globals",
+    "expected",
+    "expected_error",
+    "seed",
+    "result",
+];
+
+/// The keywords of Rust 2015 and later editions, which can't be used as a plain identifier.
+const KEYWORDS: &[&str] = &[
+    "_", "abstract", "as", "async", "await", "become", "box", "break", "const", "continue",
+    "crate", "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if",
+    "impl", "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub",
+    "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "try", "type",
+    "typeof", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// Whether `format` is a strftime format without line breaks.
+fn is_valid_time_format(format: &str) -> bool {
+    !format.contains('\n') && !StrftimeItems::new(format).any(|item| item == Item::Error)
+}
+
+/// Whether `path` is a `::`-separated list of module names.
+fn is_valid_module_path(path: &str) -> bool {
+    path.split("::").all(is_identifier)
+}
+
+/// `attribute` without the surrounding `#[...]`, if it has them.
+fn bare_attribute(attribute: &str) -> &str {
+    let attribute = attribute.trim();
+    attribute
+        .strip_prefix("#[")
+        .and_then(|a| a.strip_suffix(']'))
+        .unwrap_or(attribute)
+}
+
+/// Check that `name` can be used as the name of a local variable in the generated test.
+fn is_valid_binding(name: &str) -> bool {
+    is_identifier(name) && !GENERATED_LOCALS.contains(&name)
+}
+
+/// Whether `name` is an ASCII identifier that is not a keyword, and can be used as is, e.g. as a field name.
+pub(crate) fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    let starts_well = match chars.next() {
+        Some(c) => c.is_ascii_alphabetic() || c == '_',
+        None => false,
+    };
+    starts_well && chars.all(|c| c.is_ascii_alphanumeric() || c == '_') && !KEYWORDS.contains(&name)
+}
+
+/// Cargo feature names consist of letters, digits, `_`, `-` and `+`, and start with a letter, digit or `_`.
+fn is_valid_feature(feature: &str) -> bool {
+    match feature.chars().next() {
+        Some(c) if c.is_ascii_alphanumeric() || c == '_' => {}
+        _ => return false,
+    }
+    feature
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '+')
+}
+
+/// A minimal sanity check of a `cfg` predicate. This does not check that the predicate is meaningful, only that it can't break out of the `#[cfg(...)]` attribute.
+fn is_valid_cfg_predicate(predicate: &str) -> bool {
+    let predicate = predicate.trim();
+    if predicate.is_empty() || predicate.contains('\n') {
+        return false;
+    }
+    let mut depth = 0i32;
+    let mut in_string = false;
+    let mut escaped = false;
+    for c in predicate.chars() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '(' if !in_string => depth += 1,
+            ')' if !in_string => {
+                depth -= 1;
+                if depth < 0 {
+                    return false;
+                }
+            }
+            _ => {}
+        }
+    }
+    depth == 0 && !in_string
+}
@@ -0,0 +1,71 @@
+use std::env;
+use std::io;
+use std::path::PathBuf;
+use {Executable, UnitTest};
+
+/// The path of `file_name` in the directory named by the `OUT_DIR` environment variable.
+///
+/// Cargo sets `OUT_DIR` for build scripts, so this is where a build script should write generated tests that are pulled in with `include!`. Returns an error of kind `NotFound` if `OUT_DIR` is not set.
+pub fn out_dir_file(file_name: &str) -> io::Result<PathBuf> {
+    match env::var_os("OUT_DIR") {
+        Some(dir) => Ok(PathBuf::from(dir).join(file_name)),
+        None => Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "OUT_DIR is not set, tests can only be written to OUT_DIR from a build script",
+        )),
+    }
+}
+
+impl<'a, E: Executable + 'a> UnitTest<'a, E> {
+    /// Append this unit test to `file_name` in `OUT_DIR`, see [out_dir_file](fn.out_dir_file.html), and return the path of the file.
+    ///
+    /// This keeps generated tests out of `src/` and out of version control. The generated file only contains the test functions, without a `mod` wrapper, and all paths in it are fully qualified, so it can be included into any module. The [banner](struct.UnitTestOptions.html#method.banner) and the [table of contents](struct.UnitTestOptions.html#method.table_of_contents) are not written, because inner doc comments are not allowed in an included file.
+    ///
+    /// The tests are generated by the build script of a separate crate, because a build script can't depend on the crate it builds. A small crate next to `my_crate` in the workspace, which depends on it both at build time and at test time, generates and includes the tests:
+    ///
+    /// ```toml
+    /// # my_crate_regressions/Cargo.toml
+    /// [build-dependencies]
+    /// exceptional = "0.2"
+    /// my_crate = { path = "../my_crate" }
+    ///
+    /// [dev-dependencies]
+    /// exceptional = "0.2"
+    /// serde_json = "1"
+    /// my_crate = { path = "../my_crate" }
+    /// ```
+    ///
+    /// ```rust,ignore
+    /// // my_crate_regressions/build.rs
+    /// extern crate exceptional;
+    /// extern crate my_crate;
+    ///
+    /// fn main() {
+    ///     let path = exceptional::out_dir_file("regressions.rs").unwrap();
+    ///     // Start with an empty file, so tests aren't duplicated when the build script runs again
+    ///     std::fs::write(&path, "").unwrap();
+    ///
+    ///     for (mut action, args) in my_crate::recorded_failures() {
+    ///         if let Err(test) = exceptional::execute(&mut action, &args) {
+    ///             test.append_to_out_dir("regressions.rs").unwrap();
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// ```rust,ignore
+    /// // my_crate_regressions/tests/regressions.rs
+    /// extern crate exceptional;
+    /// extern crate serde_json;
+    /// extern crate my_crate;
+    ///
+    /// include!(concat!(env!("OUT_DIR"), "/regressions.rs"));
+    /// ```
+    pub fn append_to_out_dir(mut self, file_name: &str) -> io::Result<PathBuf> {
+        let path = out_dir_file(file_name)?;
+        self.options.banner = None;
+        self.options.table_of_contents = false;
+        self.append_to_file(&path)?;
+        Ok(path)
+    }
+}
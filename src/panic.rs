@@ -0,0 +1,57 @@
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe, RefUnwindSafe};
+use {Executable, MaybeOwned, UnitTest};
+
+/// Like [execute](fn.execute.html), but also captures a panic of the executable, like an index out of bounds or an `unwrap` on `None`, instead of letting it propagate.
+///
+/// The executable must have `String` errors, because the message of a caught panic becomes the [error](struct.UnitTest.html#structfield.error) of the test. It is also stored in [panic_message](struct.UnitTest.html#structfield.panic_message), and the generated test checks that the executable panics again with that message:
+///
+/// ```rust,ignore
+/// #[should_panic(expected = "index out of bounds: the len is 3 but the index is 7")]
+/// #[test]
+/// pub fn test_1539761233543() {
+///     ...
+///     let _ = obj.execute(&args);
+///     panic!("{} no longer panics", obj.description());
+/// }
+/// ```
+///
+/// Errors that are returned are captured like in `execute`. A panic with a payload that is not a string is captured with an empty message, and its test only checks that the executable panics.
+///
+/// # Unwind safety
+///
+/// The arguments must be `RefUnwindSafe`, because the executable only borrows them and they are used again after the panic to render the test. The executable itself is not required to be `UnwindSafe`: it is cloned before the call, and the test is generated from that clone, so the state that the panic left behind is never serialized. That state is still in `executable` afterwards, though, so don't rely on its invariants after a caught panic, and drop or reset it instead.
+///
+/// The panic is still reported by the panic hook, so its message is printed to stderr as usual.
+#[track_caller]
+pub fn execute_catch_unwind<'a, E>(
+    executable: &'a mut E,
+    arguments: &'a E::Arguments,
+) -> Result<E::Result, UnitTest<'a, E>>
+where
+    E: Executable<Error = String> + 'a,
+    E::Arguments: RefUnwindSafe,
+{
+    let old = executable.clone();
+    match panic::catch_unwind(AssertUnwindSafe(|| executable.execute(arguments))) {
+        Ok(Ok(value)) => Ok(value),
+        Ok(Err(error)) => Err(UnitTest::new(old, MaybeOwned::Borrowed(arguments), error)),
+        Err(payload) => {
+            let message = panic_message(&*payload);
+            let mut test = UnitTest::new(old, MaybeOwned::Borrowed(arguments), message.clone());
+            test.panic_message = Some(message);
+            Err(test)
+        }
+    }
+}
+
+/// The message of a panic with `payload`, or an empty string if the payload is not a string.
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        (*message).to_owned()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        String::new()
+    }
+}
@@ -0,0 +1,362 @@
+//! Reading back files that were generated by this crate.
+//!
+//! Every generated test starts with a marker comment, in the format that is described at [Marker](struct.Marker.html). The item that belongs to a marker extends from the start of the marker line to the closing brace of the first braced block after it, which is the body of the generated function.
+
+use severity::Severity;
+use std::ops::Range;
+
+/// The prefix of every marker comment.
+pub(crate) const MARKER_PREFIX: &str = "// @exceptional ";
+
+/// The prefix of the names of the consts that hold [hoisted inputs](struct.UnitTestOptions.html#method.hoist_inputs).
+pub(crate) const INPUT_PREFIX: &str = "INPUT_";
+
+/// The call that restores the [base64 byte arrays](struct.UnitTestOptions.html#method.base64_bytes) of an embedded input, after the `&` and the `::` that tests for [Rust 2015](enum.Edition.html#variant.Edition2015) start it with.
+const DECODE_BASE64: &str = "exceptional::decode_base64_json(";
+
+/// The machine-readable comment that starts every generated item, so tools can find generated tests in a file without parsing Rust:
+///
+/// ```text
+/// // @exceptional id=<id> hash=<hash> path=<full_path> time=<rfc3339>
+/// ```
+///
+/// `id` is the suffix of the function name, `hash` the hex [payload hash](struct.UnitTestMeta.html#structfield.payload_hash) of the snapshot, `path` the [full path](trait.Executable.html#tymethod.full_path) of the executable and `time` the time of the failure with millisecond precision. A `severity=<name>` field follows if the executable has a [severity](trait.Executable.html#method.severity) other than the default, and an `error=<hash>` field with the hex hash of the `Debug` output of the error for items that were generated from a single failure. The marker may be indented.
+///
+/// This format is stable: upserts, deduplication, [rerun_file](fn.rerun_file.html), [resplit_by_type](fn.resplit_by_type.html), [prune_sidecars](fn.prune_sidecars.html), [tests_with_severity](fn.tests_with_severity.html) and [diff_files](fn.diff_files.html) all rely on it, so files generated by older versions keep working. New fields are only ever added at the end, and parsers ignore fields they don't know. Use [parse_markers](fn.parse_markers.html) to read the markers of a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Marker {
+    /// The id of the test, which is the suffix of its function name, like `1539761233543` for `test_1539761233543`
+    pub id: String,
+
+    /// The hash of the serialized executable and arguments, as 16 hex digits unless another [hasher](struct.UnitTestOptions.html#method.hasher) was configured
+    pub hash: String,
+
+    /// The full path of the executable
+    pub path: String,
+
+    /// The time of the failure, in RFC 3339 format
+    pub time: String,
+
+    /// The severity of the failure, which is `Medium` if the marker does not have one
+    pub severity: Severity,
+
+    /// The hash of the `Debug` output of the error, like the hash of the executable and arguments. This is empty for groups of several failures and for tests that were generated by older versions.
+    pub error: String,
+}
+
+impl Marker {
+    /// The [fingerprint](struct.UnitTest.html#method.fingerprint) of the failure that the item was generated for. Items without an error hash, like groups and tests of older versions, never have the fingerprint of a unit test.
+    pub fn fingerprint(&self) -> String {
+        format!("{}-{}", self.hash, self.error)
+    }
+
+    /// Parse a single marker line. Leading whitespace is ignored.
+    ///
+    /// Values don't contain whitespace, except for the full path, which can contain generic arguments like `<A, B>`. A word without a `key=` prefix is therefore added to the previous value.
+    pub(crate) fn parse(line: &str) -> Option<Marker> {
+        let fields = line.trim_start().strip_prefix(MARKER_PREFIX)?;
+        let mut marker = Marker {
+            id: String::new(),
+            hash: String::new(),
+            path: String::new(),
+            time: String::new(),
+            severity: Severity::default(),
+            error: String::new(),
+        };
+        let mut pairs: Vec<(&str, String)> = Vec::new();
+        for word in fields.split_whitespace() {
+            let mut parts = word.splitn(2, '=');
+            let key = parts.next().unwrap_or("");
+            match (parts.next(), pairs.last_mut()) {
+                (Some(value), _) if is_key(key) => pairs.push((key, value.to_owned())),
+                (_, Some(&mut (_, ref mut value))) => {
+                    value.push(' ');
+                    value.push_str(word);
+                }
+                (_, None) => {}
+            }
+        }
+        for (key, value) in pairs {
+            match key {
+                "id" => marker.id = value,
+                "hash" => marker.hash = value,
+                "path" => marker.path = value,
+                "time" => marker.time = value,
+                "severity" => marker.severity = Severity::from_name(&value).unwrap_or_default(),
+                "error" => marker.error = value,
+                _ => {}
+            }
+        }
+        if marker.id.is_empty() {
+            None
+        } else {
+            Some(marker)
+        }
+    }
+}
+
+/// Whether `word` can be the key of a marker field, so `key=value` starts a new field.
+fn is_key(word: &str) -> bool {
+    !word.is_empty() && word.chars().all(|c| c.is_ascii_lowercase() || c == '_')
+}
+
+/// Find the [marker](struct.Marker.html) comments in `src`, in order.
+///
+/// Every line that is a marker is returned, whether or not it is followed by a complete generated item.
+pub fn parse_markers(src: &str) -> Vec<Marker> {
+    src.lines().filter_map(Marker::parse).collect()
+}
+
+/// A generated test that was found in a source file.
+#[derive(Debug, Clone)]
+pub(crate) struct GeneratedItem {
+    pub marker: Marker,
+    /// The byte range of the item in the source, including the marker line and the trailing newline.
+    pub range: Range<usize>,
+}
+
+/// Find all generated tests in the given source.
+///
+/// Markers that are not followed by a complete braced block are ignored.
+pub(crate) fn find_items(src: &str) -> Vec<GeneratedItem> {
+    let mut items = Vec::new();
+    let mut line_start = 0;
+    while line_start < src.len() {
+        let line_end = src[line_start..]
+            .find('\n')
+            .map(|i| line_start + i + 1)
+            .unwrap_or_else(|| src.len());
+        if let Some(marker) = Marker::parse(&src[line_start..line_end]) {
+            if let Some(end) = item_end(src, line_end) {
+                items.push(GeneratedItem {
+                    marker,
+                    range: line_start..end,
+                });
+                line_start = end;
+                continue;
+            }
+        }
+        line_start = line_end;
+    }
+    items
+}
+
+/// Find the module with the given name, like `mod regression_tests { ... }`, and return the offsets of its opening and closing braces.
+///
+/// Modules in comments and string literals are ignored, and so are modules that are declared without a body.
+pub(crate) fn find_module(src: &str, name: &str) -> Option<(usize, usize)> {
+    let bytes = src.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        if let Some(next) = skip_non_code(src, i) {
+            i = next;
+            continue;
+        }
+        let at_word_start = i == 0 || !is_ident_byte(bytes[i - 1]);
+        if at_word_start && src[i..].starts_with("mod") {
+            let rest = &src[i + 3..];
+            let name_start = rest.len() - rest.trim_start().len();
+            let after_name = rest[name_start..].strip_prefix(name);
+            if name_start > 0 {
+                if let Some(after_name) = after_name {
+                    let after = after_name.trim_start();
+                    if after.starts_with('{')
+                        && !after_name.starts_with(|c: char| c.is_ascii_alphanumeric() || c == '_')
+                    {
+                        let open = src.len() - after.len();
+                        return matching_brace(src, open).map(|close| (open, close));
+                    }
+                }
+            }
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Find the end of the first braced block that starts at or after `from`, including a trailing newline.
+fn item_end(src: &str, from: usize) -> Option<usize> {
+    let bytes = src.as_bytes();
+    let mut i = from;
+    while i < bytes.len() {
+        if let Some(next) = skip_non_code(src, i) {
+            i = next;
+            continue;
+        }
+        if bytes[i] == b'{' {
+            let close = matching_brace(src, i)?;
+            let mut end = close + 1;
+            if src[end..].starts_with("\r\n") {
+                end += 2;
+            } else if src[end..].starts_with('\n') {
+                end += 1;
+            }
+            return Some(end);
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Given the offset of a `{`, return the offset of the matching `}`.
+///
+/// Braces in comments, string literals, raw string literals and character literals are skipped.
+pub(crate) fn matching_brace(src: &str, open: usize) -> Option<usize> {
+    let bytes = src.as_bytes();
+    debug_assert_eq!(bytes[open], b'{');
+    let mut depth = 0usize;
+    let mut i = open;
+    while i < bytes.len() {
+        if let Some(next) = skip_non_code(src, i) {
+            i = next;
+            continue;
+        }
+        match bytes[i] {
+            b'{' => depth += 1,
+            b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// If a comment or a literal starts at offset `i`, return the offset just past its end.
+fn skip_non_code(src: &str, i: usize) -> Option<usize> {
+    let bytes = src.as_bytes();
+    if !matches!(bytes[i], b'/' | b'r' | b'"' | b'\'') {
+        return None;
+    }
+    let rest = &src[i..];
+    if rest.starts_with("//") {
+        return Some(
+            rest.find('\n')
+                .map(|n| i + n + 1)
+                .unwrap_or_else(|| src.len()),
+        );
+    }
+    if let Some(comment) = rest.strip_prefix("/*") {
+        return Some(
+            comment
+                .find("*/")
+                .map(|n| i + n + 4)
+                .unwrap_or_else(|| src.len()),
+        );
+    }
+    let preceded_by_ident = i > 0 && is_ident_byte(bytes[i - 1]);
+    if bytes[i] == b'r' && !preceded_by_ident {
+        let hashes = rest[1..].bytes().take_while(|b| *b == b'#').count();
+        if rest[1 + hashes..].starts_with('"') {
+            let terminator = format!("\"{}", "#".repeat(hashes));
+            let body = i + 2 + hashes;
+            return Some(
+                src[body..]
+                    .find(&terminator)
+                    .map(|n| body + n + terminator.len())
+                    .unwrap_or_else(|| src.len()),
+            );
+        }
+    }
+    if bytes[i] == b'"' {
+        let mut j = i + 1;
+        while j < bytes.len() {
+            match bytes[j] {
+                b'\\' => j += 2,
+                b'"' => return Some(j + 1),
+                _ => j += 1,
+            }
+        }
+        return Some(src.len());
+    }
+    if bytes[i] == b'\'' {
+        // Either a character literal or a lifetime. Lifetimes are left alone.
+        if rest[1..].starts_with('\\') {
+            return rest[2..].find('\'').map(|n| i + n + 3);
+        }
+        let mut chars = rest[1..].chars();
+        if let Some(c) = chars.next() {
+            if chars.next() == Some('\'') {
+                return Some(i + 2 + c.len_utf8());
+            }
+        }
+    }
+    None
+}
+
+fn is_ident_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_'
+}
+
+/// Where a generated test reads one of its JSON snapshots from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum SnapshotSource {
+    /// A raw string literal in the test itself
+    Inline(String),
+    /// A sidecar file, with the path from the `include_str!`, relative to the test file
+    Include(String),
+}
+
+/// Find the value that the generated item assigns to the given local, like `obj_json`. A [hoisted input](struct.UnitTestOptions.html#method.hoist_inputs) is read from its const in the item, and [base64 byte arrays](struct.UnitTestOptions.html#method.base64_bytes) are decoded.
+pub(crate) fn snapshot_source(item: &str, local: &str) -> Option<SnapshotSource> {
+    let statement = format!("let {} = ", local);
+    let start = item.find(&statement)? + statement.len();
+    expression_source(item, &item[start..])
+}
+
+/// The value of the expression at the start of `expression`, which is part of `item`.
+fn expression_source(item: &str, expression: &str) -> Option<SnapshotSource> {
+    if let Some(literal) = raw_literal(expression) {
+        Some(SnapshotSource::Inline(literal.to_owned()))
+    } else if let Some(path) = expression.strip_prefix("include_str!(") {
+        let end = path.find(");")?;
+        ::serde_json::from_str(&path[..end])
+            .ok()
+            .map(SnapshotSource::Include)
+    } else if expression.starts_with(INPUT_PREFIX) {
+        let end = expression.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))?;
+        input_const(item, &expression[..end])
+            .map(|literal| SnapshotSource::Inline(literal.to_owned()))
+    } else if let Some(inner) = expression
+        .strip_prefix('&')
+        .map(|call| call.trim_start_matches("::"))
+        .and_then(|call| call.strip_prefix(DECODE_BASE64))
+    {
+        match expression_source(item, inner)? {
+            SnapshotSource::Inline(json) => {
+                Some(SnapshotSource::Inline(::base64::decode_base64_json(&json)))
+            }
+            SnapshotSource::Include(_) => None,
+        }
+    } else {
+        None
+    }
+}
+
+/// The contents of the raw string literal at the start of `src`, with any number of `#`.
+fn raw_literal(src: &str) -> Option<&str> {
+    let rest = src.strip_prefix('r')?;
+    let hashes = rest.bytes().take_while(|b| *b == b'#').count();
+    let literal = rest[hashes..].strip_prefix('"')?;
+    let end = literal.find(&format!("\"{}", "#".repeat(hashes)))?;
+    Some(&literal[..end])
+}
+
+/// The contents of the const with the given name that holds a hoisted input, if `item` defines it. Only consts at the start of a line count, like the generated ones.
+fn input_const<'s>(item: &'s str, name: &str) -> Option<&'s str> {
+    let definition = format!("const {}: &str = ", name);
+    let mut line_start = 0;
+    for line in item.split_inclusive('\n') {
+        let code = line.trim_start();
+        if code.starts_with(definition.as_str()) {
+            let offset = line_start + (line.len() - code.len()) + definition.len();
+            return raw_literal(&item[offset..]);
+        }
+        line_start += line.len();
+    }
+    None
+}
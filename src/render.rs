@@ -0,0 +1,1394 @@
+use chrono::SecondsFormat;
+use error::{RenderError, SerializeError};
+use error_render::ErrorRender;
+use globals::Globals;
+use marker_index::MarkerIndex;
+use options::{
+    CommentStyle, Edition, SerializationFormat, TestKind, UnitTestMeta, UnitTestOptions,
+};
+use parse::MARKER_PREFIX;
+use serde::Serialize;
+use serde_json::Value;
+use severity::Severity;
+use std::borrow::Cow;
+use std::collections::{BTreeSet, HashSet};
+use std::fmt::{self, Write};
+use writer::DedupKey;
+use {Executable, UnitTest};
+
+/// The serialized state of a [UnitTest](struct.UnitTest.html) and its id, computed once per render.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Snapshot {
+    pub executable_json: String,
+    pub arguments_json: String,
+    pub id: String,
+    /// The hash of the executable and arguments with the configured [hasher](struct.UnitTestOptions.html#method.hasher), as it appears in the `hash=` field of the marker comment
+    pub hash: String,
+    /// The hash of the `Debug` output of the error with the configured hasher, as it appears in the `error=` field of the marker comment
+    pub error_hash: String,
+    /// The executable and the arguments in the configured [serialization format](struct.UnitTestOptions.html#method.serialization_format), if it is not JSON
+    pub formatted: Option<(String, String)>,
+}
+
+impl Snapshot {
+    /// The stable FNV-1a hash of this snapshot, regardless of the configured hasher.
+    pub(crate) fn payload_hash(&self) -> u64 {
+        ::hash::payload_hash(&self.executable_json, &self.arguments_json)
+    }
+}
+
+impl<'a, E: Executable + 'a> UnitTest<'a, E> {
+    /// Serialize the executable and the arguments, and determine the id of the generated function.
+    ///
+    /// The JSON is deserialized again, so a snapshot that the generated test can't read is never written.
+    pub(crate) fn snapshot(&self) -> Result<Snapshot, RenderError> {
+        self.options.validate()?;
+        let (executable_json, arguments_json) = self.snapshots()?;
+        let mut snapshot = Snapshot {
+            executable_json,
+            arguments_json,
+            id: String::new(),
+            hash: String::new(),
+            error_hash: String::new(),
+            formatted: None,
+        };
+        if let Some(limit) = self.options.max_snapshot_bytes {
+            let bytes = snapshot.executable_json.len() + snapshot.arguments_json.len();
+            if bytes > limit {
+                return Err(RenderError::SnapshotTooLarge { bytes, limit });
+            }
+        }
+        if let Some(expr) = self.executable.construct_expr() {
+            if expr.trim().is_empty() {
+                return Err(RenderError::EmptyConstructExpr);
+            }
+        }
+        self.check_snapshots(&snapshot.executable_json, &snapshot.arguments_json)?;
+        let hasher = self.options.hasher;
+        snapshot.hash = hasher.hash(&::hash::payload(
+            &snapshot.executable_json,
+            &snapshot.arguments_json,
+        ));
+        snapshot.error_hash = hasher.hash(format!("{:?}", self.error).as_bytes());
+        for hash in &[&snapshot.hash, &snapshot.error_hash] {
+            if !::hash::is_valid_hash(hash) {
+                return Err(RenderError::InvalidHash(hash.to_string()));
+            }
+        }
+        snapshot.formatted = self.formatted()?;
+        snapshot.id = self.id(&snapshot)?;
+        Ok(snapshot)
+    }
+
+    /// Render this unit test, or return an error if the executable or arguments can not be serialized.
+    ///
+    /// If serialization fails, the error names the path of the field that could not be serialized, e.g. `lookup` for a `HashMap` field with keys that are not strings. If the serialized executable or arguments can't be deserialized into an equal value, [ExecutableRoundTrip](enum.RenderError.html#variant.ExecutableRoundTrip) or [ArgumentsRoundTrip](enum.RenderError.html#variant.ArgumentsRoundTrip) is returned.
+    pub fn try_to_test_source(&self) -> Result<String, RenderError> {
+        let mut source = String::new();
+        self.render_into(&mut source)?;
+        Ok(source)
+    }
+
+    /// The rendered test, like [try_to_test_source](#method.try_to_test_source), without consuming this unit test, so the same failure can be sent to several places, like a code review tool and a log, without touching the file system. This is what the `Display` implementation writes if the test can be rendered.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the test can not be rendered. Use `try_to_test_source` to handle that as an error.
+    pub fn to_test_source(&self) -> String {
+        self.try_to_test_source()
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Like [try_to_test_source](#method.try_to_test_source), but append the test to `buf` instead of allocating a new `String`, so one buffer can be reused for a large batch:
+    ///
+    /// ```rust,ignore
+    /// let mut buf = String::with_capacity(64 * 1024);
+    /// for test in tests {
+    ///     buf.clear();
+    ///     test.render_into(&mut buf)?;
+    ///     out.write_all(buf.as_bytes())?;
+    /// }
+    /// ```
+    ///
+    /// If rendering fails, `buf` is left as it was.
+    pub fn render_into(&self, buf: &mut String) -> Result<(), RenderError> {
+        let snapshot = self.snapshot()?;
+        self.render_snapshot_into(buf, &snapshot);
+        Ok(())
+    }
+
+    /// Append the test for `snapshot` to `buf`, with the configured line ending.
+    pub(crate) fn render_snapshot_into(&self, buf: &mut String, snapshot: &Snapshot) {
+        let start = buf.len();
+        self.write_test(buf, snapshot)
+            .expect("Writing to a String cannot fail");
+        if let Some(ending) = self.options.line_ending {
+            if let Cow::Owned(text) = ending.apply(&buf[start..]) {
+                buf.truncate(start);
+                buf.push_str(&text);
+            }
+        }
+    }
+
+    /// The number of bytes that [append_to_file](#method.append_to_file) adds to the disk for this test, without writing anything, e.g. to enforce a budget on the size of the tests that a single CI run may generate.
+    ///
+    /// This is the length of the rendered test, plus the size of the [sidecar files](struct.UnitTestOptions.html#method.sidecar_dir) if they are configured. A [banner](struct.UnitTestOptions.html#method.banner) or a [target module](struct.UnitTestOptions.html#method.target_module) that still has to be created, an older version of the test that an [upsert](struct.UnitTestOptions.html#method.upsert) replaces, and the line ending of the file if no [line ending](struct.UnitTestOptions.html#method.line_ending) is configured, are not taken into account. Use [total_rendered_len](fn.total_rendered_len.html) for several tests.
+    pub fn rendered_len(&self) -> Result<usize, RenderError> {
+        let snapshot = self.snapshot()?;
+        let mut source = String::new();
+        self.render_snapshot_into(&mut source, &snapshot);
+        let sidecars = match self.options.sidecar_dir {
+            Some(_) => snapshot.executable_json.len() + snapshot.arguments_json.len(),
+            None => 0,
+        };
+        Ok(source.len() + sidecars)
+    }
+
+    /// Serialize the executable and the arguments, in the same JSON form that is embedded in the generated test, without rendering the test itself.
+    ///
+    /// Returns `(executable_json, arguments_json)`, after the configured [post-processing](struct.UnitTestOptions.html#method.json_postprocess) and [key sorting](struct.UnitTestOptions.html#method.sort_keys). This is useful to store failures in a different format, like a database, and to [replay](fn.replay.html) them later. Serialization errors are returned like in [try_to_test_source](#method.try_to_test_source).
+    pub fn snapshots(&self) -> Result<(String, String), RenderError> {
+        self.snapshots_with(&self.options)
+    }
+
+    /// Like [snapshots](#method.snapshots), with the post-processing and key sorting of `options` instead of the options of this test.
+    fn snapshots_with(&self, options: &UnitTestOptions) -> Result<(String, String), RenderError> {
+        let executable_json = to_json(&self.executable).map_err(RenderError::Executable)?;
+        let arguments_json = to_json(&self.arguments).map_err(RenderError::Arguments)?;
+        if options.json_postprocess.is_none() && !options.sort_keys {
+            return Ok((executable_json, arguments_json));
+        }
+        Ok((
+            postprocessed(options, &executable_json),
+            postprocessed(options, &arguments_json),
+        ))
+    }
+
+    /// The executable and the arguments in the configured [serialization format](struct.UnitTestOptions.html#method.serialization_format), or `None` for JSON.
+    fn formatted(&self) -> Result<Option<(String, String)>, RenderError> {
+        let format = self.options.serialization_format;
+        if format == SerializationFormat::Json {
+            return Ok(None);
+        }
+        if self.options.json_postprocess.is_some() || self.options.sort_keys {
+            return Err(RenderError::PostprocessedFormat(format));
+        }
+        let executable = ::ron::to_ron(&self.executable).map_err(RenderError::Executable)?;
+        let arguments = ::ron::to_ron(&*self.arguments).map_err(RenderError::Arguments)?;
+        Ok(Some((executable, arguments)))
+    }
+
+    /// The `hash` and `error` fields of the marker that this test gets with `options`, without checking the snapshots or rendering the test.
+    pub(crate) fn marker_hashes(
+        &self,
+        options: &UnitTestOptions,
+    ) -> Result<(String, String), RenderError> {
+        let (executable_json, arguments_json) = self.snapshots_with(options)?;
+        let hash = options
+            .hasher
+            .hash(&::hash::payload(&executable_json, &arguments_json));
+        let error_hash = options.hasher.hash(format!("{:?}", self.error).as_bytes());
+        Ok((hash, error_hash))
+    }
+
+    /// Returns `true` if a test with the same executable and arguments is in `index`, like the deduplication of a [StreamingWriter](struct.StreamingWriter.html).
+    ///
+    /// This serializes and hashes the executable and the arguments, but doesn't render the test or read the file, so it is cheap enough to call for every failure in a hot loop. A test that can't be serialized is never captured, and returns `false`.
+    pub fn is_captured(&self, index: &MarkerIndex) -> bool {
+        match self.marker_hashes(&self.options) {
+            Ok((hash, error)) => index.contains(DedupKey::ExecutableAndArgs, &hash, &error),
+            Err(_) => false,
+        }
+    }
+
+    /// A stable fingerprint of this failure: the hash of the serialized executable and arguments, and the hash of the `Debug` output of the error, like `3f2a9c01d4e5b6a7-a1b2c3d4e5f60718`. The time of the failure is not part of it, so the same failure always has the same fingerprint.
+    ///
+    /// Both hashes are written to the marker of the generated test, so the fingerprint of a test in a file is [that of its marker](struct.Marker.html#method.fingerprint). The hashes use the configured [hasher](struct.UnitTestOptions.html#method.hasher), after the [post-processing](struct.UnitTestOptions.html#method.json_postprocess) of the JSON. Serialization errors are returned like in [try_to_test_source](#method.try_to_test_source).
+    pub fn fingerprint(&self) -> Result<String, RenderError> {
+        let (hash, error) = self.marker_hashes(&self.options)?;
+        Ok(format!("{}-{}", hash, error))
+    }
+
+    /// The metadata that is passed to the configured [id strategy](struct.UnitTestOptions.html#method.id_strategy) and [route](struct.UnitTestOptions.html#method.route).
+    pub(crate) fn meta(&self, snapshot: &Snapshot) -> UnitTestMeta {
+        UnitTestMeta {
+            time: self.time,
+            description: self.executable.description(),
+            full_path: self.executable.full_path(),
+            payload_hash: snapshot.payload_hash(),
+            content_hash: snapshot.hash.clone(),
+            severity: self.executable.severity(),
+            metadata: self.metadata.clone(),
+        }
+    }
+
+    /// Get the suffix of the generated function name.
+    ///
+    /// This is the millisecond timestamp of the failure, [namespaced](struct.UnitTestOptions.html#method.namespace_id) if configured, unless an [id strategy](struct.UnitTestOptions.html#method.id_strategy) is configured.
+    fn id(&self, snapshot: &Snapshot) -> Result<String, RenderError> {
+        match self.options.id_strategy {
+            Some(ref strategy) => {
+                let id = strategy(&self.meta(snapshot));
+                if is_valid_id(&id) {
+                    Ok(id)
+                } else {
+                    Err(RenderError::InvalidId(id))
+                }
+            }
+            None if self.options.namespace_id => Ok(format!(
+                "{}_{:08x}",
+                self.time.timestamp_millis(),
+                ::hash::type_hash(&self.executable.description(), self.executable.full_path())
+            )),
+            None => Ok(self.time.timestamp_millis().to_string()),
+        }
+    }
+
+    /// The id of the generated function, changed if it is one of the `taken` ids, e.g. of the tests that are already in a file, so two failures in the same millisecond don't generate two functions with the same name.
+    ///
+    /// An id that is not taken is returned as is. Otherwise the first 8 hex digits of the FNV-1a hash of the serialized executable and arguments are appended, like `1539761233543_3f2a9c01`, and if that is taken as well, a counter, like `1539761233543_3f2a9c01_2`. [append_to_file](#method.append_to_file) and the [StreamingWriter](struct.StreamingWriter.html) use this with the ids of the tests in the file. Errors are returned like in [try_to_test_source](#method.try_to_test_source).
+    ///
+    /// ```rust,ignore
+    /// let taken: HashSet<String> = exceptional::parse_markers(&src).into_iter().map(|m| m.id).collect();
+    /// let id = test.unique_id(&taken)?;
+    /// ```
+    pub fn unique_id(&self, taken: &HashSet<String>) -> Result<String, RenderError> {
+        let snapshot = self.snapshot()?;
+        Ok(unique_id(&snapshot, taken))
+    }
+
+    /// Write the marker comment that lets this crate find the generated item again.
+    pub(crate) fn write_marker<W: Write>(&self, fmt: &mut W, snapshot: &Snapshot) -> fmt::Result {
+        self.write_marker_for(
+            fmt,
+            &snapshot.id,
+            &snapshot.hash,
+            Some(&snapshot.error_hash),
+        )
+    }
+
+    /// Write a marker comment with the given id and hashes, for items that are not rendered from a single snapshot. Items that contain several errors have no error hash.
+    pub(crate) fn write_marker_for<W: Write>(
+        &self,
+        fmt: &mut W,
+        id: &str,
+        hash: &str,
+        error_hash: Option<&str>,
+    ) -> fmt::Result {
+        write!(
+            fmt,
+            "{}id={} hash={} path={} time={}",
+            MARKER_PREFIX,
+            id,
+            hash,
+            self.executable.full_path(),
+            self.time.to_rfc3339_opts(SecondsFormat::Millis, true)
+        )?;
+        if self.executable.severity() != Severity::Medium {
+            write!(fmt, " severity={}", self.executable.severity().name())?;
+        }
+        match error_hash {
+            Some(error_hash) => writeln!(fmt, " error={}", error_hash),
+            None => writeln!(fmt),
+        }
+    }
+
+    /// Write the comment header describing this failure.
+    pub(crate) fn write_header<W: Write>(&self, fmt: &mut W, kind: &str) -> fmt::Result {
+        let prefix = self.options.comment_style.prefix();
+        writeln!(
+            fmt,
+            "{} Automatically generated {} for Executable\n",
+            prefix, kind
+        )?;
+        write_comment_lines(fmt, prefix, "", &self.executable.description())?;
+        if self.options.link_executable && self.options.comment_style == CommentStyle::Doc {
+            let path = self.type_path(self.executable.full_path());
+            let (name, target) = doc_link(&path);
+            writeln!(fmt, "{} executable: [`{}`]({})", prefix, name, target)?;
+        }
+        match self.options.time_format {
+            Some(ref format) => {
+                writeln!(fmt, "{} generated at {}", prefix, self.time.format(format))?
+            }
+            None => writeln!(fmt, "{} generated at {}", prefix, self.time.to_rfc2822())?,
+        }
+        if let Some(location) = self.location {
+            writeln!(
+                fmt,
+                "{} originally failed at {}:{}",
+                prefix,
+                location.file(),
+                location.line()
+            )?;
+        }
+        #[cfg(feature = "build-info")]
+        {
+            if self.options.include_build_info {
+                writeln!(
+                    fmt,
+                    "{} rustc: {}",
+                    prefix,
+                    env!("EXCEPTIONAL_RUSTC_VERSION")
+                )?;
+                writeln!(fmt, "{} target: {}", prefix, env!("EXCEPTIONAL_TARGET"))?;
+            }
+        }
+        if let Some(seed) = self.executable.rng_seed() {
+            writeln!(fmt, "{} rng seed: {}", prefix, seed)?;
+        }
+        match self.executable.severity() {
+            Severity::Medium => {}
+            severity => writeln!(fmt, "{} severity: {}", prefix, severity.name())?,
+        }
+        if let Some(ref reason) = self.capture_reason {
+            writeln!(
+                fmt,
+                "{} captured because: {}",
+                prefix,
+                escape_comment(reason)
+            )?;
+        }
+        for (key, value) in &self.metadata {
+            writeln!(
+                fmt,
+                "{} meta: {} = {}",
+                prefix,
+                escape_comment(key),
+                escape_comment(value)
+            )?;
+        }
+        if let Some(ref post_state) = self.post_state {
+            self.write_state_changes(fmt, post_state)?;
+        }
+        writeln!(fmt)?;
+        if let Some(ref message) = self.panic_message {
+            return writeln!(fmt, "{} panicked with {}", prefix, escape_comment(message));
+        }
+        match (&self.error_message, &self.error_display, self.error_render) {
+            (Some(message), _, _) => write_comment_lines(fmt, prefix, "exception was ", message),
+            (None, Some(display), ErrorRender::Display) => {
+                write_comment_lines(fmt, prefix, "exception was ", display)
+            }
+            (None, Some(display), ErrorRender::Both) => {
+                write_comment_lines(fmt, prefix, "exception was ", display)?;
+                let debug = format!("{:?}", self.error);
+                write_comment_lines(fmt, prefix, "exception debug: ", &debug)
+            }
+            _ => {
+                let debug = format!("{:?}", self.error);
+                write_comment_lines(fmt, prefix, "exception was ", &debug)
+            }
+        }
+    }
+
+    /// Write a header line for every value that differs between the executable and the [state after the failure](struct.UnitTest.html#structfield.post_state).
+    fn write_state_changes<W: Write>(&self, fmt: &mut W, post_state: &str) -> fmt::Result {
+        let prefix = self.options.comment_style.prefix();
+        let before = serde_json::to_value(&self.executable);
+        let after = serde_json::from_str::<Value>(post_state);
+        let (before, after) = match (before, after) {
+            (Ok(before), Ok(after)) => (before, after),
+            _ => return Ok(()),
+        };
+        let mut changes = Vec::new();
+        state_changes(&before, &after, &mut String::new(), &mut changes);
+        if changes.is_empty() {
+            return writeln!(fmt, "{} not changed during the run", prefix);
+        }
+        for change in changes {
+            writeln!(
+                fmt,
+                "{} changed during the run: {}",
+                prefix,
+                escape_comment(&change)
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Write the attributes that go before the attribute of the test harness: the configured `#[cfg(...)]`, if any, and `#[allow(missing_docs)]` if the header is not a doc comment, because the generated function is `pub`.
+    pub(crate) fn write_attributes<W: Write>(&self, fmt: &mut W) -> fmt::Result {
+        if let Some(ref predicate) = self.options.cfg_gate {
+            writeln!(fmt, "#[cfg({})]", predicate)?;
+        }
+        match self.options.comment_style {
+            CommentStyle::Doc => Ok(()),
+            CommentStyle::Plain => writeln!(fmt, "#[allow(missing_docs)]"),
+        }
+    }
+
+    /// Write the statements that deserialize the executable into `obj` and the arguments into `args`.
+    pub(crate) fn write_snapshot<W: Write>(&self, fmt: &mut W, snapshot: &Snapshot) -> fmt::Result {
+        match self.options.sidecar_dir {
+            Some(ref dir) => {
+                let (executable, arguments) = ::sidecar::file_names(&snapshot.id);
+                match self.executable.construct_expr() {
+                    Some(expr) => self.write_obj_from_expr(fmt, &expr)?,
+                    None => {
+                        writeln!(
+                            fmt,
+                            "\tlet obj_json = include_str!({:?});",
+                            format!("{}/{}", dir, executable)
+                        )?;
+                        self.write_obj_from_json(fmt)?;
+                    }
+                }
+                writeln!(fmt, "\t")?;
+                if !self.deserializes_arguments() {
+                    return self.write_unit_args(fmt, snapshot);
+                }
+                writeln!(
+                    fmt,
+                    "\tlet arg_json = include_str!({:?});",
+                    format!("{}/{}", dir, arguments)
+                )?;
+            }
+            None => {
+                match (
+                    self.executable.construct_expr(),
+                    self.default_overrides(snapshot),
+                ) {
+                    (Some(expr), _) => self.write_obj_from_expr(fmt, &expr)?,
+                    (None, Some(overrides)) => self.write_obj_from_default(fmt, &overrides)?,
+                    (None, None) => {
+                        writeln!(
+                            fmt,
+                            "\tlet obj_{} = {};",
+                            self.format(snapshot).name(),
+                            self.input(snapshot, "OBJ", self.executable_input(snapshot))
+                        )?;
+                        self.write_obj_from(fmt, self.format(snapshot))?;
+                    }
+                }
+                writeln!(fmt, "\t")?;
+                if !self.deserializes_arguments() {
+                    return self.write_unit_args(fmt, snapshot);
+                }
+                if let Some(fields) = self.arguments_fields(snapshot) {
+                    return self.write_args_from_fields(fmt, &fields);
+                }
+                writeln!(
+                    fmt,
+                    "\tlet arg_{} = {};",
+                    self.format(snapshot).name(),
+                    self.input(snapshot, "ARGS", self.arguments_input(snapshot))
+                )?;
+                return self.write_args_from(fmt, self.format(snapshot));
+            }
+        }
+        self.write_args_from_json(fmt)
+    }
+
+    /// The format of the embedded inputs of `snapshot`.
+    fn format(&self, snapshot: &Snapshot) -> SerializationFormat {
+        match snapshot.formatted {
+            Some(_) => self.options.serialization_format,
+            None => SerializationFormat::Json,
+        }
+    }
+
+    /// The executable of `snapshot` in the format that it is embedded in.
+    fn executable_input<'s>(&self, snapshot: &'s Snapshot) -> &'s str {
+        match snapshot.formatted {
+            Some((ref executable, _)) => executable,
+            None => &snapshot.executable_json,
+        }
+    }
+
+    /// The arguments of `snapshot` in the format that they are embedded in.
+    fn arguments_input<'s>(&self, snapshot: &'s Snapshot) -> &'s str {
+        match snapshot.formatted {
+            Some((_, ref arguments)) => arguments,
+            None => &snapshot.arguments_json,
+        }
+    }
+
+    /// `path` as it is written in the generated code, for the configured [test kind](struct.UnitTestOptions.html#method.test_kind) and [edition](struct.UnitTestOptions.html#method.edition).
+    pub(crate) fn type_path<'p>(&self, path: &'p str) -> Cow<'p, str> {
+        let path = self.test_kind_path(path);
+        match self.options.edition {
+            Edition::Edition2015 => path,
+            Edition::Edition2018 => {
+                let integration_crate = match self.options.test_kind {
+                    Some((TestKind::Integration, ref crate_name)) => Some(crate_name.as_str()),
+                    _ => None,
+                };
+                Cow::Owned(crate_relative(&path, integration_crate))
+            }
+        }
+    }
+
+    /// `path` as it is written in the generated code, for the configured test kind.
+    fn test_kind_path<'p>(&self, path: &'p str) -> Cow<'p, str> {
+        let (kind, crate_name) = match self.options.test_kind {
+            Some((kind, ref crate_name)) => (kind, crate_name),
+            None => return Cow::Borrowed(path),
+        };
+        let relative = path.trim_start_matches("::");
+        let rest = match kind {
+            TestKind::Unit => relative
+                .strip_prefix(crate_name.as_str())
+                .and_then(|rest| rest.strip_prefix("::")),
+            TestKind::Integration => relative.strip_prefix("crate::"),
+        };
+        match (kind, rest) {
+            (TestKind::Unit, Some(rest)) => Cow::Owned(format!("crate::{}", rest)),
+            (TestKind::Integration, Some(rest)) => {
+                Cow::Owned(format!("::{}::{}", crate_name, rest))
+            }
+            (_, None) => Cow::Borrowed(path),
+        }
+    }
+
+    /// The expression for the embedded `input`: a raw string literal, or the name of its const if the inputs are [hoisted](struct.UnitTestOptions.html#method.hoist_inputs).
+    fn input(&self, snapshot: &Snapshot, kind: &str, input: &str) -> String {
+        let embedded = self.embedded(snapshot, input);
+        let expression = if self.options.hoist_inputs {
+            input_name(&snapshot.id, kind)
+        } else {
+            ::literal::raw_string(&embedded)
+        };
+        match embedded {
+            Cow::Owned(_) => format!(
+                "&{}({})",
+                self.options
+                    .extern_path("::exceptional::decode_base64_json"),
+                expression
+            ),
+            Cow::Borrowed(_) => expression,
+        }
+    }
+
+    /// The embedded form of an `input` of `snapshot`, with its byte arrays [encoded as base64](struct.UnitTestOptions.html#method.base64_bytes) if that is configured and `input` is JSON that has any.
+    fn embedded<'j>(&self, snapshot: &Snapshot, input: &'j str) -> Cow<'j, str> {
+        if snapshot.formatted.is_some() {
+            return Cow::Borrowed(input);
+        }
+        let encoded = self
+            .options
+            .base64_bytes
+            .and_then(|min_len| ::base64::encode_byte_arrays(input, min_len));
+        match encoded {
+            Some(encoded) => Cow::Owned(encoded),
+            None => Cow::Borrowed(input),
+        }
+    }
+
+    /// Write the consts for the [hoisted inputs](struct.UnitTestOptions.html#method.hoist_inputs) that [write_snapshot](#method.write_snapshot) refers to.
+    pub(crate) fn write_inputs<W: Write>(&self, fmt: &mut W, snapshot: &Snapshot) -> fmt::Result {
+        if !self.options.hoist_inputs || self.options.sidecar_dir.is_some() {
+            return Ok(());
+        }
+        if self.executable.construct_expr().is_none() && self.default_overrides(snapshot).is_none()
+        {
+            write_input(
+                fmt,
+                &snapshot.id,
+                "OBJ",
+                &self.embedded(snapshot, self.executable_input(snapshot)),
+            )?;
+        }
+        if self.deserializes_arguments() && self.arguments_fields(snapshot).is_none() {
+            write_input(
+                fmt,
+                &snapshot.id,
+                "ARGS",
+                &self.embedded(snapshot, self.arguments_input(snapshot)),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Whether the generated test deserializes the arguments, which it doesn't for executables that [take their arguments from themselves](trait.Executable.html#method.arguments_from_self) and for arguments that are [only captured with Debug](trait.DebugExecutable.html).
+    fn deserializes_arguments(&self) -> bool {
+        !self.executable.arguments_from_self() && self.executable.debug_arguments_type().is_none()
+    }
+
+    /// Write the statement that binds the [arguments binding](struct.UnitTestOptions.html#method.args_binding) to `()`, or the stub for arguments that were only captured with `Debug`, after a comment with their `Debug` output.
+    fn write_unit_args<W: Write>(&self, fmt: &mut W, snapshot: &Snapshot) -> fmt::Result {
+        let type_name = match self.executable.debug_arguments_type() {
+            Some(type_name) => type_name,
+            None => return writeln!(fmt, "\tlet {} = ();", self.options.args_binding),
+        };
+        let debug: String = serde_json::from_str(&snapshot.arguments_json)
+            .unwrap_or_else(|_| snapshot.arguments_json.clone());
+        writeln!(
+            fmt,
+            "\t// The arguments can not be serialized. Their `Debug` output was:"
+        )?;
+        writeln!(fmt, "\t// {}", escape_comment(&debug))?;
+        writeln!(fmt, "\t// TODO: reconstruct arguments")?;
+        writeln!(
+            fmt,
+            "\tlet {}: {} = compile_error!(\"TODO: reconstruct the arguments from their Debug output in the comment above\");",
+            self.options.args_binding, type_name
+        )
+    }
+
+    /// The fields of the arguments, if the executable has an [arguments path](trait.Executable.html#method.arguments_path) and the arguments serialize to a JSON object.
+    fn arguments_fields(&self, snapshot: &Snapshot) -> Option<Vec<(String, String)>> {
+        self.executable.arguments_path()?;
+        ::literal::struct_fields(&snapshot.arguments_json)
+    }
+
+    /// Write the statement that constructs the [arguments binding](struct.UnitTestOptions.html#method.args_binding) as a struct literal with the given fields.
+    fn write_args_from_fields<W: Write>(
+        &self,
+        fmt: &mut W,
+        fields: &[(String, String)],
+    ) -> fmt::Result {
+        let path = self
+            .executable
+            .arguments_path()
+            .expect("the fields are only read with an arguments path");
+        writeln!(
+            fmt,
+            "\tlet {} = {} {{",
+            self.options.args_binding,
+            self.type_path(path)
+        )?;
+        for (field, json) in fields {
+            writeln!(
+                fmt,
+                "\t\t{}: {}({}).expect(\"Could not deserialize json\"),",
+                field,
+                self.options.extern_path("::serde_json::from_str"),
+                ::literal::raw_string(json)
+            )?;
+        }
+        writeln!(fmt, "\t}};")
+    }
+
+    /// Write the statement that deserializes `obj_json` into the [executable binding](struct.UnitTestOptions.html#method.obj_binding).
+    pub(crate) fn write_obj_from_json<W: Write>(&self, fmt: &mut W) -> fmt::Result {
+        self.write_obj_from(fmt, SerializationFormat::Json)
+    }
+
+    /// Write the statement that deserializes `obj_json`, or the local of another `format`, into the executable binding.
+    fn write_obj_from<W: Write>(&self, fmt: &mut W, format: SerializationFormat) -> fmt::Result {
+        writeln!(
+            fmt,
+            "\tlet mut {}: {} = {}(obj_{}).expect(\"Could not deserialize {}\");",
+            self.options.obj_binding,
+            self.type_path(self.executable.full_path()),
+            self.options.extern_path(format.deserialize_path()),
+            format.name(),
+            format.name()
+        )
+    }
+
+    /// Write the statement that assigns the [construct_expr](trait.Executable.html#method.construct_expr) of the executable to the executable binding.
+    fn write_obj_from_expr<W: Write>(&self, fmt: &mut W, expr: &str) -> fmt::Result {
+        writeln!(
+            fmt,
+            "\tlet mut {}: {} = {};",
+            self.options.obj_binding,
+            self.type_path(self.executable.full_path()),
+            expr.trim()
+        )
+    }
+
+    /// The fields that differ from the default of the executable, if the test [constructs it from its default](struct.UnitTest.html#method.with_default_overrides).
+    fn default_overrides(&self, snapshot: &Snapshot) -> Option<Vec<(String, String)>> {
+        let default_json = self.default_json.as_ref()?;
+        ::defaults::overrides(default_json, &snapshot.executable_json)
+    }
+
+    /// Write the statements that construct the [executable binding](struct.UnitTestOptions.html#method.obj_binding) from its default and set the given fields.
+    fn write_obj_from_default<W: Write>(
+        &self,
+        fmt: &mut W,
+        overrides: &[(String, String)],
+    ) -> fmt::Result {
+        let obj = &self.options.obj_binding;
+        writeln!(
+            fmt,
+            "\tlet mut {}: {} = Default::default();",
+            obj,
+            self.type_path(self.executable.full_path())
+        )?;
+        for (field, json) in overrides {
+            writeln!(
+                fmt,
+                "\t{}.{} = {}({}).expect(\"Could not deserialize json\");",
+                obj,
+                field,
+                self.options.extern_path("::serde_json::from_str"),
+                ::literal::raw_string(json)
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Write the statement that deserializes `arg_json` into the [arguments binding](struct.UnitTestOptions.html#method.args_binding).
+    pub(crate) fn write_args_from_json<W: Write>(&self, fmt: &mut W) -> fmt::Result {
+        self.write_args_from(fmt, SerializationFormat::Json)
+    }
+
+    /// Write the statement that deserializes `arg_json`, or the local of another `format`, into the arguments binding.
+    fn write_args_from<W: Write>(&self, fmt: &mut W, format: SerializationFormat) -> fmt::Result {
+        writeln!(
+            fmt,
+            "\tlet {} = {}(arg_{}).expect(\"Could not deserialize {}\");",
+            self.options.args_binding,
+            self.options.extern_path(format.deserialize_path()),
+            format.name(),
+            format.name()
+        )
+    }
+
+    /// Render this failure as a [criterion](https://docs.rs/criterion) benchmark instead of a `#[test]`.
+    ///
+    /// The generated function is named `bench_<timestamp>` and measures `obj.execute(&args)` inside `b.iter_batched(...)`. The executable is cloned in the setup closure, so the clone is not part of the measurement.
+    ///
+    /// The crate that contains the benchmark needs `criterion` as a dev-dependency and a `[[bench]]` target with `harness = false`. Write the output to a file in `benches/` and register the function in a group:
+    ///
+    /// ```toml
+    /// [dev-dependencies]
+    /// criterion = "0.3"
+    ///
+    /// [[bench]]
+    /// name = "regressions"
+    /// harness = false
+    /// ```
+    ///
+    /// ```rust,ignore
+    /// // benches/regressions.rs
+    /// #[macro_use]
+    /// extern crate criterion;
+    /// extern crate exceptional;
+    /// extern crate serde_json;
+    /// extern crate my_crate;
+    ///
+    /// use my_crate::SomeImportantAction;
+    ///
+    /// include!("generated.rs");
+    ///
+    /// criterion_group!(regressions, bench_1539761233543);
+    /// criterion_main!(regressions);
+    /// ```
+    #[cfg(feature = "criterion")]
+    pub fn render_as_benchmark(&self) -> Result<String, RenderError> {
+        let snapshot = self.snapshot()?;
+        let mut fmt = String::new();
+        self.write_benchmark(&mut fmt, &snapshot)
+            .expect("Writing to a String cannot fail");
+        Ok(fmt)
+    }
+
+    #[cfg(feature = "criterion")]
+    fn write_benchmark<W: Write>(&self, fmt: &mut W, snapshot: &Snapshot) -> fmt::Result {
+        let id = &snapshot.id;
+        self.write_marker(fmt, snapshot)?;
+        self.write_inputs(fmt, snapshot)?;
+        self.write_header(fmt, "benchmark")?;
+        self.write_attributes(fmt)?;
+        writeln!(
+            fmt,
+            "pub fn bench_{}(c: &mut {}) {{",
+            id,
+            self.options.extern_path("::criterion::Criterion")
+        )?;
+        writeln!(fmt, "\tuse exceptional::Executable;")?;
+        self.write_snapshot(fmt, snapshot)?;
+        writeln!(fmt)?;
+        writeln!(
+            fmt,
+            "\tc.bench_function({:?}, |b| {{",
+            format!("{}_{}", self.executable.full_path(), id)
+        )?;
+        writeln!(fmt, "\t\tb.iter_batched(")?;
+        let obj = &self.options.obj_binding;
+        writeln!(fmt, "\t\t\t|| {}.clone(),", obj)?;
+        writeln!(fmt, "\t\t\t|mut {}| {},", obj, self.call(false))?;
+        writeln!(
+            fmt,
+            "\t\t\t{},",
+            self.options
+                .extern_path("::criterion::BatchSize::SmallInput")
+        )?;
+        writeln!(fmt, "\t\t)")?;
+        writeln!(fmt, "\t}});")?;
+        writeln!(fmt, "}}")?;
+
+        Ok(())
+    }
+}
+
+/// Writes the [rendered test](struct.UnitTest.html#method.to_test_source). A test that can not be rendered is written as a comment with the [RenderError](enum.RenderError.html), because panicking while formatting aborts macros like `println!` halfway, and an error from `fmt` can't tell why.
+impl<'a, E: Executable + 'a> fmt::Display for UnitTest<'a, E> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self.try_to_test_source() {
+            Ok(source) => fmt.write_str(&source),
+            Err(e) => writeln!(
+                fmt,
+                "// Could not render the unit test for {}: {}",
+                self.executable.full_path(),
+                escape_comment(&e.to_string())
+            ),
+        }
+    }
+}
+
+impl<'a, E: Executable + 'a> UnitTest<'a, E> {
+    /// Write the generated `#[test]` for an already serialized snapshot. The stages are the sections of a [GeneratedTest](struct.GeneratedTest.html).
+    pub(crate) fn write_test<W: Write>(&self, fmt: &mut W, snapshot: &Snapshot) -> fmt::Result {
+        self.write_marker(fmt, snapshot)?;
+        self.write_inputs(fmt, snapshot)?;
+        self.write_test_header(fmt, snapshot)?;
+        self.write_test_attributes(fmt)?;
+        self.write_signature(fmt, snapshot)?;
+        writeln!(fmt, " {{")?;
+        self.write_imports(fmt)?;
+        self.write_test_setup(fmt, snapshot)?;
+        self.write_test_body(fmt)?;
+        self.write_test_teardown(fmt)?;
+        writeln!(fmt, "}}")?;
+
+        Ok(())
+    }
+
+    /// Write the header comment of the generated `#[test]`, with the [run hint](struct.UnitTestOptions.html#method.run_hint) if configured.
+    pub(crate) fn write_test_header<W: Write>(
+        &self,
+        fmt: &mut W,
+        snapshot: &Snapshot,
+    ) -> fmt::Result {
+        self.write_header(fmt, "unit test")?;
+        if self.options.run_hint {
+            writeln!(
+                fmt,
+                "{} run with: cargo test {}",
+                self.options.comment_style.prefix(),
+                self.test_filter(snapshot)
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Write the attributes of the generated `#[test]`, ending with the attribute of the test harness, which is the [test attribute](struct.UnitTestOptions.html#method.test_attribute) for synchronous tests.
+    pub(crate) fn write_test_attributes<W: Write>(&self, fmt: &mut W) -> fmt::Result {
+        self.write_attributes(fmt)?;
+        match self.panic_message {
+            Some(ref message) if message.is_empty() => writeln!(fmt, "#[should_panic]")?,
+            Some(ref message) => writeln!(fmt, "#[should_panic(expected = {:?})]", message)?,
+            None => {}
+        }
+        match self.options.async_test {
+            Some(ref attribute) => writeln!(fmt, "#[{}]", attribute),
+            None => writeln!(fmt, "#[{}]", self.options.test_attribute),
+        }
+    }
+
+    /// Write the signature of the generated `#[test]`, without the opening brace and the line break.
+    pub(crate) fn write_signature<W: Write>(
+        &self,
+        fmt: &mut W,
+        snapshot: &Snapshot,
+    ) -> fmt::Result {
+        match self.options.async_test {
+            Some(_) => write!(fmt, "pub async fn test_{}()", snapshot.id),
+            None => write!(fmt, "pub fn test_{}()", snapshot.id),
+        }
+    }
+
+    /// Write the statements of the generated `#[test]` that deserialize the executable and the arguments, after the [prelude](trait.Executable.html#method.test_prelude) of the executable, followed by an empty line.
+    pub(crate) fn write_test_setup<W: Write>(
+        &self,
+        fmt: &mut W,
+        snapshot: &Snapshot,
+    ) -> fmt::Result {
+        self.write_prelude(fmt)?;
+        self.write_snapshot(fmt, snapshot)?;
+        writeln!(fmt)
+    }
+
+    /// Write the [prelude](trait.Executable.html#method.test_prelude) of the executable, if it has one.
+    pub(crate) fn write_prelude<W: Write>(&self, fmt: &mut W) -> fmt::Result {
+        match self.executable.test_prelude() {
+            Some(prelude) => write_indented(fmt, &prelude, "\t"),
+            None => Ok(()),
+        }
+    }
+
+    /// Write the statements of the generated `#[test]` after the check: the [epilogue](trait.Executable.html#method.test_epilogue) of the executable, if it has one.
+    pub(crate) fn write_test_teardown<W: Write>(&self, fmt: &mut W) -> fmt::Result {
+        match self.executable.test_epilogue() {
+            Some(epilogue) => write_indented(fmt, &epilogue, "\t"),
+            None => Ok(()),
+        }
+    }
+
+    /// Write the statements of the generated `#[test]` that run the executable and check the outcome.
+    pub(crate) fn write_test_body<W: Write>(&self, fmt: &mut W) -> fmt::Result {
+        let seed = self.executable.rng_seed().map(|seed| seed.to_string());
+        self.write_body(fmt, seed.as_deref())
+    }
+
+    /// The `cargo test` filter that selects only the generated function: its name, prefixed with the configured module path and target module.
+    fn test_filter(&self, snapshot: &Snapshot) -> String {
+        let mut filter = String::new();
+        for module in self
+            .options
+            .module_path
+            .as_deref()
+            .iter()
+            .chain(self.target_module().iter())
+        {
+            filter.push_str(module);
+            filter.push_str("::");
+        }
+        filter.push_str("test_");
+        filter.push_str(&snapshot.id);
+        filter
+    }
+
+    /// The module that [append_to_file](struct.UnitTest.html#method.append_to_file) puts this test in: the name of its [severity](struct.UnitTestOptions.html#method.severity_modules) if configured, or the [target module](struct.UnitTestOptions.html#method.target_module).
+    pub(crate) fn target_module(&self) -> Option<&str> {
+        if self.options.severity_modules {
+            Some(self.executable.severity().name())
+        } else {
+            self.options.target_module.as_deref()
+        }
+    }
+
+    /// Write the statements that run right before the executable: if there is a `seed` expression, bind `seed` to it and write the [RNG setup](trait.Executable.html#method.rng_setup) of the executable, and [restore](trait.Executable.html#method.restore_context) the captured [context](struct.UnitTest.html#structfield.context), if there is one.
+    pub(crate) fn write_setup<W: Write>(&self, fmt: &mut W, seed: Option<&str>) -> fmt::Result {
+        if let (Some(seed), Some(setup)) = (seed, self.executable.rng_setup()) {
+            writeln!(fmt, "\tlet seed: u64 = {};", seed)?;
+            write_indented(fmt, &setup, "\t")?;
+        }
+        if let Some(ref context) = self.context {
+            writeln!(
+                fmt,
+                "\t<{} as {}>::restore_context({:?});",
+                self.type_path(self.executable.full_path()),
+                self.options.extern_path("::exceptional::Executable"),
+                context
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Write the statement that creates the guard for the [captured globals](struct.UnitTest.html#structfield.globals).
+    fn write_globals_guard<W: Write>(&self, fmt: &mut W) -> fmt::Result {
+        let default = Globals::default();
+        let globals = self.globals.as_ref().unwrap_or(&default);
+        let current_dir = globals.current_dir.as_ref().and_then(|dir| dir.to_str());
+        let env: Vec<String> = globals
+            .env
+            .iter()
+            .map(|(name, value)| format!("({:?}, {:?})", name, value))
+            .collect();
+        writeln!(
+            fmt,
+            "\tlet _globals = {}({:?}, &[{}]);",
+            self.options.extern_path("::exceptional::GlobalsGuard::set"),
+            current_dir,
+            env.join(", ")
+        )
+    }
+
+    /// Write the `use` statement for the traits that the generated test calls. It is allowed to be unused, because a [transformed](struct.TransformRenderer.html) body might not call them, and generated files have to compile with warnings denied.
+    pub(crate) fn write_imports<W: Write>(&self, fmt: &mut W) -> fmt::Result {
+        writeln!(fmt, "\t#[allow(unused_imports)]")?;
+        match self.options.async_test {
+            Some(_) => writeln!(fmt, "\tuse exceptional::{{AsyncExecutable, Executable}};"),
+            None => writeln!(fmt, "\tuse exceptional::Executable;"),
+        }
+    }
+
+    /// The expression that executes the deserialized executable with the deserialized arguments, awaited for [async tests](struct.UnitTestOptions.html#method.async_test).
+    pub(crate) fn execute_call(&self) -> String {
+        self.call(self.options.async_test.is_some())
+    }
+
+    /// The format string that the generated code prints the error with, see [prefer_display](struct.UnitTest.html#method.prefer_display).
+    pub(crate) fn error_format(&self) -> &'static str {
+        if self.prefer_display {
+            "{}"
+        } else {
+            "{:?}"
+        }
+    }
+
+    /// The expression that executes the deserialized executable, awaited if it is `asynchronous`, and passed through `black_box` if [configured](struct.UnitTestOptions.html#method.black_box).
+    pub(crate) fn call(&self, asynchronous: bool) -> String {
+        let (obj, args) = (&self.options.obj_binding, &self.options.args_binding);
+        let black_box = self.options.extern_path("::std::hint::black_box");
+        let args = if self.options.black_box {
+            format!("{}(&{})", black_box, args)
+        } else {
+            format!("&{}", args)
+        };
+        let call = if asynchronous {
+            format!("{}.execute_async({}).await", obj, args)
+        } else {
+            format!("{}.execute({})", obj, args)
+        };
+        if self.options.black_box {
+            format!("{}({})", black_box, call)
+        } else {
+            call
+        }
+    }
+
+    /// Write the statements that seed the RNG with the expression `seed`, if there is one, run `obj.execute(&args)` and check the outcome, on a separate thread if a [timeout](struct.UnitTestOptions.html#method.timeout) is configured.
+    pub(crate) fn write_body<W: Write>(&self, fmt: &mut W, seed: Option<&str>) -> fmt::Result {
+        if self.options.restore_globals {
+            self.write_globals_guard(fmt)?;
+        }
+        match self.options.timeout {
+            Some(timeout) if self.options.async_test.is_none() => {
+                // The RNG is seeded and the context is restored on the thread that runs the executable, because they may be thread-local.
+                let mut inner = String::new();
+                self.write_setup(&mut inner, seed)?;
+                self.write_checks(&mut inner)?;
+                writeln!(
+                    fmt,
+                    "\t{}({}({}), move || {{",
+                    self.options.extern_path("::exceptional::run_with_timeout"),
+                    self.options
+                        .extern_path("::std::time::Duration::from_millis"),
+                    timeout.as_millis()
+                )?;
+                write_indented(fmt, &inner, "\t")?;
+                writeln!(fmt, "\t}});")
+            }
+            _ => {
+                self.write_setup(fmt, seed)?;
+                self.write_checks(fmt)
+            }
+        }
+    }
+
+    /// Write the statements that run `obj.execute(&args)` and check the outcome: the caught [panic](struct.UnitTest.html#structfield.panic_message), the executable's [assertion](trait.Executable.html#method.assertion), the [expected value](struct.UnitTest.html#structfield.expected), the [expected error](struct.UnitTest.html#structfield.expected_error), the [error message](struct.UnitTest.html#structfield.error_message), or a panic if it fails.
+    fn write_checks<W: Write>(&self, fmt: &mut W) -> fmt::Result {
+        let obj = &self.options.obj_binding;
+        let call = self.execute_call();
+        if self.panic_message.is_some() {
+            writeln!(fmt, "\tlet _ = {};", call)?;
+            return writeln!(
+                fmt,
+                "\tpanic!(\"{{}} no longer panics\", {}.description());",
+                obj
+            );
+        }
+        let assertion = self.executable.assertion();
+        if assertion.is_none() {
+            if let Some(body) = self.executable.assertion_body(RESULT_BINDING) {
+                writeln!(fmt, "\tlet {} = {};", RESULT_BINDING, call)?;
+                return write_indented(fmt, &body, "\t");
+            }
+        }
+        match (
+            assertion,
+            &self.expected,
+            &self.expected_error,
+            &self.error_message,
+            self.executable.error_pattern(&self.error),
+        ) {
+            (Some(assertion), _, _, _, _) => write_indented(fmt, &assertion, "\t"),
+            (None, Some(expected), _, _, _) => {
+                writeln!(
+                    fmt,
+                    "\tlet expected = {}(&{}, {});",
+                    self.options.extern_path("::exceptional::expected_result"),
+                    obj,
+                    ::literal::raw_string(expected)
+                )?;
+                writeln!(fmt, "\tassert_eq!({}.unwrap(), expected);", call)
+            }
+            (None, None, Some(expected_error), _, _) => {
+                writeln!(
+                    fmt,
+                    "\tlet expected_error = {}(&{}, {});",
+                    self.options.extern_path("::exceptional::expected_error"),
+                    obj,
+                    ::literal::raw_string(expected_error)
+                )?;
+                writeln!(fmt, "\tmatch {} {{", call)?;
+                writeln!(
+                    fmt,
+                    "\t\tOk(_) => panic!(\"{{}} no longer fails\", {}.description()),",
+                    obj
+                )?;
+                writeln!(fmt, "\t\tErr(e) => assert_eq!(e, expected_error),")?;
+                writeln!(fmt, "\t}}")
+            }
+            (None, None, None, Some(message), _) => {
+                writeln!(fmt, "\tmatch {} {{", call)?;
+                writeln!(
+                    fmt,
+                    "\t\tOk(_) => panic!(\"{{}} no longer fails\", {}.description()),",
+                    obj
+                )?;
+                writeln!(fmt, "\t\tErr(e) => {{")?;
+                writeln!(
+                    fmt,
+                    "\t\t\tlet message = {}(&*e);",
+                    self.options.extern_path("::exceptional::error_chain")
+                )?;
+                writeln!(
+                    fmt,
+                    "\t\t\tassert!(message.contains({:?}), \"unexpected error: {{}}\", message);",
+                    message
+                )?;
+                writeln!(fmt, "\t\t}}")?;
+                writeln!(fmt, "\t}}")
+            }
+            (None, None, None, None, Some(pattern)) => writeln!(
+                fmt,
+                "\tassert!(matches!({}, Err({})), \"{{}} no longer fails with {{}}\", {}.description(), {:?});",
+                call, pattern, obj, pattern
+            ),
+            (None, None, None, None, None) if !self.options.verbose_body => {
+                if self.prefer_display {
+                    writeln!(
+                        fmt,
+                        "\t{}.unwrap_or_else(|e| panic!(\"regression still fails: {{}}\", e));",
+                        call
+                    )
+                } else {
+                    writeln!(fmt, "\t{}.expect(\"regression still fails\");", call)
+                }
+            }
+            (None, None, None, None, None) => {
+                writeln!(fmt, "\tif let Err(e) = {} {{", call)?;
+                writeln!(
+                    fmt,
+                    "\t\tprintln!(\"Could not execute {{}}\", {}.description());",
+                    obj
+                )?;
+                writeln!(fmt, "\t\tprintln!(\"{}\", e);", self.error_format())?;
+                writeln!(fmt, "\t\tpanic!();")?;
+                writeln!(fmt, "\t}}")
+            }
+        }
+    }
+}
+
+/// The sum of the [rendered_len](struct.UnitTest.html#method.rendered_len) of all `tests`, or the first error if one of them can't be rendered.
+///
+/// ```rust,ignore
+/// let tests = exceptional::execute_all(&action, inputs, exceptional::Mode::AllFailures);
+/// let bytes = exceptional::total_rendered_len(&tests)?;
+/// assert!(bytes <= 64 * 1024, "this run would add {} bytes of generated tests", bytes);
+/// ```
+pub fn total_rendered_len<'t, 'a: 't, E, I>(tests: I) -> Result<usize, RenderError>
+where
+    E: Executable + 'a,
+    I: IntoIterator<Item = &'t UnitTest<'a, E>>,
+{
+    tests
+        .into_iter()
+        .map(UnitTest::rendered_len)
+        .try_fold(0, |total, len| len.map(|len| total + len))
+}
+
+/// Serialize `value` to pretty JSON. On failure, serialize it again to find the path of the value that failed.
+pub(crate) fn to_json<T: Serialize + ?Sized>(value: &T) -> Result<String, SerializeError> {
+    serde_json::to_string_pretty(value).map_err(|e| SerializeError {
+        path: ::serialize::locate_error(value),
+        message: e.to_string(),
+    })
+}
+
+/// Apply the post-processing of `options` to the JSON `Value` of `json`, sort its keys if configured, and serialize it again.
+fn postprocessed(options: &UnitTestOptions, json: &str) -> String {
+    let mut value: Value = serde_json::from_str(json).expect("serde_json produced invalid JSON");
+    if let Some(ref postprocess) = options.json_postprocess {
+        postprocess(&mut value);
+    }
+    if options.sort_keys {
+        sort_keys(&mut value);
+    }
+    serde_json::to_string_pretty(&value).expect("A JSON value can always be serialized")
+}
+
+/// Sort the keys of every object in `value`, recursively. This does not rely on the ordering of `serde_json::Map`, which keeps the insertion order if its `preserve_order` feature is enabled anywhere in the dependency graph.
+fn sort_keys(value: &mut Value) {
+    match *value {
+        Value::Array(ref mut items) => items.iter_mut().for_each(sort_keys),
+        Value::Object(ref mut object) => {
+            let mut entries: Vec<(String, Value)> = ::std::mem::take(object).into_iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            for (key, mut item) in entries {
+                sort_keys(&mut item);
+                object.insert(key, item);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Crates that a leading `::` refers to in paths for [Edition2018](enum.Edition.html#variant.Edition2018), because they are never a module of the crate.
+const EXTERN_CRATES: &[&str] = &["alloc", "core", "exceptional", "std"];
+
+/// `path` with its absolute paths written for Rust 2018, like `crate::Action<std::string::String>` for `::Action<::std::string::String>`. Paths that start with one of the [EXTERN_CRATES](constant.EXTERN_CRATES.html) or with `integration_crate` lose their leading `::`, and the others start with `crate::`.
+fn crate_relative(path: &str, integration_crate: Option<&str>) -> String {
+    let mut rewritten = String::with_capacity(path.len());
+    let bytes = path.as_bytes();
+    let mut i = 0;
+    while let Some(n) = path[i..].find("::") {
+        let start = i + n;
+        rewritten.push_str(&path[i..start]);
+        i = start + 2;
+        let absolute =
+            start == 0 || matches!(bytes[start - 1], b'<' | b',' | b' ' | b'(' | b'[' | b'&');
+        if !absolute {
+            rewritten.push_str("::");
+            continue;
+        }
+        let name_len = path[i..]
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(path.len() - i);
+        let name = &path[i..i + name_len];
+        if !(EXTERN_CRATES.contains(&name) || integration_crate == Some(name)) {
+            rewritten.push_str("crate::");
+        }
+    }
+    rewritten.push_str(&path[i..]);
+    rewritten
+}
+
+/// The name of the const that holds the [hoisted](struct.UnitTestOptions.html#method.hoist_inputs) input of the given kind of the test with the given id, like `INPUT_1539761233543_OBJ`.
+fn input_name(id: &str, kind: &str) -> String {
+    format!("{}{}_{}", ::parse::INPUT_PREFIX, id.to_uppercase(), kind)
+}
+
+/// Write the const that holds the [hoisted](struct.UnitTestOptions.html#method.hoist_inputs) input `json`.
+fn write_input<W: Write>(fmt: &mut W, id: &str, kind: &str, json: &str) -> fmt::Result {
+    writeln!(
+        fmt,
+        "const {}: &str = {};",
+        input_name(id, kind),
+        ::literal::raw_string(json)
+    )
+}
+
+/// The name and the intra-doc link target of the type at `full_path`, like `SomeImportantAction` and `my_crate::SomeImportantAction` for `::my_crate::SomeImportantAction<u32>`.
+fn doc_link(full_path: &str) -> (&str, &str) {
+    let path = full_path.trim_start_matches("::");
+    let target = path.split('<').next().unwrap_or(path).trim_end();
+    let name = target.rsplit("::").next().unwrap_or(target);
+    (name, target)
+}
+
+/// Push a `path: before -> after` line to `changes` for every value that differs between `before` and `after`, which are at `path`. Objects and arrays are compared element by element, and missing elements are written as `(missing)`.
+fn state_changes(before: &Value, after: &Value, path: &mut String, changes: &mut Vec<String>) {
+    let len = path.len();
+    match (before, after) {
+        (Value::Object(before), Value::Object(after)) => {
+            let keys: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+            for key in keys {
+                if !path.is_empty() {
+                    path.push('.');
+                }
+                path.push_str(key);
+                match (before.get(key), after.get(key)) {
+                    (Some(b), Some(a)) => state_changes(b, a, path, changes),
+                    (b, a) => changes.push(change(path, b, a)),
+                }
+                path.truncate(len);
+            }
+        }
+        (Value::Array(before), Value::Array(after)) => {
+            for i in 0..before.len().max(after.len()) {
+                path.push_str(&format!("[{}]", i));
+                match (before.get(i), after.get(i)) {
+                    (Some(b), Some(a)) => state_changes(b, a, path, changes),
+                    (b, a) => changes.push(change(path, b, a)),
+                }
+                path.truncate(len);
+            }
+        }
+        (before, after) if before != after => changes.push(change(path, Some(before), Some(after))),
+        _ => {}
+    }
+}
+
+/// A line of `state_changes`.
+fn change(path: &str, before: Option<&Value>, after: Option<&Value>) -> String {
+    let value = |value: Option<&Value>| match value {
+        Some(value) => value.to_string(),
+        None => String::from("(missing)"),
+    };
+    let path = if path.is_empty() { "state" } else { path };
+    format!("{}: {} -> {}", path, value(before), value(after))
+}
+
+/// The name of the result of the call in the generated test, for an [assertion body](trait.Executable.html#method.assertion_body).
+const RESULT_BINDING: &str = "result";
+
+/// Escape control characters, like line breaks, so `text` stays on a single comment line.
+fn escape_comment(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if c.is_control() {
+            escaped.extend(c.escape_default());
+        } else {
+            escaped.push(c);
+        }
+    }
+    escaped
+}
+
+/// Write `text` as comment lines that start with `prefix`, the first one with `label` in front of it. Every line of `text` gets a comment line of its own, with control characters escaped, so a line break in it can't end the comment.
+pub(crate) fn write_comment_lines<W: Write>(
+    fmt: &mut W,
+    prefix: &str,
+    label: &str,
+    text: &str,
+) -> fmt::Result {
+    let mut lines = text.lines();
+    let first = lines.next().unwrap_or("");
+    writeln!(fmt, "{} {}{}", prefix, label, escape_comment(first))?;
+    for line in lines {
+        if line.trim().is_empty() {
+            writeln!(fmt, "{}", prefix)?;
+        } else {
+            writeln!(fmt, "{} {}", prefix, escape_comment(line))?;
+        }
+    }
+    Ok(())
+}
+
+/// Write every line of `code` prefixed with `indent`. Empty lines are written without indentation.
+fn write_indented<W: Write>(fmt: &mut W, code: &str, indent: &str) -> fmt::Result {
+    for line in code.lines() {
+        if line.trim().is_empty() {
+            writeln!(fmt)?;
+        } else {
+            writeln!(fmt, "{}{}", indent, line)?;
+        }
+    }
+    Ok(())
+}
+
+/// The id of `snapshot`, or one derived from it that is not `taken`. See [UnitTest::unique_id](struct.UnitTest.html#method.unique_id).
+pub(crate) fn unique_id(snapshot: &Snapshot, taken: &HashSet<String>) -> String {
+    unique_id_for(&snapshot.id, snapshot.payload_hash(), taken)
+}
+
+/// Like `unique_id`, for a test that asked for `id` and has the given [payload hash](struct.Snapshot.html#method.payload_hash).
+pub(crate) fn unique_id_for(id: &str, payload_hash: u64, taken: &HashSet<String>) -> String {
+    if !taken.contains(id) {
+        return id.to_owned();
+    }
+    let hash = format!("{:016x}", payload_hash);
+    let id = format!("{}_{}", id, &hash[..8]);
+    if !taken.contains(&id) {
+        return id;
+    }
+    (2..)
+        .map(|n| format!("{}_{}", id, n))
+        .find(|id| !taken.contains(id))
+        .expect("There are fewer taken ids than counters")
+}
+
+/// Check that `id` can be used as the suffix of a function name.
+fn is_valid_id(id: &str) -> bool {
+    !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
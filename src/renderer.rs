@@ -0,0 +1,112 @@
+use chrono::SecondsFormat;
+use error::RenderError;
+use {Executable, UnitTest};
+
+/// A backend that turns a [UnitTest](struct.UnitTest.html) into text.
+///
+/// The built-in options only tweak the generated `#[test]`. A renderer replaces it completely, e.g. with a JSON report or a test in another language, while reusing the way failures are captured and written. Use it with [render_with](struct.UnitTest.html#method.render_with) and [append_to_file_with](struct.UnitTest.html#method.append_to_file_with).
+///
+/// ```rust
+/// # use exceptional::{Executable, RenderError, Renderer, UnitTest};
+/// struct OneLine;
+///
+/// impl Renderer for OneLine {
+///     fn render<'a, E: Executable + 'a>(&self, test: &UnitTest<'a, E>) -> Result<String, RenderError> {
+///         let (executable, arguments) = test.snapshots()?;
+///         Ok(format!("{} {} failed with {:?}\n", executable.replace('\n', ""), arguments.replace('\n', ""), test.error))
+///     }
+/// }
+/// ```
+pub trait Renderer {
+    /// Render `test`, or return an error if it can't be rendered.
+    fn render<'a, E: Executable + 'a>(&self, test: &UnitTest<'a, E>)
+        -> Result<String, RenderError>;
+}
+
+/// The renderer that generates the usual `#[test]` function, as configured by the [options](struct.UnitTestOptions.html) of the test.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DefaultRenderer;
+
+impl Renderer for DefaultRenderer {
+    fn render<'a, E: Executable + 'a>(
+        &self,
+        test: &UnitTest<'a, E>,
+    ) -> Result<String, RenderError> {
+        test.try_to_test_source()
+    }
+}
+
+impl<'a, E: Executable + 'a> UnitTest<'a, E> {
+    /// Render this unit test with a custom [Renderer](trait.Renderer.html). `test.render_with(&DefaultRenderer)` is the same as [try_to_test_source](#method.try_to_test_source).
+    pub fn render_with<R: Renderer>(&self, renderer: &R) -> Result<String, RenderError> {
+        renderer.render(self)
+    }
+}
+
+/// A renderer that outputs a JUnit XML `<testcase>` element with a `<failure>` for every test, for the test reports of CI systems like Jenkins, GitLab and GitHub.
+///
+/// The name of the test case is the [description](trait.Executable.html#tymethod.description) of the executable, its class name is the [full path](trait.Executable.html#tymethod.full_path), and the failure message is the `Debug` output of the error, or the [error message](struct.UnitTest.html#structfield.error_message) if there is one. The elements of several tests are combined into a report with [testsuite](#method.testsuite):
+///
+/// ```rust,ignore
+/// let cases: Vec<String> = tests.iter().map(|test| test.render_with(&JunitRenderer).unwrap()).collect();
+/// std::fs::write("target/exceptional.xml", JunitRenderer::testsuite("exceptional", &cases))?;
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct JunitRenderer;
+
+impl JunitRenderer {
+    /// Wrap rendered `<testcase>` elements in an XML document with a single `<testsuite>` named `name`. Every element counts as one failed test.
+    pub fn testsuite(name: &str, testcases: &[String]) -> String {
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str(&format!(
+            "<testsuite name=\"{}\" tests=\"{}\" failures=\"{}\">\n",
+            escape_xml(name),
+            testcases.len(),
+            testcases.len()
+        ));
+        for testcase in testcases {
+            xml.push_str(testcase);
+        }
+        xml.push_str("</testsuite>\n");
+        xml
+    }
+}
+
+impl Renderer for JunitRenderer {
+    fn render<'a, E: Executable + 'a>(
+        &self,
+        test: &UnitTest<'a, E>,
+    ) -> Result<String, RenderError> {
+        let message = match test.error_message {
+            Some(ref message) => message.clone(),
+            None => format!("{:?}", test.error),
+        };
+        let message = escape_xml(&message);
+        Ok(format!(
+            "  <testcase name=\"{}\" classname=\"{}\" timestamp=\"{}\">\n    <failure message=\"{}\">{}</failure>\n  </testcase>\n",
+            escape_xml(&test.executable.description()),
+            escape_xml(test.executable.full_path()),
+            test.time.to_rfc3339_opts(SecondsFormat::Secs, true),
+            message,
+            message
+        ))
+    }
+}
+
+/// Escape `text` for use in XML attributes and text. Control characters that XML 1.0 does not allow are written as Rust escapes, like `\u{1b}`.
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if c.is_control() => escaped.extend(c.escape_unicode()),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,47 @@
+use chrono::SecondsFormat;
+use error::RenderError;
+use {Executable, UnitTest};
+
+/// A serializable summary of a failure, for shipping it to other systems, e.g. as a JSON body of a request or as a line of NDJSON.
+///
+/// Unlike the generated test, the shape of this struct does not depend on the [options](struct.UnitTestOptions.html) of the test. The executable and the arguments are serialized in the same JSON form that the generated test embeds.
+///
+/// ```rust,ignore
+/// let report: UnitTestReport = Result::from(&test)?;
+/// writeln!(log, "{}", serde_json::to_string(&report)?)?;
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct UnitTestReport {
+    /// The [description](trait.Executable.html#tymethod.description) of the executable
+    pub description: String,
+
+    /// The [full path](trait.Executable.html#tymethod.full_path) of the executable
+    pub full_path: String,
+
+    /// The time at which the executable failed, as an RFC 3339 timestamp in UTC with millisecond precision
+    pub time: String,
+
+    /// The `Debug` output of the error
+    pub error_debug: String,
+
+    /// The serialized executable, with the state from before it failed
+    pub executable_json: String,
+
+    /// The serialized arguments
+    pub arguments_json: String,
+}
+
+impl<'a, 'b, E: Executable + 'a> From<&'b UnitTest<'a, E>> for Result<UnitTestReport, RenderError> {
+    /// Summarize `test`, or return an error if the executable or the arguments can not be serialized, like [snapshots](struct.UnitTest.html#method.snapshots).
+    fn from(test: &'b UnitTest<'a, E>) -> Self {
+        let (executable_json, arguments_json) = test.snapshots()?;
+        Ok(UnitTestReport {
+            description: test.executable.description(),
+            full_path: test.executable.full_path().to_owned(),
+            time: test.time.to_rfc3339_opts(SecondsFormat::Millis, true),
+            error_debug: format!("{:?}", test.error),
+            executable_json,
+            arguments_json,
+        })
+    }
+}
@@ -0,0 +1,133 @@
+use parse::{self, SnapshotSource};
+use serde::de::DeserializeOwned;
+use std::error::Error;
+use std::fmt::{self, Debug};
+use std::fs;
+use std::io;
+use std::path::Path;
+use Executable;
+
+/// The result of executing the snapshot of a generated test again. See [rerun_file](fn.rerun_file.html).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    /// The executable succeeded, so the failure the test was generated for is fixed.
+    Passes,
+    /// The executable still fails, with the `Debug` output of its error.
+    Fails(String),
+    /// The snapshot could not be read or deserialized, with the reason.
+    Unreadable(String),
+}
+
+/// Execute the snapshot of every generated test for `E` in `file` again, in this process, and report which ones still fail.
+///
+/// This is the read side of [append_to_file](struct.UnitTest.html#method.append_to_file): the embedded or [sidecar](struct.UnitTestOptions.html#method.sidecar_dir) JSON of every test is deserialized into `E` and `E::Arguments` and executed, without compiling the file. Tests are identified by the id in their marker comment and returned in the order of the file. Tests whose executable deserializes into an `E` with a different [full path](trait.Executable.html#tymethod.full_path) than the marker are skipped, but tests of unrelated types usually don't deserialize and are reported as [Unreadable](enum.Outcome.html#variant.Unreadable), so this works best on files with a single executable type, like the ones written by [resplit_by_type](fn.resplit_by_type.html).
+///
+/// Only the result of executing is reported. A custom [assertion](trait.Executable.html#method.assertion), an [expected](struct.UnitTest.html#method.with_expected) value or an [error message](struct.UnitTest.html#structfield.error_message) check in the test is not evaluated, and grouped [test-case](enum.Harness.html#variant.TestCase) functions, tests that [construct the executable from its default](struct.UnitTest.html#method.with_default_overrides) and tests that construct their [arguments with a struct literal](trait.Executable.html#method.arguments_path) are reported as unreadable.
+///
+/// ```rust,ignore
+/// for (id, outcome) in exceptional::rerun_file::<SomeImportantAction>("src/test.rs")? {
+///     if outcome == exceptional::Outcome::Passes {
+///         println!("test_{} is fixed and can be removed", id);
+///     }
+/// }
+/// ```
+pub fn rerun_file<E: Executable>(file: impl AsRef<Path>) -> io::Result<Vec<(String, Outcome)>> {
+    let file = file.as_ref();
+    let src = fs::read_to_string(file)?;
+    let mut outcomes = Vec::new();
+    for item in parse::find_items(&src) {
+        let item_src = &src[item.range];
+        let executable: E = match read_snapshot(file, item_src, "obj_json") {
+            Ok(executable) => executable,
+            Err(reason) => {
+                outcomes.push((item.marker.id, Outcome::Unreadable(reason)));
+                continue;
+            }
+        };
+        if executable.full_path() != item.marker.path {
+            continue;
+        }
+        let outcome = match read_snapshot::<E::Arguments>(file, item_src, "arg_json") {
+            Ok(arguments) => match executable.clone().execute(&arguments) {
+                Ok(_) => Outcome::Passes,
+                Err(error) => Outcome::Fails(format!("{:?}", error)),
+            },
+            Err(reason) => Outcome::Unreadable(reason),
+        };
+        outcomes.push((item.marker.id, outcome));
+    }
+    Ok(outcomes)
+}
+
+/// Why [replay](fn.replay.html) did not return the result of the executable.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ReplayError<E> {
+    /// The executable could not be deserialized, with the reason.
+    Executable(String),
+    /// The arguments could not be deserialized, with the reason.
+    Arguments(String),
+    /// The executable failed with this error.
+    Failed(E),
+}
+
+impl<E: Debug> fmt::Display for ReplayError<E> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReplayError::Executable(e) => {
+                write!(fmt, "could not deserialize the executable: {}", e)
+            }
+            ReplayError::Arguments(e) => write!(fmt, "could not deserialize the arguments: {}", e),
+            ReplayError::Failed(e) => write!(fmt, "the executable failed with {:?}", e),
+        }
+    }
+}
+
+impl<E: Debug> Error for ReplayError<E> {}
+
+/// Deserialize an executable and its arguments from JSON, and execute it, like a generated test does without compiling it.
+///
+/// This replays failures that are stored somewhere else than in a test file, like a database. The JSON is what [snapshots](struct.UnitTest.html#method.snapshots) returns, or what a generated test embeds:
+///
+/// ```rust,ignore
+/// // When the failure happens:
+/// let (obj_json, arg_json) = test.snapshots()?;
+/// db.insert_failure(&obj_json, &arg_json)?;
+///
+/// // Later, e.g. after a fix:
+/// match exceptional::replay::<SomeImportantAction>(&obj_json, &arg_json) {
+///     Ok(_) => println!("fixed"),
+///     Err(ReplayError::Failed(e)) => println!("still fails with {:?}", e),
+///     Err(e) => println!("can't be replayed: {}", e),
+/// }
+/// ```
+///
+/// Nothing is executed if either of them can't be deserialized. Like [rerun_file](fn.rerun_file.html), only the result of executing is returned, without the checks of the generated test.
+pub fn replay<E: Executable>(
+    obj_json: &str,
+    arg_json: &str,
+) -> Result<E::Result, ReplayError<E::Error>> {
+    let mut executable: E =
+        ::serde_json::from_str(obj_json).map_err(|e| ReplayError::Executable(e.to_string()))?;
+    let arguments: E::Arguments =
+        ::serde_json::from_str(arg_json).map_err(|e| ReplayError::Arguments(e.to_string()))?;
+    executable.execute(&arguments).map_err(ReplayError::Failed)
+}
+
+/// Read and deserialize the snapshot that the generated item assigns to `local`.
+fn read_snapshot<T: DeserializeOwned>(file: &Path, item: &str, local: &str) -> Result<T, String> {
+    let json = read_json(file, item, local)?;
+    ::serde_json::from_str(&json).map_err(|e| format!("could not deserialize `{}`: {}", local, e))
+}
+
+/// Read the JSON that the generated item in `file` assigns to `local`, from the item itself or from its sidecar file.
+pub(crate) fn read_json(file: &Path, item: &str, local: &str) -> Result<String, String> {
+    match parse::snapshot_source(item, local) {
+        Some(SnapshotSource::Inline(json)) => Ok(json),
+        Some(SnapshotSource::Include(path)) => {
+            let path = file.parent().unwrap_or_else(|| Path::new("")).join(path);
+            fs::read_to_string(&path)
+                .map_err(|e| format!("could not read {}: {}", path.display(), e))
+        }
+        None => Err(format!("the test does not assign `{}`", local)),
+    }
+}
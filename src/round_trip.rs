@@ -0,0 +1,79 @@
+//! Checking that the embedded JSON can be read back by the generated test.
+//!
+//! A type whose `Deserialize` does not accept its own `Serialize` output, e.g. because of a `#[serde(skip_serializing)]` field without a default, produces a test that only panics with "Could not deserialize json" when it is run. These checks parse the JSON right after it was produced, so such a snapshot is never written.
+
+use error::RenderError;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use {Executable, UnitTest};
+
+impl<'a, E: Executable + 'a> UnitTest<'a, E> {
+    /// Check that the serialized executable and arguments can be deserialized again, into values that serialize to the same JSON. With [json_postprocess](struct.UnitTestOptions.html#method.json_postprocess), they only have to deserialize.
+    ///
+    /// Rendering a test runs the same check and fails with [ExecutableRoundTrip](enum.RenderError.html#variant.ExecutableRoundTrip) or [ArgumentsRoundTrip](enum.RenderError.html#variant.ArgumentsRoundTrip), so this is only needed to check a failure without rendering it:
+    ///
+    /// ```rust,ignore
+    /// if let Err(test) = exceptional::execute(&mut action, &args) {
+    ///     test.check_round_trip().expect("Snapshot does not round-trip");
+    /// }
+    /// ```
+    pub fn check_round_trip(&self) -> Result<(), RenderError> {
+        let (executable_json, arguments_json) = self.snapshots()?;
+        self.check_snapshots(&executable_json, &arguments_json)
+    }
+
+    /// Check that the given snapshots of this test round-trip, as described in [check_round_trip](#method.check_round_trip).
+    pub(crate) fn check_snapshots(
+        &self,
+        executable_json: &str,
+        arguments_json: &str,
+    ) -> Result<(), RenderError> {
+        if self.executable.construct_expr().is_some() {
+            // The generated test constructs the executable from its expression, so only the arguments are deserialized.
+            return if self.options.json_postprocess.is_some() {
+                deserializes::<E::Arguments>(arguments_json)
+                    .map_err(RenderError::ArgumentsRoundTrip)
+            } else {
+                check::<E::Arguments>(arguments_json).map_err(RenderError::ArgumentsRoundTrip)
+            };
+        }
+        if self.options.json_postprocess.is_some() {
+            // Post-processing may remove fields on purpose, so the result only has to deserialize.
+            deserializes::<E>(executable_json).map_err(RenderError::ExecutableRoundTrip)?;
+            deserializes::<E::Arguments>(arguments_json).map_err(RenderError::ArgumentsRoundTrip)
+        } else {
+            check::<E>(executable_json).map_err(RenderError::ExecutableRoundTrip)?;
+            check::<E::Arguments>(arguments_json).map_err(RenderError::ArgumentsRoundTrip)
+        }
+    }
+}
+
+/// Deserialize `json` into a `T`, and check that it serializes to the same JSON again. Maps are compared regardless of their order, so a `HashMap` round-trips even if its iteration order changes.
+pub(crate) fn check<T: Serialize + DeserializeOwned>(json: &str) -> Result<(), String> {
+    let value: T = ::serde_json::from_str(json).map_err(|e| e.to_string())?;
+    let again = ::serde_json::to_value(&value).map_err(|e| e.to_string())?;
+    let original: Value = ::serde_json::from_str(json).map_err(|e| e.to_string())?;
+    if again == original {
+        Ok(())
+    } else {
+        Err(format!("it deserializes to a different value: {}", again))
+    }
+}
+
+/// Check that `json` deserializes into a `T`, without comparing the result.
+pub(crate) fn deserializes<T: DeserializeOwned>(json: &str) -> Result<(), String> {
+    ::serde_json::from_str::<T>(json)
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// A copy of `value` that was made by serializing and deserializing it, or `None` if the copy does not serialize to the same JSON.
+pub(crate) fn copy<T: Serialize + DeserializeOwned>(value: &T) -> Option<T> {
+    let json = ::serde_json::to_value(value).ok()?;
+    let copy: T = ::serde_json::from_value(json.clone()).ok()?;
+    match ::serde_json::to_value(&copy) {
+        Ok(ref again) if *again == json => Some(copy),
+        _ => None,
+    }
+}
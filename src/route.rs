@@ -0,0 +1,64 @@
+use error::AppendError;
+use parse;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use {Executable, UnitTest};
+
+impl<'a, E: Executable + 'a> UnitTest<'a, E> {
+    /// Append this unit test to the file that the configured [route](struct.UnitTestOptions.html#method.route) chooses for it, and return that file, so one capture loop can write its tests to several files.
+    ///
+    /// Returns `None` without writing anything if the route vetoes the test, or if the chosen file already contains a test for the same executable state and arguments, according to the `hash` in its marker comment. With [upsert](struct.UnitTestOptions.html#method.upsert) enabled, the upsert rules decide instead, so a test whose error changed is replaced. Otherwise the test is placed like in [append_to_file](#method.append_to_file), including its fallback directory, and the returned path is the file it was actually written to.
+    ///
+    /// Nothing is cached between calls: the chosen file is read again every time to find duplicates, so every file is deduplicated on its own and tests that were added or removed by other means are taken into account. A [StreamingWriter](struct.StreamingWriter.html) only knows the hashes it read when it was opened, so tests routed to a file that a writer has open are not deduplicated by that writer.
+    ///
+    /// If no route is configured, an error of kind `InvalidInput` is returned. If the test can not be rendered, the [RenderError](enum.RenderError.html) is returned as an error of kind `InvalidData`.
+    ///
+    /// ```rust,ignore
+    /// let options = UnitTestOptions::default().route(|meta| {
+    ///     match meta.metadata.get("category").map(String::as_str) {
+    ///         Some("db") => Some(PathBuf::from("tests/db_repros.rs")),
+    ///         _ => Some(PathBuf::from("tests/repros.rs")),
+    ///     }
+    /// });
+    /// for (mut action, args) in failures {
+    ///     if let Err(test) = exceptional::execute(&mut action, &args) {
+    ///         test.with_options(options.clone()).append_routed()?;
+    ///     }
+    /// }
+    /// ```
+    pub fn append_routed(self) -> io::Result<Option<PathBuf>> {
+        let route = match self.options.route {
+            Some(ref route) => route.clone(),
+            None => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "no route is configured, see UnitTestOptions::route",
+                ))
+            }
+        };
+        let snapshot = self
+            .snapshot()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let file = match route(&self.meta(&snapshot)) {
+            Some(file) => file,
+            None => return Ok(None),
+        };
+        if !self.options.upsert {
+            let existing = match fs::read_to_string(&file) {
+                Ok(existing) => existing,
+                Err(ref e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+                Err(e) => return Err(e),
+            };
+            if parse::parse_markers(&existing)
+                .iter()
+                .any(|marker| marker.hash == snapshot.hash)
+            {
+                return Ok(None);
+            }
+        }
+        self.append_snapshot(&file, &snapshot)
+            .map(Some)
+            .map_err(AppendError::into_io)
+    }
+}
@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+use {execute, Executable, UnitTest};
+
+/// An executable whose arguments are part of its own state, so `execute` takes no separate arguments.
+///
+/// This is for logic whose "arguments" would borrow from the executable, like a field of it, which can't be expressed with [Executable::Arguments](trait.Executable.html#associatedtype.Arguments). Every `ExecutableSelfArgs` is an [Executable](trait.Executable.html) with `()` as its arguments, and the generated test only deserializes the executable:
+///
+/// ```rust,ignore
+/// #[derive(Serialize, Deserialize, Clone)]
+/// pub struct Checkout {
+///     cart: Cart,
+///     coupon: Option<String>,
+/// }
+///
+/// impl exceptional::ExecutableSelfArgs for Checkout {
+///     type Result = u64;
+///     type Error = String;
+///
+///     fn full_path(&self) -> &'static str { "::my_crate::Checkout" }
+///     fn description(&self) -> String { String::from("Checks out the cart") }
+///     fn execute(&mut self) -> Result<u64, String> {
+///         self.cart.total(self.coupon.as_ref())
+///     }
+/// }
+///
+/// if let Err(test) = exceptional::execute_self(&mut checkout) {
+///     test.append_to_file("src/regressions.rs").unwrap();
+/// }
+/// ```
+///
+/// The hooks of `Executable`, like its [severity](trait.Executable.html#method.severity), keep their default implementations. Implement `Executable` with `()` as the arguments instead to override them.
+pub trait ExecutableSelfArgs: Serialize + for<'a> Deserialize<'a> + Clone {
+    /// The result of the execute action.
+    type Result;
+
+    /// The error of the execute action.
+    type Error: Debug;
+
+    /// Get the full path for this type. This will be used to generate the unit test.
+    fn full_path(&self) -> &'static str;
+
+    /// Get the description for this type, which will show up in the description of the unit test.
+    fn description(&self) -> String;
+
+    /// Execute the logic, with the arguments that are part of `self`.
+    fn execute(&mut self) -> Result<Self::Result, Self::Error>;
+}
+
+impl<T: ExecutableSelfArgs> Executable for T {
+    type Result = T::Result;
+    type Error = T::Error;
+    type Arguments = ();
+
+    fn full_path(&self) -> &'static str {
+        ExecutableSelfArgs::full_path(self)
+    }
+
+    fn arguments_from_self(&self) -> bool {
+        true
+    }
+
+    fn description(&self) -> String {
+        ExecutableSelfArgs::description(self)
+    }
+
+    fn execute(&mut self, _args: &()) -> Result<T::Result, T::Error> {
+        ExecutableSelfArgs::execute(self)
+    }
+}
+
+/// Like [execute](fn.execute.html), for an [ExecutableSelfArgs](trait.ExecutableSelfArgs.html), which takes no separate arguments.
+#[track_caller]
+pub fn execute_self<'a, E: ExecutableSelfArgs + 'a>(
+    executable: &'a mut E,
+) -> Result<E::Result, UnitTest<'a, E>> {
+    execute(executable, &())
+}
@@ -0,0 +1,88 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use Executable;
+
+/// An executable that runs several steps of another executable in order, on the same state.
+///
+/// The arguments of a sequence are the arguments of every step. The steps are executed one after another until one of them fails, so each step sees the state that the previous steps left behind. The generated test embeds the state from before the first step and the arguments of all steps, so it reproduces the whole sequence:
+///
+/// ```rust,ignore
+/// let mut sequence = exceptional::Sequence::new(account);
+/// let steps = vec![Operation::Deposit(10), Operation::Withdraw(20)];
+/// if let Err(test) = exceptional::execute(&mut sequence, &steps) {
+///     test.append_to_file("src/regressions.rs").unwrap();
+/// }
+/// ```
+///
+/// State and arguments have to round-trip through JSON, so all steps are executed by the same executable type. Steps that do different things are modelled with an enum as the `Arguments` of that executable. A sequence is serialized exactly like the executable it wraps.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sequence<E: Executable> {
+    /// The executable that runs every step
+    pub executable: E,
+}
+
+impl<E: Executable> Sequence<E> {
+    /// Run the steps of a sequence on `executable`.
+    pub fn new(executable: E) -> Self {
+        Sequence { executable }
+    }
+}
+
+/// The error of a [Sequence](struct.Sequence.html): the step that failed, and its error.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SequenceError<T> {
+    /// The index of the step that failed, starting at 0
+    pub step: usize,
+
+    /// The error that the step failed with
+    pub error: T,
+}
+
+impl<E: Executable> Executable for Sequence<E> {
+    type Result = Vec<E::Result>;
+    type Error = SequenceError<E::Error>;
+    type Arguments = Vec<E::Arguments>;
+
+    fn full_path(&self) -> &'static str {
+        sequence_path(self.executable.full_path())
+    }
+
+    fn description(&self) -> String {
+        format!("A sequence of steps of: {}", self.executable.description())
+    }
+
+    fn execute(&mut self, steps: &Vec<E::Arguments>) -> Result<Vec<E::Result>, Self::Error> {
+        let mut results = Vec::with_capacity(steps.len());
+        for (step, arguments) in steps.iter().enumerate() {
+            match self.executable.execute(arguments) {
+                Ok(result) => results.push(result),
+                Err(error) => return Err(SequenceError { step, error }),
+            }
+        }
+        Ok(results)
+    }
+}
+
+impl<E: Executable> Serialize for Sequence<E> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.executable.serialize(serializer)
+    }
+}
+
+impl<'de, E: Executable> Deserialize<'de> for Sequence<E> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        E::deserialize(deserializer).map(Sequence::new)
+    }
+}
+
+/// The full path of a sequence of the executable at `inner`, like `::exceptional::Sequence<::my_crate::Action>`.
+///
+/// `full_path` has to return a `&'static str`, so every distinct path is leaked once and reused afterwards.
+fn sequence_path(inner: &'static str) -> &'static str {
+    static PATHS: Mutex<BTreeMap<&'static str, &'static str>> = Mutex::new(BTreeMap::new());
+    let mut paths = PATHS.lock().unwrap_or_else(|e| e.into_inner());
+    paths.entry(inner).or_insert_with(|| {
+        Box::leak(format!("::exceptional::Sequence<{}>", inner).into_boxed_str())
+    })
+}
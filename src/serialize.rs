@@ -0,0 +1,390 @@
+//! Locating the value that makes serialization fail.
+//!
+//! serde_json errors like "key must be a string" don't say where in the value they happened. [locate_error](fn.locate_error.html) serializes the value again through a wrapper around serde_json's `Value` serializer that keeps track of the path to the value that is being serialized, and reports the path at which the first error happened.
+
+use serde::ser::{self, Serialize, Serializer};
+use std::cell::RefCell;
+
+enum Segment {
+    Field(&'static str),
+    Index(usize),
+    Key(String),
+}
+
+#[derive(Default)]
+struct State {
+    path: Vec<Segment>,
+    failed_at: Option<String>,
+}
+
+impl State {
+    fn record<T, E>(&mut self, result: Result<T, E>) -> Result<T, E> {
+        if result.is_err() && self.failed_at.is_none() {
+            self.failed_at = Some(format_path(&self.path));
+        }
+        result
+    }
+}
+
+fn format_path(path: &[Segment]) -> String {
+    let mut formatted = String::new();
+    for segment in path {
+        match segment {
+            Segment::Field(name) => {
+                if !formatted.is_empty() {
+                    formatted.push('.');
+                }
+                formatted.push_str(name);
+            }
+            Segment::Index(index) => formatted.push_str(&format!("[{}]", index)),
+            Segment::Key(key) => formatted.push_str(&format!("[{}]", key)),
+        }
+    }
+    formatted
+}
+
+/// Serialize `value` to a `serde_json::Value` and return the path of the value that failed, or `None` if serialization succeeds.
+///
+/// The path of the top-level value is the empty string.
+pub(crate) fn locate_error<T: Serialize + ?Sized>(value: &T) -> Option<String> {
+    let state = RefCell::new(State::default());
+    let result = Tracked {
+        value,
+        state: &state,
+    }
+    .serialize(::serde_json::value::Serializer);
+    match result {
+        Ok(_) => None,
+        Err(_) => state.into_inner().failed_at.or_else(|| Some(String::new())),
+    }
+}
+
+/// A value that is serialized with a `PathSerializer`.
+struct Tracked<'v, 's, T: ?Sized + 'v> {
+    value: &'v T,
+    state: &'s RefCell<State>,
+}
+
+impl<'v, 's, T: Serialize + ?Sized> Serialize for Tracked<'v, 's, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let result = self.value.serialize(PathSerializer {
+            inner: serializer,
+            state: self.state,
+        });
+        self.state.borrow_mut().record(result)
+    }
+}
+
+struct PathSerializer<'s, S> {
+    inner: S,
+    state: &'s RefCell<State>,
+}
+
+impl<'s, S> PathSerializer<'s, S> {
+    fn nested<'v, T: ?Sized>(&self, value: &'v T) -> Tracked<'v, 's, T> {
+        Tracked {
+            value,
+            state: self.state,
+        }
+    }
+}
+
+/// Start tracking a compound value, if the inner serializer could start it.
+fn compound<'s, C, E>(
+    state: &'s RefCell<State>,
+    result: Result<C, E>,
+) -> Result<Compound<'s, C>, E> {
+    state.borrow_mut().record(result).map(|inner| Compound {
+        inner,
+        state,
+        index: 0,
+    })
+}
+
+macro_rules! forward_primitive {
+    ($($method:ident($ty:ty),)*) => {
+        $(
+            fn $method(self, v: $ty) -> Result<S::Ok, S::Error> {
+                let result = self.inner.$method(v);
+                self.state.borrow_mut().record(result)
+            }
+        )*
+    };
+}
+
+impl<'s, S: Serializer> Serializer for PathSerializer<'s, S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+    type SerializeSeq = Compound<'s, S::SerializeSeq>;
+    type SerializeTuple = Compound<'s, S::SerializeTuple>;
+    type SerializeTupleStruct = Compound<'s, S::SerializeTupleStruct>;
+    type SerializeTupleVariant = Compound<'s, S::SerializeTupleVariant>;
+    type SerializeMap = Compound<'s, S::SerializeMap>;
+    type SerializeStruct = Compound<'s, S::SerializeStruct>;
+    type SerializeStructVariant = Compound<'s, S::SerializeStructVariant>;
+
+    forward_primitive! {
+        serialize_bool(bool),
+        serialize_i8(i8),
+        serialize_i16(i16),
+        serialize_i32(i32),
+        serialize_i64(i64),
+        serialize_i128(i128),
+        serialize_u8(u8),
+        serialize_u16(u16),
+        serialize_u32(u32),
+        serialize_u64(u64),
+        serialize_u128(u128),
+        serialize_f32(f32),
+        serialize_f64(f64),
+        serialize_char(char),
+        serialize_str(&str),
+        serialize_bytes(&[u8]),
+        serialize_unit_struct(&'static str),
+    }
+
+    fn serialize_none(self) -> Result<S::Ok, S::Error> {
+        let result = self.inner.serialize_none();
+        self.state.borrow_mut().record(result)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<S::Ok, S::Error> {
+        let nested = self.nested(value);
+        let result = self.inner.serialize_some(&nested);
+        self.state.borrow_mut().record(result)
+    }
+
+    fn serialize_unit(self) -> Result<S::Ok, S::Error> {
+        let result = self.inner.serialize_unit();
+        self.state.borrow_mut().record(result)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<S::Ok, S::Error> {
+        let result = self
+            .inner
+            .serialize_unit_variant(name, variant_index, variant);
+        self.state.borrow_mut().record(result)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<S::Ok, S::Error> {
+        let nested = self.nested(value);
+        let result = self.inner.serialize_newtype_struct(name, &nested);
+        self.state.borrow_mut().record(result)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<S::Ok, S::Error> {
+        let nested = self.nested(value);
+        let state = self.state;
+        state.borrow_mut().path.push(Segment::Field(variant));
+        let result = self
+            .inner
+            .serialize_newtype_variant(name, variant_index, variant, &nested);
+        let result = state.borrow_mut().record(result);
+        state.borrow_mut().path.pop();
+        result
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, S::Error> {
+        let result = self.inner.serialize_seq(len);
+        compound(self.state, result)
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, S::Error> {
+        let result = self.inner.serialize_tuple(len);
+        compound(self.state, result)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, S::Error> {
+        let result = self.inner.serialize_tuple_struct(name, len);
+        compound(self.state, result)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, S::Error> {
+        let result = self
+            .inner
+            .serialize_tuple_variant(name, variant_index, variant, len);
+        compound(self.state, result)
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, S::Error> {
+        let result = self.inner.serialize_map(len);
+        compound(self.state, result)
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, S::Error> {
+        let result = self.inner.serialize_struct(name, len);
+        compound(self.state, result)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, S::Error> {
+        let result = self
+            .inner
+            .serialize_struct_variant(name, variant_index, variant, len);
+        compound(self.state, result)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.inner.is_human_readable()
+    }
+}
+
+/// The state of a compound value (sequence, map, struct, ...) that is being serialized.
+struct Compound<'s, C> {
+    inner: C,
+    state: &'s RefCell<State>,
+    /// The index of the next element, for sequences and tuples.
+    index: usize,
+}
+
+impl<'s, C> Compound<'s, C> {
+    /// Run `f` with `segment` appended to the path.
+    fn with_segment<T, E, F>(&mut self, segment: Segment, f: F) -> Result<T, E>
+    where
+        F: FnOnce(&mut C, &'s RefCell<State>) -> Result<T, E>,
+    {
+        let state = self.state;
+        state.borrow_mut().path.push(segment);
+        let result = f(&mut self.inner, state);
+        let result = state.borrow_mut().record(result);
+        state.borrow_mut().path.pop();
+        result
+    }
+
+    fn next_index(&mut self) -> Segment {
+        self.index += 1;
+        Segment::Index(self.index - 1)
+    }
+
+    fn end_with<T, E>(state: &'s RefCell<State>, result: Result<T, E>) -> Result<T, E> {
+        state.borrow_mut().record(result)
+    }
+}
+
+macro_rules! impl_indexed {
+    ($trait:ident, $method:ident) => {
+        impl<'s, C: ser::$trait> ser::$trait for Compound<'s, C> {
+            type Ok = C::Ok;
+            type Error = C::Error;
+
+            fn $method<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), C::Error> {
+                let segment = self.next_index();
+                self.with_segment(segment, |inner, state| {
+                    inner.$method(&Tracked { value, state })
+                })
+            }
+
+            fn end(self) -> Result<C::Ok, C::Error> {
+                let state = self.state;
+                Self::end_with(state, self.inner.end())
+            }
+        }
+    };
+}
+
+impl_indexed!(SerializeSeq, serialize_element);
+impl_indexed!(SerializeTuple, serialize_element);
+impl_indexed!(SerializeTupleStruct, serialize_field);
+impl_indexed!(SerializeTupleVariant, serialize_field);
+
+macro_rules! impl_named {
+    ($trait:ident) => {
+        impl<'s, C: ser::$trait> ser::$trait for Compound<'s, C> {
+            type Ok = C::Ok;
+            type Error = C::Error;
+
+            fn serialize_field<T: Serialize + ?Sized>(
+                &mut self,
+                key: &'static str,
+                value: &T,
+            ) -> Result<(), C::Error> {
+                self.with_segment(Segment::Field(key), |inner, state| {
+                    inner.serialize_field(key, &Tracked { value, state })
+                })
+            }
+
+            fn skip_field(&mut self, key: &'static str) -> Result<(), C::Error> {
+                self.inner.skip_field(key)
+            }
+
+            fn end(self) -> Result<C::Ok, C::Error> {
+                let state = self.state;
+                Self::end_with(state, self.inner.end())
+            }
+        }
+    };
+}
+
+impl_named!(SerializeStruct);
+impl_named!(SerializeStructVariant);
+
+impl<'s, C: ser::SerializeMap> ser::SerializeMap for Compound<'s, C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), C::Error> {
+        let segment = Segment::Key(describe_key(key));
+        self.with_segment(segment, |inner, state| {
+            inner.serialize_key(&Tracked { value: key, state })
+        })?;
+        // The key stays on the path until its value has been serialized.
+        self.state
+            .borrow_mut()
+            .path
+            .push(Segment::Key(describe_key(key)));
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), C::Error> {
+        let state = self.state;
+        let result = self.inner.serialize_value(&Tracked { value, state });
+        let result = state.borrow_mut().record(result);
+        state.borrow_mut().path.pop();
+        result
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        let state = self.state;
+        Self::end_with(state, self.inner.end())
+    }
+}
+
+/// A short description of a map key for use in a path, e.g. `"name"` or `[1,2]`.
+fn describe_key<T: Serialize + ?Sized>(key: &T) -> String {
+    ::serde_json::to_string(&::serde_json::to_value(key).unwrap_or(::serde_json::Value::Null))
+        .unwrap_or_else(|_| String::from("?"))
+}
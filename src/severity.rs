@@ -0,0 +1,63 @@
+use parse;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// How urgent a failure is, as returned by [Executable::severity](trait.Executable.html#method.severity).
+///
+/// The severity is written to the comment and the marker of the generated test, and can put the test in a module named after it with [severity_modules](struct.UnitTestOptions.html#method.severity_modules), so CI can run only the urgent reproductions with e.g. `cargo test critical::`. Severities are ordered from `Low` to `Critical`.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub enum Severity {
+    /// A cosmetic or unlikely failure.
+    Low,
+    /// A regular failure. This is the default, and is not written to the generated test.
+    #[default]
+    Medium,
+    /// A failure that breaks an important feature.
+    High,
+    /// A failure that has to be fixed right away, like data loss or a crash in production.
+    Critical,
+}
+
+impl Severity {
+    /// The lowercase name of the severity, like `critical`, which is used in comments, markers and module names.
+    pub fn name(self) -> &'static str {
+        match self {
+            Severity::Low => "low",
+            Severity::Medium => "medium",
+            Severity::High => "high",
+            Severity::Critical => "critical",
+        }
+    }
+
+    /// Parse the [name](#method.name) of a severity.
+    pub fn from_name(name: &str) -> Option<Severity> {
+        match name {
+            "low" => Some(Severity::Low),
+            "medium" => Some(Severity::Medium),
+            "high" => Some(Severity::High),
+            "critical" => Some(Severity::Critical),
+            _ => None,
+        }
+    }
+}
+
+/// List the ids of the generated tests in `file` that have at least the given severity, in the order of the file.
+///
+/// The severity is read from the marker comment. Tests without a severity, including tests that were generated before severities were recorded, count as [Medium](enum.Severity.html#variant.Medium).
+///
+/// ```rust,ignore
+/// for id in exceptional::tests_with_severity("src/regressions.rs", Severity::High)? {
+///     println!("test_{} needs attention", id);
+/// }
+/// ```
+pub fn tests_with_severity(file: impl AsRef<Path>, min: Severity) -> io::Result<Vec<String>> {
+    let src = fs::read_to_string(file)?;
+    Ok(parse::find_items(&src)
+        .into_iter()
+        .filter(|item| item.marker.severity >= min)
+        .map(|item| item.marker.id)
+        .collect())
+}
@@ -0,0 +1,77 @@
+use parse;
+use render::Snapshot;
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The suffix of the file that holds the serialized executable of a test.
+const EXECUTABLE_SUFFIX: &str = ".exec.json";
+
+/// The suffix of the file that holds the serialized arguments of a test.
+const ARGUMENTS_SUFFIX: &str = ".args.json";
+
+/// The names of the sidecar files of the test with the given id, relative to the sidecar directory.
+pub(crate) fn file_names(id: &str) -> (String, String) {
+    (
+        format!("{}{}", id, EXECUTABLE_SUFFIX),
+        format!("{}{}", id, ARGUMENTS_SUFFIX),
+    )
+}
+
+/// The directory that holds the sidecar files of the tests in `test_file`. `dir` is relative to the directory of `test_file`, like the paths in `include_str!`.
+fn directory(test_file: &Path, dir: &str) -> PathBuf {
+    test_file
+        .parent()
+        .unwrap_or_else(|| Path::new(""))
+        .join(dir)
+}
+
+/// Write the serialized executable and arguments of `snapshot` next to `test_file`, creating the sidecar directory if needed.
+pub(crate) fn write(test_file: &Path, dir: &str, snapshot: &Snapshot) -> io::Result<()> {
+    let dir = directory(test_file, dir);
+    fs::create_dir_all(&dir)?;
+    let (executable, arguments) = file_names(&snapshot.id);
+    fs::write(dir.join(executable), &snapshot.executable_json)?;
+    fs::write(dir.join(arguments), &snapshot.arguments_json)
+}
+
+/// Remove the sidecar files of tests that are no longer in `test_file`, e.g. after tests were deleted or replaced by an [upsert](struct.UnitTestOptions.html#method.upsert).
+///
+/// `dir` is the [sidecar directory](struct.UnitTestOptions.html#method.sidecar_dir) as it was configured, relative to the directory of `test_file`. Only files named `<id>.exec.json` and `<id>.args.json` are considered, and those whose id is not in a marker comment of `test_file` are removed. Other files in the directory are left alone.
+///
+/// Returns the paths of the files that were removed.
+pub fn prune_sidecars(test_file: impl AsRef<Path>, dir: &str) -> io::Result<Vec<PathBuf>> {
+    let test_file = test_file.as_ref();
+    let src = fs::read_to_string(test_file)?;
+    let ids: HashSet<String> = parse::find_items(&src)
+        .into_iter()
+        .map(|item| item.marker.id)
+        .collect();
+
+    let dir = directory(test_file, dir);
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut removed = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        let name = match path.file_name().and_then(|name| name.to_str()) {
+            Some(name) => name,
+            None => continue,
+        };
+        let id = name
+            .strip_suffix(EXECUTABLE_SUFFIX)
+            .or_else(|| name.strip_suffix(ARGUMENTS_SUFFIX));
+        if let Some(id) = id {
+            if !ids.contains(id) {
+                fs::remove_file(&path)?;
+                removed.push(path);
+            }
+        }
+    }
+    removed.sort();
+    Ok(removed)
+}
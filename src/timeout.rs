@@ -0,0 +1,26 @@
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+/// Run `f` on a new thread, and panic if it does not finish within `timeout`. Generated tests call this if a [timeout](struct.UnitTestOptions.html#method.timeout) is configured.
+///
+/// A panic in `f`, like a failed assertion, is resumed on the calling thread, so the test fails with the original message. After a timeout the thread is left running, because Rust threads can't be cancelled.
+pub fn run_with_timeout<F: FnOnce() + Send + 'static>(timeout: Duration, f: F) {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let result = panic::catch_unwind(AssertUnwindSafe(f));
+        // The receiver is gone if the timeout expired already.
+        let _ = sender.send(result);
+    });
+    match receiver.recv_timeout(timeout) {
+        Ok(Ok(())) => {}
+        Ok(Err(payload)) => panic::resume_unwind(payload),
+        Err(RecvTimeoutError::Timeout) => {
+            panic!("the executable did not finish within {:?}", timeout)
+        }
+        Err(RecvTimeoutError::Disconnected) => {
+            panic!("the thread of the executable stopped without a result")
+        }
+    }
+}
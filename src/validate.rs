@@ -0,0 +1,85 @@
+use error::RenderError;
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::Path;
+use {Executable, UnitTest};
+
+/// The reason why a generated test failed [validation](struct.UnitTest.html#method.validate).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationError {
+    /// The test could not be rendered.
+    Render(RenderError),
+
+    /// The [full path](trait.Executable.html#tymethod.full_path) or the [arguments path](trait.Executable.html#method.arguments_path) of the executable is not a Rust path.
+    InvalidPath {
+        /// The path as it is written in the generated test
+        path: String,
+
+        /// The message of the parser
+        message: String,
+    },
+
+    /// The rendered test is not valid Rust syntax.
+    Syntax(String),
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ValidationError::Render(e) => write!(fmt, "could not render the test: {}", e),
+            ValidationError::InvalidPath { path, message } => {
+                write!(fmt, "{:?} is not a valid path: {}", path, message)
+            }
+            ValidationError::Syntax(message) => {
+                write!(fmt, "the generated test does not parse: {}", message)
+            }
+        }
+    }
+}
+
+impl Error for ValidationError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ValidationError::Render(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<RenderError> for ValidationError {
+    fn from(e: RenderError) -> Self {
+        ValidationError::Render(e)
+    }
+}
+
+impl<'a, E: Executable + 'a> UnitTest<'a, E> {
+    /// Check that the generated test is valid Rust before it is written, so a bad [full_path](trait.Executable.html#tymethod.full_path) or an escaping bug is found when the failure is captured, instead of by `cargo test` in a file that has accumulated more tests since.
+    ///
+    /// The paths of the executable and its arguments have to parse as Rust paths, as they are written in the test, and the rendered test has to parse as a Rust file, with [syn](https://docs.rs/syn). This only checks the syntax: a path that doesn't name an existing type, or code from a hook that doesn't type check, is still only found by the compiler. This needs the `validate` feature.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let paths = Some(self.executable.full_path())
+            .into_iter()
+            .chain(self.executable.arguments_path());
+        for path in paths {
+            let path = self.type_path(path);
+            if let Err(e) = syn::parse_str::<syn::Path>(&path) {
+                return Err(ValidationError::InvalidPath {
+                    path: path.into_owned(),
+                    message: e.to_string(),
+                });
+            }
+        }
+        let source = self.try_to_test_source()?;
+        syn::parse_file(&source)
+            .map(|_| ())
+            .map_err(|e| ValidationError::Syntax(e.to_string()))
+    }
+
+    /// Like [append_to_file](#method.append_to_file), but only if the test passes [validation](#method.validate). A test that doesn't is an error of kind `InvalidData`, with the [ValidationError](enum.ValidationError.html) as its inner error, and the file is not touched.
+    pub fn append_to_file_checked(self, file: impl AsRef<Path>) -> io::Result<()> {
+        self.validate()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        self.append_to_file(file)
+    }
+}
@@ -0,0 +1,462 @@
+use error::RenderError;
+use file::LockedFile;
+use marker_index::MarkerIndex;
+use options::LineEnding;
+use parse;
+use render::{self, Snapshot};
+use sidecar;
+use std::collections::HashSet;
+use std::fs::OpenOptions;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use {Executable, UnitTest};
+
+/// An in-memory buffer of generated tests for any number of different [Executable](trait.Executable.html) types.
+///
+/// A `Vec<UnitTest<E>>` can only hold failures of a single executable type. This writer renders every test as soon as it is pushed, so tests of different types can be interleaved in one ordered buffer and written to a file at once.
+///
+/// ```rust,no_run
+/// # extern crate exceptional;
+/// # fn run<A: exceptional::Executable, B: exceptional::Executable>(a: &mut A, a_args: &A::Arguments, b: &mut B, b_args: &B::Arguments) {
+/// let mut writer = exceptional::TestFileWriter::new();
+/// if let Err(test) = exceptional::execute(a, a_args) {
+///     writer.push(test);
+/// }
+/// if let Err(test) = exceptional::execute(b, b_args) {
+///     writer.push(test);
+/// }
+/// writer.flush_to_file("src/test.rs").expect("Could not write unit tests");
+/// # }
+/// # fn main() {}
+/// ```
+#[derive(Debug, Default)]
+pub struct TestFileWriter {
+    buffer: String,
+    /// The tests in the buffer
+    tests: Vec<PendingTest>,
+    /// The ids of the tests in the buffer
+    ids: HashSet<String>,
+}
+
+impl TestFileWriter {
+    /// Create an empty writer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an empty writer with room for `bytes` bytes of rendered tests, so a large batch doesn't have to grow the buffer repeatedly. The buffer keeps its capacity when it is flushed.
+    pub fn with_capacity(bytes: usize) -> Self {
+        TestFileWriter {
+            buffer: String::with_capacity(bytes),
+            ..Self::default()
+        }
+    }
+
+    /// Render the given test and add it to the end of the buffer.
+    ///
+    /// A test with the same id as a test that is already in the buffer, e.g. because both failed in the same millisecond, gets a [unique id](struct.UnitTest.html#method.unique_id).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the test can not be rendered, like [to_test_source](struct.UnitTest.html#method.to_test_source).
+    pub fn push<E: Executable>(&mut self, test: UnitTest<E>) {
+        let pending = PendingTest::new(&test, &self.ids).unwrap_or_else(|e| panic!("{}", e));
+        self.buffer.push_str(&pending.source);
+        self.ids.insert(pending.snapshot.id.clone());
+        self.tests.push(pending);
+    }
+
+    /// The tests that have been pushed since the last flush.
+    pub fn as_str(&self) -> &str {
+        &self.buffer
+    }
+
+    /// Returns `true` if no tests have been pushed since the last flush.
+    pub fn is_empty(&self) -> bool {
+        self.buffer.is_empty()
+    }
+
+    /// Append all buffered tests to a file and clear the buffer.
+    ///
+    /// A test whose id is already taken by a test in the file gets a [unique id](struct.UnitTest.html#method.unique_id) in the file. [Sidecar files](struct.UnitTestOptions.html#method.sidecar_dir) are written relative to the file, with the ids of the tests in it. If writing fails, the buffer is left untouched so the flush can be retried. The file is locked the same way as in [append_to_file](struct.UnitTest.html#method.append_to_file), and the ids in the file are read under that lock.
+    pub fn flush_to_file(&mut self, file: impl AsRef<Path>) -> io::Result<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        write_pending(file.as_ref(), &self.tests)?;
+        self.buffer.clear();
+        self.tests.clear();
+        self.ids.clear();
+        Ok(())
+    }
+}
+
+/// The text of a generated test in place of its id while it is waiting to be written, so it can still be renamed. It is a valid id, so the rendered test is the same as for any other id, and its upper case form is replaced as well, for the names of [hoisted inputs](struct.UnitTestOptions.html#method.hoist_inputs).
+const ID_PLACEHOLDER: &str = "exceptional_pending_id";
+
+/// A test that was rendered when it was pushed to a [TestFileWriter](struct.TestFileWriter.html) or a [TestSuite](struct.TestSuite.html), and can still get another id when it is written, if its id is taken in the file by then.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct PendingTest {
+    /// The snapshot of the test, with the id that it was rendered with
+    snapshot: Snapshot,
+    /// The id that the test asked for, before it was made unique
+    requested_id: String,
+    /// The rendered test, with `ID_PLACEHOLDER` instead of its id
+    template: String,
+    /// The rendered test
+    source: String,
+    /// The [sidecar directory](struct.UnitTestOptions.html#method.sidecar_dir) of the test
+    sidecar_dir: Option<String>,
+}
+
+impl PendingTest {
+    /// Render `test` with an id that is not `taken`.
+    fn new<E: Executable>(
+        test: &UnitTest<E>,
+        taken: &HashSet<String>,
+    ) -> Result<Self, RenderError> {
+        let mut snapshot = test.snapshot()?;
+        let requested_id = snapshot.id.clone();
+        let id = render::unique_id(&snapshot, taken);
+        snapshot.id = ID_PLACEHOLDER.to_owned();
+        let mut template = String::new();
+        test.render_snapshot_into(&mut template, &snapshot);
+        snapshot.id = id;
+        let source = fill_in_id(&template, &snapshot.id);
+        Ok(PendingTest {
+            snapshot,
+            requested_id,
+            template,
+            source,
+            sidecar_dir: test.options.sidecar_dir.clone(),
+        })
+    }
+
+    /// This test with an id that is not `taken`.
+    fn renamed(&self, taken: &HashSet<String>) -> Self {
+        let id = render::unique_id_for(&self.requested_id, self.snapshot.payload_hash(), taken);
+        if id == self.snapshot.id {
+            return self.clone();
+        }
+        let mut renamed = self.clone();
+        renamed.source = fill_in_id(&self.template, &id);
+        renamed.snapshot.id = id;
+        renamed
+    }
+}
+
+/// Replace the placeholder in a test that was rendered with `ID_PLACEHOLDER` by `id`.
+fn fill_in_id(template: &str, id: &str) -> String {
+    template
+        .replace(ID_PLACEHOLDER, id)
+        .replace(&ID_PLACEHOLDER.to_uppercase(), &id.to_uppercase())
+}
+
+/// Append `tests` to `file`, renaming the ones whose ids are taken by tests in the file, and write their sidecar files relative to `file`. The ids are read under the lock of the file, so they can't be taken by another process before the tests are written.
+fn write_pending(file: &Path, tests: &[PendingTest]) -> io::Result<()> {
+    ::file::try_edit(file, false, |existing| {
+        let mut taken: HashSet<String> = parse::parse_markers(existing)
+            .into_iter()
+            .map(|marker| marker.id)
+            .collect();
+        let mut edited = existing.to_owned();
+        for test in tests {
+            let test = test.renamed(&taken);
+            if let Some(ref dir) = test.sidecar_dir {
+                sidecar::write(file, dir, &test.snapshot)?;
+            }
+            edited.push_str(&test.source);
+            taken.insert(test.snapshot.id);
+        }
+        Ok(edited)
+    })
+}
+
+/// The rendered sources of many generated tests, kept separately so they can be inspected before they are written to a file at once.
+///
+/// Like a [TestFileWriter](struct.TestFileWriter.html), a suite renders every test when it is pushed, so it can hold tests of different executable types, and a push is only an in-memory operation. Unlike the writer, it keeps one string per test, and [writing](#method.write_to_file) doesn't clear it.
+///
+/// ```rust,no_run
+/// # extern crate exceptional;
+/// # fn run<E: exceptional::Executable>(executable: &mut E, inputs: &[E::Arguments]) -> std::io::Result<()> {
+/// let mut suite = exceptional::TestSuite::new();
+/// for input in inputs {
+///     if let Err(test) = exceptional::execute(executable, input) {
+///         suite.push(test);
+///     }
+/// }
+/// println!("{} tests, the first one is:\n{:?}", suite.len(), suite.iter().next());
+/// suite.write_to_file("src/regressions.rs")?;
+/// # Ok(())
+/// # }
+/// # fn main() {}
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TestSuite {
+    tests: Vec<PendingTest>,
+    /// The ids of the tests in the suite
+    ids: HashSet<String>,
+}
+
+impl TestSuite {
+    /// Create an empty suite.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Render the given test and add it to the end of the suite.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the test can not be rendered, like [to_test_source](struct.UnitTest.html#method.to_test_source). Use [try_push](#method.try_push) to handle that as an error.
+    pub fn push<E: Executable>(&mut self, test: UnitTest<E>) {
+        if let Err(e) = self.try_push(test) {
+            panic!("{}", e);
+        }
+    }
+
+    /// Like [push](#method.push), but returns the error if the test can not be rendered, and leaves the suite unchanged.
+    ///
+    /// A test with the same id as a test that is already in the suite gets a [unique id](struct.UnitTest.html#method.unique_id), like in a [TestFileWriter](struct.TestFileWriter.html#method.push).
+    pub fn try_push<E: Executable>(&mut self, test: UnitTest<E>) -> Result<(), RenderError> {
+        let pending = PendingTest::new(&test, &self.ids)?;
+        self.ids.insert(pending.snapshot.id.clone());
+        self.tests.push(pending);
+        Ok(())
+    }
+
+    /// The number of tests in the suite.
+    pub fn len(&self) -> usize {
+        self.tests.len()
+    }
+
+    /// Returns `true` if the suite contains no tests.
+    pub fn is_empty(&self) -> bool {
+        self.tests.is_empty()
+    }
+
+    /// The rendered sources of the tests, in the order they were pushed.
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.tests.iter().map(|test| test.source.as_str())
+    }
+
+    /// Append all tests to a file, with a single write. The file is locked the same way as in [append_to_file](struct.UnitTest.html#method.append_to_file), and the suite is kept, so it can be written to several files.
+    ///
+    /// A test whose id is already taken by a test in the file gets a [unique id](struct.UnitTest.html#method.unique_id) in that file, and sidecar files are written relative to it, like in [TestFileWriter::flush_to_file](struct.TestFileWriter.html#method.flush_to_file). The tests in the suite keep their ids.
+    pub fn write_to_file(&self, file: impl AsRef<Path>) -> io::Result<()> {
+        if self.tests.is_empty() {
+            return Ok(());
+        }
+        write_pending(file.as_ref(), &self.tests)
+    }
+}
+
+/// Writes generated tests to a file as soon as they are recorded, for long-running generators that should not hold failures in memory.
+///
+/// The file stays open and, with the `locking` feature, exclusively locked for the lifetime of the writer. Every [record](#method.record) writes one complete test, so the file is in a compilable state between calls, even if the process is killed.
+///
+/// Tests are deduplicated by the hash of their serialized executable and arguments, or by another [DedupKey](enum.DedupKey.html) with [record_by](#method.record_by). The sets of known hashes are seeded from the marker comments already in the file, so restarting a run does not write the same failures again. Only the hashes are kept in memory.
+///
+/// Tests can be grouped under headers with [begin_section](#method.begin_section). [Sidecar files](struct.UnitTestOptions.html#method.sidecar_dir) are written relative to the file, before the test that refers to them.
+pub struct StreamingWriter {
+    file: LockedFile,
+    /// The path that the file was opened with, for the sidecar files
+    path: PathBuf,
+    /// The hashes of the written tests
+    index: MarkerIndex,
+    /// The ids of the written tests
+    ids: HashSet<String>,
+    /// The section that recorded tests belong to
+    section: Option<String>,
+    /// The section that the end of the file is in
+    written_section: Option<String>,
+    /// The line ending that most lines of the file ended with when it was opened
+    line_ending: Option<LineEnding>,
+}
+
+/// What makes two tests the same for [StreamingWriter::record_by](struct.StreamingWriter.html#method.record_by).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum DedupKey {
+    /// The serialized executable and arguments, and the error. A failure with known state and arguments is only written again if it fails in a different way.
+    FullSnapshot,
+    /// Only the `Debug` output of the error, so the file keeps one representative reproduction per distinct error, however many states and arguments run into it. This is meant for triage, when a single bug shows up for thousands of inputs.
+    ErrorOnly,
+    /// The serialized executable and arguments. This is the default, and what [record](struct.StreamingWriter.html#method.record) uses.
+    #[default]
+    ExecutableAndArgs,
+}
+
+/// The start of every section header line.
+const SECTION_PREFIX: &str = "// ===== ";
+
+/// The end of every section header line.
+const SECTION_SUFFIX: &str = " =====";
+
+/// The start of the title of the line that closes a section.
+const SECTION_END: &str = "end of ";
+
+/// Find the section that the end of `src` is in, from the last section header or end line.
+fn last_section(src: &str) -> Option<String> {
+    let title = src.lines().rev().find_map(|line| {
+        line.strip_prefix(SECTION_PREFIX)?
+            .strip_suffix(SECTION_SUFFIX)
+    })?;
+    if title.starts_with(SECTION_END) {
+        None
+    } else {
+        Some(title.to_owned())
+    }
+}
+
+impl StreamingWriter {
+    /// Open (or create) the given file for appending, and read the hashes of the tests it already contains.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref().to_owned();
+        let file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(&path)?;
+        let mut file = LockedFile::acquire(file)?;
+        let mut existing = String::new();
+        file.read_to_string(&mut existing)?;
+        Ok(StreamingWriter {
+            file,
+            path,
+            index: MarkerIndex::from_source(&existing),
+            ids: parse::parse_markers(&existing)
+                .into_iter()
+                .map(|marker| marker.id)
+                .collect(),
+            section: None,
+            written_section: last_section(&existing),
+            line_ending: LineEnding::detect(&existing),
+        })
+    }
+
+    /// Render the given test and write it to the file immediately.
+    ///
+    /// Returns `false` without writing anything if a test with the same executable and arguments is already in the file. The file is synced to disk after the write if [sync_on_write](struct.UnitTestOptions.html#method.sync_on_write) is enabled for the test. If the test can not be rendered, the [RenderError](enum.RenderError.html) is returned as an error of kind `InvalidData`.
+    pub fn record<E: Executable>(&mut self, test: UnitTest<E>) -> io::Result<bool> {
+        self.record_by(test, DedupKey::ExecutableAndArgs)
+    }
+
+    /// Like [record](#method.record), but with the given [DedupKey](enum.DedupKey.html) instead of the serialized executable and arguments.
+    ///
+    /// The key only decides whether this test is written. Every written test is remembered by all keys, so writers can mix keys between calls:
+    ///
+    /// ```rust,no_run
+    /// # extern crate exceptional;
+    /// # fn run<E: exceptional::Executable>(test: exceptional::UnitTest<E>) -> std::io::Result<()> {
+    /// let mut writer = exceptional::StreamingWriter::open("src/triage.rs")?;
+    /// writer.record_by(test, exceptional::DedupKey::ErrorOnly)?;
+    /// # Ok(())
+    /// # }
+    /// # fn main() {}
+    /// ```
+    ///
+    /// Tests that were generated by older versions don't record their error in the marker, so they are not known by their error.
+    pub fn record_by<E: Executable>(
+        &mut self,
+        test: UnitTest<E>,
+        key: DedupKey,
+    ) -> io::Result<bool> {
+        self.record_ref(&test, key)
+    }
+
+    /// Like [record_by](#method.record_by), but without taking the test, so its error can still be returned.
+    pub(crate) fn record_ref<E: Executable>(
+        &mut self,
+        test: &UnitTest<E>,
+        key: DedupKey,
+    ) -> io::Result<bool> {
+        let mut snapshot = test
+            .snapshot()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let payload = snapshot.hash.clone();
+        let error = snapshot.error_hash.clone();
+        if self.index.contains(key, &payload, &error) {
+            return Ok(false);
+        }
+        snapshot.id = render::unique_id(&snapshot, &self.ids);
+        if let Some(ref dir) = test.options.sidecar_dir {
+            sidecar::write(&self.path, dir, &snapshot)?;
+        }
+        let mut text = self.section_lines();
+        test.write_test(&mut text, &snapshot)
+            .expect("Writing to a String cannot fail");
+        if let Some(ending) = test.options.line_ending.or(self.line_ending) {
+            text = ending.apply(&text).into_owned();
+        }
+        self.file.write_all(text.as_bytes())?;
+        self.written_section = self.section.clone();
+        self.file.flush()?;
+        if test.options.sync_on_write {
+            self.file.sync_all()?;
+        }
+        self.index.insert(payload, error);
+        self.ids.insert(snapshot.id);
+        Ok(true)
+    }
+
+    /// Group the tests that are recorded from now on under a header line:
+    ///
+    /// ```text
+    /// // ===== nightly fuzzing run =====
+    /// ```
+    ///
+    /// The header is written right before the first new test of the section, so a section of only known failures leaves the file unchanged. If the file already ends in a section with the same title, e.g. because the writer was opened again for the same run, new tests are appended to it without writing the header again. A section that is followed by other tests is closed with a `// ===== end of <title> =====` line. A previous section is ended first.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `title` is empty or spans multiple lines.
+    pub fn begin_section(&mut self, title: &str) {
+        assert!(
+            !title.trim().is_empty() && !title.contains('\n'),
+            "{:?} is not a valid section title",
+            title
+        );
+        self.section = Some(title.trim().to_owned());
+    }
+
+    /// Stop grouping recorded tests under the current [section](#method.begin_section). Tests that are recorded afterwards are written after the end line of the section.
+    pub fn end_section(&mut self) {
+        self.section = None;
+    }
+
+    /// The lines that end the section of the file and start the current section, if they differ.
+    fn section_lines(&self) -> String {
+        let mut lines = String::new();
+        if self.written_section == self.section {
+            return lines;
+        }
+        if let Some(ref written) = self.written_section {
+            lines.push_str(&format!(
+                "{}{}{}{}\n",
+                SECTION_PREFIX, SECTION_END, written, SECTION_SUFFIX
+            ));
+        }
+        if let Some(ref section) = self.section {
+            lines.push_str(&format!(
+                "{}{}{}\n",
+                SECTION_PREFIX, section, SECTION_SUFFIX
+            ));
+        }
+        lines
+    }
+
+    /// The number of distinct tests in the file, including the ones that were there when it was opened.
+    pub fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    /// Returns `true` if the file contains no generated tests.
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+
+    /// The hashes of the tests in the file, including the ones that were written by this writer.
+    pub fn index(&self) -> &MarkerIndex {
+        &self.index
+    }
+}
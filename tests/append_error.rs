@@ -0,0 +1,128 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use common::{scratch_dir, SomeImportantAction};
+use exceptional::{AppendError, Mode, OwnedUnitTest, RenderError, UnitTestOptions};
+use std::fs::{self, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+
+fn failure(options: UnitTestOptions) -> OwnedUnitTest<SomeImportantAction> {
+    let action = SomeImportantAction { var_1: 0, var_2: 1 };
+    exceptional::execute_all(&action, vec![(2, 3)], Mode::FirstFailure)
+        .pop()
+        .unwrap()
+        .with_options(options)
+}
+
+/// Create a read-only file in a fresh scratch directory. Returns `None` if the permissions are not enforced, e.g. when the tests run as root.
+fn read_only_file(name: &str) -> Option<(PathBuf, PathBuf)> {
+    let dir = scratch_dir(name);
+    let file = dir.join("tests.rs");
+    fs::write(&file, "").unwrap();
+    let mut permissions = fs::metadata(&file).unwrap().permissions();
+    permissions.set_readonly(true);
+    fs::set_permissions(&file, permissions).unwrap();
+    if OpenOptions::new().append(true).open(&file).is_ok() {
+        return None;
+    }
+    Some((dir, file))
+}
+
+#[test]
+fn written_file_is_returned() {
+    let dir = scratch_dir("append_error_written");
+    let file = dir.join("tests.rs");
+    let written = failure(UnitTestOptions::default())
+        .try_append_to_file(&file)
+        .unwrap();
+    assert_eq!(written, file);
+    assert!(fs::read_to_string(&file).unwrap().contains("#[test]"));
+}
+
+#[test]
+fn read_only_file_is_reported() {
+    let (_dir, file) = match read_only_file("append_error_read_only") {
+        Some(paths) => paths,
+        None => return,
+    };
+    match failure(UnitTestOptions::default()).try_append_to_file(&file) {
+        Err(AppendError::ReadOnly { path, .. }) => assert_eq!(path, file),
+        other => panic!("expected ReadOnly, got {:?}", other),
+    }
+    let error = failure(UnitTestOptions::default())
+        .append_to_file(&file)
+        .unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::PermissionDenied);
+}
+
+#[test]
+fn read_only_file_falls_back() {
+    let (dir, file) = match read_only_file("append_error_fallback") {
+        Some(paths) => paths,
+        None => return,
+    };
+    let fallback = dir.join("fallback");
+    let options = UnitTestOptions::default().fallback_dir(fallback.to_str().unwrap());
+    let written = failure(options.clone()).try_append_to_file(&file).unwrap();
+    assert_eq!(written, fallback.join("tests.rs"));
+    assert!(fs::read_to_string(&written).unwrap().contains("#[test]"));
+    assert_eq!(fs::read_to_string(&file).unwrap(), "");
+
+    failure(options).append_to_file(&file).unwrap();
+    assert_eq!(
+        fs::read_to_string(&written)
+            .unwrap()
+            .matches("#[test]")
+            .count(),
+        2
+    );
+}
+
+#[test]
+fn other_errors_do_not_fall_back() {
+    let dir = scratch_dir("append_error_missing_dir");
+    let file = dir.join("missing").join("tests.rs");
+    let fallback = dir.join("fallback");
+    let options = UnitTestOptions::default().fallback_dir(fallback.to_str().unwrap());
+    match failure(options).try_append_to_file(&file) {
+        Err(AppendError::Io { path, source }) => {
+            assert_eq!(path, file);
+            assert_eq!(source.kind(), io::ErrorKind::NotFound);
+        }
+        other => panic!("expected Io, got {:?}", other),
+    }
+    assert!(!Path::new(&fallback).exists());
+}
+
+#[test]
+fn render_errors_are_returned() {
+    let dir = scratch_dir("append_error_render");
+    let file = dir.join("tests.rs");
+    let options = UnitTestOptions::default().id_strategy(|_| String::from("not an id"));
+    match failure(options).try_append_to_file(&file) {
+        Err(AppendError::Render(RenderError::InvalidId(id))) => assert_eq!(id, "not an id"),
+        other => panic!("expected a render error, got {:?}", other),
+    }
+    assert!(!file.exists());
+}
+
+#[test]
+fn a_borrowed_test_can_be_written_to_several_files() {
+    let dir = scratch_dir("append_to_file_ref");
+    let test = failure(UnitTestOptions::default());
+    test.append_to_file_ref(dir.join("first.rs")).unwrap();
+    test.append_to_file_ref(dir.join("second.rs")).unwrap();
+    assert_eq!(
+        fs::read_to_string(dir.join("first.rs")).unwrap(),
+        fs::read_to_string(dir.join("second.rs")).unwrap()
+    );
+
+    // The test is still available afterwards.
+    assert_eq!(test.error_ref(), "Whoopsie");
+    let report = Result::from(&test).unwrap();
+    assert_eq!(report.description, "Executes some very important action!");
+}
@@ -0,0 +1,65 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use common::{scratch_dir, OtherAction, SomeImportantAction};
+use exceptional::UnitTestOptions;
+use std::fs;
+
+const IMPORTS: &str = "    #[allow(unused_imports)]\n    use super::*;\n    #[allow(unused_imports)]\n    use exceptional::Executable;\n\n";
+
+#[test]
+fn a_missing_file_gets_the_module_with_its_imports() {
+    let file = scratch_dir("append_to_module").join("regressions.rs");
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+    exceptional::execute(&mut action, &(2, 3))
+        .unwrap_err()
+        .append_to_module(&file, "regressions")
+        .unwrap();
+    let mut other = OtherAction {
+        message: String::from("nope"),
+    };
+    exceptional::execute(&mut other, &())
+        .unwrap_err()
+        .append_to_module(&file, "regressions")
+        .unwrap();
+
+    let contents = fs::read_to_string(&file).unwrap();
+    let start = "/// Regression tests that were generated from failures.\n#[allow(missing_docs)]\nmod regressions {\n";
+    assert!(contents.starts_with(&format!("{}{}    // @exceptional ", start, IMPORTS)));
+    assert!(contents.ends_with("    }\n}\n"));
+    assert_eq!(contents.matches("use exceptional::Executable;").count(), 1);
+    assert_eq!(contents.matches("mod regressions").count(), 1);
+    assert_eq!(exceptional::parse_markers(&contents).len(), 2);
+}
+
+#[test]
+fn an_existing_module_gets_the_missing_imports_once() {
+    let file = scratch_dir("append_to_module_existing").join("lib.rs");
+    let before = "fn before() {}\n\nmod regressions {\n    use super::*;\n\n    #[test]\n    fn existing() {\n        use exceptional::Executable;\n    }\n}\n";
+    fs::write(&file, before).unwrap();
+    for var_1 in 0..2 {
+        let mut action = SomeImportantAction { var_1, var_2: 1 };
+        exceptional::execute(&mut action, &(2, 3))
+            .unwrap_err()
+            .with_options(UnitTestOptions::default().use_insertion_marker(true))
+            .append_to_module(&file, "regressions")
+            .unwrap();
+    }
+
+    let contents = fs::read_to_string(&file).unwrap();
+    assert!(contents.starts_with("fn before() {}\n\nmod regressions {\n    #[allow(unused_imports)]\n    use exceptional::Executable;\n\n    use super::*;\n\n    #[test]\n    fn existing() {\n"));
+    assert_eq!(
+        contents
+            .matches("    use exceptional::Executable;\n")
+            .count(),
+        2
+    );
+    assert_eq!(contents.matches("use super::*;").count(), 1);
+    // The new tests don't import anything, and are added at the end of the module.
+    assert!(!contents.contains("\tuse exceptional::Executable;"));
+    assert_eq!(exceptional::parse_markers(&contents).len(), 2);
+    assert!(contents.ends_with("    }\n}\n"));
+}
@@ -0,0 +1,86 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+use exceptional::Executable;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct Scale {
+    factor: f64,
+    custom_assertion: bool,
+}
+
+impl Executable for Scale {
+    type Result = f64;
+    type Error = String;
+    type Arguments = f64;
+
+    fn full_path(&self) -> &'static str {
+        "::Scale"
+    }
+
+    fn description(&self) -> String {
+        String::from("Scales a value")
+    }
+
+    fn execute(&mut self, value: &f64) -> Result<f64, String> {
+        if self.factor == 0.0 {
+            Err(String::from("zero factor"))
+        } else {
+            Ok(value * self.factor)
+        }
+    }
+
+    fn assertion(&self) -> Option<String> {
+        if self.custom_assertion {
+            // A blank line, a line of only whitespace and a nested block.
+            Some(String::from(
+                "let result = obj.execute(&args).unwrap();\n\nif result.is_nan() {\n    panic!(\"not a number\");\n}\n   \nassert!((result - 1.0).abs() < 1e-9);",
+            ))
+        } else {
+            None
+        }
+    }
+}
+
+fn render(custom_assertion: bool) -> String {
+    let mut scale = Scale {
+        factor: 0.0,
+        custom_assertion,
+    };
+    exceptional::execute(&mut scale, &2.0)
+        .unwrap_err()
+        .to_string()
+}
+
+#[test]
+fn the_default_check_fails_if_the_executable_fails() {
+    let source = render(false);
+    assert!(
+        source.contains("\tif let Err(e) = obj.execute(&args) {\n"),
+        "{}",
+        source
+    );
+}
+
+#[test]
+fn assertions_replace_the_default_check() {
+    let source = render(true);
+    assert!(!source.contains("if let Err(e)"), "{}", source);
+    assert!(!source.contains("Could not execute"), "{}", source);
+    // Every line is indented one level, inside the test function, and blank lines are not indented.
+    assert!(
+        source.contains(concat!(
+            "\n\tlet result = obj.execute(&args).unwrap();\n",
+            "\n",
+            "\tif result.is_nan() {\n",
+            "\t    panic!(\"not a number\");\n",
+            "\t}\n",
+            "\n",
+            "\tassert!((result - 1.0).abs() < 1e-9);\n",
+            "}\n"
+        )),
+        "{}",
+        source
+    );
+}
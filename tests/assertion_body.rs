@@ -0,0 +1,81 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+use exceptional::{Executable, UnitTestOptions};
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct Divide {
+    dividend: u32,
+    custom_assertion: bool,
+}
+
+impl Executable for Divide {
+    type Result = u32;
+    type Error = String;
+    type Arguments = u32;
+
+    fn full_path(&self) -> &'static str {
+        "::Divide"
+    }
+
+    fn description(&self) -> String {
+        String::from("Divides the dividend")
+    }
+
+    fn execute(&mut self, divisor: &u32) -> Result<u32, String> {
+        self.dividend
+            .checked_div(*divisor)
+            .ok_or_else(|| String::from("division by zero"))
+    }
+
+    fn assertion(&self) -> Option<String> {
+        if self.custom_assertion {
+            Some(String::from("obj.execute(&args).unwrap();"))
+        } else {
+            None
+        }
+    }
+
+    fn assertion_body(&self, result_var: &str) -> Option<String> {
+        Some(format!(
+            "assert_eq!({0}, Err(String::from(\"division by zero\")));\nassert_eq!({0}.unwrap_or_default(), 0);",
+            result_var
+        ))
+    }
+}
+
+fn render(custom_assertion: bool, options: UnitTestOptions) -> String {
+    let mut divide = Divide {
+        dividend: 6,
+        custom_assertion,
+    };
+    exceptional::execute(&mut divide, &0)
+        .unwrap_err()
+        .with_options(options)
+        .to_string()
+}
+
+#[test]
+fn the_result_is_bound_for_the_assertion_body() {
+    let source = render(false, UnitTestOptions::default().obj_binding("divide"));
+    assert!(
+        source.contains("\tlet result = divide.execute(&args);\n\tassert_eq!(result, Err(String::from(\"division by zero\")));\n\tassert_eq!(result.unwrap_or_default(), 0);\n}\n"),
+        "{}",
+        source
+    );
+    assert!(!source.contains("no longer fails"));
+}
+
+#[test]
+fn assertions_take_precedence() {
+    let source = render(true, UnitTestOptions::default());
+    assert!(source.contains("\tobj.execute(&args).unwrap();\n}\n"));
+    assert!(!source.contains("let result"));
+}
+
+#[test]
+#[should_panic(expected = "can not be used as a binding")]
+fn the_result_binding_is_reserved() {
+    let _ = UnitTestOptions::default().args_binding("result");
+}
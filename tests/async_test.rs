@@ -0,0 +1,77 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+use exceptional::{AsyncExecutable, Executable, ExecuteFuture, UnitTestOptions};
+use std::future::{self, Future};
+use std::pin::Pin;
+use std::task::{Context, Poll, Waker};
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct Fetch {
+    id: u32,
+}
+
+impl Executable for Fetch {
+    type Result = String;
+    type Error = String;
+    type Arguments = u32;
+
+    fn full_path(&self) -> &'static str {
+        "::Fetch"
+    }
+
+    fn description(&self) -> String {
+        String::from("Fetches a record")
+    }
+
+    fn execute(&mut self, _retries: &u32) -> Result<String, String> {
+        unreachable!("only executed asynchronously")
+    }
+}
+
+impl AsyncExecutable for Fetch {
+    fn execute_async<'a>(&'a mut self, retries: &'a u32) -> ExecuteFuture<'a, Self> {
+        let result = if *retries == 0 {
+            Err(format!("record {} timed out", self.id))
+        } else {
+            Ok(String::from("record"))
+        };
+        Box::pin(future::ready(result))
+    }
+}
+
+/// Poll a future that is ready immediately.
+fn now<F: Future + Unpin>(mut future: F) -> F::Output {
+    match Pin::new(&mut future).poll(&mut Context::from_waker(Waker::noop())) {
+        Poll::Ready(output) => output,
+        Poll::Pending => panic!("future is not ready"),
+    }
+}
+
+#[test]
+fn async_test_awaits_the_executable() {
+    let mut fetch = Fetch { id: 7 };
+    let line = line!() + 1;
+    let test = now(exceptional::execute_async(&mut fetch, &0)).unwrap_err();
+    assert_eq!(test.error, "record 7 timed out");
+    assert_eq!(test.location.unwrap().line(), line);
+
+    let source = test
+        .with_options(UnitTestOptions::default().async_test("#[tokio::test]"))
+        .to_string();
+    assert!(source.contains("/// exception was \"record 7 timed out\"\n"));
+    assert!(source.contains("#[tokio::test]\npub async fn test_"));
+    assert!(source.contains("\tuse exceptional::{AsyncExecutable, Executable};\n"));
+    assert!(source.contains("\tif let Err(e) = obj.execute_async(&args).await {\n"));
+    assert!(!source.contains("#[test]"));
+}
+
+#[test]
+fn async_success_is_returned() {
+    let mut fetch = Fetch { id: 7 };
+    assert_eq!(
+        now(exceptional::execute_async(&mut fetch, &1)).ok(),
+        Some(String::from("record"))
+    );
+}
@@ -0,0 +1,38 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use common::SomeImportantAction;
+use exceptional::{CommentStyle, UnitTestOptions};
+use std::fs;
+
+#[test]
+fn banner_is_written_once() {
+    let dir = common::scratch_dir("banner");
+    let file = dir.join("generated.rs");
+    fs::write(&file, "// existing content\n").unwrap();
+
+    for _ in 0..2 {
+        let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+        exceptional::execute(&mut action, &(2, 3))
+            .unwrap_err()
+            .with_options(
+                UnitTestOptions::default()
+                    .default_banner()
+                    .comment_style(CommentStyle::Plain),
+            )
+            .append_to_file(&file)
+            .unwrap();
+    }
+
+    let contents = fs::read_to_string(&file).unwrap();
+    assert!(contents
+        .starts_with("//! This file is automatically generated by exceptional.\n//! DO NOT EDIT"));
+    assert_eq!(contents.matches("DO NOT EDIT").count(), 1);
+    assert!(contents.contains("\n// existing content\n"));
+    assert!(!contents.contains("///"));
+
+    let _ = fs::remove_dir_all(&dir);
+}
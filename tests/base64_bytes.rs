@@ -0,0 +1,113 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use common::scratch_dir;
+use exceptional::{Executable, Outcome, UnitTestOptions};
+
+/// An executable that holds binary data.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct Decoder {
+    header: Vec<u8>,
+    chunks: Vec<Vec<u8>>,
+    name: String,
+}
+
+impl Executable for Decoder {
+    type Result = ();
+    type Error = String;
+    type Arguments = u8;
+
+    fn full_path(&self) -> &'static str {
+        "::Decoder"
+    }
+
+    fn description(&self) -> String {
+        String::from("Decodes an image")
+    }
+
+    fn execute(&mut self, version: &u8) -> Result<(), String> {
+        if self.header.starts_with(&[0x89, 0x50]) {
+            Err(format!("version {} is not supported", version))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+fn decoder() -> Decoder {
+    Decoder {
+        header: vec![0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a],
+        chunks: vec![vec![0, 255, 1], vec![7]],
+        name: String::from("[1, 2]"),
+    }
+}
+
+#[test]
+fn byte_arrays_are_embedded_as_base64() {
+    let mut decoder = decoder();
+    let test = exceptional::execute(&mut decoder, &2)
+        .unwrap_err()
+        .with_options(UnitTestOptions::default().base64_bytes(3));
+    let source = test.to_string();
+    let (executable_json, _) = test.snapshots().unwrap();
+
+    assert!(source.contains("\tlet obj_json = &::exceptional::decode_base64_json(r#\"{\n"));
+    assert!(source.contains("\"header\": {\"$base64\": \"iVBORw0KGgo=\"}"));
+    // Shorter arrays, and numbers in strings, are kept.
+    assert!(source.contains("{\"$base64\": \"AP8B\"},\n    [\n      7\n    ]"));
+    assert!(source.contains("\"name\": \"[1, 2]\""));
+    // The arguments are not an array, so they are embedded as is.
+    assert!(source.contains("\tlet arg_json = r#\"2\"#;\n"));
+
+    let start = source.find("r#\"{").unwrap() + 3;
+    let end = start + source[start..].find("\"#").unwrap();
+    assert_eq!(
+        exceptional::decode_base64_json(&source[start..end]),
+        executable_json
+    );
+
+    let plain = exceptional::execute(&mut decoder, &2)
+        .unwrap_err()
+        .to_string();
+    assert!(plain.len() > source.len());
+    assert!(!plain.contains("$base64"));
+}
+
+#[test]
+fn json_without_encoded_arrays_is_decoded_as_is() {
+    let json = "{\n  \"data\": {\"$base64\": \"not base64!\"},\n  \"list\": [\n    1\n  ]\n}";
+    assert_eq!(exceptional::decode_base64_json(json), json);
+}
+
+#[test]
+fn base64_snapshots_can_be_rerun() {
+    let file = scratch_dir("base64_bytes").join("regressions.rs");
+    for &hoist in &[false, true] {
+        let mut decoder = decoder();
+        exceptional::execute(&mut decoder, &2)
+            .unwrap_err()
+            .with_options(
+                UnitTestOptions::default()
+                    .base64_bytes(1)
+                    .hoist_inputs(hoist)
+                    .id_strategy(move |_| format!("hoisted_{}", hoist)),
+            )
+            .append_to_file(&file)
+            .unwrap();
+    }
+    let contents = std::fs::read_to_string(&file).unwrap();
+    assert!(contents.contains("&::exceptional::decode_base64_json(INPUT_HOISTED_TRUE_OBJ);"));
+
+    let outcomes = exceptional::rerun_file::<Decoder>(&file).unwrap();
+    let failure = Outcome::Fails(String::from("\"version 2 is not supported\""));
+    assert_eq!(
+        outcomes,
+        vec![
+            (String::from("hoisted_false"), failure.clone()),
+            (String::from("hoisted_true"), failure),
+        ]
+    );
+}
@@ -0,0 +1,65 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use common::SomeImportantAction;
+use exceptional::UnitTestOptions;
+
+fn failure(options: UnitTestOptions) -> exceptional::OwnedUnitTest<SomeImportantAction> {
+    let action = SomeImportantAction { var_1: 0, var_2: 1 };
+    exceptional::execute_all(&action, vec![(2, 3)], exceptional::Mode::FirstFailure)
+        .pop()
+        .unwrap()
+        .with_options(options)
+}
+
+const CALL: &str = "::std::hint::black_box(obj.execute(::std::hint::black_box(&args)))";
+
+#[test]
+fn the_call_is_passed_through_black_box() {
+    let source = failure(UnitTestOptions::default().black_box(true)).to_string();
+    assert!(source.contains(&format!("\tif let Err(e) = {} {{\n", CALL)));
+
+    let source = failure(
+        UnitTestOptions::default()
+            .black_box(true)
+            .verbose_body(false),
+    )
+    .to_string();
+    assert!(source.contains(&format!("\t{}.expect(\"regression still fails\");\n", CALL)));
+
+    let source = failure(UnitTestOptions::default().black_box(true))
+        .render_as_example()
+        .unwrap();
+    assert!(source.contains(&format!("\tmatch {} {{\n", CALL)));
+}
+
+#[test]
+fn async_calls_are_awaited_inside_black_box() {
+    let source = failure(
+        UnitTestOptions::default()
+            .black_box(true)
+            .async_test("tokio::test"),
+    )
+    .to_string();
+    assert!(source.contains(
+        "::std::hint::black_box(obj.execute_async(::std::hint::black_box(&args)).await)"
+    ));
+}
+
+#[cfg(feature = "criterion")]
+#[test]
+fn benchmarks_pass_the_call_through_black_box() {
+    let source = failure(UnitTestOptions::default().black_box(true))
+        .render_as_benchmark()
+        .unwrap();
+    assert!(source.contains(&format!("\t\t\t|mut obj| {},\n", CALL)));
+}
+
+#[test]
+fn calls_are_not_wrapped_by_default() {
+    let source = failure(UnitTestOptions::default()).to_string();
+    assert!(!source.contains("::std::hint::black_box"));
+}
@@ -0,0 +1,67 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use common::SomeImportantAction;
+use exceptional::UnitTestOptions;
+
+#[test]
+fn quiet_body_has_no_println() {
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+    let args = (2, 3);
+    let source = exceptional::execute(&mut action, &args)
+        .unwrap_err()
+        .with_options(UnitTestOptions::default().verbose_body(false))
+        .to_string();
+
+    assert!(!source.contains("println!"));
+    assert!(source.contains("\tobj.execute(&args).expect(\"regression still fails\");\n"));
+}
+
+#[test]
+fn body_is_verbose_by_default() {
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+    let args = (2, 3);
+    let source = exceptional::execute(&mut action, &args)
+        .unwrap_err()
+        .to_string();
+
+    assert!(source.contains("println!(\"Could not execute {}\", obj.description());"));
+}
+
+#[test]
+fn bindings_are_used_throughout_the_body() {
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+    let args = (2, 3);
+    let source = exceptional::execute(&mut action, &args)
+        .unwrap_err()
+        .with_options(
+            UnitTestOptions::default()
+                .obj_binding("action")
+                .args_binding("input"),
+        )
+        .to_string();
+
+    assert!(source.contains("\tlet mut action: ::common::SomeImportantAction = "));
+    assert!(source.contains("\tlet input = ::serde_json::from_str(arg_json)"));
+    assert!(source.contains("if let Err(e) = action.execute(&input) {"));
+    assert!(source.contains("action.description()"));
+    assert!(!source.contains("obj."));
+    assert!(!source.contains("&args"));
+}
+
+#[test]
+#[should_panic(expected = "can not be used as a binding")]
+fn keyword_bindings_are_rejected() {
+    let _ = UnitTestOptions::default().args_binding("match");
+}
+
+#[test]
+#[should_panic(expected = "can not be used as a binding")]
+fn equal_bindings_are_rejected() {
+    let _ = UnitTestOptions::default()
+        .obj_binding("input")
+        .args_binding("input");
+}
@@ -0,0 +1,60 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+use exceptional::Executable;
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug)]
+struct ConfigError(std::num::ParseIntError);
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("could not parse config")
+    }
+}
+
+impl Error for ConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+struct ParseConfig {
+    value: String,
+}
+
+impl Executable for ParseConfig {
+    type Result = u32;
+    type Error = Box<dyn Error>;
+    type Arguments = ();
+
+    fn full_path(&self) -> &'static str {
+        "::ParseConfig"
+    }
+
+    fn description(&self) -> String {
+        String::from("Parses the config")
+    }
+
+    fn execute(&mut self, _args: &()) -> Result<u32, Box<dyn Error>> {
+        Ok(self.value.parse().map_err(ConfigError)?)
+    }
+}
+
+#[test]
+fn boxed_error_chain_is_embedded() {
+    let mut config = ParseConfig {
+        value: String::from("abc"),
+    };
+    let test = exceptional::execute_boxed_err(&mut config, &()).unwrap_err();
+    let message = "could not parse config: invalid digit found in string";
+    assert_eq!(test.error_message.as_deref(), Some(message));
+
+    let source = test.to_string();
+    assert!(source.contains(&format!("/// exception was {}\n", message)));
+    assert!(source.contains(&format!("assert!(message.contains({:?})", message)));
+    assert!(source.contains("let message = ::exceptional::error_chain(&*e);"));
+}
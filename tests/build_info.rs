@@ -0,0 +1,21 @@
+#![cfg(feature = "build-info")]
+
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use common::SomeImportantAction;
+use exceptional::UnitTestOptions;
+
+#[test]
+fn build_info_is_included_in_header() {
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+    let source = exceptional::execute(&mut action, &(2, 3))
+        .unwrap_err()
+        .with_options(UnitTestOptions::default().include_build_info(true))
+        .to_string();
+    assert!(source.contains("/// rustc: rustc "));
+    assert!(source.contains("/// target: "));
+}
@@ -0,0 +1,62 @@
+#[macro_use]
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use common::SomeImportantAction;
+use exceptional::Executable;
+use std::fs;
+
+/// An operation that isn't `execute`, but fails the same way.
+fn check(args: &(u32, u32)) -> Result<u32, String> {
+    if args.1 == 3 {
+        Err(String::from("Whoopsie"))
+    } else {
+        Ok(args.0 + args.1)
+    }
+}
+
+#[test]
+fn failures_are_appended_and_returned() {
+    let dir = common::scratch_dir("capture");
+    let file = dir.join("generated.rs");
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+
+    let result = capture!(&file, action, "running the action", action.execute((2, 3)));
+    assert_eq!(result, Err(String::from("Whoopsie")));
+    let written = fs::read_to_string(&file).unwrap();
+    assert!(
+        written.contains("/// meta: context = running the action\n"),
+        "{}",
+        written
+    );
+    assert!(written.contains("/// originally failed at tests/capture.rs:"));
+    assert!(written.contains("\"var_1\": 0"));
+
+    let result = capture!(&file, action, "checking", check((2, 3)));
+    assert_eq!(result, Err(String::from("Whoopsie")));
+    assert_eq!(
+        fs::read_to_string(&file)
+            .unwrap()
+            .matches("#[test]")
+            .count(),
+        2
+    );
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn successes_are_passed_through() {
+    let dir = common::scratch_dir("capture_ok");
+    let file = dir.join("generated.rs");
+    let action = SomeImportantAction { var_1: 0, var_2: 1 };
+
+    let sum = capture!(&file, action, "checking", check((2, 4)));
+    assert_eq!(sum, Ok(6));
+    assert!(!file.exists());
+
+    let _ = fs::remove_dir_all(&dir);
+}
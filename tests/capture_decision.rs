@@ -0,0 +1,112 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+use exceptional::{CaptureDecision, Executable, ExecuteError, ExecuteOptions};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[derive(Serialize, Deserialize, Clone)]
+struct Lookup {
+    key: String,
+}
+
+impl Executable for Lookup {
+    type Result = ();
+    type Error = String;
+    type Arguments = ();
+
+    fn full_path(&self) -> &'static str {
+        "::Lookup"
+    }
+
+    fn description(&self) -> String {
+        String::from("Looks up a key")
+    }
+
+    fn execute(&mut self, _args: &()) -> Result<(), String> {
+        Err(format!("no {}", self.key))
+    }
+
+    fn capture_decision(&self, error: &String) -> CaptureDecision {
+        match self.key.as_str() {
+            "missing" => CaptureDecision::Skip {
+                reason: Some(String::from("expected")),
+            },
+            "plain" => CaptureDecision::Capture { reason: None },
+            _ => CaptureDecision::Capture {
+                reason: Some(format!("{} is corrupt\nsomehow", error)),
+            },
+        }
+    }
+}
+
+/// Returns whether a unit test was generated for looking up `key`.
+fn captured(key: &str, options: &ExecuteOptions<Lookup>) -> bool {
+    let mut lookup = Lookup {
+        key: key.to_owned(),
+    };
+    let error = exceptional::execute_with_options(&mut lookup, &(), options).unwrap_err();
+    matches!(error, ExecuteError::Captured(_))
+}
+
+#[test]
+fn the_reason_of_a_capture_is_written_to_the_header() {
+    let mut lookup = Lookup {
+        key: String::from("corrupt"),
+    };
+    let options = ExecuteOptions::default();
+    let test = exceptional::execute_with_options(&mut lookup, &(), &options)
+        .unwrap_err()
+        .unit_test()
+        .unwrap();
+    assert_eq!(
+        test.capture_reason.as_ref().unwrap(),
+        "no corrupt is corrupt\nsomehow"
+    );
+    assert!(test
+        .to_string()
+        .contains("\n/// captured because: no corrupt is corrupt\\nsomehow\n"));
+
+    let mut lookup = Lookup {
+        key: String::from("plain"),
+    };
+    let test = exceptional::execute_with_options(&mut lookup, &(), &options)
+        .unwrap_err()
+        .unit_test()
+        .unwrap();
+    assert_eq!(test.capture_reason, None);
+    assert!(!test.to_string().contains("captured because"));
+}
+
+#[test]
+fn skipped_errors_are_passed_to_the_hook() {
+    let skipped = Rc::new(RefCell::new(Vec::new()));
+    let log = skipped.clone();
+    let options = ExecuteOptions::default()
+        .ignore(|e: &String| e == "no invalid")
+        .on_skip(move |e: &String, reason| {
+            log.borrow_mut()
+                .push((e.clone(), reason.map(str::to_owned)))
+        });
+    assert!(!captured("missing", &options));
+    assert!(!captured("invalid", &options));
+    assert!(captured("corrupt", &options));
+    assert_eq!(
+        *skipped.borrow(),
+        vec![
+            (String::from("no missing"), Some(String::from("expected"))),
+            (String::from("no invalid"), None),
+        ]
+    );
+}
+
+#[test]
+fn decisions_report_their_reason() {
+    let decision = CaptureDecision::Skip {
+        reason: Some(String::from("expected")),
+    };
+    assert!(!decision.captures());
+    assert_eq!(decision.reason(), Some("expected"));
+    assert!(CaptureDecision::Capture { reason: None }.captures());
+}
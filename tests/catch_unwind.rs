@@ -0,0 +1,67 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+use exceptional::Executable;
+
+/// Looks up an item by index, and panics if it is out of bounds.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct Lookup {
+    items: Vec<u32>,
+}
+
+impl Executable for Lookup {
+    type Result = u32;
+    type Error = String;
+    type Arguments = usize;
+
+    fn full_path(&self) -> &'static str {
+        "::Lookup"
+    }
+
+    fn description(&self) -> String {
+        String::from("Looks up an item")
+    }
+
+    fn execute(&mut self, index: &usize) -> Result<u32, String> {
+        match self.items[*index] {
+            0 => Err(String::from("the item is empty")),
+            item => Ok(item),
+        }
+    }
+}
+
+#[test]
+fn panics_are_captured() {
+    let mut lookup = Lookup {
+        items: vec![1, 0, 3],
+    };
+    let test = exceptional::execute_catch_unwind(&mut lookup, &7).unwrap_err();
+    let message = "index out of bounds: the len is 3 but the index is 7";
+    assert_eq!(test.error, message);
+    assert_eq!(test.panic_message.as_deref(), Some(message));
+
+    let source = test.to_string();
+    assert!(source.contains(&format!("/// panicked with {}\n", message)));
+    assert!(source.contains(&format!(
+        "#[should_panic(expected = {:?})]\n#[test]\n",
+        message
+    )));
+    assert!(source.contains("\tlet _ = obj.execute(&args);\n"));
+    assert!(source.contains("\tpanic!(\"{} no longer panics\", obj.description());\n"));
+}
+
+#[test]
+fn returned_errors_are_captured_as_usual() {
+    let mut lookup = Lookup {
+        items: vec![1, 0, 3],
+    };
+    assert_eq!(
+        exceptional::execute_catch_unwind(&mut lookup, &2).ok(),
+        Some(3)
+    );
+    let test = exceptional::execute_catch_unwind(&mut lookup, &1).unwrap_err();
+    assert_eq!(test.error, "the item is empty");
+    assert_eq!(test.panic_message, None);
+    assert!(!test.to_string().contains("should_panic"));
+}
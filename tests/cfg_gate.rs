@@ -0,0 +1,54 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use common::SomeImportantAction;
+use exceptional::UnitTestOptions;
+
+#[test]
+fn cfg_gate_is_emitted_above_test_attribute() {
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+    let args = (2, 3);
+    let source = exceptional::execute(&mut action, &args)
+        .unwrap_err()
+        .with_options(UnitTestOptions::default().cfg_gate("target_os = \"linux\""))
+        .to_string();
+
+    let lines: Vec<&str> = source.lines().collect();
+    let test = lines.iter().position(|l| *l == "#[test]").unwrap();
+    assert_eq!(lines[test - 1], "#[cfg(target_os = \"linux\")]");
+    assert!(lines[test + 1].starts_with("pub fn test_"));
+}
+
+#[test]
+#[should_panic(expected = "not a valid cfg predicate")]
+fn cfg_gate_rejects_unbalanced_predicate() {
+    let _ = UnitTestOptions::default().cfg_gate("all(unix");
+}
+
+#[test]
+fn feature_gate_compiles_the_test_only_with_the_feature() {
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+    let args = (2, 3);
+    let source = exceptional::execute(&mut action, &args)
+        .unwrap_err()
+        .with_options(UnitTestOptions::default().feature_gate("generated_regressions"))
+        .to_string();
+    assert!(source.contains("#[cfg(feature = \"generated_regressions\")]\n#[test]\n"));
+
+    let options = UnitTestOptions::default()
+        .cfg_gate("unix")
+        .feature_gate("generated_regressions");
+    assert_eq!(
+        options.cfg_gate.as_deref(),
+        Some("all(unix, feature = \"generated_regressions\")")
+    );
+}
+
+#[test]
+#[should_panic(expected = "not a valid feature name")]
+fn feature_gate_rejects_invalid_names() {
+    let _ = UnitTestOptions::default().feature_gate("generated regressions");
+}
@@ -0,0 +1,66 @@
+// Not every test file uses every helper.
+#![allow(dead_code)]
+
+use exceptional::Executable;
+use std::path::PathBuf;
+
+/// Create an empty scratch directory for a test.
+pub fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("exceptional-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).expect("Could not create scratch dir");
+    dir
+}
+
+/// An executable that fails whenever the second argument is 3, like the example in `main.rs`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SomeImportantAction {
+    pub var_1: u32,
+    pub var_2: u32,
+}
+
+impl Executable for SomeImportantAction {
+    type Result = ();
+    type Error = String;
+    type Arguments = (u32, u32);
+
+    fn full_path(&self) -> &'static str {
+        "::common::SomeImportantAction"
+    }
+
+    fn description(&self) -> String {
+        String::from("Executes some very important action!")
+    }
+
+    fn execute(&mut self, args: &(u32, u32)) -> Result<(), String> {
+        if args.1 == 3 {
+            Err(String::from("Whoopsie"))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// An executable that always fails with its own message.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct OtherAction {
+    pub message: String,
+}
+
+impl Executable for OtherAction {
+    type Result = ();
+    type Error = String;
+    type Arguments = ();
+
+    fn full_path(&self) -> &'static str {
+        "::common::OtherAction"
+    }
+
+    fn description(&self) -> String {
+        String::from("Always fails")
+    }
+
+    fn execute(&mut self, _args: &()) -> Result<(), String> {
+        Err(self.message.clone())
+    }
+}
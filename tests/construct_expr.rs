@@ -0,0 +1,77 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+use exceptional::{Executable, RenderError, UnitTestOptions};
+
+/// An executable whose state doesn't round-trip through JSON, because `NaN` is serialized as `null`.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct Ratio {
+    value: f64,
+    expr: Option<String>,
+}
+
+impl Executable for Ratio {
+    type Result = ();
+    type Error = String;
+    type Arguments = u32;
+
+    fn full_path(&self) -> &'static str {
+        "::Ratio"
+    }
+
+    fn description(&self) -> String {
+        String::from("Scales a ratio")
+    }
+
+    fn execute(&mut self, factor: &u32) -> Result<(), String> {
+        Err(format!("can't scale {} by {}", self.value, factor))
+    }
+
+    fn construct_expr(&self) -> Option<String> {
+        self.expr.clone()
+    }
+}
+
+fn failure(expr: Option<&str>) -> exceptional::UnitTest<'static, Ratio> {
+    let ratio = Ratio {
+        value: f64::NAN,
+        expr: expr.map(str::to_owned),
+    };
+    let result = ratio.clone().execute(&2);
+    exceptional::UnitTest::from_result(ratio, 2, result).unwrap_err()
+}
+
+const EXPR: &str = "::Ratio { value: f64::NAN, expr: None }";
+
+#[test]
+fn the_executable_is_constructed_from_its_expression() {
+    let source = failure(Some(EXPR)).try_to_test_source().unwrap();
+    assert!(source.contains(&format!("\n\tlet mut obj: ::Ratio = {};\n", EXPR)));
+    assert!(!source.contains("obj_json"));
+    // The arguments are deserialized as usual.
+    assert!(source.contains("\tlet arg_json = r#\"2\"#;\n"));
+
+    let source = failure(Some(EXPR))
+        .with_options(UnitTestOptions::default().hoist_inputs(true))
+        .try_to_test_source()
+        .unwrap();
+    assert!(!source.contains("_OBJ"));
+    assert!(source.contains("_ARGS"));
+}
+
+#[test]
+fn executables_without_an_expression_have_to_round_trip() {
+    match failure(None).try_to_test_source() {
+        Err(RenderError::ExecutableRoundTrip(_)) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn empty_expressions_are_rejected() {
+    assert_eq!(
+        failure(Some(" \n")).try_to_test_source(),
+        Err(RenderError::EmptyConstructExpr)
+    );
+}
@@ -0,0 +1,79 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+use exceptional::{Executable, UnitTestOptions};
+use std::cell::RefCell;
+use std::time::Duration;
+
+thread_local! {
+    static TENANT: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Charge {
+    pub cents: u64,
+}
+
+impl Executable for Charge {
+    type Result = ();
+    type Error = String;
+    type Arguments = ();
+
+    fn full_path(&self) -> &'static str {
+        "::Charge"
+    }
+
+    fn description(&self) -> String {
+        String::from("Charges the current tenant")
+    }
+
+    fn context_snapshot(&self) -> Option<String> {
+        TENANT.with(|tenant| tenant.borrow().clone())
+    }
+
+    fn restore_context(context: &str) {
+        TENANT.with(|tenant| *tenant.borrow_mut() = Some(context.to_owned()));
+    }
+
+    fn execute(&mut self, _args: &()) -> Result<(), String> {
+        match TENANT.with(|tenant| tenant.borrow().clone()) {
+            Some(ref tenant) if tenant == "acme \"inc\"" => Err(format!("{} has no card", tenant)),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[test]
+fn context_is_captured_and_restored() {
+    Charge::restore_context("acme \"inc\"");
+    let mut charge = Charge { cents: 100 };
+    let test = exceptional::execute(&mut charge, &()).unwrap_err();
+    assert_eq!(test.context.as_deref(), Some("acme \"inc\""));
+    let source = test.to_string();
+    assert!(source.contains(
+        "\t<::Charge as ::exceptional::Executable>::restore_context(\"acme \\\"inc\\\"\");\n\tif let Err(e) = obj.execute(&args) {"
+    ), "{}", source);
+
+    let source = exceptional::execute(&mut charge, &())
+        .unwrap_err()
+        .with_options(UnitTestOptions::default().timeout(Duration::from_secs(1)))
+        .to_string();
+    let restore = source.find("restore_context").unwrap();
+    assert!(
+        source.find("run_with_timeout").unwrap() < restore,
+        "{}",
+        source
+    );
+}
+
+#[test]
+fn no_context_generates_no_setup() {
+    // Every test runs on its own thread, so the tenant is not set here.
+    let mut charge = Charge { cents: 100 };
+    assert!(exceptional::execute(&mut charge, &()).is_ok());
+    TENANT.with(|tenant| *tenant.borrow_mut() = Some(String::from("acme \"inc\"")));
+    let mut test = exceptional::execute(&mut charge, &()).unwrap_err();
+    test.context = None;
+    assert!(!test.to_string().contains("restore_context"));
+}
@@ -0,0 +1,79 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+use exceptional::{DebugExecutable, UnitTestOptions};
+
+/// Arguments without a `Serialize` implementation, like a client of another crate.
+#[derive(Debug)]
+struct Client {
+    base_url: &'static str,
+    retries: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct Download {
+    file: String,
+}
+
+impl DebugExecutable for Download {
+    type Result = ();
+    type Error = String;
+    type Arguments = Client;
+
+    fn full_path(&self) -> &'static str {
+        "::Download"
+    }
+
+    fn description(&self) -> String {
+        String::from("Downloads a file")
+    }
+
+    fn execute(&mut self, client: &Client) -> Result<(), String> {
+        Err(format!(
+            "{} returned 404 after {} retries",
+            client.base_url, client.retries
+        ))
+    }
+}
+
+fn client(retries: u32) -> Client {
+    Client {
+        base_url: "https://example.com\n",
+        retries,
+    }
+}
+
+#[test]
+fn generated_test_documents_the_arguments() {
+    let mut download = Download {
+        file: String::from("a.txt"),
+    };
+    let test = exceptional::execute_debug(&mut download, &client(3)).unwrap_err();
+    assert_eq!(
+        test.error,
+        "https://example.com\n returned 404 after 3 retries"
+    );
+    let source = test.to_string();
+    assert!(source.contains("\tlet mut obj: ::Download = ::serde_json::from_str(obj_json)"));
+    assert!(source.contains(
+        "\t// The arguments can not be serialized. Their `Debug` output was:\n\t// Client { base_url: \"https://example.com\\n\", retries: 3 }\n\t// TODO: reconstruct arguments\n\tlet args: debug_arguments::Client = compile_error!("
+    ), "{}", source);
+    assert!(!source.contains("arg_json"));
+
+    let hoisted = exceptional::execute_debug(&mut download, &client(3))
+        .unwrap_err()
+        .with_options(UnitTestOptions::default().hoist_inputs(true))
+        .to_string();
+    assert!(!hoisted.contains("_ARGS"));
+}
+
+#[test]
+fn different_arguments_are_different_tests() {
+    let mut download = Download {
+        file: String::from("a.txt"),
+    };
+    let first = exceptional::execute_debug(&mut download, &client(3)).unwrap_err();
+    let second = exceptional::execute_debug(&mut download, &client(4)).unwrap_err();
+    assert_ne!(first.fingerprint().unwrap(), second.fingerprint().unwrap());
+}
@@ -0,0 +1,66 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+use exceptional::{Executable, UnitTestOptions};
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Config {
+    pub name: String,
+    pub retries: u32,
+    pub verbose: bool,
+    pub tags: Vec<String>,
+}
+
+impl Executable for Config {
+    type Result = ();
+    type Error = String;
+    type Arguments = ();
+
+    fn full_path(&self) -> &'static str {
+        "::Config"
+    }
+
+    fn description(&self) -> String {
+        String::from("Validates a configuration")
+    }
+
+    fn execute(&mut self, _args: &()) -> Result<(), String> {
+        Err(format!(
+            "{} retries of {} are too many",
+            self.retries, self.name
+        ))
+    }
+}
+
+#[test]
+fn only_fields_that_differ_from_the_default_are_set() {
+    let mut config = Config {
+        name: String::from("nightly"),
+        retries: 3,
+        ..Config::default()
+    };
+    let source = exceptional::execute(&mut config, &())
+        .unwrap_err()
+        .with_default_overrides()
+        .to_string();
+
+    assert!(source.contains(
+        "\tlet mut obj: ::Config = Default::default();\n\tobj.name = ::serde_json::from_str(r#\"\"nightly\"\"#).expect(\"Could not deserialize json\");\n\tobj.retries = ::serde_json::from_str(r#\"3\"#).expect(\"Could not deserialize json\");\n\t\n"
+    ), "{}", source);
+    assert!(!source.contains("obj_json"));
+    assert!(!source.contains("obj.verbose"));
+    assert!(!source.contains("obj.tags"));
+}
+
+#[test]
+fn full_json_is_used_with_sidecars() {
+    let mut config = Config::default();
+    let source = exceptional::execute(&mut config, &())
+        .unwrap_err()
+        .with_default_overrides()
+        .with_options(UnitTestOptions::default().sidecar_dir("snapshots"))
+        .to_string();
+    assert!(source.contains("let obj_json = include_str!("));
+    assert!(!source.contains("Default::default()"));
+}
@@ -0,0 +1,73 @@
+extern crate chrono;
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use chrono::{TimeZone, Utc};
+use common::{scratch_dir, SomeImportantAction};
+use exceptional::TestDiff;
+use std::fs;
+
+fn generated(var_1: u32, millis: i64) -> String {
+    let mut action = SomeImportantAction { var_1, var_2: 1 };
+    exceptional::execute(&mut action, &(2, 3))
+        .unwrap_err()
+        .with_time(Utc.timestamp_millis_opt(millis).unwrap())
+        .to_string()
+}
+
+#[test]
+fn added_removed_and_changed_tests_are_reported() {
+    let dir = scratch_dir("diff");
+    let base = dir.join("base.rs");
+    let head = dir.join("head.rs");
+    fs::write(
+        &base,
+        generated(0, 1_000) + &generated(0, 2_000) + &generated(0, 3_000),
+    )
+    .unwrap();
+    fs::write(
+        &head,
+        generated(0, 1_000) + &generated(7, 3_000) + &generated(0, 4_000),
+    )
+    .unwrap();
+
+    let diff = exceptional::diff_files(&base, &head).unwrap();
+    assert_eq!(
+        diff.added.iter().map(|m| m.id.as_str()).collect::<Vec<_>>(),
+        vec!["4000"]
+    );
+    assert_eq!(
+        diff.removed
+            .iter()
+            .map(|m| m.id.as_str())
+            .collect::<Vec<_>>(),
+        vec!["2000"]
+    );
+    assert_eq!(diff.changed.len(), 1);
+    assert_eq!(diff.changed[0].head.id, "3000");
+    assert!(diff.changed[0].snapshot_changed());
+    assert!(!diff.changed[0].error_changed());
+    assert_eq!(
+        diff.to_string(),
+        "1 added, 1 removed, 1 changed\n\
+         + test_4000 ::common::SomeImportantAction\n\
+         - test_2000 ::common::SomeImportantAction\n\
+         ~ test_3000 ::common::SomeImportantAction (snapshot changed)\n"
+    );
+
+    assert!(exceptional::diff_files(&head, &head).unwrap().is_empty());
+}
+
+#[test]
+fn changed_errors_are_reported() {
+    let base = "// @exceptional id=1 hash=00000000000000ff path=::A time=t error=00000000000000aa\nfn test_1() {}\n";
+    let head = "// @exceptional id=1 hash=00000000000000ff path=::A time=t error=00000000000000bb\nfn test_1() {}\n";
+    let diff = TestDiff::between(base, head);
+    assert!(diff.added.is_empty() && diff.removed.is_empty());
+    assert!(diff.changed[0].error_changed());
+    assert!(!diff.changed[0].snapshot_changed());
+    assert!(diff.to_string().ends_with("~ test_1 ::A (error changed)\n"));
+}
@@ -0,0 +1,33 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use common::SomeImportantAction;
+use exceptional::{CommentStyle, UnitTestOptions};
+
+fn render(options: UnitTestOptions) -> String {
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+    exceptional::execute(&mut action, &(2, 3))
+        .unwrap_err()
+        .with_options(options)
+        .to_string()
+}
+
+#[test]
+fn header_links_to_the_executable() {
+    let source = render(UnitTestOptions::default().link_executable(true));
+    assert!(source.contains(
+        "/// Executes some very important action!\n/// executable: [`SomeImportantAction`](common::SomeImportantAction)\n"
+    ), "{}", source);
+}
+
+#[test]
+fn links_are_opt_in() {
+    assert!(!render(UnitTestOptions::default()).contains("executable: ["));
+    let plain = UnitTestOptions::default()
+        .link_executable(true)
+        .comment_style(CommentStyle::Plain);
+    assert!(!render(plain).contains("executable: ["));
+}
@@ -0,0 +1,77 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use common::{scratch_dir, SomeImportantAction};
+use exceptional::{Edition, Outcome, Sequence, UnitTestOptions};
+use std::time::Duration;
+
+fn render(options: UnitTestOptions) -> String {
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+    exceptional::execute(&mut action, &(2, 3))
+        .unwrap_err()
+        .with_options(options)
+        .to_string()
+}
+
+#[test]
+fn paths_are_written_for_rust_2018() {
+    let source = render(
+        UnitTestOptions::default()
+            .edition(Edition::Edition2018)
+            .black_box(true)
+            .timeout(Duration::from_secs(1)),
+    );
+    assert!(source.contains(
+        "let mut obj: crate::common::SomeImportantAction = serde_json::from_str(obj_json)"
+    ));
+    assert!(source.contains("\tlet args = serde_json::from_str(arg_json)"));
+    assert!(source.contains(
+        "\texceptional::run_with_timeout(std::time::Duration::from_millis(1000), move || {\n"
+    ));
+    assert!(source.contains("std::hint::black_box(obj.execute(std::hint::black_box(&args)))"));
+    assert!(!source.contains(" ::"));
+    assert!(!source.contains("(::"));
+    // The marker keeps the full path.
+    assert!(source.contains(" path=::common::SomeImportantAction "));
+}
+
+#[test]
+fn rust_2015_paths_are_the_default() {
+    let source = render(UnitTestOptions::default());
+    assert!(source
+        .contains("let mut obj: ::common::SomeImportantAction = ::serde_json::from_str(obj_json)"));
+}
+
+#[test]
+fn external_crates_keep_their_names() {
+    let mut sequence = Sequence::new(SomeImportantAction { var_1: 0, var_2: 1 });
+    let source = exceptional::execute(&mut sequence, &vec![(2, 3)])
+        .unwrap_err()
+        .with_options(UnitTestOptions::default().edition(Edition::Edition2018))
+        .to_string();
+    assert!(source
+        .contains("let mut obj: exceptional::Sequence<crate::common::SomeImportantAction> = "));
+}
+
+#[test]
+fn base64_inputs_are_read_back() {
+    let file = scratch_dir("edition").join("test.rs");
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+    exceptional::execute(&mut action, &(2, 3))
+        .unwrap_err()
+        .with_options(
+            UnitTestOptions::default()
+                .edition(Edition::Edition2018)
+                .base64_bytes(1),
+        )
+        .append_to_file(&file)
+        .unwrap();
+    let contents = std::fs::read_to_string(&file).unwrap();
+    assert!(contents.contains("\tlet arg_json = &exceptional::decode_base64_json(r#\""));
+
+    let outcomes = exceptional::rerun_file::<SomeImportantAction>(&file).unwrap();
+    assert_eq!(outcomes[0].1, Outcome::Fails(String::from("\"Whoopsie\"")));
+}
@@ -0,0 +1,66 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use common::{OtherAction, SomeImportantAction};
+use exceptional::{ErasedExecutable, Executable, RenderError, UnitTestOptions};
+
+#[test]
+fn executables_of_different_types_share_a_vec() {
+    let mut registry: Vec<(Box<dyn ErasedExecutable>, &str)> = vec![
+        (
+            Box::new(SomeImportantAction { var_1: 0, var_2: 1 }),
+            "[2, 4]",
+        ),
+        (
+            Box::new(SomeImportantAction { var_1: 0, var_2: 1 }),
+            "[2, 3]",
+        ),
+        (
+            Box::new(OtherAction {
+                message: String::from("Whoopsie"),
+            }),
+            "null",
+        ),
+    ];
+    let paths: Vec<&str> = registry.iter().map(|(e, _)| e.erased_full_path()).collect();
+    assert_eq!(
+        paths,
+        [
+            "::common::SomeImportantAction",
+            "::common::SomeImportantAction",
+            "::common::OtherAction"
+        ]
+    );
+
+    let options = UnitTestOptions::default().verbose_body(false);
+    let mut tests = Vec::new();
+    for (executable, arguments_json) in &mut registry {
+        if let Some(test) = executable.execute_json(arguments_json, &options).unwrap() {
+            tests.push(test);
+        }
+    }
+    assert_eq!(tests.len(), 2);
+    assert!(tests[0].contains("let mut obj: ::common::SomeImportantAction"));
+    assert!(tests[1].contains("let mut obj: ::common::OtherAction"));
+    assert!(tests
+        .iter()
+        .all(|test| test.contains(".expect(\"regression still fails\");")));
+}
+
+#[test]
+fn invalid_arguments_are_not_executed() {
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+    let erased: &mut dyn ErasedExecutable = &mut action;
+    assert_eq!(
+        erased.executable_json().unwrap(),
+        "{\n  \"var_1\": 0,\n  \"var_2\": 1\n}"
+    );
+    match erased.execute_json("\"three\"", &UnitTestOptions::default()) {
+        Err(RenderError::InvalidArguments(_)) => {}
+        other => panic!("Expected invalid arguments, got {:?}", other),
+    }
+    assert_eq!(action.description(), "Executes some very important action!");
+}
@@ -0,0 +1,72 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+use exceptional::Executable;
+
+#[derive(Debug)]
+enum LookupError {
+    Missing { key: u32 },
+    Corrupt(String),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct Lookup;
+
+impl Executable for Lookup {
+    type Result = ();
+    type Error = LookupError;
+    type Arguments = u32;
+
+    fn full_path(&self) -> &'static str {
+        "::Lookup"
+    }
+
+    fn description(&self) -> String {
+        String::from("Looks up a key")
+    }
+
+    fn execute(&mut self, key: &u32) -> Result<(), LookupError> {
+        match *key {
+            0 => Err(LookupError::Corrupt(String::from("bad checksum"))),
+            key => Err(LookupError::Missing { key }),
+        }
+    }
+
+    fn error_pattern(&self, error: &LookupError) -> Option<String> {
+        match error {
+            LookupError::Missing { .. } => Some(String::from("::LookupError::Missing { .. }")),
+            LookupError::Corrupt(_) => None,
+        }
+    }
+}
+
+#[test]
+fn error_pattern_asserts_the_variant() {
+    let mut lookup = Lookup;
+    let test = exceptional::execute(&mut lookup, &7).unwrap_err();
+    match test.error {
+        LookupError::Missing { key } => assert_eq!(key, 7),
+        ref other => panic!("Unexpected error {:?}", other),
+    }
+    let source = test.to_string();
+    assert!(
+        source.contains("\tassert!(matches!(obj.execute(&args), Err(::LookupError::Missing { .. })), \"{} no longer fails with {}\", obj.description(), \"::LookupError::Missing { .. }\");\n"),
+        "{}",
+        source
+    );
+    assert!(!source.contains("panic!();"));
+}
+
+#[test]
+fn errors_without_a_pattern_keep_the_default_check() {
+    let mut lookup = Lookup;
+    let test = exceptional::execute(&mut lookup, &0).unwrap_err();
+    match test.error {
+        LookupError::Corrupt(ref message) => assert_eq!(message, "bad checksum"),
+        ref other => panic!("Unexpected error {:?}", other),
+    }
+    let source = test.to_string();
+    assert!(!source.contains("matches!"));
+    assert!(source.contains("\tif let Err(e) = obj.execute(&args) {\n"));
+}
@@ -0,0 +1,79 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+use exceptional::{ErrorRender, Executable};
+use std::fmt;
+
+#[derive(Debug)]
+pub struct Timeout {
+    pub host: String,
+    pub seconds: u32,
+}
+
+impl fmt::Display for Timeout {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            fmt,
+            "connection to {} timed out\nafter {}s",
+            self.host, self.seconds
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Connect;
+
+impl Executable for Connect {
+    type Result = ();
+    type Error = Timeout;
+    type Arguments = ();
+
+    fn full_path(&self) -> &'static str {
+        "::Connect"
+    }
+
+    fn description(&self) -> String {
+        String::from("Connects to the database")
+    }
+
+    fn execute(&mut self, _args: &()) -> Result<(), Timeout> {
+        Err(Timeout {
+            host: String::from("db-1"),
+            seconds: 30,
+        })
+    }
+}
+
+fn render(render: ErrorRender) -> String {
+    exceptional::execute(&mut Connect, &())
+        .unwrap_err()
+        .with_error_render(render)
+        .to_string()
+}
+
+#[test]
+fn error_is_described_by_debug_by_default() {
+    let debug = "/// exception was Timeout { host: \"db-1\", seconds: 30 }\n";
+    let source = exceptional::execute(&mut Connect, &())
+        .unwrap_err()
+        .to_string();
+    assert!(source.contains(debug), "{}", source);
+    assert!(render(ErrorRender::Debug).contains(debug));
+}
+
+#[test]
+fn error_can_be_described_by_display() {
+    let source = render(ErrorRender::Display);
+    assert!(
+        source.contains("/// exception was connection to db-1 timed out\n/// after 30s\n"),
+        "{}",
+        source
+    );
+    assert!(!source.contains("exception debug"));
+
+    let source = render(ErrorRender::Both);
+    assert!(source.contains(
+        "/// exception was connection to db-1 timed out\n/// after 30s\n/// exception debug: Timeout { host: \"db-1\", seconds: 30 }\n"
+    ), "{}", source);
+}
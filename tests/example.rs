@@ -0,0 +1,59 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use common::{scratch_dir, SomeImportantAction};
+use exceptional::{Sequence, UnitTestOptions};
+use std::fs;
+
+#[test]
+fn example_prints_the_error_from_main() {
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+    let source = exceptional::execute(&mut action, &(2, 3))
+        .unwrap_err()
+        .render_as_example()
+        .unwrap();
+
+    assert!(source.starts_with(
+        "extern crate exceptional;\nextern crate serde_json;\nextern crate common;\n\n// @exceptional id="
+    ));
+    assert!(source.contains("/// Automatically generated reproduction for Executable\n"));
+    assert!(source.contains("fn main() {\n\tuse exceptional::Executable;\n"));
+    assert!(source.contains("\t\t\tprintln!(\"{:?}\", e);\n\t\t\t::std::process::exit(1);\n"));
+    assert!(!source.contains("#[test]"));
+    assert!(!source.contains("panic!"));
+}
+
+#[test]
+fn crates_of_nested_paths_are_declared_once() {
+    let mut sequence = Sequence::new(SomeImportantAction { var_1: 0, var_2: 1 });
+    let source = exceptional::execute(&mut sequence, &vec![(2, 3)])
+        .unwrap_err()
+        .render_as_example()
+        .unwrap();
+    assert_eq!(source.matches("extern crate").count(), 3);
+    assert!(source.contains("extern crate common;\n"));
+}
+
+#[test]
+fn write_example_uses_the_examples_directory() {
+    let dir = scratch_dir("example");
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+    let path = exceptional::execute(&mut action, &(2, 3))
+        .unwrap_err()
+        .with_options(
+            UnitTestOptions::default()
+                .id_strategy(|_| String::from("whoopsie"))
+                .sidecar_dir("snapshots"),
+        )
+        .write_example(&dir)
+        .unwrap();
+
+    assert_eq!(path, dir.join("examples").join("repro_whoopsie.rs"));
+    let source = fs::read_to_string(&path).unwrap();
+    assert!(source.contains("include_str!(\"snapshots/whoopsie.exec.json\")"));
+    assert!(dir.join("examples/snapshots/whoopsie.exec.json").exists());
+    assert!(dir.join("examples/snapshots/whoopsie.args.json").exists());
+}
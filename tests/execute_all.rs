@@ -0,0 +1,37 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use common::SomeImportantAction;
+use exceptional::Mode;
+
+fn inputs() -> Vec<(u32, u32)> {
+    vec![(1, 3), (2, 2), (2, 3), (1, 3)]
+}
+
+#[test]
+fn first_failure_stops_early() {
+    let action = SomeImportantAction { var_1: 0, var_2: 1 };
+    let tests = exceptional::execute_all(&action, inputs(), Mode::FirstFailure);
+    assert_eq!(tests.len(), 1);
+    assert_eq!(*tests[0].arguments, (1, 3));
+}
+
+#[test]
+fn all_failures_are_collected() {
+    let action = SomeImportantAction { var_1: 0, var_2: 1 };
+    let tests = exceptional::execute_all(&action, inputs(), Mode::AllFailures);
+    let arguments: Vec<(u32, u32)> = tests.iter().map(|test| *test.arguments).collect();
+    assert_eq!(arguments, vec![(1, 3), (2, 3), (1, 3)]);
+    assert!(tests.iter().all(|test| test.executable == action));
+}
+
+#[test]
+fn unique_failures_skip_duplicate_inputs() {
+    let action = SomeImportantAction { var_1: 0, var_2: 1 };
+    let tests = exceptional::execute_all(&action, inputs(), Mode::UniqueFailures);
+    let arguments: Vec<(u32, u32)> = tests.iter().map(|test| *test.arguments).collect();
+    assert_eq!(arguments, vec![(1, 3), (2, 3)]);
+}
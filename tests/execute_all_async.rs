@@ -0,0 +1,124 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+use exceptional::{AsyncExecutable, Executable, ExecuteFuture};
+use std::cell::Cell;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll, Waker};
+
+thread_local! {
+    static RUNNING: Cell<usize> = const { Cell::new(0) };
+    static MAX_RUNNING: Cell<usize> = const { Cell::new(0) };
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct Ping {
+    host: String,
+}
+
+impl Executable for Ping {
+    type Result = u32;
+    type Error = String;
+    type Arguments = u32;
+
+    fn full_path(&self) -> &'static str {
+        "::Ping"
+    }
+
+    fn description(&self) -> String {
+        String::from("Pings a host")
+    }
+
+    fn execute(&mut self, _port: &u32) -> Result<u32, String> {
+        unreachable!("only executed asynchronously")
+    }
+}
+
+impl AsyncExecutable for Ping {
+    fn execute_async<'a>(&'a mut self, port: &'a u32) -> ExecuteFuture<'a, Self> {
+        RUNNING.with(|running| running.set(running.get() + 1));
+        MAX_RUNNING.with(|max| max.set(max.get().max(RUNNING.with(Cell::get))));
+        Box::pin(Request {
+            host: &self.host,
+            port,
+            polls: Cell::new(0),
+        })
+    }
+}
+
+/// A request that is pending for `port % 3` polls, and fails for odd ports. It counts as running until it is dropped.
+struct Request<'a> {
+    host: &'a str,
+    port: &'a u32,
+    polls: Cell<u32>,
+}
+
+impl<'a> Future for Request<'a> {
+    type Output = Result<u32, String>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let polls = self.polls.get();
+        if polls < *self.port % 3 {
+            self.polls.set(polls + 1);
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+        Poll::Ready(if self.port.is_multiple_of(2) {
+            Ok(*self.port)
+        } else {
+            Err(format!("{}:{} is closed", self.host, self.port))
+        })
+    }
+}
+
+impl<'a> Drop for Request<'a> {
+    fn drop(&mut self) {
+        RUNNING.with(|running| running.set(running.get() - 1));
+    }
+}
+
+/// Poll `future` until it is ready. Every future in this file wakes itself before it returns `Pending`.
+fn block_on<F: Future + Unpin>(mut future: F) -> F::Output {
+    let mut cx = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = Pin::new(&mut future).poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+#[test]
+fn failures_are_collected_with_bounded_concurrency() {
+    let ping = Ping {
+        host: String::from("localhost"),
+    };
+    let line = line!() + 1;
+    let tests = block_on(exceptional::execute_all_async(&ping, 0..20, 4));
+
+    let mut ports: Vec<u32> = tests.iter().map(|test| *test.arguments).collect();
+    ports.sort();
+    assert_eq!(
+        ports,
+        (0..20).filter(|port| port % 2 == 1).collect::<Vec<_>>()
+    );
+    for test in &tests {
+        assert_eq!(
+            test.error,
+            format!("localhost:{} is closed", *test.arguments)
+        );
+        assert_eq!(test.location.unwrap().line(), line);
+    }
+    assert_eq!(MAX_RUNNING.with(Cell::get), 4);
+    assert_eq!(RUNNING.with(Cell::get), 0);
+}
+
+#[test]
+#[should_panic(expected = "concurrency must be at least 1")]
+fn zero_concurrency_is_rejected() {
+    let ping = Ping {
+        host: String::from("localhost"),
+    };
+    drop(exceptional::execute_all_async(&ping, 0..1, 0));
+}
@@ -0,0 +1,74 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+use exceptional::Executable;
+use std::cell::Cell;
+
+thread_local! {
+    static CLONES: Cell<usize> = const { Cell::new(0) };
+}
+
+fn clones() -> usize {
+    CLONES.with(Cell::get)
+}
+
+/// An executable that counts how often it is cloned.
+#[derive(Serialize, Deserialize, Debug)]
+struct Expensive {
+    limit: u32,
+}
+
+impl Clone for Expensive {
+    fn clone(&self) -> Self {
+        CLONES.with(|count| count.set(count.get() + 1));
+        Expensive { limit: self.limit }
+    }
+}
+
+impl Executable for Expensive {
+    type Result = u32;
+    type Error = String;
+    type Arguments = u32;
+
+    fn full_path(&self) -> &'static str {
+        "::Expensive"
+    }
+
+    fn description(&self) -> String {
+        String::from("Checks the limit")
+    }
+
+    fn execute(&mut self, value: &u32) -> Result<u32, String> {
+        if *value > self.limit {
+            Err(format!("{} is over the limit", value))
+        } else {
+            Ok(*value)
+        }
+    }
+}
+
+#[test]
+fn the_success_path_does_not_clone() {
+    let mut expensive = Expensive { limit: 10 };
+    let before = clones();
+    for value in 0..10 {
+        let result = exceptional::execute_with(&mut expensive, &value, Expensive::clone);
+        assert_eq!(result.ok(), Some(value));
+    }
+    assert_eq!(clones(), before);
+
+    // execute clones on every call.
+    assert!(exceptional::execute(&mut expensive, &3).is_ok());
+    assert_eq!(clones(), before + 1);
+}
+
+#[test]
+fn the_snapshot_is_taken_on_failure() {
+    let mut expensive = Expensive { limit: 10 };
+    let test =
+        exceptional::execute_with(&mut expensive, &11, |_| Expensive { limit: 5 }).unwrap_err();
+    assert_eq!(test.error, "11 is over the limit");
+    assert_eq!(test.executable.limit, 5);
+    assert!(test.to_string().contains("\"limit\": 5"));
+}
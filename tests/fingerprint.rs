@@ -0,0 +1,62 @@
+extern crate chrono;
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use chrono::{TimeZone, Utc};
+use common::{scratch_dir, OtherAction, SomeImportantAction};
+use std::fs;
+use std::path::Path;
+
+fn fingerprint(message: &str, millis: i64) -> String {
+    let mut action = OtherAction {
+        message: String::from(message),
+    };
+    let test = exceptional::execute(&mut action, &())
+        .unwrap_err()
+        .with_time(Utc.timestamp_millis_opt(millis).unwrap());
+    let fingerprint = test.fingerprint().unwrap();
+    assert_eq!(
+        exceptional::parse_markers(&test.to_string())[0].fingerprint(),
+        fingerprint
+    );
+    fingerprint
+}
+
+fn append(file: &Path, message: &str) -> bool {
+    let mut action = OtherAction {
+        message: String::from(message),
+    };
+    exceptional::execute(&mut action, &())
+        .unwrap_err()
+        .append_to_file_dedup(file)
+        .unwrap()
+}
+
+#[test]
+fn fingerprints_ignore_the_time() {
+    let first = fingerprint("nope", 1_539_761_233_543);
+    assert_eq!(first, fingerprint("nope", 1_539_761_299_000));
+    assert_ne!(first, fingerprint("other", 1_539_761_233_543));
+}
+
+#[test]
+fn known_failures_are_skipped() {
+    let file = scratch_dir("fingerprint").join("regressions.rs");
+    assert!(append(&file, "nope"));
+    assert!(!append(&file, "nope"));
+    assert!(append(&file, "other"));
+
+    // The same executable and arguments with a different error are a different failure.
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+    let mut test = exceptional::execute(&mut action, &(2, 3)).unwrap_err();
+    test.error = String::from("Different");
+    assert!(test.append_to_file_dedup(&file).unwrap());
+    let test = exceptional::execute(&mut action, &(2, 3)).unwrap_err();
+    assert!(test.append_to_file_dedup(&file).unwrap());
+
+    let contents = fs::read_to_string(&file).unwrap();
+    assert_eq!(exceptional::parse_markers(&contents).len(), 4);
+}
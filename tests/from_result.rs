@@ -0,0 +1,50 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use common::SomeImportantAction;
+use exceptional::{Executable, OwnedUnitTest, UnitTest};
+
+/// Runs the action without `execute`, and returns the snapshot and the result.
+fn already_ran(args: (u32, u32)) -> (SomeImportantAction, Result<(), String>) {
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+    let snapshot = action.clone();
+    let result = action.execute(&args);
+    (snapshot, result)
+}
+
+#[test]
+fn errors_are_wrapped_after_the_fact() {
+    let (snapshot, result) = already_ran((2, 3));
+    let line = line!() + 1;
+    let result = UnitTest::from_result(snapshot.clone(), (2, 3), result);
+    let test: OwnedUnitTest<SomeImportantAction> = result.unwrap_err();
+    assert_eq!(test.error, "Whoopsie");
+    assert_eq!(test.executable, snapshot);
+    assert_eq!(*test.arguments, (2, 3));
+    assert_eq!(test.location.unwrap().line(), line);
+    assert!(test.to_string().contains("\"Whoopsie\""));
+}
+
+#[test]
+fn ok_values_are_passed_through() {
+    let (snapshot, result) = already_ran((2, 4));
+    assert!(UnitTest::from_result(snapshot, (2, 4), result).is_ok());
+}
+
+#[test]
+fn consumed_arguments_are_owned_by_the_test() {
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+    let line = line!() + 1;
+    let test: OwnedUnitTest<_> = exceptional::execute_consuming(&mut action, (2, 3)).unwrap_err();
+    assert_eq!(test.error, "Whoopsie");
+    assert_eq!(*test.arguments, (2, 3));
+    assert_eq!(test.location.unwrap().line(), line);
+    assert!(test
+        .to_string()
+        .contains("let arg_json = r#\"[\n  2,\n  3\n]\"#;"));
+
+    assert!(exceptional::execute_consuming(&mut action, (2, 4)).is_ok());
+}
@@ -0,0 +1,83 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use common::{scratch_dir, SomeImportantAction};
+use exceptional::{GeneratedTest, TransformRenderer, UnitTestOptions};
+use std::fs;
+use std::time::Duration;
+
+fn tests() -> Vec<exceptional::UnitTest<'static, SomeImportantAction>> {
+    let action = SomeImportantAction { var_1: 0, var_2: 1 };
+    let options = vec![
+        UnitTestOptions::default(),
+        UnitTestOptions::default()
+            .run_hint(true)
+            .cfg_gate("unix")
+            .timeout(Duration::from_secs(1)),
+        UnitTestOptions::default().async_test("tokio::test"),
+    ];
+    options
+        .into_iter()
+        .map(|options| {
+            exceptional::execute_all(&action, vec![(2, 3)], exceptional::Mode::FirstFailure)
+                .pop()
+                .unwrap()
+                .with_options(options)
+        })
+        .collect()
+}
+
+#[test]
+fn unchanged_generated_test_renders_the_same_source() {
+    for test in tests() {
+        let generated = test.generated_test().unwrap();
+        assert_eq!(generated.render(), test.to_string());
+        assert!(generated.marker.starts_with("// @exceptional id="));
+        assert!(generated
+            .signature
+            .ends_with(&format!("test_{}()", generated.id)));
+        assert!(generated.teardown.is_empty());
+    }
+}
+
+#[test]
+fn sections_can_be_changed_before_rendering() {
+    let test = tests().remove(0);
+    let mut generated = test.generated_test().unwrap();
+    assert_eq!(generated.attributes, vec!["#[test]"]);
+    assert_eq!(
+        generated.imports,
+        vec![
+            "\t#[allow(unused_imports)]",
+            "\tuse exceptional::Executable;"
+        ]
+    );
+    generated.setup.insert(0, String::from("\tinit_logging();"));
+    generated.teardown.push(String::from("\tflush_logs();"));
+
+    let source = generated.render();
+    assert!(source.contains("\tuse exceptional::Executable;\n\tinit_logging();\n\tlet obj_json"));
+    assert!(source.ends_with("\t}\n\tflush_logs();\n}\n"), "{}", source);
+}
+
+#[test]
+fn transformed_tests_are_written_to_files() {
+    let file = scratch_dir("generated_test").join("regressions.rs");
+    let renderer = TransformRenderer::new(|generated: &mut GeneratedTest| {
+        generated.attributes.insert(0, String::from("#[ignore]"));
+    });
+    tests()
+        .remove(0)
+        .append_to_file_with(&file, &renderer)
+        .unwrap();
+    let contents = fs::read_to_string(&file).unwrap();
+    assert!(
+        contents.contains("#[ignore]\n#[test]\npub fn test_"),
+        "{}",
+        contents
+    );
+    assert_eq!(exceptional::parse_markers(&contents).len(), 1);
+}
@@ -0,0 +1,56 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use common::{scratch_dir, OtherAction, SomeImportantAction};
+use exceptional::{DedupKey, Generator, UnitTestOptions};
+use std::fs;
+
+#[test]
+fn one_generator_captures_every_executable_type() {
+    let file = scratch_dir("generator").join("regressions.rs");
+    let mut generator = Generator::open(&file, UnitTestOptions::default().run_hint(true)).unwrap();
+
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+    assert_eq!(generator.run(&mut action, &(2, 4)).unwrap(), Ok(()));
+    assert_eq!(
+        generator.run(&mut action, &(2, 3)).unwrap(),
+        Err(String::from("Whoopsie"))
+    );
+    let mut other = OtherAction {
+        message: String::from("nope"),
+    };
+    assert_eq!(
+        generator.run(&mut other, &()).unwrap(),
+        Err(String::from("nope"))
+    );
+    // A failure that was already written is skipped.
+    assert!(generator.run(&mut action, &(2, 3)).unwrap().is_err());
+    let test = exceptional::execute(&mut other, &()).unwrap_err();
+    assert!(!generator.capture(test).unwrap());
+
+    assert_eq!(generator.runs(), 4);
+    assert_eq!(generator.failures(), 4);
+    assert_eq!(generator.written(), 2);
+    drop(generator);
+
+    let contents = fs::read_to_string(&file).unwrap();
+    let markers = exceptional::parse_markers(&contents);
+    let paths: Vec<&str> = markers.iter().map(|marker| marker.path.as_str()).collect();
+    assert_eq!(
+        paths,
+        ["::common::SomeImportantAction", "::common::OtherAction"]
+    );
+    // The shared options are used for every test.
+    assert_eq!(contents.matches("/// run with: cargo test").count(), 2);
+
+    // The cache is seeded from the file when a generator is opened again.
+    let mut generator = Generator::open(&file, UnitTestOptions::default())
+        .unwrap()
+        .dedup_by(DedupKey::ErrorOnly);
+    let mut action = SomeImportantAction { var_1: 5, var_2: 1 };
+    assert!(generator.run(&mut action, &(2, 3)).unwrap().is_err());
+    assert_eq!(generator.written(), 0);
+}
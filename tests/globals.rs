@@ -0,0 +1,48 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use common::SomeImportantAction;
+use exceptional::{GlobalsGuard, UnitTestOptions};
+use std::env;
+
+#[test]
+fn guard_is_emitted_with_captured_globals() {
+    // Holding a guard keeps the other test from changing the current directory meanwhile.
+    let _globals = GlobalsGuard::set(None, &[]);
+    env::set_var("EXCEPTIONAL_GLOBALS_SET", "value");
+    env::remove_var("EXCEPTIONAL_GLOBALS_UNSET");
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+    let source = exceptional::execute(&mut action, &(2, 3))
+        .unwrap_err()
+        .capture_globals(&["EXCEPTIONAL_GLOBALS_SET", "EXCEPTIONAL_GLOBALS_UNSET"])
+        .with_options(UnitTestOptions::default().restore_globals(true))
+        .to_string();
+
+    let cwd = env::current_dir().unwrap();
+    let expected = format!(
+        "\tlet _globals = ::exceptional::GlobalsGuard::set(Some({:?}), &[(\"EXCEPTIONAL_GLOBALS_SET\", Some(\"value\")), (\"EXCEPTIONAL_GLOBALS_UNSET\", None)]);\n",
+        cwd.to_str().unwrap()
+    );
+    assert!(source.contains(&expected), "{}", source);
+}
+
+#[test]
+fn guard_restores_the_environment() {
+    let dir = common::scratch_dir("globals");
+    let cwd = env::current_dir().unwrap();
+    env::set_var("EXCEPTIONAL_GLOBALS_RESTORED", "before");
+    {
+        let _globals = GlobalsGuard::set(
+            dir.to_str(),
+            &[("EXCEPTIONAL_GLOBALS_RESTORED", Some("during"))],
+        );
+        assert_eq!(env::current_dir().unwrap(), dir.canonicalize().unwrap());
+        assert_eq!(env::var("EXCEPTIONAL_GLOBALS_RESTORED").unwrap(), "during");
+    }
+    assert_eq!(env::current_dir().unwrap(), cwd);
+    assert_eq!(env::var("EXCEPTIONAL_GLOBALS_RESTORED").unwrap(), "before");
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,77 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+use exceptional::Executable;
+
+/// Doubles its argument, but fails for odd numbers until it is "fixed".
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+struct Doubler {
+    fixed: bool,
+}
+
+impl Executable for Doubler {
+    type Result = u32;
+    type Error = String;
+    type Arguments = u32;
+
+    fn full_path(&self) -> &'static str {
+        "::Doubler"
+    }
+
+    fn description(&self) -> String {
+        String::from("Doubles a number")
+    }
+
+    fn execute(&mut self, value: &u32) -> Result<u32, String> {
+        if value % 2 == 1 && !self.fixed {
+            Err(String::from("odd"))
+        } else {
+            Ok(value * 2)
+        }
+    }
+}
+
+#[test]
+fn expected_value_is_captured_after_the_fix() {
+    let mut doubler = Doubler { fixed: false };
+    let test = exceptional::execute(&mut doubler, &21).unwrap_err();
+    assert_eq!(test.capture_expected().err(), Some(String::from("odd")));
+
+    let mut doubler = Doubler { fixed: false };
+    let mut test = exceptional::execute(&mut doubler, &21).unwrap_err();
+    test.executable.fixed = true;
+    let source = test.capture_expected().unwrap().to_string();
+    assert!(source.contains("\tlet expected = ::exceptional::expected_result(&obj, r#\"42\"#);\n"));
+    assert!(source.contains("\tassert_eq!(obj.execute(&args).unwrap(), expected);\n"));
+}
+
+#[test]
+fn expected_value_can_be_overridden() {
+    let mut doubler = Doubler { fixed: false };
+    let source = exceptional::execute(&mut doubler, &21)
+        .unwrap_err()
+        .with_expected(&7)
+        .to_string();
+    assert!(source.contains("expected_result(&obj, r#\"7\"#)"));
+    assert_eq!(
+        exceptional::expected_result(&Doubler { fixed: true }, "7"),
+        7
+    );
+}
+
+#[test]
+fn the_error_can_be_pinned() {
+    let mut doubler = Doubler { fixed: false };
+    let source = exceptional::execute(&mut doubler, &21)
+        .unwrap_err()
+        .with_expected_error()
+        .to_string();
+    assert!(source
+        .contains("\tlet expected_error = ::exceptional::expected_error(&obj, r#\"\"odd\"\"#);\n"));
+    assert!(source.contains("\t\tErr(e) => assert_eq!(e, expected_error),\n"));
+
+    let doubler = Doubler { fixed: false };
+    let error: String = exceptional::expected_error(&doubler, "\"odd\"");
+    assert_eq!(error, "odd");
+}
@@ -0,0 +1,85 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use common::SomeImportantAction;
+use exceptional::{Hasher, RenderError, UnitTestOptions};
+
+fn render(options: UnitTestOptions) -> Result<String, RenderError> {
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+    exceptional::execute(&mut action, &(2, 3))
+        .unwrap_err()
+        .with_options(options)
+        .try_to_test_source()
+}
+
+#[test]
+fn sha256_matches_known_digests() {
+    assert_eq!(
+        Hasher::Sha256.hash(b""),
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+    );
+    assert_eq!(
+        Hasher::Sha256.hash(b"abc"),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+    assert_eq!(
+        Hasher::Sha256.hash(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"),
+        "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+    );
+    assert_eq!(
+        Hasher::Sha256.hash(&[b'a'; 1_000_000][..]),
+        "cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0"
+    );
+    let long: Vec<u8> = (0..1000).map(|i| (i % 251) as u8).collect();
+    assert_eq!(
+        Hasher::Sha256.hash(&long),
+        "4e4c294b331f7a2099a379bec34b9f9fc03dc46ab465d998f4d683da53487e6d"
+    );
+    assert_eq!(Hasher::Fnv1a.hash(b""), "cbf29ce484222325");
+}
+
+#[test]
+fn marker_and_ids_use_the_configured_hasher() {
+    let default = render(UnitTestOptions::default()).unwrap();
+    let source = render(
+        UnitTestOptions::default()
+            .hasher(Hasher::Sha256)
+            .id_strategy(|meta| meta.content_hash.clone()),
+    )
+    .unwrap();
+    let marker = exceptional::parse_markers(&source).remove(0);
+    assert_eq!(marker.hash.len(), 64);
+    assert_eq!(marker.error.len(), 64);
+    assert_eq!(marker.id, marker.hash);
+    // The same snapshot always gets the same hash.
+    let again = render(UnitTestOptions::default().hasher(Hasher::Sha256)).unwrap();
+    assert_eq!(
+        exceptional::parse_markers(&again).remove(0).hash,
+        marker.hash
+    );
+    assert_eq!(
+        exceptional::parse_markers(&default).remove(0).hash.len(),
+        16
+    );
+}
+
+fn constant(_bytes: &[u8]) -> String {
+    String::from("same")
+}
+
+fn invalid(_bytes: &[u8]) -> String {
+    String::from("not a hash")
+}
+
+#[test]
+fn custom_hashers_are_validated() {
+    let source = render(UnitTestOptions::default().hasher(Hasher::Custom(constant))).unwrap();
+    assert!(source.contains(" hash=same "), "{}", source);
+    match render(UnitTestOptions::default().hasher(Hasher::Custom(invalid))) {
+        Err(RenderError::InvalidHash(hash)) => assert_eq!(hash, "not a hash"),
+        other => panic!("expected an invalid hash, got {:?}", other),
+    }
+}
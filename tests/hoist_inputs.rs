@@ -0,0 +1,159 @@
+extern crate chrono;
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use chrono::{TimeZone, Utc};
+use common::{scratch_dir, SomeImportantAction};
+use exceptional::{DedupKey, Outcome, StreamingWriter, TestFileWriter, UnitTestOptions};
+use std::fs;
+use std::path::Path;
+
+fn failure(
+    var_1: u32,
+    millis: i64,
+    options: UnitTestOptions,
+) -> exceptional::OwnedUnitTest<SomeImportantAction> {
+    let action = SomeImportantAction { var_1, var_2: 1 };
+    exceptional::execute_all(&action, vec![(2, 3)], exceptional::Mode::FirstFailure)
+        .pop()
+        .unwrap()
+        .with_time(Utc.timestamp_millis_opt(millis).unwrap())
+        .with_options(options)
+}
+
+fn append(file: &Path, var_1: u32, millis: i64) -> String {
+    let test = failure(var_1, millis, UnitTestOptions::default().hoist_inputs(true));
+    let source = test.to_string();
+    test.append_to_file(file).unwrap();
+    source
+}
+
+fn const_names(source: &str) -> Vec<&str> {
+    source
+        .lines()
+        .filter_map(|line| line.trim_start().strip_prefix("const "))
+        .map(|line| &line[..line.find(':').unwrap()])
+        .collect()
+}
+
+#[test]
+fn inputs_are_written_to_consts_above_the_test() {
+    let file = scratch_dir("hoist_inputs_render").join("regressions.rs");
+    let source = append(&file, 0, 1_000);
+    assert_eq!(const_names(&source), ["INPUT_1000_OBJ", "INPUT_1000_ARGS"]);
+
+    let mut lines = source.lines();
+    assert!(lines
+        .next()
+        .unwrap()
+        .starts_with("// @exceptional id=1000 "));
+    assert_eq!(lines.next().unwrap(), "const INPUT_1000_OBJ: &str = r#\"{");
+    assert!(source.contains("\tlet obj_json = INPUT_1000_OBJ;\n"));
+    assert!(source.contains("\tlet arg_json = INPUT_1000_ARGS;\n"));
+    assert!(!source.contains("= r#\"{\n  \"var_1\": 0,\n  \"var_2\": 1\n}\"#;\n\tlet"));
+    assert_eq!(exceptional::parse_markers(&source).len(), 1);
+
+    let test = failure(0, 1_000, UnitTestOptions::default().hoist_inputs(true));
+    let generated = test.generated_test().unwrap();
+    assert_eq!(
+        generated.header_lines[0],
+        "const INPUT_1000_OBJ: &str = r#\"{"
+    );
+    assert_eq!(generated.render(), source);
+}
+
+#[test]
+fn every_test_keeps_its_own_consts() {
+    let dir = scratch_dir("hoist_inputs_own");
+    let file = dir.join("regressions.rs");
+    append(&file, 0, 1_000);
+    append(&file, 0, 2_000);
+    let contents = fs::read_to_string(&file).unwrap();
+    assert_eq!(
+        const_names(&contents),
+        [
+            "INPUT_1000_OBJ",
+            "INPUT_1000_ARGS",
+            "INPUT_2000_OBJ",
+            "INPUT_2000_ARGS"
+        ]
+    );
+
+    // The writers, which don't read the whole file, write the consts of every test as well.
+    let mut writer = TestFileWriter::new();
+    for &millis in &[1_000, 2_000] {
+        writer.push(failure(
+            0,
+            millis,
+            UnitTestOptions::default().hoist_inputs(true),
+        ));
+    }
+    assert_eq!(
+        const_names(writer.as_str()),
+        [
+            "INPUT_1000_OBJ",
+            "INPUT_1000_ARGS",
+            "INPUT_2000_OBJ",
+            "INPUT_2000_ARGS"
+        ]
+    );
+    let streamed = dir.join("streamed.rs");
+    let mut writer = StreamingWriter::open(&streamed).unwrap();
+    for &millis in &[1_000, 2_000] {
+        let test = failure(0, millis, UnitTestOptions::default().hoist_inputs(true));
+        writer.record_by(test, DedupKey::ErrorOnly).unwrap();
+    }
+    writer
+        .record(failure(
+            1,
+            3_000,
+            UnitTestOptions::default().hoist_inputs(true),
+        ))
+        .unwrap();
+    drop(writer);
+    let streamed = fs::read_to_string(&streamed).unwrap();
+    assert_eq!(
+        const_names(&streamed),
+        [
+            "INPUT_1000_OBJ",
+            "INPUT_1000_ARGS",
+            "INPUT_3000_OBJ",
+            "INPUT_3000_ARGS"
+        ]
+    );
+}
+
+#[test]
+fn hoisted_inputs_can_be_rerun() {
+    let file = scratch_dir("hoist_inputs_rerun").join("regressions.rs");
+    append(&file, 0, 1_000);
+    append(&file, 1, 2_000);
+    let outcomes = exceptional::rerun_file::<SomeImportantAction>(&file).unwrap();
+    assert_eq!(
+        outcomes,
+        vec![
+            (
+                String::from("1000"),
+                Outcome::Fails(String::from("\"Whoopsie\""))
+            ),
+            (
+                String::from("2000"),
+                Outcome::Fails(String::from("\"Whoopsie\""))
+            ),
+        ]
+    );
+}
+
+#[test]
+fn inputs_are_not_hoisted_by_default() {
+    let file = scratch_dir("hoist_inputs_default").join("regressions.rs");
+    failure(0, 1_000, UnitTestOptions::default())
+        .append_to_file(&file)
+        .unwrap();
+    let contents = fs::read_to_string(&file).unwrap();
+    assert!(contents.contains("\tlet obj_json = r#\""), "{}", contents);
+    assert!(!contents.contains("const INPUT_"), "{}", contents);
+}
@@ -0,0 +1,110 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+use exceptional::{Executable, ExecuteError, ExecuteOptions, RenderError, UnitTestOptions};
+
+#[derive(Debug, Clone, PartialEq)]
+enum LookupError {
+    NotFound,
+    Invalid,
+    Corrupt,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+struct Lookup {
+    key: String,
+}
+
+impl Executable for Lookup {
+    type Result = ();
+    type Error = LookupError;
+    type Arguments = ();
+
+    fn full_path(&self) -> &'static str {
+        "::Lookup"
+    }
+
+    fn description(&self) -> String {
+        String::from("Looks up a key")
+    }
+
+    fn execute(&mut self, _args: &()) -> Result<(), LookupError> {
+        match self.key.as_str() {
+            "missing" => Err(LookupError::NotFound),
+            "invalid" => Err(LookupError::Invalid),
+            _ => Err(LookupError::Corrupt),
+        }
+    }
+
+    fn should_capture(&self, error: &LookupError) -> bool {
+        *error != LookupError::NotFound
+    }
+}
+
+/// Returns the error of looking up `key`, and whether a unit test was generated for it.
+fn run(key: &str) -> (LookupError, bool) {
+    let options = ExecuteOptions::default().ignore(|e: &LookupError| *e == LookupError::Invalid);
+    let mut lookup = Lookup {
+        key: key.to_owned(),
+    };
+    let error = exceptional::execute_with_options(&mut lookup, &(), &options).unwrap_err();
+    let captured = matches!(error, ExecuteError::Captured(_));
+    (error.error().clone(), captured)
+}
+
+#[test]
+fn ignored_errors_do_not_generate_tests() {
+    assert_eq!(run("missing"), (LookupError::NotFound, false));
+    assert_eq!(run("invalid"), (LookupError::Invalid, false));
+    assert_eq!(run("corrupt"), (LookupError::Corrupt, true));
+}
+
+#[test]
+fn captured_tests_get_the_test_options() {
+    let options =
+        ExecuteOptions::default().with_test_options(UnitTestOptions::default().verbose_body(false));
+    let mut lookup = Lookup {
+        key: String::from("corrupt"),
+    };
+    let mut test = exceptional::execute_with_options(&mut lookup, &(), &options)
+        .unwrap_err()
+        .unit_test()
+        .unwrap();
+    assert!(!test.options.verbose_body);
+    assert!(test
+        .to_string()
+        .contains("\tobj.execute(&args).expect(\"regression still fails\");\n"));
+
+    test.options_mut().verbose_body = true;
+    assert!(test.to_string().contains("\t\tpanic!();\n"));
+}
+
+#[test]
+fn options_written_directly_are_checked_when_rendering() {
+    let mut lookup = Lookup {
+        key: String::from("corrupt"),
+    };
+    let mut test = exceptional::execute(&mut lookup, &()).unwrap_err();
+    test.options_mut().obj_binding = String::from("fn");
+    assert_eq!(
+        test.try_to_test_source(),
+        Err(RenderError::InvalidOption {
+            option: "obj_binding",
+            value: String::from("fn"),
+        })
+    );
+
+    test.options_mut().obj_binding = String::from("lookup");
+    test.options_mut().cfg_gate = Some(String::from("feature = \"slow"));
+    assert_eq!(
+        test.try_to_test_source().unwrap_err().to_string(),
+        "\"feature = \\\"slow\" is not a valid value for cfg_gate, see UnitTestOptions::cfg_gate"
+    );
+
+    test.options_mut().cfg_gate = None;
+    test.options_mut().test_attribute = String::from("#[tokio::test]");
+    assert!(test.try_to_test_source().is_err());
+    test.options_mut().test_attribute = String::from("tokio::test");
+    assert!(test.to_string().contains("#[tokio::test]\npub fn test_"));
+}
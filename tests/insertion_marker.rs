@@ -0,0 +1,39 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use common::SomeImportantAction;
+use exceptional::UnitTestOptions;
+use std::fs;
+
+#[test]
+fn tests_are_inserted_above_the_marker() {
+    let dir = common::scratch_dir("insertion-marker");
+    let file = dir.join("generated.rs");
+    fs::write(
+        &file,
+        "mod regressions {\n    use super::*;\n\n    // EXCEPTIONAL:INSERT\n}\n",
+    )
+    .unwrap();
+
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+    let test = exceptional::execute(&mut action, &(2, 3))
+        .unwrap_err()
+        .with_options(UnitTestOptions::default().use_insertion_marker(true));
+    let rendered = test.to_string();
+    test.append_to_file(&file).unwrap();
+
+    let contents = fs::read_to_string(&file).unwrap();
+    let test_start = contents.find("    #[test]\n").unwrap();
+    let marker = contents.find("    // EXCEPTIONAL:INSERT\n").unwrap();
+    assert!(test_start < marker);
+    assert!(contents.ends_with("    // EXCEPTIONAL:INSERT\n}\n"));
+    assert_eq!(
+        contents.matches("#[test]").count(),
+        rendered.matches("#[test]").count()
+    );
+
+    let _ = fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,56 @@
+#![cfg(feature = "insta")]
+
+extern crate chrono;
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use chrono::{TimeZone, Utc};
+use common::{scratch_dir, SomeImportantAction};
+use exceptional::{InstaRenderer, UnitTest};
+use std::fs;
+
+fn failure(action: &mut SomeImportantAction) -> UnitTest<'_, SomeImportantAction> {
+    exceptional::execute(action, &(2, 3))
+        .unwrap_err()
+        .with_time(Utc.timestamp_millis_opt(1_539_761_233_543).unwrap())
+}
+
+#[test]
+fn renders_an_insta_assertion() {
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+    let source = failure(&mut action)
+        .render_with(&InstaRenderer::new("my_crate::regressions"))
+        .unwrap();
+    assert!(source.contains("#[test]\npub fn test_1539761233543() {\n"));
+    assert!(source.contains(
+        "\t::insta::assert_json_snapshot!(\"test_1539761233543\", obj.execute(&args).map_err(|e| format!(\"{:?}\", e)));\n}\n"
+    ));
+    assert!(!source.contains("panic!"));
+}
+
+#[test]
+fn snapshot_file_is_seeded_with_the_error() {
+    let dir = scratch_dir("insta");
+    let test_file = dir.join("regressions.rs");
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+    let test = failure(&mut action);
+    let renderer = InstaRenderer::new("my_crate::regressions");
+
+    let snap = renderer.write_snapshot_file(&test, &test_file).unwrap();
+    test.append_to_file_with(&test_file, &renderer).unwrap();
+
+    assert_eq!(
+        snap,
+        dir.join("snapshots")
+            .join("my_crate__regressions__test_1539761233543.snap")
+    );
+    let contents = fs::read_to_string(&snap).unwrap();
+    assert!(contents.starts_with("---\nsource: "));
+    assert!(contents.ends_with("---\n{\n  \"Err\": \"\\\"Whoopsie\\\"\"\n}\n"));
+    assert!(fs::read_to_string(&test_file)
+        .unwrap()
+        .contains("assert_json_snapshot!(\"test_1539761233543\""));
+}
@@ -0,0 +1,82 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_json;
+
+use exceptional::{Executable, RenderError, UnitTestOptions};
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct Session {
+    user: String,
+    #[serde(default)]
+    last_seen: u64,
+}
+
+impl Executable for Session {
+    type Result = ();
+    type Error = String;
+    type Arguments = (String, u64);
+
+    fn full_path(&self) -> &'static str {
+        "::Session"
+    }
+
+    fn description(&self) -> String {
+        String::from("Refreshes a session")
+    }
+
+    fn execute(&mut self, _args: &(String, u64)) -> Result<(), String> {
+        Err(format!("{} expired at {}", self.user, self.last_seen))
+    }
+}
+
+fn strip_volatile(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(object) => {
+            object.remove("last_seen");
+        }
+        serde_json::Value::Array(items) => items[1] = serde_json::Value::from(0),
+        _ => {}
+    }
+}
+
+#[test]
+fn volatile_fields_are_removed_before_embedding() {
+    let render = |last_seen: u64| {
+        let mut session = Session {
+            user: String::from("alice"),
+            last_seen,
+        };
+        let args = (String::from("token"), last_seen);
+        let test = exceptional::execute(&mut session, &args)
+            .unwrap_err()
+            .with_options(UnitTestOptions::default().json_postprocess(strip_volatile));
+        assert_eq!(test.check_round_trip(), Ok(()));
+        test.snapshots().unwrap()
+    };
+
+    let (executable_json, arguments_json) = render(1_539_761_233);
+    assert_eq!(executable_json, "{\n  \"user\": \"alice\"\n}");
+    assert_eq!(arguments_json, "[\n  \"token\",\n  0\n]");
+    assert_eq!(render(42), (executable_json, arguments_json));
+}
+
+#[test]
+fn postprocessed_json_must_still_deserialize() {
+    let mut session = Session {
+        user: String::from("alice"),
+        last_seen: 1,
+    };
+    let args = (String::from("token"), 1);
+    let test = exceptional::execute(&mut session, &args)
+        .unwrap_err()
+        .with_options(UnitTestOptions::default().json_postprocess(|value| {
+            if let Some(object) = value.as_object_mut() {
+                object.remove("user");
+            }
+        }));
+    match test.try_to_test_source() {
+        Err(RenderError::ExecutableRoundTrip(e)) => assert!(e.contains("user"), "{}", e),
+        other => panic!("Expected a round-trip error, got {:?}", other.map(|_| ())),
+    }
+}
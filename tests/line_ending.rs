@@ -0,0 +1,93 @@
+extern crate chrono;
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use chrono::{TimeZone, Utc};
+use common::{scratch_dir, SomeImportantAction};
+use exceptional::{LineEnding, Outcome, StreamingWriter, UnitTestOptions};
+use std::fs;
+
+fn failure(
+    var_1: u32,
+    options: UnitTestOptions,
+) -> exceptional::OwnedUnitTest<SomeImportantAction> {
+    let action = SomeImportantAction { var_1, var_2: 1 };
+    exceptional::execute_all(&action, vec![(2, 3)], exceptional::Mode::FirstFailure)
+        .pop()
+        .unwrap()
+        .with_time(Utc.timestamp_millis_opt(1_000 + i64::from(var_1)).unwrap())
+        .with_options(options)
+}
+
+/// Returns `true` if every line break of `text` is `\r\n`.
+fn only_crlf(text: &str) -> bool {
+    text.match_indices('\n')
+        .all(|(i, _)| text[..i].ends_with('\r'))
+}
+
+#[test]
+fn configured_line_endings_are_used_for_rendered_tests() {
+    let options = UnitTestOptions::default().line_ending(LineEnding::CrLf);
+    let source = failure(0, options.clone()).to_string();
+    assert!(source.contains("\r\n"));
+    assert!(only_crlf(&source));
+    assert_eq!(failure(0, options).rendered_len().unwrap(), source.len());
+
+    let source = failure(0, UnitTestOptions::default()).to_string();
+    assert!(!source.contains('\r'));
+}
+
+#[test]
+fn appended_tests_match_the_file() {
+    let file = scratch_dir("line_ending").join("regressions.rs");
+    fs::write(&file, "// hand-written\r\nuse std::fmt;\r\n").unwrap();
+    failure(0, UnitTestOptions::default())
+        .append_to_file(&file)
+        .unwrap();
+    let mut writer = StreamingWriter::open(&file).unwrap();
+    assert!(writer
+        .record(failure(1, UnitTestOptions::default()))
+        .unwrap());
+    assert!(!writer
+        .record(failure(0, UnitTestOptions::default()))
+        .unwrap());
+    drop(writer);
+
+    let contents = fs::read_to_string(&file).unwrap();
+    assert!(only_crlf(&contents), "{:?}", contents);
+    assert_eq!(exceptional::parse_markers(&contents).len(), 2);
+    let outcomes = exceptional::rerun_file::<SomeImportantAction>(&file).unwrap();
+    assert_eq!(outcomes.len(), 2);
+    for (_, outcome) in outcomes {
+        assert_eq!(outcome, Outcome::Fails(String::from("\"Whoopsie\"")));
+    }
+
+    // New files use `\n`.
+    let file = scratch_dir("line_ending_new").join("regressions.rs");
+    failure(0, UnitTestOptions::default())
+        .append_to_file(&file)
+        .unwrap();
+    assert!(!fs::read_to_string(&file).unwrap().contains('\r'));
+}
+
+#[test]
+fn edited_files_are_converted_to_the_configured_line_ending() {
+    let file = scratch_dir("line_ending_edit").join("regressions.rs");
+    let src = format!("mod a {{\r\n}}\n{}\n", exceptional::INSERTION_MARKER);
+    fs::write(&file, src).unwrap();
+    failure(
+        0,
+        UnitTestOptions::default()
+            .use_insertion_marker(true)
+            .line_ending(LineEnding::CrLf),
+    )
+    .append_to_file(&file)
+    .unwrap();
+    let contents = fs::read_to_string(&file).unwrap();
+    assert!(contents.starts_with("mod a {\r\n}\r\n// @exceptional id="));
+    assert!(contents.ends_with(&format!("{}\r\n", exceptional::INSERTION_MARKER)));
+    assert!(only_crlf(&contents), "{:?}", contents);
+}
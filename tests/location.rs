@@ -0,0 +1,22 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use common::SomeImportantAction;
+
+#[test]
+fn call_site_is_recorded() {
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+    let args = (2, 3);
+
+    let line = line!() + 1;
+    let test = exceptional::execute(&mut action, &args).unwrap_err();
+    let location = test.location.expect("execute records its caller");
+    assert_eq!(location.file(), file!());
+    assert_eq!(location.line(), line);
+
+    let source = test.to_string();
+    assert!(source.contains(&format!("/// originally failed at {}:{}\n", file!(), line)));
+}
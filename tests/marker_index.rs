@@ -0,0 +1,60 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use common::{scratch_dir, OtherAction, SomeImportantAction};
+use exceptional::{DedupKey, Generator, MarkerIndex, UnitTestOptions};
+
+fn failure(var_1: u32) -> exceptional::OwnedUnitTest<SomeImportantAction> {
+    let action = SomeImportantAction { var_1, var_2: 1 };
+    exceptional::execute_all(&action, vec![(2, 3)], exceptional::Mode::FirstFailure)
+        .pop()
+        .unwrap()
+}
+
+#[test]
+fn captured_failures_are_found_in_the_index() {
+    let file = scratch_dir("marker_index").join("regressions.rs");
+    assert!(MarkerIndex::load(&file).unwrap().is_empty());
+    failure(0).append_to_file(&file).unwrap();
+
+    let index = MarkerIndex::load(&file).unwrap();
+    assert_eq!(index.len(), 1);
+    assert!(failure(0).is_captured(&index));
+    assert!(!failure(1).is_captured(&index));
+    // The hashes depend on the options, like the markers that are written.
+    assert!(!failure(0)
+        .with_options(UnitTestOptions::default().hasher(exceptional::Hasher::Sha256))
+        .is_captured(&index));
+
+    // The index is not updated by appending to the file.
+    failure(1).append_to_file(&file).unwrap();
+    assert!(!failure(1).is_captured(&index));
+    let marker = exceptional::parse_markers(&std::fs::read_to_string(&file).unwrap()).remove(1);
+    let mut index = index;
+    index.insert(marker.hash.clone(), marker.error.clone());
+    assert!(failure(1).is_captured(&index));
+    assert!(index.contains(DedupKey::FullSnapshot, &marker.hash, &marker.error));
+    assert_eq!(index, MarkerIndex::load(&file).unwrap());
+}
+
+#[test]
+fn generators_check_with_their_own_options() {
+    let file = scratch_dir("marker_index_generator").join("regressions.rs");
+    let mut generator = Generator::open(&file, UnitTestOptions::default().sort_keys(true))
+        .unwrap()
+        .dedup_by(DedupKey::ErrorOnly);
+    assert!(!generator.is_captured(&failure(0)));
+    assert!(generator.capture(failure(0)).unwrap());
+    assert!(generator.is_captured(&failure(0)));
+    // The error is the same, which is what this generator compares.
+    assert!(generator.is_captured(&failure(1)));
+
+    let mut other = OtherAction {
+        message: String::from("nope"),
+    };
+    let test = exceptional::execute(&mut other, &()).unwrap_err();
+    assert!(!generator.is_captured(&test));
+}
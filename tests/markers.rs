@@ -0,0 +1,49 @@
+extern crate chrono;
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use chrono::{TimeZone, Utc};
+use common::SomeImportantAction;
+use exceptional::{Marker, Severity};
+
+#[test]
+fn markers_of_generated_tests_are_parsed() {
+    let mut source = String::new();
+    for &millis in &[1_539_761_233_543, 1_539_761_233_544] {
+        let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+        let test = exceptional::execute(&mut action, &(2, 3))
+            .unwrap_err()
+            .with_time(Utc.timestamp_millis_opt(millis).unwrap());
+        source.push_str(&test.to_string());
+    }
+
+    let markers = exceptional::parse_markers(&source);
+    assert_eq!(markers.len(), 2);
+    assert_eq!(markers[0].id, "1539761233543");
+    assert_eq!(markers[0].path, "::common::SomeImportantAction");
+    assert_eq!(markers[0].time, "2018-10-17T07:27:13.543Z");
+    assert_eq!(markers[0].hash.len(), 16);
+    assert_eq!(markers[0].severity, Severity::Medium);
+    assert_eq!(markers[0].error.len(), 16);
+    assert_eq!(markers[1].id, "1539761233544");
+    assert_eq!(markers[0].hash, markers[1].hash);
+}
+
+#[test]
+fn marker_format_is_stable() {
+    let src = "fn main() {}\n    // @exceptional id=7 hash=00000000000000ff path=::a::Map<::a::K, ::a::V> time=2018-10-17T07:27:13.543Z severity=high error=00000000000000aa future=field\n// @exceptional no-id\n// @exceptional-ish id=8\n";
+    assert_eq!(
+        exceptional::parse_markers(src),
+        vec![Marker {
+            id: String::from("7"),
+            hash: String::from("00000000000000ff"),
+            path: String::from("::a::Map<::a::K, ::a::V>"),
+            time: String::from("2018-10-17T07:27:13.543Z"),
+            severity: Severity::High,
+            error: String::from("00000000000000aa"),
+        }]
+    );
+}
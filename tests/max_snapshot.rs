@@ -0,0 +1,50 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use common::{scratch_dir, OtherAction};
+use exceptional::{DefaultRenderer, RenderError, UnitTestOptions};
+
+#[test]
+fn oversized_snapshots_are_refused() {
+    let mut action = OtherAction {
+        message: "x".repeat(1000),
+    };
+    let test = exceptional::execute(&mut action, &())
+        .unwrap_err()
+        .with_options(UnitTestOptions::default().max_snapshot_bytes(100));
+
+    let error = test.try_to_test_source().unwrap_err();
+    match error {
+        RenderError::SnapshotTooLarge { bytes, limit } => {
+            assert!(bytes > 1000, "{}", bytes);
+            assert_eq!(limit, 100);
+        }
+        ref other => panic!("Expected a size error, got {:?}", other),
+    }
+    let message = error.to_string();
+    assert!(
+        message.contains("more than the maximum of 100 bytes"),
+        "{}",
+        message
+    );
+    assert!(message.contains("execute_minimized"), "{}", message);
+
+    let file = scratch_dir("max_snapshot").join("test.rs");
+    let written = test.append_to_file_with(&file, &DefaultRenderer);
+    assert_eq!(written.unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+    assert!(!file.exists());
+}
+
+#[test]
+fn snapshots_within_the_limit_are_rendered() {
+    let mut action = OtherAction {
+        message: String::from("Whoopsie"),
+    };
+    let test = exceptional::execute(&mut action, &())
+        .unwrap_err()
+        .with_options(UnitTestOptions::default().max_snapshot_bytes(100));
+    assert!(test.try_to_test_source().is_ok());
+}
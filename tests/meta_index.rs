@@ -0,0 +1,107 @@
+extern crate chrono;
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_json;
+
+mod common;
+
+use chrono::{TimeZone, Utc};
+use common::{scratch_dir, SomeImportantAction};
+use exceptional::{IndexEntry, UnitTestOptions};
+use std::fs;
+use std::path::Path;
+
+fn append(file: &Path, var_1: u32, millis: i64) {
+    let action = SomeImportantAction { var_1, var_2: 1 };
+    exceptional::execute_all(&action, vec![(2, 3)], exceptional::Mode::FirstFailure)
+        .pop()
+        .unwrap()
+        .with_time(Utc.timestamp_millis_opt(millis).unwrap())
+        .with_options(UnitTestOptions::default().meta_index(true))
+        .append_to_file(file)
+        .unwrap();
+}
+
+fn read_index(file: &Path) -> Vec<IndexEntry> {
+    serde_json::from_str(&fs::read_to_string(exceptional::index_path(file)).unwrap()).unwrap()
+}
+
+#[test]
+fn the_index_lists_every_test_in_the_file() {
+    let file = scratch_dir("meta_index").join("regressions.rs");
+    append(&file, 0, 1_000);
+    append(&file, 1, 2_000);
+    assert_eq!(
+        exceptional::index_path(&file),
+        file.with_file_name("regressions.rs.meta.json")
+    );
+
+    let index = read_index(&file);
+    let markers = exceptional::parse_markers(&fs::read_to_string(&file).unwrap());
+    assert_eq!(index.len(), 2);
+    for (entry, marker) in index.iter().zip(&markers) {
+        assert_eq!(entry.id, marker.id);
+        assert_eq!(entry.hash, marker.hash);
+        assert_eq!(entry.error_hash, marker.error);
+        assert_eq!(entry.full_path, "::common::SomeImportantAction");
+        assert_eq!(entry.severity, "medium");
+        assert_eq!(
+            entry.description.as_ref().unwrap(),
+            "Executes some very important action!"
+        );
+        assert_eq!(entry.error_debug.as_ref().unwrap(), "\"Whoopsie\"");
+    }
+    assert_eq!(index[1].time, "1970-01-01T00:00:02.000Z");
+
+    let json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(exceptional::index_path(&file)).unwrap()).unwrap();
+    let keys: Vec<&String> = json[0].as_object().unwrap().keys().collect();
+    assert_eq!(
+        keys,
+        [
+            "description",
+            "error_debug",
+            "error_hash",
+            "full_path",
+            "hash",
+            "id",
+            "severity",
+            "time"
+        ]
+    );
+}
+
+#[test]
+fn the_index_follows_changes_to_the_file() {
+    let file = scratch_dir("meta_index_changes").join("regressions.rs");
+    append(&file, 0, 1_000);
+    append(&file, 1, 2_000);
+
+    // Remove the first test by hand.
+    let src = fs::read_to_string(&file).unwrap();
+    let second = src.find("// @exceptional id=2000").unwrap();
+    fs::write(&file, &src[second..]).unwrap();
+    let index = exceptional::rebuild_index(&file).unwrap();
+    assert_eq!(index, read_index(&file));
+    assert_eq!(index.len(), 1);
+    assert_eq!(index[0].id, "2000");
+    assert!(index[0].description.is_some());
+
+    // Renamed tests keep their details.
+    let migration = exceptional::migrate_ids(&file).unwrap();
+    let index = read_index(&file);
+    assert_eq!(index[0].id, migration.renamed[0].1);
+    assert_eq!(index[0].error_debug.as_ref().unwrap(), "\"Whoopsie\"");
+
+    // Tests that were not written with the index are listed without details.
+    let action = SomeImportantAction { var_1: 2, var_2: 1 };
+    exceptional::execute_all(&action, vec![(2, 3)], exceptional::Mode::FirstFailure)
+        .pop()
+        .unwrap()
+        .append_to_file(&file)
+        .unwrap();
+    let index = exceptional::rebuild_index(&file).unwrap();
+    assert_eq!(index.len(), 2);
+    assert_eq!(index[1].description, None);
+}
@@ -0,0 +1,31 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use common::SomeImportantAction;
+
+#[test]
+fn metadata_is_sorted_and_escaped() {
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+    let args = (2, 3);
+    let source = exceptional::execute(&mut action, &args)
+        .unwrap_err()
+        .with_metadata("severity", "high")
+        .with_metadata("owner", "payments\n#[test] fn injected() {}")
+        .with_metadata("severity", "low")
+        .to_string();
+
+    let meta: Vec<&str> = source
+        .lines()
+        .filter(|line| line.starts_with("/// meta: "))
+        .collect();
+    assert_eq!(
+        meta,
+        vec![
+            "/// meta: owner = payments\\n#[test] fn injected() {}",
+            "/// meta: severity = low",
+        ]
+    );
+}
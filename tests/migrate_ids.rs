@@ -0,0 +1,115 @@
+extern crate chrono;
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use chrono::{TimeZone, Utc};
+use common::SomeImportantAction;
+use exceptional::{IdMigration, UnitTestOptions};
+use std::fs;
+use std::path::Path;
+
+/// Append the failure of `var_1` with a timestamp id and the given options, and return the id that the content-hash id strategy gives it.
+fn append(file: &Path, var_1: u32, millis: i64, options: UnitTestOptions) -> String {
+    let mut action = SomeImportantAction { var_1, var_2: 1 };
+    let hashed = exceptional::execute(&mut action, &(2, 3))
+        .unwrap_err()
+        .with_options(
+            UnitTestOptions::default().id_strategy(|meta| format!("{:016x}", meta.payload_hash)),
+        )
+        .to_string();
+    exceptional::execute(&mut action, &(2, 3))
+        .unwrap_err()
+        .with_time(Utc.timestamp_millis_opt(millis).unwrap())
+        .with_options(options)
+        .append_to_file(file)
+        .unwrap();
+    exceptional::parse_markers(&hashed).remove(0).id
+}
+
+#[test]
+fn tests_are_renamed_to_content_hashes() {
+    let dir = common::scratch_dir("migrate_ids");
+    let file = dir.join("generated.rs");
+    let first = append(&file, 0, 1_539_761_233_543, UnitTestOptions::default());
+    let second = append(&file, 1, 1_539_761_233_544, UnitTestOptions::default());
+    // The same failure as the first test, a millisecond later.
+    append(&file, 0, 1_539_761_233_545, UnitTestOptions::default());
+    let mut src = fs::read_to_string(&file).unwrap();
+    src.push_str("fn helper() {\n    test_1539761233543();\n    test_1539761233543_x();\n}\n");
+    fs::write(&file, &src).unwrap();
+
+    let migration = exceptional::migrate_ids(&file).unwrap();
+    assert_eq!(
+        migration,
+        IdMigration {
+            renamed: vec![
+                (String::from("1539761233543"), first.clone()),
+                (String::from("1539761233544"), second.clone()),
+            ],
+            removed: vec![String::from("1539761233545")],
+        }
+    );
+
+    let migrated = fs::read_to_string(&file).unwrap();
+    let ids: Vec<String> = exceptional::parse_markers(&migrated)
+        .into_iter()
+        .map(|marker| marker.id)
+        .collect();
+    assert_eq!(ids, vec![first.clone(), second.clone()]);
+    assert!(migrated.contains(&format!("pub fn test_{}() {{", first)));
+    assert!(migrated.contains(&format!("pub fn test_{}() {{", second)));
+    assert!(migrated.contains(&format!("    test_{}();\n", first)));
+    assert!(migrated.contains("    test_1539761233543_x();\n"));
+    assert!(!migrated.contains("1539761233545"));
+    // Only the names changed.
+    let original_body = &src[src.find("\tuse exceptional::Executable;").unwrap()..];
+    let migrated_body = &migrated[migrated.find("\tuse exceptional::Executable;").unwrap()..];
+    assert_eq!(
+        original_body[..original_body.find('}').unwrap()],
+        migrated_body[..migrated_body.find('}').unwrap()]
+    );
+
+    // Migrating again changes nothing.
+    assert_eq!(
+        exceptional::migrate_ids(&file).unwrap(),
+        IdMigration::default()
+    );
+    assert_eq!(fs::read_to_string(&file).unwrap(), migrated);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn sidecar_files_are_renamed() {
+    let dir = common::scratch_dir("migrate_ids_sidecar");
+    let file = dir.join("generated.rs");
+    let id = append(
+        &file,
+        0,
+        1_539_761_233_543,
+        UnitTestOptions::default().sidecar_dir("snapshots"),
+    );
+
+    exceptional::migrate_ids(&file).unwrap();
+    let migrated = fs::read_to_string(&file).unwrap();
+    assert!(migrated.contains(&format!(
+        "let obj_json = include_str!(\"snapshots/{}.exec.json\");",
+        id
+    )));
+    assert!(migrated.contains(&format!(
+        "let arg_json = include_str!(\"snapshots/{}.args.json\");",
+        id
+    )));
+    let snapshots = dir.join("snapshots");
+    assert!(snapshots.join(format!("{}.exec.json", id)).exists());
+    assert!(snapshots.join(format!("{}.args.json", id)).exists());
+    assert!(!snapshots.join("1539761233543.exec.json").exists());
+    assert!(exceptional::prune_sidecars(&file, "snapshots")
+        .unwrap()
+        .is_empty());
+
+    let _ = fs::remove_dir_all(&dir);
+}
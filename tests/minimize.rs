@@ -0,0 +1,56 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+use exceptional::Executable;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+struct Inventory {
+    owner: String,
+    limit: u32,
+    enabled: bool,
+    items: Vec<u32>,
+}
+
+impl Executable for Inventory {
+    type Result = ();
+    type Error = String;
+    type Arguments = u32;
+
+    fn full_path(&self) -> &'static str {
+        "::Inventory"
+    }
+
+    fn description(&self) -> String {
+        String::from("Looks up an item")
+    }
+
+    fn execute(&mut self, needle: &u32) -> Result<(), String> {
+        if self.items.contains(needle) {
+            Err(format!("found forbidden item {}", needle))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[test]
+fn minimized_state_still_fails() {
+    let mut inventory = Inventory {
+        owner: String::from("someone"),
+        limit: 40,
+        enabled: true,
+        items: vec![1, 2, 7, 9],
+    };
+    let test = exceptional::execute_minimized(&mut inventory, &7).unwrap_err();
+    assert_eq!(
+        test.executable,
+        Inventory {
+            owner: String::new(),
+            limit: 0,
+            enabled: false,
+            items: vec![7],
+        }
+    );
+    assert_eq!(test.error, "found forbidden item 7");
+}
@@ -0,0 +1,81 @@
+//! Generated tests have to compile in crates that deny missing docs and unused imports, even if the module they are written to is public.
+#![deny(missing_docs, unused_imports)]
+
+extern crate chrono;
+extern crate exceptional;
+extern crate serde_json;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use chrono::{TimeZone, Utc};
+use common::scratch_dir;
+use exceptional::{CommentStyle, Executable, UnitTestOptions};
+use std::cell::Cell;
+use std::fs;
+
+thread_local! {
+    static BROKEN: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Looks something up, and fails while it is broken on the current thread.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Lookup {
+    key: String,
+}
+
+impl Executable for Lookup {
+    type Result = ();
+    type Error = String;
+    type Arguments = u32;
+
+    fn full_path(&self) -> &'static str {
+        "::Lookup"
+    }
+
+    fn description(&self) -> String {
+        String::from("Looks up a key")
+    }
+
+    fn execute(&mut self, _attempt: &u32) -> Result<(), String> {
+        if BROKEN.with(Cell::get) {
+            Err(format!("{} is broken", self.key))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// The tests that `generated_file_is_up_to_date` generates, compiled as a public module of this crate.
+// The header of a generated test is separated by empty lines, and the file is compared byte-for-byte.
+#[allow(clippy::empty_line_after_doc_comments)]
+#[rustfmt::skip]
+#[path = "missing_docs/generated.rs"]
+pub mod generated;
+
+#[test]
+fn generated_file_is_up_to_date() {
+    let file = scratch_dir("missing_docs").join("generated.rs");
+    BROKEN.with(|broken| broken.set(true));
+    for (millis, style) in &[(1_000, CommentStyle::Doc), (2_000, CommentStyle::Plain)] {
+        let mut lookup = Lookup {
+            key: String::from("answer"),
+        };
+        let mut test = exceptional::execute(&mut lookup, &1).unwrap_err();
+        test.location = None;
+        test.with_time(Utc.timestamp_millis_opt(*millis).unwrap())
+            .with_options(
+                UnitTestOptions::default()
+                    .default_banner()
+                    .comment_style(*style),
+            )
+            .append_to_file(&file)
+            .unwrap();
+    }
+    BROKEN.with(|broken| broken.set(false));
+    assert_eq!(
+        fs::read_to_string(&file).unwrap(),
+        include_str!("missing_docs/generated.rs")
+    );
+}
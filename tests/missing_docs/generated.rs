@@ -0,0 +1,54 @@
+//! This file is automatically generated by exceptional.
+//! DO NOT EDIT: manual changes may be lost when tests are generated again.
+
+// @exceptional id=1000 hash=ee7c05db6ea8b93f path=::Lookup time=1970-01-01T00:00:01.000Z error=0f8d23ed7b3c2f14
+/// Automatically generated unit test for Executable
+
+/// Looks up a key
+/// generated at Thu, 01 Jan 1970 00:00:01 +0000
+
+/// exception was "answer is broken"
+#[test]
+pub fn test_1000() {
+	#[allow(unused_imports)]
+	use exceptional::Executable;
+	let obj_json = r#"{
+  "key": "answer"
+}"#;
+	let mut obj: ::Lookup = ::serde_json::from_str(obj_json).expect("Could not deserialize json");
+	
+	let arg_json = r#"1"#;
+	let args = ::serde_json::from_str(arg_json).expect("Could not deserialize json");
+
+	if let Err(e) = obj.execute(&args) {
+		println!("Could not execute {}", obj.description());
+		println!("{:?}", e);
+		panic!();
+	}
+}
+// @exceptional id=2000 hash=ee7c05db6ea8b93f path=::Lookup time=1970-01-01T00:00:02.000Z error=0f8d23ed7b3c2f14
+// Automatically generated unit test for Executable
+
+// Looks up a key
+// generated at Thu, 01 Jan 1970 00:00:02 +0000
+
+// exception was "answer is broken"
+#[allow(missing_docs)]
+#[test]
+pub fn test_2000() {
+	#[allow(unused_imports)]
+	use exceptional::Executable;
+	let obj_json = r#"{
+  "key": "answer"
+}"#;
+	let mut obj: ::Lookup = ::serde_json::from_str(obj_json).expect("Could not deserialize json");
+	
+	let arg_json = r#"1"#;
+	let args = ::serde_json::from_str(arg_json).expect("Could not deserialize json");
+
+	if let Err(e) = obj.execute(&args) {
+		println!("Could not execute {}", obj.description());
+		println!("{:?}", e);
+		panic!();
+	}
+}
@@ -0,0 +1,100 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use common::scratch_dir;
+use exceptional::{Executable, UnitTestOptions};
+use std::cell::Cell;
+use std::fmt;
+use std::fs;
+
+thread_local! {
+    /// The number of lines in the errors of `Narrate` on the current thread, which isn't part of its state.
+    static ERROR_LINES: Cell<u32> = const { Cell::new(2) };
+}
+
+pub struct Report {
+    pub lines: u32,
+}
+
+impl fmt::Debug for Report {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "Report(")?;
+        for line in 0..self.lines {
+            write!(fmt, "\n    line\r{}", line)?;
+        }
+        write!(fmt, "\n)")
+    }
+}
+
+/// An executable with a description and an error that span several lines.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Narrate;
+
+impl Executable for Narrate {
+    type Result = ();
+    type Error = Report;
+    type Arguments = ();
+
+    fn full_path(&self) -> &'static str {
+        "::Narrate"
+    }
+
+    fn description(&self) -> String {
+        String::from("Narrates a repro\n\n1. open the file\n2. close it */ twice")
+    }
+
+    fn execute(&mut self, _args: &()) -> Result<(), Report> {
+        Err(Report {
+            lines: ERROR_LINES.with(Cell::get),
+        })
+    }
+}
+
+#[test]
+fn every_line_of_the_header_is_a_comment() {
+    let source = exceptional::execute(&mut Narrate, &())
+        .unwrap_err()
+        .to_string();
+    assert!(
+        source.contains(
+            "/// Narrates a repro\n///\n/// 1. open the file\n/// 2. close it */ twice\n"
+        ),
+        "{}",
+        source
+    );
+    assert!(
+        source.contains(
+            "/// exception was Report(\n///     line\\r0\n///     line\\r1\n/// )\n#[test]\n"
+        ),
+        "{}",
+        source
+    );
+    let header = &source[..source.find("#[test]").unwrap()];
+    assert!(
+        header
+            .lines()
+            .all(|line| line.is_empty() || line.starts_with("//")),
+        "{}",
+        source
+    );
+    assert_eq!(exceptional::parse_markers(&source).len(), 1);
+}
+
+#[test]
+fn a_change_on_a_later_line_of_the_error_is_upserted() {
+    let file = scratch_dir("multiline_comments").join("tests.rs");
+    for lines in 1..3 {
+        ERROR_LINES.with(|cell| cell.set(lines));
+        exceptional::execute(&mut Narrate, &())
+            .unwrap_err()
+            .with_options(UnitTestOptions::default().upsert(true))
+            .append_to_file(&file)
+            .unwrap();
+    }
+    let contents = fs::read_to_string(&file).unwrap();
+    assert_eq!(exceptional::parse_markers(&contents).len(), 1);
+    assert!(contents.contains("///     line\\r1\n"), "{}", contents);
+}
@@ -0,0 +1,80 @@
+extern crate chrono;
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use chrono::{TimeZone, Utc};
+use common::{OtherAction, SomeImportantAction};
+use exceptional::UnitTestOptions;
+
+fn function_name(source: &str) -> &str {
+    let start = source.find("pub fn ").unwrap() + "pub fn ".len();
+    let end = start + source[start..].find('(').unwrap();
+    &source[start..end]
+}
+
+/// Render a failure of both executables at the same millisecond, and return their function names.
+fn render(
+    mut action: SomeImportantAction,
+    mut other: OtherAction,
+    options: UnitTestOptions,
+) -> (String, String) {
+    // 2018-01-01T00:00:00Z
+    let time = Utc.timestamp_millis_opt(1_514_764_800_000).unwrap();
+    let first = exceptional::execute(&mut action, &(2, 3))
+        .unwrap_err()
+        .with_time(time)
+        .with_options(options.clone())
+        .to_string();
+    let second = exceptional::execute(&mut other, &())
+        .unwrap_err()
+        .with_time(time)
+        .with_options(options)
+        .to_string();
+    (
+        function_name(&first).to_owned(),
+        function_name(&second).to_owned(),
+    )
+}
+
+#[test]
+fn namespaced_ids_differ_between_executables() {
+    let action = SomeImportantAction { var_1: 0, var_2: 1 };
+    let other = OtherAction {
+        message: String::from("Whoopsie"),
+    };
+
+    let (first, second) = render(action.clone(), other.clone(), UnitTestOptions::default());
+    assert_eq!(first, "test_1514764800000");
+    assert_eq!(first, second);
+
+    let namespaced = UnitTestOptions::default().namespace_id(true);
+    let (first, second) = render(action, other, namespaced.clone());
+    assert!(first.starts_with("test_1514764800000_"));
+    assert_eq!(first.len(), "test_1514764800000_".len() + 8);
+    assert!(second.starts_with("test_1514764800000_"));
+    assert_ne!(first, second);
+
+    // The hash only depends on the type of the executable, not on its state.
+    let action = SomeImportantAction { var_1: 5, var_2: 1 };
+    let other = OtherAction {
+        message: String::from("Another"),
+    };
+    assert_eq!(render(action, other, namespaced), (first, second));
+}
+
+#[test]
+fn id_strategy_is_not_namespaced() {
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+    let source = exceptional::execute(&mut action, &(2, 3))
+        .unwrap_err()
+        .with_options(
+            UnitTestOptions::default()
+                .namespace_id(true)
+                .id_strategy(|_| String::from("custom")),
+        )
+        .to_string();
+    assert_eq!(function_name(&source), "test_custom");
+}
@@ -0,0 +1,96 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use common::scratch_dir;
+use exceptional::{Executable, StreamingWriter};
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct Upload {
+    retries: u32,
+    last_attempt: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct Request {
+    size: u64,
+    received_at: u64,
+}
+
+impl Executable for Upload {
+    type Result = ();
+    type Error = String;
+    type Arguments = Request;
+
+    fn full_path(&self) -> &'static str {
+        "::Upload"
+    }
+
+    fn description(&self) -> String {
+        String::from("Uploads a file")
+    }
+
+    fn execute(&mut self, request: &Request) -> Result<(), String> {
+        Err(format!(
+            "{} bytes is too large (attempt at {}, received at {})",
+            request.size, self.last_attempt, request.received_at
+        ))
+    }
+
+    fn normalize(&mut self, request: &mut Request) {
+        self.last_attempt = 0;
+        request.received_at = 0;
+    }
+}
+
+fn fail(time: u64) -> exceptional::UnitTest<'static, Upload> {
+    let mut upload = Upload {
+        retries: 2,
+        last_attempt: time,
+    };
+    let request = Request {
+        size: 10,
+        received_at: time,
+    };
+    let result = upload.execute(&request);
+    exceptional::UnitTest::from_result(upload.clone(), request, result).unwrap_err()
+}
+
+#[test]
+fn the_normalized_input_is_captured() {
+    let mut upload = Upload {
+        retries: 2,
+        last_attempt: 7,
+    };
+    let request = Request {
+        size: 10,
+        received_at: 8,
+    };
+    let test = exceptional::execute(&mut upload, &request).unwrap_err();
+
+    // The executable ran on the original input.
+    assert_eq!(
+        test.error,
+        "10 bytes is too large (attempt at 7, received at 8)"
+    );
+    assert_eq!(test.executable.last_attempt, 0);
+    assert_eq!(test.arguments.received_at, 0);
+    assert_eq!(request.received_at, 8);
+
+    let source = test.to_string();
+    assert!(source.contains("\"last_attempt\": 0"));
+    assert!(source.contains("\"received_at\": 0"));
+}
+
+#[test]
+fn failures_that_differ_before_normalizing_are_deduplicated() {
+    let file = scratch_dir("normalize").join("regressions.rs");
+    let mut writer = StreamingWriter::open(&file).unwrap();
+    assert!(writer.record(fail(1)).unwrap());
+    assert!(!writer.record(fail(2)).unwrap());
+    drop(writer);
+    let contents = std::fs::read_to_string(&file).unwrap();
+    assert_eq!(exceptional::parse_markers(&contents).len(), 1);
+}
@@ -0,0 +1,30 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use common::SomeImportantAction;
+use exceptional::UnitTestOptions;
+use std::fs;
+
+#[test]
+fn tests_are_appended_to_out_dir_without_banner() {
+    let dir = common::scratch_dir("out-dir");
+    std::env::set_var("OUT_DIR", &dir);
+
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+    let path = exceptional::execute(&mut action, &(2, 3))
+        .unwrap_err()
+        .with_options(UnitTestOptions::default().default_banner())
+        .append_to_out_dir("regressions.rs")
+        .unwrap();
+    assert_eq!(path, dir.join("regressions.rs"));
+
+    let contents = fs::read_to_string(&path).unwrap();
+    assert!(contents.starts_with("// @exceptional "));
+    assert!(!contents.contains("//!"));
+    assert_eq!(contents.matches("#[test]").count(), 1);
+
+    let _ = fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,73 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use exceptional::{Executable, UnitTestOptions};
+use std::fs;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Lookup {
+    pub preamble: String,
+}
+
+impl Executable for Lookup {
+    type Result = ();
+    type Error = String;
+    type Arguments = u32;
+
+    fn full_path(&self) -> &'static str {
+        "::Lookup"
+    }
+
+    fn description(&self) -> String {
+        String::from("Looks up a key")
+    }
+
+    fn file_preamble(&self) -> Option<String> {
+        Some(self.preamble.clone())
+    }
+
+    fn execute(&mut self, key: &u32) -> Result<(), String> {
+        Err(format!("{} is missing", key))
+    }
+}
+
+fn append(file: &std::path::Path, preamble: &str, key: u32, options: UnitTestOptions) {
+    let mut lookup = Lookup {
+        preamble: String::from(preamble),
+    };
+    exceptional::execute(&mut lookup, &key)
+        .unwrap_err()
+        .with_options(options)
+        .append_to_file(file)
+        .unwrap();
+}
+
+#[test]
+fn preamble_is_written_once() {
+    let dir = common::scratch_dir("preamble");
+    let file = dir.join("generated.rs");
+    let options = || UnitTestOptions::default().banner("Generated");
+
+    append(&file, "type Key = u32;", 1, options());
+    append(&file, "type Key = u32;", 2, options());
+    let written = fs::read_to_string(&file).unwrap();
+    assert!(written.starts_with(
+        "//! Generated\n\n// @exceptional-preamble path=::Lookup\ntype Key = u32;\n// @exceptional-preamble-end\n\n// @exceptional id="
+    ), "{}", written);
+    assert_eq!(written.matches("\ntype Key").count(), 1);
+    assert_eq!(written.matches("#[test]").count(), 2);
+    assert_eq!(exceptional::parse_markers(&written).len(), 2);
+
+    // A changed preamble replaces the old one.
+    append(&file, "type Key = u64;\n", 3, options());
+    let written = fs::read_to_string(&file).unwrap();
+    assert!(written.contains("\ntype Key = u64;\n// @exceptional-preamble-end\n"));
+    assert!(!written.contains("\ntype Key = u32;"));
+    assert_eq!(written.matches("@exceptional-preamble path=").count(), 1);
+    assert_eq!(written.matches("#[test]").count(), 3);
+
+    let _ = fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,131 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+use exceptional::{ErrorRender, Executable, UnitTestOptions};
+use std::fmt;
+
+/// An error with a hand-written `Display`.
+#[derive(Debug)]
+pub struct Overdrawn {
+    pub balance: i64,
+}
+
+impl fmt::Display for Overdrawn {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "the account is overdrawn by {}", -self.balance)
+    }
+}
+
+/// An error that only implements `Debug`.
+#[derive(Debug)]
+pub struct Opaque(pub u8);
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Withdraw;
+
+impl Executable for Withdraw {
+    type Result = ();
+    type Error = Overdrawn;
+    type Arguments = ();
+
+    fn full_path(&self) -> &'static str {
+        "::Withdraw"
+    }
+
+    fn description(&self) -> String {
+        String::from("Withdraws money")
+    }
+
+    fn execute(&mut self, _args: &()) -> Result<(), Overdrawn> {
+        Err(Overdrawn { balance: -10 })
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Poke;
+
+impl Executable for Poke {
+    type Result = ();
+    type Error = Opaque;
+    type Arguments = ();
+
+    fn full_path(&self) -> &'static str {
+        "::Poke"
+    }
+
+    fn description(&self) -> String {
+        String::from("Pokes")
+    }
+
+    fn execute(&mut self, _args: &()) -> Result<(), Opaque> {
+        Err(Opaque(7))
+    }
+}
+
+#[test]
+fn display_errors_can_be_printed_with_display() {
+    let source = exceptional::execute(&mut Withdraw, &())
+        .unwrap_err()
+        .prefer_display()
+        .to_string();
+    // The header describes the error by its `Display` output as well.
+    assert!(
+        source.contains("/// exception was the account is overdrawn by 10\n"),
+        "{}",
+        source
+    );
+    assert!(source.contains("\t\tprintln!(\"{}\", e);\n"), "{}", source);
+    assert!(!source.contains("{:?}"), "{}", source);
+
+    let source = exceptional::execute(&mut Withdraw, &())
+        .unwrap_err()
+        .with_options(UnitTestOptions::default().verbose_body(false))
+        .prefer_display()
+        .to_string();
+    assert!(
+        source.contains(
+            "\tobj.execute(&args).unwrap_or_else(|e| panic!(\"regression still fails: {}\", e));\n"
+        ),
+        "{}",
+        source
+    );
+}
+
+#[test]
+fn an_earlier_error_render_is_kept() {
+    let source = exceptional::execute(&mut Withdraw, &())
+        .unwrap_err()
+        .with_error_render(ErrorRender::Both)
+        .prefer_display()
+        .to_string();
+    assert!(
+        source.contains("/// exception debug: Overdrawn { balance: -10 }\n"),
+        "{}",
+        source
+    );
+    assert!(source.contains("\t\tprintln!(\"{}\", e);\n"), "{}", source);
+}
+
+#[test]
+fn debug_only_errors_are_printed_with_debug_by_default() {
+    let source = exceptional::execute(&mut Poke, &())
+        .unwrap_err()
+        .to_string();
+    assert!(
+        source.contains("/// exception was Opaque(7)\n"),
+        "{}",
+        source
+    );
+    assert!(
+        source.contains("\t\tprintln!(\"{:?}\", e);\n"),
+        "{}",
+        source
+    );
+
+    let source = exceptional::execute(&mut Poke, &())
+        .unwrap_err()
+        .with_options(UnitTestOptions::default().verbose_body(false))
+        .to_string();
+    assert!(source.contains("\tobj.execute(&args).expect(\"regression still fails\");\n"));
+}
@@ -0,0 +1,74 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use common::SomeImportantAction;
+use exceptional::Executable;
+
+/// An executable that needs global setup before it runs.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Logged;
+
+impl Executable for Logged {
+    type Result = ();
+    type Error = String;
+    type Arguments = ();
+
+    fn full_path(&self) -> &'static str {
+        "::Logged"
+    }
+
+    fn description(&self) -> String {
+        String::from("Fails with logging")
+    }
+
+    fn test_prelude(&self) -> Option<String> {
+        Some(String::from(
+            "let _ = env_logger::try_init();\nstd::env::set_var(\"MODE\", \"strict\");",
+        ))
+    }
+
+    fn test_epilogue(&self) -> Option<String> {
+        Some(String::from("std::env::remove_var(\"MODE\");"))
+    }
+
+    fn execute(&mut self, _args: &()) -> Result<(), String> {
+        Err(String::from("failed"))
+    }
+}
+
+#[test]
+fn prelude_and_epilogue_surround_the_body() {
+    let mut logged = Logged;
+    let test = exceptional::execute(&mut logged, &()).unwrap_err();
+    let source = test.to_string();
+    assert!(
+        source.contains("\tuse exceptional::Executable;\n\tlet _ = env_logger::try_init();\n\tstd::env::set_var(\"MODE\", \"strict\");\n\tlet obj_json = "),
+        "{}",
+        source
+    );
+    assert!(
+        source.ends_with("\t}\n\tstd::env::remove_var(\"MODE\");\n}\n"),
+        "{}",
+        source
+    );
+
+    let generated = test.generated_test().unwrap();
+    assert_eq!(generated.setup[0], "\tlet _ = env_logger::try_init();");
+    assert_eq!(
+        generated.teardown,
+        vec![String::from("\tstd::env::remove_var(\"MODE\");")]
+    );
+    assert_eq!(generated.render(), source);
+}
+
+#[test]
+fn executables_without_hooks_render_as_before() {
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+    let test = exceptional::execute(&mut action, &(2, 3)).unwrap_err();
+    let source = test.to_string();
+    assert!(source.contains("\tuse exceptional::Executable;\n\tlet obj_json = "));
+    assert!(test.generated_test().unwrap().teardown.is_empty());
+}
@@ -0,0 +1,50 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use common::{scratch_dir, OtherAction};
+use exceptional::{Outcome, UnitTestOptions};
+use std::fs;
+
+const MESSAGE: &str = "a \"# and a \"## in the payload";
+
+fn append(file: &std::path::Path, id: &'static str, options: UnitTestOptions) {
+    let mut action = OtherAction {
+        message: String::from(MESSAGE),
+    };
+    exceptional::execute(&mut action, &())
+        .unwrap_err()
+        .with_options(options.id_strategy(move |_| id.to_owned()))
+        .append_to_file(file)
+        .unwrap();
+}
+
+#[test]
+fn literals_do_not_end_inside_of_the_payload() {
+    let file = scratch_dir("raw_string").join("test.rs");
+    append(&file, "inline", UnitTestOptions::default());
+    append(
+        &file,
+        "hoisted",
+        UnitTestOptions::default().hoist_inputs(true),
+    );
+
+    let contents = fs::read_to_string(&file).unwrap();
+    assert!(contents.contains("let obj_json = r###\"{"));
+    assert!(contents.contains("const INPUT_HOISTED_OBJ: &str = r###\"{"));
+    // Literals without the delimiter in them keep a single `#`.
+    assert!(contents.contains("let arg_json = r#\"null\"#;"));
+
+    assert_eq!(exceptional::parse_markers(&contents).len(), 2);
+    let outcomes = exceptional::rerun_file::<OtherAction>(&file).unwrap();
+    let ids: Vec<&str> = outcomes.iter().map(|(id, _)| id.as_str()).collect();
+    assert_eq!(ids, ["inline", "hoisted"]);
+    for (_, outcome) in outcomes {
+        match outcome {
+            Outcome::Fails(ref error) => assert!(error.contains("in the payload"), "{}", error),
+            other => panic!("Expected the snapshot to be read back, got {:?}", other),
+        }
+    }
+}
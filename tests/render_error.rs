@@ -0,0 +1,101 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use exceptional::{Executable, RenderError};
+use std::collections::HashMap;
+use std::{fs, io};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct Inner {
+    lookup: HashMap<Point, u32>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct Grid {
+    name: String,
+    inner: Inner,
+}
+
+impl Executable for Grid {
+    type Result = ();
+    type Error = String;
+    type Arguments = ();
+
+    fn full_path(&self) -> &'static str {
+        "::Grid"
+    }
+
+    fn description(&self) -> String {
+        String::from("Uses a map with struct keys")
+    }
+
+    fn execute(&mut self, _args: &()) -> Result<(), String> {
+        Err(String::from("always fails"))
+    }
+}
+
+fn grid() -> Grid {
+    let mut lookup = HashMap::new();
+    lookup.insert(Point { x: 1, y: 2 }, 3);
+    Grid {
+        name: String::from("grid"),
+        inner: Inner { lookup },
+    }
+}
+
+#[test]
+fn render_error_names_the_unserializable_field() {
+    let mut grid = grid();
+    let test = exceptional::execute(&mut grid, &()).unwrap_err();
+
+    match test.try_to_test_source() {
+        Err(RenderError::Executable(e)) => {
+            assert_eq!(e.path.as_deref(), Some("inner.lookup[{\"x\":1,\"y\":2}]"));
+            assert!(e.message.contains("key must be a string"), "{}", e.message);
+        }
+        other => panic!("Expected an executable error, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn display_writes_the_error_as_a_comment() {
+    let mut grid = grid();
+    let test = exceptional::execute(&mut grid, &()).unwrap_err();
+    let source = test.to_string();
+    assert!(
+        source.starts_with(
+            "// Could not render the unit test for ::Grid: could not serialize the executable"
+        ),
+        "{}",
+        source
+    );
+    assert_eq!(source.lines().count(), 1);
+}
+
+#[test]
+fn append_to_file_returns_the_error() {
+    let file = common::scratch_dir("render_error").join("regressions.rs");
+    let mut grid = grid();
+    let error = exceptional::execute(&mut grid, &())
+        .unwrap_err()
+        .append_to_file(&file)
+        .unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    assert!(error
+        .to_string()
+        .contains("could not serialize the executable"));
+    assert!(!file.exists());
+    assert!(fs::read_dir(file.parent().unwrap())
+        .unwrap()
+        .next()
+        .is_none());
+}
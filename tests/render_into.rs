@@ -0,0 +1,81 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use common::SomeImportantAction;
+use exceptional::{Mode, OwnedUnitTest};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+/// Counts the allocations of the current thread, so tests that run in parallel don't interfere.
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn allocations(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    f();
+    ALLOCATIONS.with(Cell::get) - before
+}
+
+fn batch() -> Vec<OwnedUnitTest<SomeImportantAction>> {
+    let action = SomeImportantAction { var_1: 0, var_2: 1 };
+    (0..10_000)
+        .flat_map(|i| exceptional::execute_all(&action, vec![(i, 3)], Mode::FirstFailure))
+        .collect()
+}
+
+#[test]
+fn render_into_appends_to_the_buffer() {
+    let tests = batch();
+    let mut buf = String::from("// existing\n");
+    tests[0].render_into(&mut buf).unwrap();
+    assert_eq!(buf, format!("// existing\n{}", tests[0]));
+}
+
+#[test]
+fn reusing_a_buffer_allocates_less_for_a_large_batch() {
+    let tests = batch();
+    let fresh = allocations(|| {
+        let mut out = String::new();
+        for test in &tests {
+            out.push_str(&test.to_string());
+        }
+    });
+    let reused = allocations(|| {
+        let mut buf = String::with_capacity(16 * 1024);
+        for test in &tests {
+            buf.clear();
+            test.render_into(&mut buf).unwrap();
+        }
+    });
+    assert!(
+        reused < fresh,
+        "{} allocations with a reused buffer, {} without",
+        reused,
+        fresh
+    );
+}
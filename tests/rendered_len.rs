@@ -0,0 +1,61 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use common::{scratch_dir, SomeImportantAction};
+use exceptional::{Mode, RenderError, UnitTestOptions};
+use std::fs;
+
+#[test]
+fn rendered_len_matches_the_appended_bytes() {
+    let dir = scratch_dir("rendered_len");
+    let file = dir.join("tests.rs");
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+    let test = exceptional::execute(&mut action, &(2, 3)).unwrap_err();
+    let len = test.rendered_len().unwrap();
+    assert_eq!(len, test.to_string().len());
+    test.append_to_file(&file).unwrap();
+    assert_eq!(fs::metadata(&file).unwrap().len() as usize, len);
+}
+
+#[test]
+fn rendered_len_includes_sidecar_files() {
+    let dir = scratch_dir("rendered_len_sidecar");
+    let file = dir.join("tests.rs");
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+    let test = exceptional::execute(&mut action, &(2, 3))
+        .unwrap_err()
+        .with_options(UnitTestOptions::default().sidecar_dir("snapshots"));
+    let len = test.rendered_len().unwrap();
+    test.append_to_file(&file).unwrap();
+
+    let mut written = fs::metadata(&file).unwrap().len() as usize;
+    for entry in fs::read_dir(dir.join("snapshots")).unwrap() {
+        written += entry.unwrap().metadata().unwrap().len() as usize;
+    }
+    assert_eq!(written, len);
+}
+
+#[test]
+fn total_adds_up_every_test() {
+    let action = SomeImportantAction { var_1: 0, var_2: 1 };
+    let tests = exceptional::execute_all(&action, (0..4).map(|i| (i, 3)), Mode::AllFailures);
+    let expected: usize = tests.iter().map(|test| test.to_string().len()).sum();
+    assert_eq!(exceptional::total_rendered_len(&tests), Ok(expected));
+    assert_eq!(exceptional::total_rendered_len(&tests[..0]), Ok(0));
+}
+
+#[test]
+fn total_returns_the_first_error() {
+    let action = SomeImportantAction { var_1: 0, var_2: 1 };
+    let tests: Vec<_> = exceptional::execute_all(&action, vec![(1, 3)], Mode::AllFailures)
+        .into_iter()
+        .map(|test| test.with_options(UnitTestOptions::default().max_snapshot_bytes(1)))
+        .collect();
+    match exceptional::total_rendered_len(&tests) {
+        Err(RenderError::SnapshotTooLarge { limit: 1, .. }) => {}
+        other => panic!("expected SnapshotTooLarge, got {:?}", other),
+    }
+}
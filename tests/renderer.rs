@@ -0,0 +1,68 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use common::SomeImportantAction;
+use exceptional::{DefaultRenderer, Executable, JunitRenderer, RenderError, Renderer, UnitTest};
+use std::fs;
+
+struct Summary;
+
+impl Renderer for Summary {
+    fn render<'a, E: Executable + 'a>(
+        &self,
+        test: &UnitTest<'a, E>,
+    ) -> Result<String, RenderError> {
+        Ok(format!(
+            "{}: {:?}\n",
+            test.executable.full_path(),
+            test.error
+        ))
+    }
+}
+
+#[test]
+fn custom_renderer_replaces_the_generated_test() {
+    let dir = common::scratch_dir("renderer");
+    let file = dir.join("summary.txt");
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+    let test = exceptional::execute(&mut action, &(2, 3)).unwrap_err();
+
+    assert_eq!(
+        test.render_with(&DefaultRenderer).unwrap(),
+        test.to_string()
+    );
+    assert_eq!(
+        test.render_with(&Summary).unwrap(),
+        "::common::SomeImportantAction: \"Whoopsie\"\n"
+    );
+
+    test.append_to_file_with(&file, &Summary).unwrap();
+    assert_eq!(
+        fs::read_to_string(&file).unwrap(),
+        "::common::SomeImportantAction: \"Whoopsie\"\n"
+    );
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn junit_renderer_escapes_the_error() {
+    let mut action = common::OtherAction {
+        message: String::from("<bad> & \"worse\"\u{1b}"),
+    };
+    let test = exceptional::execute(&mut action, &()).unwrap_err();
+    let testcase = test.render_with(&JunitRenderer).unwrap();
+
+    assert!(testcase.starts_with(
+        "  <testcase name=\"Always fails\" classname=\"::common::OtherAction\" timestamp=\""
+    ));
+    assert!(testcase.contains(
+        "<failure message=\"&quot;&lt;bad&gt; &amp; \\&quot;worse\\&quot;\\u{1b}&quot;\">"
+    ));
+
+    let suite = JunitRenderer::testsuite("exceptional", std::slice::from_ref(&testcase));
+    assert!(suite.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuite name=\"exceptional\" tests=\"1\" failures=\"1\">\n"));
+    assert!(suite.ends_with(&format!("{}</testsuite>\n", testcase)));
+}
@@ -0,0 +1,40 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use common::SomeImportantAction;
+use exceptional::ReplayError;
+
+#[test]
+fn stored_snapshots_are_replayed() {
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+    let test = exceptional::execute(&mut action, &(2, 3)).unwrap_err();
+    let (obj_json, arg_json) = test.snapshots().unwrap();
+    assert_eq!(
+        exceptional::replay::<SomeImportantAction>(&obj_json, &arg_json),
+        Err(ReplayError::Failed(String::from("Whoopsie")))
+    );
+    assert_eq!(
+        exceptional::replay::<SomeImportantAction>(&obj_json, "[2, 4]"),
+        Ok(())
+    );
+}
+
+#[test]
+fn invalid_json_is_an_error() {
+    let obj_json = r#"{"var_1": 0, "var_2": 1}"#;
+    match exceptional::replay::<SomeImportantAction>(r#"{"var_1": 0}"#, "[2, 3]") {
+        Err(ReplayError::Executable(e)) => assert!(e.contains("var_2"), "{}", e),
+        other => panic!("Expected an executable error, got {:?}", other),
+    }
+    let error = exceptional::replay::<SomeImportantAction>(obj_json, "[2]").unwrap_err();
+    match error {
+        ReplayError::Arguments(_) => {}
+        ref other => panic!("Expected an arguments error, got {:?}", other),
+    }
+    assert!(error
+        .to_string()
+        .starts_with("could not deserialize the arguments: "));
+}
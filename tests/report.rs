@@ -0,0 +1,41 @@
+extern crate chrono;
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_json;
+
+mod common;
+
+use chrono::{TimeZone, Utc};
+use common::SomeImportantAction;
+use exceptional::{RenderError, UnitTestReport};
+
+#[test]
+fn report_summarizes_the_failure() {
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+    let test = exceptional::execute(&mut action, &(2, 3))
+        .unwrap_err()
+        // 2018-10-17T07:27:13.543Z
+        .with_time(Utc.timestamp_millis_opt(1_539_761_233_543).unwrap());
+
+    let report: Result<UnitTestReport, RenderError> = Result::from(&test);
+    let report = report.unwrap();
+    let (executable_json, arguments_json) = test.snapshots().unwrap();
+    assert_eq!(
+        report,
+        UnitTestReport {
+            description: String::from("Executes some very important action!"),
+            full_path: String::from("::common::SomeImportantAction"),
+            time: String::from("2018-10-17T07:27:13.543Z"),
+            error_debug: String::from("\"Whoopsie\""),
+            executable_json,
+            arguments_json,
+        }
+    );
+
+    let line = serde_json::to_string(&report).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&line).unwrap();
+    assert_eq!(value["error_debug"], "\"Whoopsie\"");
+    assert_eq!(value["time"], "2018-10-17T07:27:13.543Z");
+    assert!(!line.contains('\n'));
+}
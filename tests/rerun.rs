@@ -0,0 +1,55 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use common::{scratch_dir, SomeImportantAction};
+use exceptional::{Outcome, UnitTestOptions};
+use std::fs;
+
+fn append(file: &std::path::Path, var_1: u32, id: &'static str, options: UnitTestOptions) {
+    let mut action = SomeImportantAction { var_1, var_2: 1 };
+    exceptional::execute(&mut action, &(2, 3))
+        .unwrap_err()
+        .with_options(options.id_strategy(move |_| id.to_owned()))
+        .append_to_file(file)
+        .unwrap();
+}
+
+#[test]
+fn rerun_reports_which_tests_still_fail() {
+    let file = scratch_dir("rerun").join("test.rs");
+    append(&file, 1, "still_fails", UnitTestOptions::default());
+    append(&file, 2, "fixed", UnitTestOptions::default());
+    append(&file, 3, "broken", UnitTestOptions::default());
+    append(
+        &file,
+        4,
+        "sidecar",
+        UnitTestOptions::default().sidecar_dir("snapshots"),
+    );
+
+    // Pretend the second failure was fixed, and break the snapshot of the third one.
+    let src = fs::read_to_string(&file).unwrap();
+    let fixed = src.find("test_fixed").unwrap();
+    let broken = src.find("test_broken").unwrap();
+    let src = format!(
+        "{}{}{}",
+        &src[..fixed],
+        src[fixed..broken].replacen("3\n]", "4\n]", 1),
+        src[broken..].replacen("\"var_1\": 3", "\"var_1\": \"three\"", 1)
+    );
+    fs::write(&file, src).unwrap();
+
+    let outcomes = exceptional::rerun_file::<SomeImportantAction>(&file).unwrap();
+    let ids: Vec<&str> = outcomes.iter().map(|(id, _)| id.as_str()).collect();
+    assert_eq!(ids, ["still_fails", "fixed", "broken", "sidecar"]);
+    assert_eq!(outcomes[0].1, Outcome::Fails(String::from("\"Whoopsie\"")));
+    assert_eq!(outcomes[1].1, Outcome::Passes);
+    match outcomes[2].1 {
+        Outcome::Unreadable(ref reason) => assert!(reason.contains("obj_json"), "{}", reason),
+        ref other => panic!("Expected an unreadable snapshot, got {:?}", other),
+    }
+    assert_eq!(outcomes[3].1, Outcome::Fails(String::from("\"Whoopsie\"")));
+}
@@ -0,0 +1,128 @@
+extern crate chrono;
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use chrono::{TimeZone, Utc};
+use common::{OtherAction, SomeImportantAction};
+use exceptional::{Executable, TestFileWriter};
+use std::fs;
+
+/// An executable that reports one of two paths which convert to the same module name.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct Client {
+    underscore: bool,
+}
+
+impl Executable for Client {
+    type Result = ();
+    type Error = String;
+    type Arguments = ();
+
+    fn full_path(&self) -> &'static str {
+        if self.underscore {
+            "::net::Http_Client"
+        } else {
+            "::net::HttpClient"
+        }
+    }
+
+    fn description(&self) -> String {
+        String::from("Connects")
+    }
+
+    fn execute(&mut self, _args: &()) -> Result<(), String> {
+        Err(String::from("refused"))
+    }
+}
+
+#[test]
+fn resplit_groups_tests_by_type() {
+    let dir = common::scratch_dir("resplit");
+    let src_file = dir.join("generated.rs");
+    let out_dir = dir.join("split");
+
+    let mut writer = TestFileWriter::new();
+    let mut expected_some = String::new();
+    let mut expected_other = String::new();
+    for i in 0..2 {
+        let time = Utc.timestamp_millis_opt(1_539_761_233_543 + i).unwrap();
+        let mut action = SomeImportantAction {
+            var_1: i as u32,
+            var_2: 0,
+        };
+        let test = exceptional::execute(&mut action, &(0, 3))
+            .unwrap_err()
+            .with_time(time);
+        expected_some.push_str(&test.to_string());
+        writer.push(test);
+
+        let mut other = OtherAction {
+            message: format!("failure {}", i),
+        };
+        // A later time, so the ids don't collide with the tests above.
+        let test = exceptional::execute(&mut other, &())
+            .unwrap_err()
+            .with_time(time + chrono::Duration::seconds(1));
+        expected_other.push_str(&test.to_string());
+        writer.push(test);
+    }
+    fs::write(&src_file, "// hand-written code is not copied\n").unwrap();
+    writer.flush_to_file(&src_file).unwrap();
+
+    let written = exceptional::resplit_by_type(&src_file, &out_dir).unwrap();
+    assert_eq!(
+        written,
+        vec![
+            out_dir.join("common_other_action_c45b4c5f.rs"),
+            out_dir.join("common_some_important_action_e640b184.rs"),
+        ]
+    );
+    assert_eq!(fs::read_to_string(&written[0]).unwrap(), expected_other);
+    assert_eq!(fs::read_to_string(&written[1]).unwrap(), expected_some);
+    assert_eq!(
+        fs::read_to_string(out_dir.join("mod.rs")).unwrap(),
+        "mod common_other_action_c45b4c5f;\nmod common_some_important_action_e640b184;\n"
+    );
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn paths_with_the_same_module_name_are_kept_apart() {
+    let dir = common::scratch_dir("resplit_collision");
+    let src_file = dir.join("generated.rs");
+    let out_dir = dir.join("split");
+
+    let mut writer = TestFileWriter::new();
+    for (i, &underscore) in [false, true].iter().enumerate() {
+        let mut client = Client { underscore };
+        let test = exceptional::execute(&mut client, &())
+            .unwrap_err()
+            .with_time(Utc.timestamp_millis_opt(1_000 + i as i64).unwrap());
+        writer.push(test);
+    }
+    writer.flush_to_file(&src_file).unwrap();
+
+    let written = exceptional::resplit_by_type(&src_file, &out_dir).unwrap();
+    assert_eq!(written.len(), 2);
+    assert_ne!(written[0], written[1]);
+    for path in &written {
+        let name = path.file_name().unwrap().to_str().unwrap();
+        assert!(name.starts_with("net_http_client_"), "{}", name);
+        assert_eq!(
+            exceptional::parse_markers(&fs::read_to_string(path).unwrap()).len(),
+            1
+        );
+    }
+
+    // Splitting again writes to the same modules.
+    assert_eq!(
+        exceptional::resplit_by_type(&src_file, &out_dir).unwrap(),
+        written
+    );
+
+    let _ = fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,154 @@
+extern crate chrono;
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+use chrono::{TimeZone, Utc};
+use exceptional::{Executable, Harness, Mode, UnitTestOptions};
+use std::cell::Cell;
+
+thread_local! {
+    static SEED: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Reseed the RNG of this thread, like a generated test does.
+pub fn reseed(seed: u64) {
+    SEED.with(|s| s.set(seed));
+}
+
+/// A very bad RNG, which is enough to make the outcome depend on the seed.
+fn next_random() -> u64 {
+    SEED.with(|s| {
+        let next = s
+            .get()
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1);
+        s.set(next);
+        next >> 33
+    })
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct Shuffle {
+    seed: Option<u64>,
+    setup: bool,
+}
+
+impl Executable for Shuffle {
+    type Result = u64;
+    type Error = String;
+    type Arguments = u64;
+
+    fn full_path(&self) -> &'static str {
+        "::Shuffle"
+    }
+
+    fn description(&self) -> String {
+        String::from("Picks a random bucket")
+    }
+
+    fn execute(&mut self, buckets: &u64) -> Result<u64, String> {
+        if let Some(seed) = self.seed {
+            reseed(seed);
+        }
+        match next_random() % buckets {
+            0 => Err(String::from("Picked the empty bucket")),
+            bucket => Ok(bucket),
+        }
+    }
+
+    fn rng_seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    fn rng_setup(&self) -> Option<String> {
+        if self.setup {
+            Some(String::from("::rng_seed::reseed(seed);"))
+        } else {
+            None
+        }
+    }
+}
+
+fn render(shuffle: Shuffle, options: UnitTestOptions) -> String {
+    // Everything lands in the empty bucket if there is only one.
+    let test = exceptional::execute_all(&shuffle, vec![1], Mode::FirstFailure)
+        .pop()
+        .unwrap();
+    test.with_time(Utc.timestamp_millis_opt(1_539_761_233_543).unwrap())
+        .with_options(options)
+        .to_string()
+}
+
+#[test]
+fn executable_uses_its_seed() {
+    reseed(0);
+    let mut shuffle = Shuffle {
+        seed: Some(3),
+        setup: true,
+    };
+    let first = shuffle.execute(&1_000);
+    assert_eq!(shuffle.execute(&1_000), first);
+}
+
+#[test]
+fn seed_is_written_to_the_comment() {
+    let source = render(
+        Shuffle {
+            seed: Some(42),
+            setup: false,
+        },
+        UnitTestOptions::default(),
+    );
+    assert!(source.contains("\n/// rng seed: 42\n"));
+    assert!(!source.contains("let seed"));
+}
+
+#[test]
+fn setup_runs_before_the_executable() {
+    let source = render(
+        Shuffle {
+            seed: Some(42),
+            setup: true,
+        },
+        UnitTestOptions::default(),
+    );
+    let setup = source
+        .find("\tlet seed: u64 = 42;\n\t::rng_seed::reseed(seed);\n")
+        .expect("no rng setup");
+    assert!(setup < source.find("obj.execute(&args)").unwrap());
+}
+
+#[test]
+fn no_seed_means_no_setup() {
+    let source = render(
+        Shuffle {
+            seed: None,
+            setup: true,
+        },
+        UnitTestOptions::default(),
+    );
+    assert!(!source.contains("rng seed"));
+    assert!(!source.contains("let seed"));
+}
+
+#[test]
+fn groups_read_the_seed_of_every_case() {
+    let tests: Vec<_> = [1, 2]
+        .iter()
+        .map(|&seed| {
+            let shuffle = Shuffle {
+                seed: Some(seed),
+                setup: true,
+            };
+            exceptional::execute_all(&shuffle, vec![1], Mode::FirstFailure)
+                .pop()
+                .unwrap()
+                .with_options(UnitTestOptions::default().harness(Harness::TestCase))
+        })
+        .collect();
+    let source = exceptional::render_group(&tests).unwrap();
+    assert!(source.contains(
+        "\tlet seed: u64 = obj.rng_seed().expect(\"the executable has no rng seed\");\n\t::rng_seed::reseed(seed);\n"
+    ));
+}
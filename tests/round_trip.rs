@@ -0,0 +1,154 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use common::SomeImportantAction;
+use exceptional::{Executable, RenderError};
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct Cached {
+    name: String,
+    #[serde(skip_serializing)]
+    cache: u32,
+}
+
+impl Executable for Cached {
+    type Result = ();
+    type Error = String;
+    type Arguments = Window;
+
+    fn full_path(&self) -> &'static str {
+        "::Cached"
+    }
+
+    fn description(&self) -> String {
+        String::from("Skips a field that it needs to deserialize")
+    }
+
+    fn execute(&mut self, _window: &Window) -> Result<(), String> {
+        Err(format!("cache {} is stale", self.cache))
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct Window {
+    start: u32,
+    #[serde(skip_deserializing)]
+    end: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct Lossless {
+    name: String,
+}
+
+impl Executable for Lossless {
+    type Result = ();
+    type Error = String;
+    type Arguments = Window;
+
+    fn full_path(&self) -> &'static str {
+        "::Lossless"
+    }
+
+    fn description(&self) -> String {
+        String::from("Round-trips, but its arguments don't")
+    }
+
+    fn execute(&mut self, _window: &Window) -> Result<(), String> {
+        Err(String::from("always fails"))
+    }
+}
+
+#[test]
+fn executable_that_does_not_deserialize_is_not_rendered() {
+    let mut cached = Cached {
+        name: String::from("cached"),
+        cache: 3,
+    };
+    let window = Window { start: 0, end: 0 };
+    let test = exceptional::execute(&mut cached, &window).unwrap_err();
+
+    match test.try_to_test_source() {
+        Err(RenderError::ExecutableRoundTrip(e)) => {
+            assert!(e.contains("missing field `cache`"), "{}", e)
+        }
+        other => panic!("Expected a round-trip error, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn lossy_arguments_are_rejected() {
+    let mut lossless = Lossless {
+        name: String::from("lossless"),
+    };
+    let window = Window { start: 1, end: 5 };
+    let test = exceptional::execute(&mut lossless, &window).unwrap_err();
+
+    match test.check_round_trip() {
+        Err(RenderError::ArgumentsRoundTrip(e)) => {
+            assert!(e.contains("different value"), "{}", e)
+        }
+        other => panic!("Expected a round-trip error, got {:?}", other),
+    }
+    match test.try_to_test_source() {
+        Err(RenderError::ArgumentsRoundTrip(_)) => {}
+        other => panic!("Expected a round-trip error, got {:?}", other.map(|_| ())),
+    }
+}
+
+/// An enum with a variant that is serialized, but can't be deserialized.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "kind")]
+enum Command {
+    Move {
+        distance: u32,
+    },
+    #[serde(skip_deserializing)]
+    Stop,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct Robot;
+
+impl Executable for Robot {
+    type Result = ();
+    type Error = String;
+    type Arguments = Command;
+
+    fn full_path(&self) -> &'static str {
+        "::Robot"
+    }
+
+    fn description(&self) -> String {
+        String::from("Runs a command")
+    }
+
+    fn execute(&mut self, _command: &Command) -> Result<(), String> {
+        Err(String::from("out of battery"))
+    }
+}
+
+#[test]
+fn enum_arguments_are_checked_when_rendering() {
+    let mut robot = Robot;
+    let test = exceptional::execute(&mut robot, &Command::Stop).unwrap_err();
+    match test.try_to_test_source() {
+        Err(RenderError::ArgumentsRoundTrip(e)) => assert!(e.contains("Stop"), "{}", e),
+        other => panic!("Expected a round-trip error, got {:?}", other.map(|_| ())),
+    }
+
+    let command = Command::Move { distance: 3 };
+    let test = exceptional::execute(&mut robot, &command).unwrap_err();
+    assert!(test.try_to_test_source().is_ok());
+    assert_eq!(test.check_round_trip(), Ok(()));
+}
+
+#[test]
+fn round_tripping_snapshots_pass() {
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+    let test = exceptional::execute(&mut action, &(2, 3)).unwrap_err();
+    assert_eq!(test.check_round_trip(), Ok(()));
+}
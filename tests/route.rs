@@ -0,0 +1,70 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use common::{scratch_dir, SomeImportantAction};
+use exceptional::UnitTestOptions;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+fn failure(var_1: u32, category: &str) -> exceptional::OwnedUnitTest<SomeImportantAction> {
+    let action = SomeImportantAction { var_1, var_2: 1 };
+    exceptional::execute_all(&action, vec![(2, 3)], exceptional::Mode::FirstFailure)
+        .pop()
+        .unwrap()
+        .with_metadata("category", category)
+}
+
+fn routed(dir: PathBuf) -> UnitTestOptions {
+    UnitTestOptions::default().route(move |meta| {
+        match meta.metadata.get("category").map(String::as_str) {
+            Some("db") => Some(dir.join("db_repros.rs")),
+            Some("ignored") => None,
+            _ => Some(dir.join("repros.rs")),
+        }
+    })
+}
+
+#[test]
+fn tests_are_routed_to_the_chosen_file_once() {
+    let dir = scratch_dir("route");
+    let options = routed(dir.clone());
+
+    let written = failure(0, "db")
+        .with_options(options.clone())
+        .append_routed();
+    assert_eq!(written.unwrap(), Some(dir.join("db_repros.rs")));
+    let written = failure(0, "ui")
+        .with_options(options.clone())
+        .append_routed();
+    assert_eq!(written.unwrap(), Some(dir.join("repros.rs")));
+
+    // The same failure is already in the file it is routed to, but not in the other one.
+    let written = failure(0, "db")
+        .with_options(options.clone())
+        .append_routed();
+    assert_eq!(written.unwrap(), None);
+    let written = failure(1, "db")
+        .with_options(options.clone())
+        .append_routed();
+    assert_eq!(written.unwrap(), Some(dir.join("db_repros.rs")));
+
+    let written = failure(2, "ignored").with_options(options).append_routed();
+    assert_eq!(written.unwrap(), None);
+
+    let db = fs::read_to_string(dir.join("db_repros.rs")).unwrap();
+    let other = fs::read_to_string(dir.join("repros.rs")).unwrap();
+    assert_eq!(exceptional::parse_markers(&db).len(), 2);
+    assert_eq!(exceptional::parse_markers(&other).len(), 1);
+    assert!(db.contains("/// meta: category = db\n"));
+    assert!(other.contains("/// meta: category = ui\n"));
+}
+
+#[test]
+fn appending_without_a_route_fails() {
+    let error = failure(0, "db").append_routed().unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+}
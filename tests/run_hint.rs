@@ -0,0 +1,60 @@
+extern crate chrono;
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use chrono::{TimeZone, Utc};
+use common::SomeImportantAction;
+use exceptional::{CommentStyle, UnitTestOptions};
+
+fn render(options: UnitTestOptions) -> String {
+    let mut action = SomeImportantAction { var_1: 1, var_2: 2 };
+    exceptional::execute(&mut action, &(2, 3))
+        .unwrap_err()
+        .with_time(Utc.timestamp_millis_opt(1_539_761_233_543).unwrap())
+        .with_options(options)
+        .to_string()
+}
+
+#[test]
+fn hint_is_off_by_default() {
+    assert!(!render(UnitTestOptions::default()).contains("run with"));
+}
+
+#[test]
+fn hint_names_the_function() {
+    let source = render(UnitTestOptions::default().run_hint(true));
+    assert!(source.contains("\n/// run with: cargo test test_1539761233543\n"));
+    assert!(source.contains("pub fn test_1539761233543()"));
+}
+
+#[test]
+fn hint_includes_the_module_path_and_target_module() {
+    let source = render(
+        UnitTestOptions::default()
+            .run_hint(true)
+            .module_path("tests::regressions")
+            .target_module("generated")
+            .comment_style(CommentStyle::Plain),
+    );
+    assert!(source
+        .contains("\n// run with: cargo test tests::regressions::generated::test_1539761233543\n"));
+}
+
+#[test]
+fn hint_uses_the_configured_id() {
+    let source = render(
+        UnitTestOptions::default()
+            .run_hint(true)
+            .id_strategy(|_| String::from("whoopsie")),
+    );
+    assert!(source.contains("run with: cargo test test_whoopsie\n"));
+}
+
+#[test]
+#[should_panic(expected = "is not a valid module path")]
+fn invalid_module_path_is_rejected() {
+    let _ = UnitTestOptions::default().module_path("tests::");
+}
@@ -0,0 +1,74 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+use exceptional::{ExecutableSelfArgs, UnitTestOptions};
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Checkout {
+    prices: Vec<u64>,
+    coupon: Option<String>,
+}
+
+impl ExecutableSelfArgs for Checkout {
+    type Result = u64;
+    type Error = String;
+
+    fn full_path(&self) -> &'static str {
+        "::Checkout"
+    }
+
+    fn description(&self) -> String {
+        String::from("Checks out the cart")
+    }
+
+    fn execute(&mut self) -> Result<u64, String> {
+        let total: u64 = self.prices.iter().sum();
+        match self.coupon {
+            Some(ref coupon) if total < 10 => Err(format!("{} needs a total of 10", coupon)),
+            _ => Ok(total),
+        }
+    }
+}
+
+fn failing() -> Checkout {
+    Checkout {
+        prices: vec![3, 4],
+        coupon: Some(String::from("TENOFF")),
+    }
+}
+
+#[test]
+fn only_the_executable_is_reconstructed() {
+    let mut checkout = failing();
+    let test = exceptional::execute_self(&mut checkout).unwrap_err();
+    assert_eq!(test.error, "TENOFF needs a total of 10");
+    let source = test.to_string();
+    assert!(!source.contains("arg_json"), "{}", source);
+    assert!(
+        source.contains("\n\t\n\tlet args = ();\n\n\tif let Err(e) = obj.execute(&args) {"),
+        "{}",
+        source
+    );
+
+    let mut checkout = Checkout {
+        prices: vec![30],
+        ..failing()
+    };
+    assert_eq!(exceptional::execute_self(&mut checkout).ok(), Some(30));
+}
+
+#[test]
+fn sidecars_only_hold_the_executable_in_the_test() {
+    let mut checkout = failing();
+    let source = exceptional::execute_self(&mut checkout)
+        .unwrap_err()
+        .with_options(
+            UnitTestOptions::default()
+                .sidecar_dir("fixtures")
+                .args_binding("input"),
+        )
+        .to_string();
+    assert!(source.contains("\tlet input = ();\n"), "{}", source);
+    assert_eq!(source.matches("include_str!").count(), 1, "{}", source);
+}
@@ -0,0 +1,71 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_json;
+
+use exceptional::{Executable, Sequence, SequenceError};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+struct Account {
+    balance: i64,
+}
+
+impl Executable for Account {
+    type Result = i64;
+    type Error = String;
+    type Arguments = i64;
+
+    fn full_path(&self) -> &'static str {
+        "::Account"
+    }
+
+    fn description(&self) -> String {
+        String::from("Changes the balance")
+    }
+
+    fn execute(&mut self, change: &i64) -> Result<i64, String> {
+        if self.balance + change < 0 {
+            return Err(String::from("Insufficient funds"));
+        }
+        self.balance += change;
+        Ok(self.balance)
+    }
+}
+
+#[test]
+fn sequence_stops_at_the_failing_step() {
+    let mut sequence = Sequence::new(Account { balance: 0 });
+    let steps = vec![10, -20, 30];
+    let test = exceptional::execute(&mut sequence, &steps).unwrap_err();
+
+    assert_eq!(
+        test.error,
+        SequenceError {
+            step: 1,
+            error: String::from("Insufficient funds")
+        }
+    );
+    assert_eq!(test.executable.executable, Account { balance: 0 });
+    let (executable_json, arguments_json) = test.snapshots().unwrap();
+    assert_eq!(
+        serde_json::from_str::<Account>(&executable_json).unwrap(),
+        Account { balance: 0 }
+    );
+    assert_eq!(
+        serde_json::from_str::<Vec<i64>>(&arguments_json).unwrap(),
+        steps
+    );
+    assert!(test
+        .to_string()
+        .contains("let mut obj: ::exceptional::Sequence<::Account> = "));
+    assert_eq!(sequence.executable, Account { balance: 10 });
+}
+
+#[test]
+fn sequence_succeeds_with_all_results() {
+    let mut sequence = Sequence::new(Account { balance: 0 });
+    assert_eq!(
+        exceptional::execute(&mut sequence, &vec![10, -5]).ok(),
+        Some(vec![10, 5])
+    );
+}
@@ -0,0 +1,108 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+use exceptional::{Executable, RenderError, SerializationFormat, UnitTestOptions};
+use std::collections::BTreeMap;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+enum Mode {
+    Strict,
+    Lenient { retries: u8 },
+    Limit(u32),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct Parser {
+    ratio: f64,
+    mode: Mode,
+    fallback: Option<Mode>,
+    name: String,
+    weights: BTreeMap<String, f32>,
+    pair: (char, bool),
+}
+
+impl Executable for Parser {
+    type Result = ();
+    type Error = String;
+    type Arguments = Vec<Mode>;
+
+    fn full_path(&self) -> &'static str {
+        "::Parser"
+    }
+
+    fn description(&self) -> String {
+        String::from("Parses")
+    }
+
+    fn execute(&mut self, _modes: &Vec<Mode>) -> Result<(), String> {
+        Err(String::from("failed"))
+    }
+}
+
+fn render(options: UnitTestOptions) -> Result<String, RenderError> {
+    let mut weights = BTreeMap::new();
+    weights.insert(String::from("a"), 0.5);
+    let mut parser = Parser {
+        ratio: 0.1 + 0.2,
+        mode: Mode::Lenient { retries: 2 },
+        fallback: Some(Mode::Strict),
+        name: String::from("say \"hi\"\n"),
+        weights,
+        pair: ('\'', true),
+    };
+    let modes = vec![Mode::Limit(3), Mode::Strict];
+    exceptional::execute(&mut parser, &modes)
+        .unwrap_err()
+        .with_options(options.id_strategy(|_| String::from("ron")))
+        .try_to_test_source()
+}
+
+#[test]
+fn inputs_are_embedded_as_ron() {
+    let source =
+        render(UnitTestOptions::default().serialization_format(SerializationFormat::Ron)).unwrap();
+    assert!(source.contains(
+        "\tlet obj_ron = r#\"(ratio: 0.30000000000000004, mode: Lenient(retries: 2), fallback: Some(Strict), name: \"say \\\"hi\\\"\\n\", weights: {\"a\": 0.5}, pair: ('\\'', true))\"#;\n"
+    ));
+    assert!(source.contains(
+        "\tlet mut obj: ::Parser = ::ron::from_str(obj_ron).expect(\"Could not deserialize ron\");\n"
+    ));
+    assert!(source.contains("\tlet arg_ron = r#\"[Limit(3), Strict]\"#;\n"));
+    assert!(source.contains(
+        "\tlet args = ::ron::from_str(arg_ron).expect(\"Could not deserialize ron\");\n"
+    ));
+    assert!(!source.contains("serde_json"));
+
+    // The marker is the same as for JSON, so the test is deduplicated against JSON tests.
+    let json = render(UnitTestOptions::default()).unwrap();
+    let hashes = |source: &str| {
+        let marker = exceptional::parse_markers(source).remove(0);
+        (marker.hash, marker.error)
+    };
+    assert_eq!(hashes(&source), hashes(&json));
+    assert!(json.contains("\tlet obj_json = r#\"{"));
+}
+
+#[test]
+fn hoisted_inputs_are_embedded_as_ron() {
+    let source = render(
+        UnitTestOptions::default()
+            .serialization_format(SerializationFormat::Ron)
+            .hoist_inputs(true),
+    )
+    .unwrap();
+    assert!(source.contains("const INPUT_RON_ARGS: &str = r#\"[Limit(3), Strict]\"#;\n"));
+    assert!(source.contains("\tlet arg_ron = INPUT_RON_ARGS;\n"));
+}
+
+#[test]
+fn post_processing_is_rejected() {
+    let options = UnitTestOptions::default()
+        .serialization_format(SerializationFormat::Ron)
+        .sort_keys(true);
+    assert_eq!(
+        render(options).unwrap_err(),
+        RenderError::PostprocessedFormat(SerializationFormat::Ron)
+    );
+}
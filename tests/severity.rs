@@ -0,0 +1,127 @@
+extern crate chrono;
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use chrono::{TimeZone, Utc};
+use common::{scratch_dir, SomeImportantAction};
+use exceptional::{Executable, Severity, UnitTestOptions};
+use std::fs;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct Transfer {
+    severity: Severity,
+}
+
+impl Executable for Transfer {
+    type Result = ();
+    type Error = String;
+    type Arguments = u32;
+
+    fn full_path(&self) -> &'static str {
+        "::Transfer"
+    }
+
+    fn description(&self) -> String {
+        String::from("Transfers money")
+    }
+
+    fn execute(&mut self, amount: &u32) -> Result<(), String> {
+        Err(format!("Lost {}", amount))
+    }
+
+    fn severity(&self) -> Severity {
+        self.severity
+    }
+}
+
+fn append(severity: Severity, millis: i64, file: &std::path::Path, options: UnitTestOptions) {
+    let mut transfer = Transfer { severity };
+    exceptional::execute(&mut transfer, &10)
+        .unwrap_err()
+        .with_time(Utc.timestamp_millis_opt(millis).unwrap())
+        .with_options(options)
+        .append_to_file(file)
+        .unwrap();
+}
+
+#[test]
+fn default_severity_is_not_written() {
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+    let source = exceptional::execute(&mut action, &(2, 3))
+        .unwrap_err()
+        .to_string();
+    assert!(!source.contains("severity=") && !source.contains("severity:"));
+}
+
+#[test]
+fn severity_is_written_to_the_comment_and_marker() {
+    let mut transfer = Transfer {
+        severity: Severity::Critical,
+    };
+    let source = exceptional::execute(&mut transfer, &10)
+        .unwrap_err()
+        .with_time(Utc.timestamp_millis_opt(1_539_761_233_543).unwrap())
+        .to_string();
+    assert!(source
+        .lines()
+        .next()
+        .unwrap()
+        .contains(" time=2018-10-17T07:27:13.543Z severity=critical error="));
+    assert!(source.contains("\n/// severity: critical\n"));
+}
+
+#[test]
+fn tests_are_listed_by_severity() {
+    let dir = scratch_dir("severity_list");
+    let file = dir.join("tests.rs");
+    append(Severity::Low, 1, &file, UnitTestOptions::default());
+    append(Severity::Critical, 2, &file, UnitTestOptions::default());
+    append(Severity::Medium, 3, &file, UnitTestOptions::default());
+    append(Severity::High, 4, &file, UnitTestOptions::default());
+
+    let ids = |min| exceptional::tests_with_severity(&file, min).unwrap();
+    assert_eq!(ids(Severity::Low), vec!["1", "2", "3", "4"]);
+    assert_eq!(ids(Severity::Medium), vec!["2", "3", "4"]);
+    assert_eq!(ids(Severity::High), vec!["2", "4"]);
+    assert_eq!(ids(Severity::Critical), vec!["2"]);
+}
+
+#[test]
+fn tests_are_grouped_in_severity_modules() {
+    let dir = scratch_dir("severity_modules");
+    let file = dir.join("tests.rs");
+    let options = UnitTestOptions::default()
+        .severity_modules(true)
+        .target_module("ignored")
+        .run_hint(true);
+    append(Severity::Critical, 1, &file, options.clone());
+    append(Severity::Low, 2, &file, options.clone());
+    append(Severity::Critical, 3, &file, options);
+
+    let contents = fs::read_to_string(&file).unwrap();
+    assert!(!contents.contains("mod ignored"));
+    assert_eq!(contents.matches("mod critical {").count(), 1);
+    assert_eq!(contents.matches("mod low {").count(), 1);
+    let critical = contents.find("mod critical {").unwrap();
+    let low = contents.find("mod low {").unwrap();
+    assert!(contents.find("pub fn test_3()").unwrap() < low);
+    assert!(contents.find("pub fn test_3()").unwrap() > critical);
+    assert!(contents.contains("run with: cargo test critical::test_3\n"));
+}
+
+#[test]
+fn severity_names_round_trip() {
+    for &severity in &[
+        Severity::Low,
+        Severity::Medium,
+        Severity::High,
+        Severity::Critical,
+    ] {
+        assert_eq!(Severity::from_name(severity.name()), Some(severity));
+    }
+    assert_eq!(Severity::from_name("urgent"), None);
+    assert_eq!(Severity::default(), Severity::Medium);
+}
@@ -0,0 +1,76 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+use exceptional::Executable;
+
+/// Fails if the sum of the arguments is at least the limit, and counts its calls in its own state.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+struct Budget {
+    limit: u32,
+    calls: u32,
+}
+
+impl Executable for Budget {
+    type Result = ();
+    type Error = String;
+    type Arguments = (u32, u32);
+
+    fn full_path(&self) -> &'static str {
+        "::Budget"
+    }
+
+    fn description(&self) -> String {
+        String::from("Spends the budget")
+    }
+
+    fn execute(&mut self, &(a, b): &(u32, u32)) -> Result<(), String> {
+        self.calls += 1;
+        if self.calls > 1 {
+            return Err(String::from("state leaked between attempts"));
+        }
+        if a + b >= self.limit {
+            Err(String::from("over budget"))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+fn shrink(&(a, b): &(u32, u32)) -> Vec<(u32, u32)> {
+    let mut candidates = Vec::new();
+    if a > 0 {
+        candidates.extend(vec![(0, b), (a / 2, b), (a - 1, b)]);
+    }
+    if b > 0 {
+        candidates.extend(vec![(a, 0), (a, b / 2), (a, b - 1)]);
+    }
+    candidates
+}
+
+#[test]
+fn arguments_are_shrunk_while_the_error_persists() {
+    let mut budget = Budget { limit: 5, calls: 0 };
+    let test = exceptional::execute_shrinking(&mut budget, &(7, 3), shrink).unwrap_err();
+    // The first candidate that still fails is shrunk further: (7, 3), (3, 3), (2, 3).
+    assert_eq!(*test.arguments, (2, 3));
+    assert_eq!(test.error, "over budget");
+    // The test starts from the state from before the first failure.
+    assert_eq!(test.executable, Budget { limit: 5, calls: 0 });
+    let (_, arguments_json) = test.snapshots().unwrap();
+    assert!(test.to_string().contains(&arguments_json));
+    assert_eq!(
+        arguments_json.split_whitespace().collect::<String>(),
+        "[2,3]"
+    );
+}
+
+#[test]
+fn arguments_that_dont_shrink_are_kept() {
+    let mut budget = Budget { limit: 5, calls: 0 };
+    let test = exceptional::execute_shrinking(&mut budget, &(7, 3), |_| Vec::new()).unwrap_err();
+    assert_eq!(*test.arguments, (7, 3));
+
+    let mut budget = Budget { limit: 5, calls: 0 };
+    assert!(exceptional::execute_shrinking(&mut budget, &(1, 1), shrink).is_ok());
+}
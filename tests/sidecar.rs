@@ -0,0 +1,118 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use common::SomeImportantAction;
+use exceptional::{Generator, StreamingWriter, TestFileWriter, TestSuite, UnitTestOptions};
+use std::fs;
+use std::path::Path;
+
+#[test]
+fn snapshots_are_written_to_sidecar_files() {
+    let dir = common::scratch_dir("sidecar");
+    let file = dir.join("generated.rs");
+
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+    let test = exceptional::execute(&mut action, &(2, 3))
+        .unwrap_err()
+        .with_options(
+            UnitTestOptions::default()
+                .id_strategy(|_| String::from("first"))
+                .sidecar_dir("snapshots"),
+        );
+    let (executable_json, arguments_json) = test.snapshots().unwrap();
+    test.append_to_file(&file).unwrap();
+
+    let contents = fs::read_to_string(&file).unwrap();
+    assert!(contents.contains("let obj_json = include_str!(\"snapshots/first.exec.json\");"));
+    assert!(contents.contains("let arg_json = include_str!(\"snapshots/first.args.json\");"));
+    assert!(!contents.contains(&executable_json));
+    let snapshots = dir.join("snapshots");
+    assert_eq!(
+        fs::read_to_string(snapshots.join("first.exec.json")).unwrap(),
+        executable_json
+    );
+    assert_eq!(
+        fs::read_to_string(snapshots.join("first.args.json")).unwrap(),
+        arguments_json
+    );
+
+    fs::write(snapshots.join("stale.exec.json"), "{}").unwrap();
+    fs::write(snapshots.join("stale.args.json"), "[]").unwrap();
+    fs::write(snapshots.join("README"), "keep me").unwrap();
+    let removed = exceptional::prune_sidecars(&file, "snapshots").unwrap();
+    assert_eq!(
+        removed,
+        vec![
+            snapshots.join("stale.args.json"),
+            snapshots.join("stale.exec.json")
+        ]
+    );
+    assert!(snapshots.join("first.exec.json").exists());
+    assert!(snapshots.join("README").exists());
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn writers_write_the_sidecar_files_of_their_tests() {
+    let dir = common::scratch_dir("sidecar_writers");
+    let options = UnitTestOptions::default()
+        .id_strategy(|_| String::from("same"))
+        .sidecar_dir("snapshots");
+    let failure = |var_1| {
+        let action = SomeImportantAction { var_1, var_2: 1 };
+        exceptional::execute_all(&action, vec![(2, 3)], exceptional::Mode::FirstFailure)
+            .pop()
+            .unwrap()
+            .with_options(options.clone())
+    };
+    let check = |file: &Path| {
+        let contents = fs::read_to_string(file).unwrap();
+        let markers = exceptional::parse_markers(&contents);
+        assert_eq!(markers.len(), 2, "{}", contents);
+        for marker in markers {
+            let executable = format!("snapshots/{}.exec.json", marker.id);
+            assert!(contents.contains(&format!("include_str!(\"{}\")", executable)));
+            assert!(dir.join(&executable).exists(), "{}", executable);
+            assert!(dir
+                .join(format!("snapshots/{}.args.json", marker.id))
+                .exists());
+        }
+    };
+
+    let file = dir.join("streaming.rs");
+    let mut writer = StreamingWriter::open(&file).unwrap();
+    writer.record(failure(0)).unwrap();
+    writer.record(failure(1)).unwrap();
+    drop(writer);
+    check(&file);
+
+    // The second test of the writer is renamed when it is flushed, and its sidecar files with it.
+    let file = dir.join("buffered.rs");
+    failure(0).append_to_file(&file).unwrap();
+    let mut writer = TestFileWriter::new();
+    writer.push(failure(1));
+    writer.flush_to_file(&file).unwrap();
+    check(&file);
+
+    let file = dir.join("suite.rs");
+    let mut suite = TestSuite::new();
+    suite.push(failure(0));
+    suite.push(failure(1));
+    suite.write_to_file(&file).unwrap();
+    check(&file);
+
+    let file = dir.join("generator.rs");
+    let mut generator = Generator::open(&file, options.clone()).unwrap();
+    for var_1 in 0..2 {
+        let mut action = SomeImportantAction { var_1, var_2: 1 };
+        generator.run(&mut action, &(2, 3)).unwrap().unwrap_err();
+    }
+    drop(generator);
+    check(&file);
+
+    let _ = fs::remove_dir_all(&dir);
+}
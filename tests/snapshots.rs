@@ -0,0 +1,25 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_json;
+
+mod common;
+
+use common::SomeImportantAction;
+
+#[test]
+fn snapshots_round_trip() {
+    let mut action = SomeImportantAction { var_1: 4, var_2: 1 };
+    let args = (2, 3);
+    let test = exceptional::execute(&mut action, &args).unwrap_err();
+
+    let (executable_json, arguments_json) = test.snapshots().unwrap();
+    let executable: SomeImportantAction = serde_json::from_str(&executable_json).unwrap();
+    let arguments: (u32, u32) = serde_json::from_str(&arguments_json).unwrap();
+    assert_eq!(executable, test.executable);
+    assert_eq!(arguments, args);
+
+    let source = test.to_string();
+    assert!(source.contains(&executable_json));
+    assert!(source.contains(&arguments_json));
+}
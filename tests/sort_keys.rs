@@ -0,0 +1,82 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+use exceptional::{Executable, UnitTestOptions};
+use std::collections::HashMap;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct Inventory {
+    stock: HashMap<String, u32>,
+}
+
+impl Executable for Inventory {
+    type Result = ();
+    type Error = String;
+    type Arguments = HashMap<String, u32>;
+
+    fn full_path(&self) -> &'static str {
+        "::Inventory"
+    }
+
+    fn description(&self) -> String {
+        String::from("Takes items out of stock")
+    }
+
+    fn execute(&mut self, order: &HashMap<String, u32>) -> Result<(), String> {
+        for (item, count) in order {
+            match self.stock.get(item) {
+                Some(stock) if stock >= count => {}
+                _ => return Err(format!("Not enough {}", item)),
+            }
+        }
+        Ok(())
+    }
+}
+
+fn snapshots(options: UnitTestOptions) -> (String, String) {
+    let names = [
+        "pears", "apples", "kiwis", "bananas", "dates", "figs", "grapes", "cherries",
+    ];
+    // Every `HashMap` gets its own random hasher, so the iteration order differs between these maps.
+    let mut inventory = Inventory {
+        stock: names.iter().map(|name| (name.to_string(), 1)).collect(),
+    };
+    let order = names.iter().map(|name| (name.to_string(), 2)).collect();
+    exceptional::execute(&mut inventory, &order)
+        .unwrap_err()
+        .with_options(options)
+        .snapshots()
+        .unwrap()
+}
+
+#[test]
+fn hash_map_keys_are_sorted() {
+    let (executable_json, arguments_json) = snapshots(UnitTestOptions::default().sort_keys(true));
+    assert_eq!(
+        executable_json,
+        "{\n  \"stock\": {\n    \"apples\": 1,\n    \"bananas\": 1,\n    \"cherries\": 1,\n    \"dates\": 1,\n    \"figs\": 1,\n    \"grapes\": 1,\n    \"kiwis\": 1,\n    \"pears\": 1\n  }\n}"
+    );
+    assert!(arguments_json.starts_with("{\n  \"apples\": 2,\n  \"bananas\": 2,"));
+}
+
+#[test]
+fn sorted_snapshots_are_stable_across_runs() {
+    let first = snapshots(UnitTestOptions::default().sort_keys(true));
+    for _ in 0..10 {
+        assert_eq!(snapshots(UnitTestOptions::default().sort_keys(true)), first);
+    }
+}
+
+#[test]
+fn sorted_snapshots_render_and_round_trip() {
+    let mut inventory = Inventory {
+        stock: HashMap::new(),
+    };
+    let order = vec![(String::from("apples"), 1)].into_iter().collect();
+    let test = exceptional::execute(&mut inventory, &order)
+        .unwrap_err()
+        .with_options(UnitTestOptions::default().sort_keys(true));
+    assert_eq!(test.check_round_trip(), Ok(()));
+    assert!(test.try_to_test_source().is_ok());
+}
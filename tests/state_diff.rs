@@ -0,0 +1,96 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+use exceptional::{Executable, ExecuteOptions};
+use std::collections::BTreeMap;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+struct Account {
+    balance: i64,
+    history: Vec<String>,
+    limits: BTreeMap<String, i64>,
+    owner: String,
+}
+
+impl Executable for Account {
+    type Result = ();
+    type Error = String;
+    type Arguments = i64;
+
+    fn full_path(&self) -> &'static str {
+        "::Account"
+    }
+
+    fn description(&self) -> String {
+        String::from("Withdraws from the account")
+    }
+
+    fn execute(&mut self, amount: &i64) -> Result<(), String> {
+        if *amount == 0 {
+            return Err(String::from("nothing to withdraw"));
+        }
+        self.balance -= amount;
+        self.history.push(String::from("withdraw"));
+        self.limits.remove("daily");
+        if self.balance < 0 {
+            Err(format!("overdrawn by {}", -self.balance))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+fn account() -> Account {
+    let mut limits = BTreeMap::new();
+    limits.insert(String::from("daily"), 100);
+    Account {
+        balance: 10,
+        history: vec![String::from("open")],
+        limits,
+        owner: String::from("someone"),
+    }
+}
+
+#[test]
+fn changes_of_the_failing_run_are_listed() {
+    let mut account = account();
+    let options = ExecuteOptions::default().with_state_diff(true);
+    let test = exceptional::execute_with_options(&mut account, &15, &options)
+        .unwrap_err()
+        .unit_test()
+        .unwrap();
+    // The test is generated from the state before the run.
+    assert_eq!(test.executable.balance, 10);
+    assert!(test.post_state.as_ref().unwrap().contains("-5"));
+
+    let source = test.to_string();
+    assert!(
+        source.contains(
+            "/// changed during the run: balance: 10 -> -5\n/// changed during the run: history[1]: (missing) -> \"withdraw\"\n/// changed during the run: limits.daily: 100 -> (missing)\n\n/// exception was "
+        ),
+        "{}",
+        source
+    );
+    assert!(!source.contains("owner: "));
+}
+
+#[test]
+fn unchanged_state_is_mentioned() {
+    let mut account = account();
+    let options = ExecuteOptions::default().with_state_diff(true);
+    let test = exceptional::execute_with_options(&mut account, &0, &options)
+        .unwrap_err()
+        .unit_test()
+        .unwrap();
+    assert!(test
+        .to_string()
+        .contains("/// not changed during the run\n"));
+
+    let test = exceptional::execute_with_options(&mut account, &0, &ExecuteOptions::default())
+        .unwrap_err()
+        .unit_test()
+        .unwrap();
+    assert!(test.post_state.is_none());
+    assert!(!test.to_string().contains("during the run"));
+}
@@ -0,0 +1,130 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use common::SomeImportantAction;
+use exceptional::{DedupKey, Mode, StreamingWriter};
+use std::fs;
+
+#[test]
+fn streaming_writer_skips_known_failures() {
+    let dir = common::scratch_dir("streaming");
+    let file = dir.join("generated.rs");
+
+    {
+        let mut writer = StreamingWriter::open(&file).unwrap();
+        for var_1 in &[0, 1, 0] {
+            let mut action = SomeImportantAction {
+                var_1: *var_1,
+                var_2: 1,
+            };
+            let test = exceptional::execute(&mut action, &(2, 3)).unwrap_err();
+            writer.record(test).unwrap();
+        }
+        assert_eq!(writer.len(), 2);
+    }
+    let written = fs::read_to_string(&file).unwrap();
+    assert_eq!(written.matches("#[test]").count(), 2);
+
+    // A new writer is seeded from the file, so the same failure is not written again.
+    let mut writer = StreamingWriter::open(&file).unwrap();
+    let mut action = SomeImportantAction { var_1: 1, var_2: 1 };
+    let test = exceptional::execute(&mut action, &(2, 3)).unwrap_err();
+    assert!(!writer.record(test).unwrap());
+    assert_eq!(fs::read_to_string(&file).unwrap(), written);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn sections_group_tests_under_headers() {
+    let dir = common::scratch_dir("streaming_sections");
+    let file = dir.join("generated.rs");
+    let record = |writer: &mut StreamingWriter, var_1: u32| {
+        let mut action = SomeImportantAction { var_1, var_2: 1 };
+        let test = exceptional::execute(&mut action, &(2, 3)).unwrap_err();
+        writer.record(test).unwrap()
+    };
+
+    {
+        let mut writer = StreamingWriter::open(&file).unwrap();
+        writer.begin_section("first run");
+        assert!(record(&mut writer, 0));
+        assert!(record(&mut writer, 1));
+        writer.begin_section("second run");
+        // Only known failures, so the header is not written.
+        assert!(!record(&mut writer, 0));
+    }
+    let written = fs::read_to_string(&file).unwrap();
+    assert!(written.starts_with("// ===== first run =====\n// @exceptional "));
+    assert_eq!(written.matches("// =====").count(), 1);
+
+    // Opening the file again continues the section without a second header.
+    {
+        let mut writer = StreamingWriter::open(&file).unwrap();
+        writer.begin_section("first run");
+        assert!(!record(&mut writer, 1));
+        assert!(record(&mut writer, 2));
+        writer.end_section();
+        assert!(record(&mut writer, 3));
+    }
+    let written = fs::read_to_string(&file).unwrap();
+    assert_eq!(written.matches("// ===== first run =====\n").count(), 1);
+    assert_eq!(written.matches("#[test]").count(), 4);
+    let end = written.find("// ===== end of first run =====\n").unwrap();
+    assert_eq!(written[end..].matches("#[test]").count(), 1);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn dedup_key_is_chosen_per_call() {
+    let dir = common::scratch_dir("streaming_dedup_key");
+    let file = dir.join("generated.rs");
+    let failure = |var_1: u32, error: &str| {
+        let action = SomeImportantAction { var_1, var_2: 1 };
+        let mut test = exceptional::execute_all(&action, vec![(2, 3)], Mode::FirstFailure)
+            .pop()
+            .unwrap();
+        test.error = String::from(error);
+        test
+    };
+
+    {
+        let mut writer = StreamingWriter::open(&file).unwrap();
+        assert!(writer.record(failure(0, "Whoopsie")).unwrap());
+        // Different state, but the same error.
+        assert!(!writer
+            .record_by(failure(1, "Whoopsie"), DedupKey::ErrorOnly)
+            .unwrap());
+        // The same state, but a different error.
+        assert!(!writer.record(failure(0, "Oh no")).unwrap());
+        assert!(writer
+            .record_by(failure(0, "Oh no"), DedupKey::FullSnapshot)
+            .unwrap());
+        assert!(!writer
+            .record_by(failure(0, "Oh no"), DedupKey::FullSnapshot)
+            .unwrap());
+        assert_eq!(writer.len(), 2);
+    }
+
+    // The error hashes are seeded from the markers in the file.
+    let mut writer = StreamingWriter::open(&file).unwrap();
+    assert!(!writer
+        .record_by(failure(5, "Oh no"), DedupKey::ErrorOnly)
+        .unwrap());
+    assert!(writer
+        .record_by(failure(5, "Kaboom"), DedupKey::ErrorOnly)
+        .unwrap());
+    assert_eq!(
+        fs::read_to_string(&file)
+            .unwrap()
+            .matches("#[test]")
+            .count(),
+        3
+    );
+
+    let _ = fs::remove_dir_all(&dir);
+}
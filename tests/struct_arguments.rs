@@ -0,0 +1,63 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use exceptional::Executable;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Query {
+    pub name: String,
+    pub limit: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Search;
+
+impl Executable for Search {
+    type Result = ();
+    type Error = String;
+    type Arguments = Query;
+
+    fn full_path(&self) -> &'static str {
+        "::Search"
+    }
+
+    fn arguments_path(&self) -> Option<&'static str> {
+        Some("::Query")
+    }
+
+    fn description(&self) -> String {
+        String::from("Searches for a name")
+    }
+
+    fn execute(&mut self, query: &Query) -> Result<(), String> {
+        Err(format!("nothing found for {}", query.name))
+    }
+}
+
+#[test]
+fn struct_arguments_are_constructed_with_a_literal() {
+    let query = Query {
+        name: String::from("bob"),
+        limit: Some(10),
+    };
+    let source = exceptional::execute(&mut Search, &query)
+        .unwrap_err()
+        .to_string();
+
+    assert!(source.contains(
+        "\tlet args = ::Query {\n\t\tlimit: ::serde_json::from_str(r#\"10\"#).expect(\"Could not deserialize json\"),\n\t\tname: ::serde_json::from_str(r#\"\"bob\"\"#).expect(\"Could not deserialize json\"),\n\t};\n"
+    ), "{}", source);
+    assert!(!source.contains("arg_json"));
+}
+
+#[test]
+fn other_arguments_use_the_json() {
+    let mut action = common::SomeImportantAction { var_1: 1, var_2: 2 };
+    let source = exceptional::execute(&mut action, &(1, 3))
+        .unwrap_err()
+        .to_string();
+    assert!(source.contains("let arg_json = r#\"["), "{}", source);
+}
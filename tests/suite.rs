@@ -0,0 +1,75 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use common::{scratch_dir, OtherAction, SomeImportantAction};
+use std::fs;
+use std::path::Path;
+
+fn append_some(file: &Path) {
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+    exceptional::execute(&mut action, &(2, 3))
+        .unwrap_err()
+        .append_to_file(file)
+        .unwrap();
+}
+
+fn append_other(file: &Path) {
+    let mut other = OtherAction {
+        message: String::from("nope"),
+    };
+    exceptional::execute(&mut other, &())
+        .unwrap_err()
+        .append_to_file(file)
+        .unwrap();
+}
+
+#[test]
+fn generated_files_are_declared_in_mod_rs() {
+    let dir = scratch_dir("suite");
+    append_some(&dir.join("some_action.rs"));
+    append_other(&dir.join("other_action.rs"));
+    fs::write(dir.join("helpers.rs"), "pub fn setup() {}\n").unwrap();
+    fs::write(dir.join("not-a-module.rs"), "").unwrap();
+    append_other(&dir.join("not-a-module.rs"));
+    fs::write(dir.join("notes.txt"), "").unwrap();
+    fs::write(dir.join("mod.rs"), "// hand-written\nmod helpers;\n").unwrap();
+
+    let modules = exceptional::regenerate_suite(&dir).unwrap();
+    assert_eq!(modules, ["other_action", "some_action"]);
+    let expected = "// hand-written\nmod helpers;\n\n// @exceptional-suite\nmod other_action;\nmod some_action;\n// @exceptional-suite-end\n";
+    assert_eq!(fs::read_to_string(dir.join("mod.rs")).unwrap(), expected);
+
+    // Regenerating is idempotent.
+    exceptional::regenerate_suite(&dir).unwrap();
+    assert_eq!(fs::read_to_string(dir.join("mod.rs")).unwrap(), expected);
+
+    // New files are added in order, and the hand-written part is kept.
+    fs::write(dir.join("mod.rs"), format!("{}mod extra;\n", expected)).unwrap();
+    append_some(&dir.join("another.rs"));
+    exceptional::regenerate_suite(&dir).unwrap();
+    assert_eq!(
+        fs::read_to_string(dir.join("mod.rs")).unwrap(),
+        "// hand-written\nmod helpers;\n\n// @exceptional-suite\nmod another;\nmod other_action;\nmod some_action;\n// @exceptional-suite-end\nmod extra;\n"
+    );
+}
+
+#[test]
+fn split_directories_are_not_declared_twice() {
+    let dir = scratch_dir("suite_resplit");
+    let src_file = dir.join("generated.rs");
+    append_some(&src_file);
+    append_other(&src_file);
+    let out_dir = dir.join("split");
+    exceptional::resplit_by_type(&src_file, &out_dir).unwrap();
+
+    let modules = exceptional::regenerate_suite(&out_dir).unwrap();
+    assert_eq!(modules.len(), 2);
+    let mod_rs = fs::read_to_string(out_dir.join("mod.rs")).unwrap();
+    for module in &modules {
+        assert_eq!(mod_rs.matches(&format!("mod {};", module)).count(), 1);
+    }
+    assert!(mod_rs.starts_with("// @exceptional-suite\n"), "{}", mod_rs);
+}
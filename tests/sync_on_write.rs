@@ -0,0 +1,46 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use common::{scratch_dir, SomeImportantAction};
+use exceptional::{StreamingWriter, UnitTestOptions};
+use std::fs;
+
+#[test]
+fn synced_writes_end_up_in_the_file() {
+    let dir = scratch_dir("sync_on_write");
+    let appended = dir.join("appended.rs");
+    let edited = dir.join("edited.rs");
+    let streamed = dir.join("streamed.rs");
+    let options = UnitTestOptions::default().sync_on_write(true);
+
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+    let test = exceptional::execute(&mut action, &(2, 3))
+        .unwrap_err()
+        .with_options(options.clone());
+    let source = test.to_string();
+    test.append_to_file(&appended).unwrap();
+    assert_eq!(fs::read_to_string(&appended).unwrap(), source);
+
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+    exceptional::execute(&mut action, &(2, 3))
+        .unwrap_err()
+        .with_options(options.clone().upsert(true))
+        .append_to_file(&edited)
+        .unwrap();
+    assert!(fs::read_to_string(&edited)
+        .unwrap()
+        .contains("#[test]\npub fn test_"));
+
+    let mut writer = StreamingWriter::open(&streamed).unwrap();
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+    let test = exceptional::execute(&mut action, &(2, 3))
+        .unwrap_err()
+        .with_options(options);
+    assert!(writer.record(test).unwrap());
+    assert!(fs::read_to_string(&streamed)
+        .unwrap()
+        .contains("#[test]\npub fn test_"));
+}
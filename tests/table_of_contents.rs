@@ -0,0 +1,67 @@
+extern crate chrono;
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use chrono::{TimeZone, Utc};
+use common::{OtherAction, SomeImportantAction};
+use exceptional::UnitTestOptions;
+use std::fs;
+
+fn options() -> UnitTestOptions {
+    UnitTestOptions::default()
+        .banner("Regression tests")
+        .table_of_contents(true)
+}
+
+#[test]
+fn table_of_contents_lists_every_test() {
+    let dir = common::scratch_dir("table_of_contents");
+    let file = dir.join("generated.rs");
+
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+    exceptional::execute(&mut action, &(2, 3))
+        .unwrap_err()
+        .with_time(Utc.timestamp_millis_opt(1_539_761_233_543).unwrap())
+        .with_options(options())
+        .append_to_file(&file)
+        .unwrap();
+    let mut other = OtherAction {
+        message: String::from("failure"),
+    };
+    exceptional::execute(&mut other, &())
+        .unwrap_err()
+        .with_time(Utc.timestamp_millis_opt(1_539_761_233_544).unwrap())
+        .with_options(options())
+        .append_to_file(&file)
+        .unwrap();
+
+    let written = fs::read_to_string(&file).unwrap();
+    assert!(
+        written.starts_with(
+            "//! Regression tests\n\n//! Generated tests:\n//! - test_1539761233543: Executes some very important action! (line 7)\n//! - test_1539761233544: Always fails (line "
+        ),
+        "{}",
+        written
+    );
+    assert_eq!(written.matches("//! Generated tests:").count(), 1);
+    assert_eq!(written.matches("//! Regression tests").count(), 1);
+
+    // Every entry points at the marker comment of its test.
+    let lines: Vec<&str> = written.lines().collect();
+    for entry in lines.iter().filter(|line| line.starts_with("//! - ")) {
+        let id = &entry["//! - test_".len()..entry.find(':').unwrap()];
+        let line: usize = entry[entry.rfind("(line ").unwrap() + 6..entry.len() - 1]
+            .parse()
+            .unwrap();
+        assert!(
+            lines[line - 1].starts_with(&format!("// @exceptional id={} ", id)),
+            "{}",
+            entry
+        );
+    }
+
+    let _ = fs::remove_dir_all(&dir);
+}
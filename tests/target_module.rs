@@ -0,0 +1,76 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use common::{scratch_dir, SomeImportantAction};
+use exceptional::UnitTestOptions;
+use std::fs;
+use std::path::Path;
+
+fn append(file: &Path) -> String {
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+    let test = exceptional::execute(&mut action, &(2, 3))
+        .unwrap_err()
+        .with_options(UnitTestOptions::default().target_module("regression_tests"));
+    let source = test.to_string();
+    test.append_to_file(file).unwrap();
+    source
+}
+
+fn indented(source: &str, indent: &str) -> String {
+    source
+        .lines()
+        .map(|line| match line.trim() {
+            "" => format!("{}\n", line),
+            _ => format!("{}{}\n", indent, line),
+        })
+        .collect()
+}
+
+#[test]
+fn tests_are_added_at_the_end_of_the_module() {
+    let file = scratch_dir("target_module").join("lib.rs");
+    let before = "// mod regression_tests { is only mentioned here\nconst S: &str = \"mod regression_tests {\";\n\nmod regression_tests {\n    #[test]\n    fn existing() {}\n}\n\nfn after() {}\n";
+    fs::write(&file, before).unwrap();
+
+    let source = append(&file);
+    let split = before.find("}\n\nfn after").unwrap();
+    let expected = format!(
+        "{}{}{}",
+        &before[..split],
+        indented(&source, "    "),
+        &before[split..]
+    );
+    assert_eq!(fs::read_to_string(&file).unwrap(), expected);
+}
+
+#[test]
+fn missing_module_is_created() {
+    let dir = scratch_dir("target_module_missing");
+    let file = dir.join("lib.rs");
+    fs::write(&file, "fn before() {}\n").unwrap();
+    let source = append(&file);
+    assert_eq!(
+        fs::read_to_string(&file).unwrap(),
+        format!(
+            "fn before() {{}}\n\n/// Regression tests that were generated from failures.\n#[allow(missing_docs)]\nmod regression_tests {{\n{}}}\n",
+            indented(&source, "    ")
+        )
+    );
+
+    let empty = dir.join("empty.rs");
+    fs::write(&empty, "mod regression_tests {}\n").unwrap();
+    let source = append(&empty);
+    assert_eq!(
+        fs::read_to_string(&empty).unwrap(),
+        format!("mod regression_tests {{\n{}}}\n", indented(&source, "    "))
+    );
+}
+
+#[test]
+#[should_panic(expected = "not a valid module name")]
+fn invalid_module_names_are_rejected() {
+    let _ = UnitTestOptions::default().target_module("regression tests");
+}
@@ -0,0 +1,44 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use common::SomeImportantAction;
+use exceptional::UnitTestOptions;
+
+fn render(options: UnitTestOptions) -> String {
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+    exceptional::execute(&mut action, &(2, 3))
+        .unwrap_err()
+        .with_options(options)
+        .to_string()
+}
+
+#[test]
+fn the_test_attribute_can_be_replaced() {
+    let source = render(UnitTestOptions::default().test_attribute("#[wasm_bindgen_test]"));
+    assert!(source.contains("\n#[wasm_bindgen_test]\npub fn test_"));
+    assert!(!source.contains("#[test]"));
+    assert!(source.contains("obj.execute(&args)"));
+
+    let source = render(UnitTestOptions::default());
+    assert!(source.contains("\n#[test]\npub fn test_"));
+}
+
+#[test]
+fn async_tests_take_precedence() {
+    let source = render(
+        UnitTestOptions::default()
+            .test_attribute("wasm_bindgen_test")
+            .async_test("tokio::test"),
+    );
+    assert!(source.contains("\n#[tokio::test]\npub async fn test_"));
+    assert!(!source.contains("wasm_bindgen_test"));
+}
+
+#[test]
+#[should_panic(expected = "the test attribute can not be empty")]
+fn empty_attributes_are_rejected() {
+    let _ = UnitTestOptions::default().test_attribute("#[]");
+}
@@ -0,0 +1,56 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use common::SomeImportantAction;
+use exceptional::{Harness, UnitTestOptions};
+
+#[test]
+fn group_renders_one_test_case_per_failure() {
+    let mut first = SomeImportantAction { var_1: 0, var_2: 1 };
+    let mut second = first.clone();
+    let first_args = (2, 3);
+    let second_args = (4, 3);
+    let options = UnitTestOptions::default().harness(Harness::TestCase);
+    let tests = vec![
+        exceptional::execute(&mut first, &first_args)
+            .unwrap_err()
+            .with_options(options.clone()),
+        exceptional::execute(&mut second, &second_args)
+            .unwrap_err()
+            .with_options(options),
+    ];
+
+    let source = exceptional::render_group(&tests).unwrap();
+    assert_eq!(source.matches("// @exceptional ").count(), 1);
+    assert_eq!(
+        source
+            .matches("#[test_case(r#\"[\n  2,\n  3\n]\"#)]")
+            .count(),
+        1
+    );
+    assert_eq!(
+        source
+            .matches("#[test_case(r#\"[\n  4,\n  3\n]\"#)]")
+            .count(),
+        1
+    );
+    assert!(source.contains("(arg_json: &str) {"));
+    assert!(!source.contains("#[test]"));
+}
+
+#[test]
+fn group_renders_plain_tests_by_default() {
+    let mut first = SomeImportantAction { var_1: 0, var_2: 1 };
+    let mut second = first.clone();
+    let args = (2, 3);
+    let tests = vec![
+        exceptional::execute(&mut first, &args).unwrap_err(),
+        exceptional::execute(&mut second, &args).unwrap_err(),
+    ];
+
+    let source = exceptional::render_group(&tests).unwrap();
+    assert_eq!(source.matches("#[test]").count(), 2);
+}
@@ -0,0 +1,84 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+use exceptional::{Executable, TestKind, UnitTestOptions};
+
+/// An executable that reports one of several forms of its full path.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct Action {
+    path: u8,
+}
+
+impl Executable for Action {
+    type Result = ();
+    type Error = String;
+    type Arguments = ();
+
+    fn full_path(&self) -> &'static str {
+        match self.path {
+            0 => "crate::module::Action",
+            1 => "::my_crate::module::Action",
+            _ => "::other::Action",
+        }
+    }
+
+    fn description(&self) -> String {
+        String::from("Fails")
+    }
+
+    fn execute(&mut self, _args: &()) -> Result<(), String> {
+        Err(String::from("failed"))
+    }
+}
+
+fn render(path: u8, options: UnitTestOptions) -> String {
+    let mut action = Action { path };
+    exceptional::execute(&mut action, &())
+        .unwrap_err()
+        .with_options(options)
+        .to_string()
+}
+
+#[test]
+fn integration_tests_name_the_crate() {
+    let source = render(
+        0,
+        UnitTestOptions::default().test_kind(TestKind::Integration, "my-crate"),
+    );
+    assert!(source.contains("\tlet mut obj: ::my_crate::module::Action = "));
+    // The marker keeps the path of the executable.
+    assert!(source.contains(" path=crate::module::Action "));
+
+    let source = render(
+        1,
+        UnitTestOptions::default().test_kind(TestKind::Integration, "my_crate"),
+    );
+    assert!(source.contains("\tlet mut obj: ::my_crate::module::Action = "));
+}
+
+#[test]
+fn unit_tests_use_crate_paths() {
+    let options = UnitTestOptions::default().test_kind(TestKind::Unit, "my_crate");
+    let source = render(1, options.clone());
+    assert!(source.contains("\tlet mut obj: crate::module::Action = "));
+    assert!(source.contains(" path=::my_crate::module::Action "));
+
+    let source = render(0, options.clone());
+    assert!(source.contains("\tlet mut obj: crate::module::Action = "));
+    // Paths of other crates are left alone.
+    let source = render(2, options);
+    assert!(source.contains("\tlet mut obj: ::other::Action = "));
+}
+
+#[test]
+fn paths_are_not_rewritten_by_default() {
+    let source = render(0, UnitTestOptions::default());
+    assert!(source.contains("\tlet mut obj: crate::module::Action = "));
+}
+
+#[test]
+#[should_panic(expected = "is not a valid crate name")]
+fn invalid_crate_names_are_rejected() {
+    let _ = UnitTestOptions::default().test_kind(TestKind::Unit, "my crate");
+}
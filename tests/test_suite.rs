@@ -0,0 +1,100 @@
+extern crate chrono;
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use chrono::{TimeZone, Utc};
+use common::{scratch_dir, OtherAction, SomeImportantAction};
+use exceptional::TestSuite;
+use std::collections::HashSet;
+use std::fs;
+
+fn ids(src: &str) -> Vec<String> {
+    exceptional::parse_markers(src)
+        .into_iter()
+        .map(|marker| marker.id)
+        .collect()
+}
+
+#[test]
+fn tests_of_several_types_are_written_at_once() {
+    let file = scratch_dir("test_suite").join("regressions.rs");
+    let mut suite = TestSuite::new();
+    assert!(suite.is_empty());
+
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+    let test = exceptional::execute(&mut action, &(2, 3))
+        .unwrap_err()
+        .with_time(Utc.timestamp_millis_opt(1_000).unwrap());
+    let first = test.to_string();
+    suite.push(test);
+    let mut other = OtherAction {
+        message: String::from("nope"),
+    };
+    let test = exceptional::execute(&mut other, &())
+        .unwrap_err()
+        .with_time(Utc.timestamp_millis_opt(2_000).unwrap());
+    let second = test.to_string();
+    suite.try_push(test).unwrap();
+
+    assert_eq!(suite.len(), 2);
+    assert_eq!(
+        suite.iter().collect::<Vec<_>>(),
+        [first.as_str(), second.as_str()]
+    );
+    assert!(!file.exists());
+    suite.write_to_file(&file).unwrap();
+    assert_eq!(
+        fs::read_to_string(&file).unwrap(),
+        format!("{}{}", first, second)
+    );
+
+    // The suite is kept after writing.
+    suite.write_to_file(&file).unwrap();
+    assert_eq!(
+        exceptional::parse_markers(&fs::read_to_string(&file).unwrap()).len(),
+        4
+    );
+}
+
+#[test]
+fn empty_suites_dont_create_the_file() {
+    let file = scratch_dir("test_suite_empty").join("regressions.rs");
+    TestSuite::new().write_to_file(&file).unwrap();
+    assert!(!file.exists());
+}
+
+#[test]
+fn tests_with_the_same_id_are_renamed() {
+    let file = scratch_dir("test_suite_ids").join("regressions.rs");
+    let time = Utc.timestamp_millis_opt(1_000).unwrap();
+    let mut suite = TestSuite::new();
+    for var_1 in 0..2 {
+        let mut action = SomeImportantAction { var_1, var_2: 1 };
+        let test = exceptional::execute(&mut action, &(2, 3))
+            .unwrap_err()
+            .with_time(time);
+        suite.push(test);
+    }
+    let pushed: Vec<_> = suite.iter().flat_map(ids).collect();
+    assert_eq!(pushed[0], "1000");
+    assert_ne!(pushed[0], pushed[1]);
+
+    // Writing the suite twice renames the tests the second time, but the suite keeps its ids.
+    suite.write_to_file(&file).unwrap();
+    suite.write_to_file(&file).unwrap();
+    assert_eq!(suite.iter().flat_map(ids).collect::<Vec<_>>(), pushed);
+
+    let contents = fs::read_to_string(&file).unwrap();
+    let ids = ids(&contents);
+    assert_eq!(ids.len(), 4);
+    assert_eq!(ids.iter().collect::<HashSet<_>>().len(), 4, "{:?}", ids);
+    for id in &ids {
+        assert_eq!(
+            contents.matches(&format!("pub fn test_{}()", id)).count(),
+            1
+        );
+    }
+}
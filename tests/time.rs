@@ -0,0 +1,103 @@
+extern crate chrono;
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use chrono::{FixedOffset, NaiveDate, TimeZone, Utc};
+use common::SomeImportantAction;
+use exceptional::UnitTestOptions;
+
+#[test]
+fn non_utc_time_is_normalized() {
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+    let args = (2, 3);
+    let local = NaiveDate::from_ymd_opt(2018, 10, 17)
+        .and_then(|date| date.and_hms_milli_opt(9, 27, 13, 543))
+        .unwrap();
+    let offset = FixedOffset::east_opt(2 * 3600)
+        .unwrap()
+        .from_local_datetime(&local)
+        .unwrap();
+    let utc = Utc.timestamp_millis_opt(1_539_761_233_543).unwrap();
+
+    let test = exceptional::execute(&mut action, &args)
+        .unwrap_err()
+        .with_time(offset);
+    assert_eq!(test.time, utc);
+
+    let source = test.to_string();
+    assert!(source.contains(&format!("pub fn test_{}()", utc.timestamp_millis())));
+    assert!(source.contains("/// generated at Wed, 17 Oct 2018 07:27:13 +0000"));
+}
+
+#[test]
+fn id_marker_and_header_describe_the_same_instant() {
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+    let args = (2, 3);
+    let time = NaiveDate::from_ymd_opt(2018, 10, 17)
+        .and_then(|date| date.and_hms_nano_opt(7, 27, 13, 543_999_999))
+        .unwrap();
+    let time = Utc.from_utc_datetime(&time);
+
+    let test = exceptional::execute(&mut action, &args)
+        .unwrap_err()
+        .with_time(time);
+    assert_eq!(
+        test.time,
+        Utc.timestamp_millis_opt(1_539_761_233_543).unwrap()
+    );
+    assert_eq!(test.time.timestamp_millis(), 1_539_761_233_543);
+
+    let source = test
+        .with_options(UnitTestOptions::default().time_format("%Y-%m-%dT%H:%M:%S%.3fZ"))
+        .to_string();
+    assert!(source.contains("pub fn test_1539761233543()"));
+    assert!(source.contains(" time=2018-10-17T07:27:13.543Z error="));
+    assert!(source.contains("/// generated at 2018-10-17T07:27:13.543Z\n"));
+}
+
+#[test]
+#[should_panic(expected = "not a valid time format")]
+fn invalid_time_format_is_rejected() {
+    let _ = UnitTestOptions::default().time_format("%Q");
+}
+
+#[test]
+fn execute_at_uses_the_given_time() {
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+    let time = Utc.timestamp_millis_opt(1_539_761_233_543).unwrap();
+    let line = line!() + 1;
+    let test = exceptional::execute_at(&mut action, &(2, 3), time).unwrap_err();
+    assert_eq!(test.time, time);
+    assert_eq!(test.location.unwrap().line(), line);
+    assert!(test.to_string().contains("pub fn test_1539761233543()"));
+
+    assert!(exceptional::execute_at(&mut action, &(2, 4), time).is_ok());
+}
+
+#[test]
+fn the_clock_makes_the_output_deterministic() {
+    let render = || {
+        let mut millis = 1_539_761_233_000;
+        let mut sources = String::new();
+        for args in &[(2, 3), (2, 4), (5, 3)] {
+            let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+            let clock = || {
+                millis += 1;
+                Utc.timestamp_millis_opt(millis).unwrap()
+            };
+            if let Err(test) = exceptional::execute_with_clock(&mut action, args, clock) {
+                sources.push_str(&test.to_string());
+            }
+        }
+        sources
+    };
+    let sources = render();
+    assert_eq!(sources, render());
+    // The clock is only asked for the time of the failures.
+    assert!(sources.contains("pub fn test_1539761233001()"));
+    assert!(sources.contains("pub fn test_1539761233002()"));
+    assert!(!sources.contains("pub fn test_1539761233003()"));
+}
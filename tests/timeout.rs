@@ -0,0 +1,65 @@
+extern crate chrono;
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use chrono::{TimeZone, Utc};
+use common::SomeImportantAction;
+use exceptional::UnitTestOptions;
+use std::thread;
+use std::time::Duration;
+
+fn render(options: UnitTestOptions) -> String {
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+    exceptional::execute(&mut action, &(2, 3))
+        .unwrap_err()
+        .with_time(Utc.timestamp_millis_opt(1_539_761_233_543).unwrap())
+        .with_options(options)
+        .to_string()
+}
+
+#[test]
+fn execute_call_is_wrapped() {
+    let source = render(UnitTestOptions::default().timeout(Duration::from_secs(5)));
+    assert!(source.contains(
+        "\t::exceptional::run_with_timeout(::std::time::Duration::from_millis(5000), move || {\n\t\tif let Err(e) = obj.execute(&args) {\n"
+    ));
+    assert!(source.ends_with("\t\t}\n\t});\n}\n"));
+}
+
+#[test]
+fn async_tests_are_not_wrapped() {
+    let source = render(
+        UnitTestOptions::default()
+            .timeout(Duration::from_secs(5))
+            .async_test("tokio::test"),
+    );
+    assert!(!source.contains("run_with_timeout"));
+}
+
+#[test]
+fn finished_calls_return() {
+    exceptional::run_with_timeout(Duration::from_secs(5), || {});
+}
+
+#[test]
+#[should_panic(expected = "regression still fails")]
+fn panics_are_resumed() {
+    exceptional::run_with_timeout(Duration::from_secs(5), || panic!("regression still fails"));
+}
+
+#[test]
+#[should_panic(expected = "did not finish within 10ms")]
+fn hanging_calls_time_out() {
+    exceptional::run_with_timeout(Duration::from_millis(10), || {
+        thread::sleep(Duration::from_secs(1))
+    });
+}
+
+#[test]
+#[should_panic(expected = "at least 1 millisecond")]
+fn zero_timeout_is_rejected() {
+    let _ = UnitTestOptions::default().timeout(Duration::from_micros(10));
+}
@@ -0,0 +1,24 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use common::{scratch_dir, SomeImportantAction};
+use std::fs;
+
+#[test]
+fn the_source_is_rendered_without_consuming_the_test() {
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+    let test = exceptional::execute(&mut action, &(2, 3)).unwrap_err();
+
+    let source = test.to_test_source();
+    assert!(source.contains("\tlet mut obj: ::common::SomeImportantAction = "));
+    // The test can be rendered again, for another sink.
+    assert_eq!(test.to_test_source(), source);
+    assert_eq!(test.to_string(), source);
+
+    let file = scratch_dir("to_test_source").join("test.rs");
+    test.append_to_file(&file).unwrap();
+    assert_eq!(fs::read_to_string(&file).unwrap(), source);
+}
@@ -0,0 +1,114 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use common::SomeImportantAction;
+use exceptional::{Executable, SnapshotError, TryExecutable, TryExecuteError};
+
+/// An account whose balance can be shared with other accounts, which then can't be snapshotted.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct Account {
+    balance: i64,
+    shared: bool,
+}
+
+impl Executable for Account {
+    type Result = i64;
+    type Error = String;
+    type Arguments = i64;
+
+    fn full_path(&self) -> &'static str {
+        "::Account"
+    }
+
+    fn description(&self) -> String {
+        String::from("Withdraws money")
+    }
+
+    fn execute(&mut self, amount: &i64) -> Result<i64, String> {
+        self.balance -= amount;
+        if self.balance < 0 {
+            Err(format!("overdrawn by {}", -self.balance))
+        } else {
+            Ok(self.balance)
+        }
+    }
+}
+
+impl TryExecutable for Account {
+    fn try_snapshot(&self) -> Result<Self, SnapshotError> {
+        if self.shared {
+            Err(SnapshotError::new("the balance is shared"))
+        } else {
+            Ok(self.clone())
+        }
+    }
+}
+
+impl TryExecutable for SomeImportantAction {}
+
+#[test]
+fn successes_return_the_result() {
+    let mut account = Account {
+        balance: 10,
+        shared: true,
+    };
+    assert_eq!(exceptional::try_execute(&mut account, &4).ok(), Some(6));
+}
+
+#[test]
+fn failures_with_a_snapshot_are_captured() {
+    let mut account = Account {
+        balance: 10,
+        shared: false,
+    };
+    let error = exceptional::try_execute(&mut account, &15).unwrap_err();
+    assert_eq!(error.error(), "overdrawn by 5");
+    let test = match error {
+        TryExecuteError::Captured(test) => test,
+        TryExecuteError::NotCaptured { reason, .. } => panic!("not captured: {}", reason),
+    };
+    // The test has the state from before the run.
+    assert_eq!(test.executable.balance, 10);
+    assert_eq!(*test.arguments, 15);
+    assert_eq!(test.error, "overdrawn by 5");
+    assert!(test.to_string().contains("/// Withdraws money\n"));
+}
+
+#[test]
+fn failures_without_a_snapshot_are_returned_without_a_test() {
+    let mut account = Account {
+        balance: 10,
+        shared: true,
+    };
+    let (error, reason) = match exceptional::try_execute(&mut account, &15) {
+        Err(TryExecuteError::NotCaptured { error, reason }) => (error, reason),
+        Err(TryExecuteError::Captured(_)) => panic!("captured without a snapshot"),
+        Ok(_) => panic!("succeeded"),
+    };
+    assert_eq!(error, "overdrawn by 5");
+    assert_eq!(reason.message(), "the balance is shared");
+    assert_eq!(
+        reason.to_string(),
+        "could not snapshot executable: the balance is shared"
+    );
+    // The executable still ran.
+    assert_eq!(account.balance, -5);
+
+    let error = exceptional::try_execute(&mut account, &1).unwrap_err();
+    assert_eq!(error.error(), "overdrawn by 6");
+    assert!(error.unit_test().is_none());
+}
+
+#[test]
+fn the_default_snapshot_is_a_clone() {
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+    let test = exceptional::try_execute(&mut action, &(2, 3))
+        .unwrap_err()
+        .unit_test()
+        .unwrap();
+    assert_eq!(test.executable, SomeImportantAction { var_1: 0, var_2: 1 });
+    assert_eq!(*test.arguments, (2, 3));
+}
@@ -0,0 +1,165 @@
+extern crate chrono;
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use chrono::{TimeZone, Utc};
+use common::{scratch_dir, SomeImportantAction};
+use exceptional::{StreamingWriter, TestFileWriter, UnitTestOptions};
+use std::collections::HashSet;
+use std::fs;
+
+fn failure(var_1: u32) -> exceptional::OwnedUnitTest<SomeImportantAction> {
+    let action = SomeImportantAction { var_1, var_2: 1 };
+    exceptional::execute_all(&action, vec![(2, 3)], exceptional::Mode::FirstFailure)
+        .pop()
+        .unwrap()
+        .with_time(Utc.timestamp_millis_opt(1_000).unwrap())
+}
+
+fn ids(src: &str) -> Vec<String> {
+    exceptional::parse_markers(src)
+        .into_iter()
+        .map(|marker| marker.id)
+        .collect()
+}
+
+#[test]
+fn taken_ids_are_extended() {
+    let test = failure(0);
+    let mut taken = HashSet::new();
+    assert_eq!(test.unique_id(&taken).unwrap(), "1000");
+
+    taken.insert(String::from("1000"));
+    let id = test.unique_id(&taken).unwrap();
+    assert!(id.starts_with("1000_"), "{}", id);
+    assert_eq!(id.len(), "1000_".len() + 8);
+    // The suffix depends on the executable and the arguments, not on the order of the failures.
+    assert_ne!(failure(1).unique_id(&taken).unwrap(), id);
+
+    taken.insert(id.clone());
+    assert_eq!(test.unique_id(&taken).unwrap(), format!("{}_2", id));
+}
+
+#[test]
+fn failures_in_the_same_millisecond_get_different_names() {
+    let file = scratch_dir("unique_id").join("regressions.rs");
+    for var_1 in 0..3 {
+        failure(var_1).append_to_file(&file).unwrap();
+    }
+    // The same failure again, without deduplication.
+    failure(0).append_to_file(&file).unwrap();
+
+    let contents = fs::read_to_string(&file).unwrap();
+    let ids = ids(&contents);
+    assert_eq!(ids.len(), 4);
+    assert_eq!(ids[0], "1000");
+    assert_eq!(ids.iter().collect::<HashSet<_>>().len(), 4, "{:?}", ids);
+    for id in &ids {
+        assert_eq!(
+            contents.matches(&format!("pub fn test_{}()", id)).count(),
+            1
+        );
+    }
+}
+
+#[test]
+fn upserts_keep_the_id_of_the_replaced_test() {
+    let file = scratch_dir("unique_id_upsert").join("regressions.rs");
+    let options = UnitTestOptions::default().upsert(true);
+    failure(0).append_to_file(&file).unwrap();
+    let mut test = failure(0).with_options(options);
+    test.error = String::from("Oops");
+    test.append_to_file(&file).unwrap();
+    assert_eq!(ids(&fs::read_to_string(&file).unwrap()), ["1000"]);
+}
+
+#[test]
+fn streaming_writers_give_every_test_its_own_name() {
+    let file = scratch_dir("unique_id_streaming").join("regressions.rs");
+    failure(0).append_to_file(&file).unwrap();
+    let mut writer = StreamingWriter::open(&file).unwrap();
+    assert!(writer.record(failure(1)).unwrap());
+    assert!(writer.record(failure(2)).unwrap());
+    drop(writer);
+
+    let ids = ids(&fs::read_to_string(&file).unwrap());
+    assert_eq!(ids.len(), 3);
+    assert_eq!(ids.iter().collect::<HashSet<_>>().len(), 3, "{:?}", ids);
+}
+
+#[test]
+fn test_file_writers_give_every_test_its_own_name() {
+    let file = scratch_dir("unique_id_test_file_writer").join("regressions.rs");
+    let mut writer = TestFileWriter::new();
+    writer.push(failure(0).with_options(UnitTestOptions::default().hoist_inputs(true)));
+    writer.push(failure(1));
+    let buffered = ids(writer.as_str());
+    assert_eq!(buffered.len(), 2);
+    assert_ne!(buffered[0], buffered[1]);
+
+    // A test with the same id is written to the file before the buffer is flushed.
+    failure(2).append_to_file(&file).unwrap();
+    writer.flush_to_file(&file).unwrap();
+    assert!(writer.is_empty());
+
+    let contents = fs::read_to_string(&file).unwrap();
+    let ids = ids(&contents);
+    assert_eq!(ids.len(), 3);
+    assert_eq!(ids.iter().collect::<HashSet<_>>().len(), 3, "{:?}", ids);
+    for id in &ids {
+        assert_eq!(
+            contents.matches(&format!("pub fn test_{}()", id)).count(),
+            1
+        );
+    }
+    // The hoisted inputs are renamed with the test.
+    assert_eq!(buffered[0], "1000");
+    let renamed = ids[1].to_uppercase();
+    assert_ne!(renamed, "1000");
+    assert!(
+        contents.contains(&format!("const INPUT_{}_OBJ: &str", renamed)),
+        "{}",
+        contents
+    );
+    assert!(!contents.contains("PENDING"), "{}", contents);
+}
+
+#[cfg(feature = "locking")]
+#[test]
+fn concurrent_appends_read_the_ids_under_the_lock() {
+    let file = scratch_dir("unique_id_concurrent").join("regressions.rs");
+    let threads: Vec<_> = (0..8)
+        .map(|_| {
+            let file = file.clone();
+            std::thread::spawn(move || failure(0).append_to_file(&file).unwrap())
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    let ids = ids(&fs::read_to_string(&file).unwrap());
+    assert_eq!(ids.len(), 8);
+    assert_eq!(ids.iter().collect::<HashSet<_>>().len(), 8, "{:?}", ids);
+}
+
+#[cfg(feature = "locking")]
+#[test]
+fn concurrent_deduplicated_appends_write_a_failure_once() {
+    let file = scratch_dir("unique_id_concurrent_dedup").join("regressions.rs");
+    let threads: Vec<_> = (0..8)
+        .map(|_| {
+            let file = file.clone();
+            std::thread::spawn(move || failure(0).append_to_file_dedup(&file).unwrap())
+        })
+        .collect();
+    let written = threads
+        .into_iter()
+        .map(|thread| thread.join().unwrap())
+        .filter(|&written| written)
+        .count();
+    assert_eq!(written, 1);
+    assert_eq!(ids(&fs::read_to_string(&file).unwrap()), ["1000"]);
+}
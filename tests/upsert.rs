@@ -0,0 +1,41 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use common::SomeImportantAction;
+use exceptional::UnitTestOptions;
+use std::fs;
+
+fn capture(error: &str, file: &std::path::Path) {
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+    let mut test = exceptional::execute(&mut action, &(2, 3))
+        .unwrap_err()
+        .with_options(UnitTestOptions::default().upsert(true));
+    test.error = error.to_owned();
+    test.append_to_file(file).unwrap();
+}
+
+#[test]
+fn upsert_replaces_tests_with_a_changed_error() {
+    let dir = common::scratch_dir("upsert");
+    let file = dir.join("generated.rs");
+    fs::write(&file, "// hand-written\n").unwrap();
+
+    capture("Whoopsie", &file);
+    let first = fs::read_to_string(&file).unwrap();
+    assert_eq!(first.matches("#[test]").count(), 1);
+
+    capture("Whoopsie", &file);
+    assert_eq!(fs::read_to_string(&file).unwrap(), first);
+
+    capture("Something else", &file);
+    let contents = fs::read_to_string(&file).unwrap();
+    assert_eq!(contents.matches("#[test]").count(), 1);
+    assert!(contents.starts_with("// hand-written\n"));
+    assert!(contents.contains("/// exception was \"Something else\"\n"));
+    assert!(!contents.contains("Whoopsie"));
+
+    let _ = fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,89 @@
+#![cfg(feature = "validate")]
+
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use common::{scratch_dir, SomeImportantAction};
+use exceptional::{Executable, ValidationError};
+use std::io;
+
+/// An executable whose path or assertion is broken.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Broken {
+    pub bad_path: bool,
+}
+
+impl Executable for Broken {
+    type Result = ();
+    type Error = String;
+    type Arguments = ();
+
+    fn full_path(&self) -> &'static str {
+        if self.bad_path {
+            "::my crate::Broken"
+        } else {
+            "::Broken"
+        }
+    }
+
+    fn description(&self) -> String {
+        String::from("Is broken")
+    }
+
+    fn assertion(&self) -> Option<String> {
+        Some(String::from("assert!(obj.execute(&args).is_err();"))
+    }
+
+    fn execute(&mut self, _args: &()) -> Result<(), String> {
+        Err(String::from("failed"))
+    }
+}
+
+#[test]
+fn generated_tests_are_valid() {
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+    let test = exceptional::execute(&mut action, &(2, 3)).unwrap_err();
+    assert_eq!(test.validate(), Ok(()));
+}
+
+#[test]
+fn invalid_paths_are_reported() {
+    let mut broken = Broken { bad_path: true };
+    let test = exceptional::execute(&mut broken, &()).unwrap_err();
+    match test.validate() {
+        Err(ValidationError::InvalidPath { path, .. }) => assert_eq!(path, "::my crate::Broken"),
+        other => panic!("unexpected {:?}", other),
+    }
+}
+
+#[test]
+fn syntax_errors_are_reported() {
+    let mut broken = Broken { bad_path: false };
+    let test = exceptional::execute(&mut broken, &()).unwrap_err();
+    match test.validate() {
+        Err(ValidationError::Syntax(_)) => {}
+        other => panic!("unexpected {:?}", other),
+    }
+}
+
+#[test]
+fn invalid_tests_are_not_written() {
+    let file = scratch_dir("validate").join("tests.rs");
+    let mut broken = Broken { bad_path: false };
+    let error = exceptional::execute(&mut broken, &())
+        .unwrap_err()
+        .append_to_file_checked(&file)
+        .unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    assert!(!file.exists());
+
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+    exceptional::execute(&mut action, &(2, 3))
+        .unwrap_err()
+        .append_to_file_checked(&file)
+        .unwrap();
+    assert!(file.exists());
+}
@@ -0,0 +1,55 @@
+extern crate exceptional;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use common::{scratch_dir, SomeImportantAction};
+use std::fs;
+
+const IMPORTS: &str = "#[allow(unused_imports)]\nuse super::*;\n#[allow(unused_imports)]\nuse exceptional::Executable;\n\n";
+
+fn write(dir: &std::path::Path) -> std::path::PathBuf {
+    let mut action = SomeImportantAction { var_1: 0, var_2: 1 };
+    exceptional::execute(&mut action, &(2, 3))
+        .unwrap_err()
+        .write_to_dir(dir)
+        .unwrap()
+}
+
+#[test]
+fn every_test_gets_its_own_module() {
+    let dir = scratch_dir("write_to_dir").join("regressions");
+    let first = write(&dir);
+    let second = write(&dir);
+    assert_ne!(first, second);
+
+    let mut declarations = String::new();
+    for path in &[&first, &second] {
+        let module = path.file_stem().unwrap().to_str().unwrap();
+        assert!(module.starts_with("test_"));
+        declarations.push_str(&format!("mod {};\n", module));
+
+        let contents = fs::read_to_string(path).unwrap();
+        assert!(contents.starts_with(&format!("{}// @exceptional ", IMPORTS)));
+        assert!(contents.contains(&format!("pub fn {}() {{", module)));
+        assert_eq!(contents.matches("use exceptional::Executable;").count(), 1);
+        assert_eq!(exceptional::parse_markers(&contents).len(), 1);
+    }
+    assert_eq!(
+        fs::read_to_string(dir.join("mod.rs")).unwrap(),
+        declarations
+    );
+}
+
+#[test]
+fn an_existing_mod_file_is_extended() {
+    let dir = scratch_dir("write_to_dir_existing");
+    fs::write(dir.join("mod.rs"), "//! Regressions\nmod helpers;").unwrap();
+    let path = write(&dir);
+    let module = path.file_stem().unwrap().to_str().unwrap();
+    assert_eq!(
+        fs::read_to_string(dir.join("mod.rs")).unwrap(),
+        format!("//! Regressions\nmod helpers;\nmod {};\n", module)
+    );
+}